@@ -0,0 +1,40 @@
+using System.Collections.Generic;
+
+static class Program
+{
+    static IEnumerable<int> count(int start, int stop)
+    {
+        if (start>=stop)
+        {
+            yield break;
+        }
+        yield return start;
+        foreach (var _v in count(start+1,stop))
+        {
+            yield return _v;
+        }
+    }
+
+    static IEnumerable<int> both(int n)
+    {
+        foreach (var _v in count(0,n))
+        {
+            yield return _v;
+        }
+        foreach (var _v in count(10,12))
+        {
+            yield return _v;
+        }
+        yield return n*100;
+    }
+
+    static int Main()
+    {
+        var total = 0;
+        foreach (var x in both(3))
+        {
+            total = total+x;
+        }
+        return total;
+    }
+}
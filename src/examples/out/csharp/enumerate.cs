@@ -0,0 +1,38 @@
+using System.Collections.Generic;
+
+static class Program
+{
+    static IEnumerable<int> digits()
+    {
+        yield return 7;
+        yield return 8;
+        yield return 9;
+    }
+
+    static int Main()
+    {
+        var total = 0;
+        {
+            var i_next = 0;
+            foreach (var x in digits())
+            {
+                var i = i_next++;
+                if (i==1)
+                {
+                    continue;
+                }
+                total = total+i*x;
+            }
+        }
+        {
+            var i_next = 0;
+            for (var x = 8; x > 5;)
+            {
+                x--;
+                var i = i_next++;
+                total = total+i+x;
+            }
+        }
+        return total;
+    }
+}
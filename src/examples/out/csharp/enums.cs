@@ -0,0 +1,53 @@
+abstract record Shape
+{
+    public sealed record Circle(double _0) : Shape;
+    public sealed record Rect(double _0, double _1) : Shape;
+    public sealed record Empty() : Shape;
+}
+
+enum Color
+{
+    Red,
+    Green,
+    Blue,
+}
+
+struct Tile
+{
+    public Color color;
+    public Shape shape;
+}
+
+static class Program
+{
+    static Color next(Color c)
+    {
+        if (c==Color.Red)
+        {
+            return Color.Green;
+        }
+        else if (c==Color.Green)
+        {
+            return Color.Blue;
+        }
+        return Color.Red;
+    }
+
+    static Shape square(double side)
+    {
+        return new Shape.Rect(side,side);
+    }
+
+    static int Main()
+    {
+        var c = next(next(Color.Red));
+        var t = new Tile { color = next(c), shape = new Shape.Empty() };
+        var big = new Tile { color = c, shape = square(2.0) };
+        var round = new Tile { color = big.color, shape = new Shape.Circle(1.5) };
+        if (round.color==Color.Blue&&t.color==Color.Red)
+        {
+            return 42;
+        }
+        return 0;
+    }
+}
@@ -0,0 +1,23 @@
+using System.Collections.Generic;
+
+static class Program
+{
+    static IEnumerable<int> test()
+    {
+        if (true)
+        {
+            yield return 10;
+            yield return 10*6+9;
+        }
+        else
+        {
+            yield return 9+10;
+        }
+    }
+
+    static int Main()
+    {
+        var temp = test();
+        return 0;
+    }
+}
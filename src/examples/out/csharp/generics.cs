@@ -0,0 +1,70 @@
+interface Sized
+{
+    int size();
+}
+
+struct Box : Sized
+{
+    public int w;
+
+    public int size()
+    {
+        var self = this;
+        return self.w;
+    }
+}
+
+struct Pair_int
+{
+    public int first;
+    public int second;
+}
+
+static class Program
+{
+    static int larger_int(int a, int b)
+    {
+        if (a>b)
+        {
+            return a;
+        }
+        return b;
+    }
+
+    static double larger_f64(double a, double b)
+    {
+        if (a>b)
+        {
+            return a;
+        }
+        return b;
+    }
+
+    static Pair_int pair_int(int a, int b)
+    {
+        return new Pair_int { first = a, second = b };
+    }
+
+    static Pair_int swap_int(Pair_int p)
+    {
+        return new Pair_int { first = p.second, second = p.first };
+    }
+
+    static int biggest_Box(Box a, Box b)
+    {
+        return larger_int(a.size(),b.size());
+    }
+
+    static int Main()
+    {
+        var p = swap_int(pair_int(3,4));
+        var small = new Box { w = 2 };
+        var big = new Box { w = 5 };
+        var total = larger_int(p.first,p.second)+biggest_Box(small,big);
+        if (larger_f64(1.5,0.5)>1.0)
+        {
+            return total;
+        }
+        return 0;
+    }
+}
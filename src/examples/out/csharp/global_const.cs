@@ -0,0 +1,9 @@
+static class Program
+{
+    const int test = 69;
+
+    static int Main()
+    {
+        return 0;
+    }
+}
@@ -0,0 +1,9 @@
+static class Program
+{
+    static int test = 69;
+
+    static int Main()
+    {
+        return 0;
+    }
+}
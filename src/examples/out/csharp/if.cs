@@ -0,0 +1,12 @@
+static class Program
+{
+    static int Main()
+    {
+        var test = true;
+        if (test)
+        {
+            test = false;
+        }
+        return 0;
+    }
+}
@@ -0,0 +1,9 @@
+using System.Text;
+
+static class Program
+{
+    static int Main()
+    {
+        return 0;
+    }
+}
@@ -0,0 +1,27 @@
+static class Program
+{
+    const int SIDE = 4;
+    static readonly long AREA = area(SIDE,3);
+    static double ratio = scale(1.5,2.0);
+
+    static long area(long w, long h)
+    {
+        return w*h;
+    }
+
+    static double scale(double x, double by)
+    {
+        return x*by;
+    }
+
+    static int Main()
+    {
+        var total = AREA+area(2,2);
+        var half = ratio/2;
+        if (total>10&&half<4.0)
+        {
+            return 3;
+        }
+        return 0;
+    }
+}
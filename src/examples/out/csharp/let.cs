@@ -0,0 +1,8 @@
+static class Program
+{
+    static int Main()
+    {
+        var test = 10;
+        return 0;
+    }
+}
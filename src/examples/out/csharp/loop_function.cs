@@ -0,0 +1,10 @@
+static class Program
+{
+    static int Main()
+    {
+        while (true)
+        {
+            var test = 10;
+        }
+    }
+}
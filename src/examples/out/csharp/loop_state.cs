@@ -0,0 +1,44 @@
+using System.Threading;
+
+static class Program
+{
+    static int ticks = 0;
+
+    static int count(int limit)
+    {
+        var n = 0;
+        var total = 0;
+        while (true)
+        {
+            n = n+1;
+            if (n>limit)
+            {
+                return total;
+            }
+            if (n%2==1)
+            {
+                continue;
+            }
+            total = total+n;
+        }
+    }
+
+    static void tick()
+    {
+        while (true)
+        {
+            Thread.Sleep(1);
+            ticks = ticks+1;
+            if (ticks==3)
+            {
+                break;
+            }
+        }
+    }
+
+    static int Main()
+    {
+        tick();
+        return count(10)+ticks;
+    }
+}
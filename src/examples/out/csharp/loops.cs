@@ -0,0 +1,83 @@
+static class Program
+{
+    static int seen = 0;
+
+    static int pairs()
+    {
+        var found = 0;
+        var a = 0;
+        while (a<5)
+        {
+            a = a+1;
+            var b = 0;
+            while (b<3)
+            {
+                b = b+1;
+                if (b>a)
+                {
+                    goto outer_continue;
+                }
+                if (a*b>12)
+                {
+                    goto outer_break;
+                }
+                found = found+1;
+            }
+            var row = a;
+            seen = seen+row;
+            outer_continue: ;
+        }
+        outer_break: ;
+        return found;
+    }
+
+    static int collatz(int n)
+    {
+        var steps = 0;
+        while (n!=1)
+        {
+            if (n%2==0)
+            {
+                n = n/2;
+            }
+            else
+            {
+                n = 3*n+1;
+            }
+            steps = steps+1;
+        }
+        return steps;
+    }
+
+    static int grid()
+    {
+        var cells = 0;
+        var row = 0;
+        while (true)
+        {
+            row = row+1;
+            var col = 0;
+            while (true)
+            {
+                col = col+1;
+                if (col>row)
+                {
+                    break;
+                }
+                if (row==4)
+                {
+                    goto rows_break;
+                }
+                cells = cells+1;
+            }
+        }
+        rows_break: ;
+        return cells;
+    }
+
+    static int Main()
+    {
+        var found = pairs();
+        return found*10+seen+collatz(6)+grid();
+    }
+}
@@ -0,0 +1,7 @@
+static class Program
+{
+    static int Main()
+    {
+        return 0;
+    }
+}
@@ -0,0 +1,48 @@
+abstract record Shape
+{
+    public sealed record Circle(double _0) : Shape;
+    public sealed record Rect(double _0, double _1) : Shape;
+    public sealed record Empty() : Shape;
+}
+
+enum Color
+{
+    Red,
+    Green,
+    Blue,
+}
+
+static class Program
+{
+    static double area(Shape s)
+    {
+        return s switch { Shape.Circle(var r) => 3.0*r*r, Shape.Rect(var w, var h) => w*h, Shape.Empty => 0.0 };
+    }
+
+    static int digits(int n)
+    {
+        var count = n switch { >= 0 and <= 9 => 1, >= 10 and <= 99 => 2, _ => 3 };
+        return count;
+    }
+
+    static int code(Color c)
+    {
+        return c switch { Color.Red => 1, Color.Green => 2, Color.Blue => 3 };
+    }
+
+    static int bit(bool on)
+    {
+        return on switch { true => 1, false => 0 };
+    }
+
+    static int Main()
+    {
+        var total = area(new Shape.Rect(2.0,3.0))+area(new Shape.Empty());
+        if (total!=6.0)
+        {
+            return 1;
+        }
+        var sizes = digits(7)+digits(42)+digits(512);
+        return sizes*5+code(Color.Blue)*4+bit(false);
+    }
+}
@@ -0,0 +1,56 @@
+struct Point
+{
+    public int x;
+    public int y;
+
+    public static Point at(int x, int y)
+    {
+        return new Point { x = x, y = y };
+    }
+
+    public static Point origin()
+    {
+        return Point.at(0,0);
+    }
+
+    public Point add(Point other)
+    {
+        var self = this;
+        return new Point { x = self.x+other.x, y = self.y+other.y };
+    }
+
+    public int dot(Point other)
+    {
+        var self = this;
+        return self.x*other.x+self.y*other.y;
+    }
+
+    public int len2()
+    {
+        var self = this;
+        return self.dot(self);
+    }
+}
+
+struct Rect
+{
+    public Point min;
+    public Point max;
+
+    public int area()
+    {
+        var self = this;
+        var size = self.max.add(Point.at(-self.min.x,-self.min.y));
+        return size.x*size.y;
+    }
+}
+
+static class Program
+{
+    static int Main()
+    {
+        var p = Point.origin().add(Point.at(3,4));
+        var r = new Rect { min = Point.at(1,1), max = p.add(p) };
+        return p.len2()+r.area()+r.max.dot(Point.at(1,0));
+    }
+}
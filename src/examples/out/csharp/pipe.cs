@@ -0,0 +1,70 @@
+using System;
+using System.Collections.Generic;
+
+static class Program
+{
+    static IEnumerable<U> map<T, U>(IEnumerable<T> xs, Func<T, U> f)
+    {
+        foreach (var x in xs)
+        {
+            yield return f(x);
+        }
+    }
+
+    static IEnumerable<T> filter<T>(IEnumerable<T> xs, Func<T, bool> p)
+    {
+        foreach (var x in xs)
+        {
+            if (p(x))
+            {
+                yield return x;
+            }
+        }
+    }
+
+    static IEnumerable<T> take<T>(IEnumerable<T> xs, long n)
+    {
+        if (n <= 0)
+        {
+            yield break;
+        }
+        foreach (var x in xs)
+        {
+            yield return x;
+            if (--n <= 0)
+            {
+                yield break;
+            }
+        }
+    }
+
+    static IEnumerable<int> naturals()
+    {
+        var n = 1;
+        while (true)
+        {
+            yield return n;
+            n = n+1;
+        }
+    }
+
+    static int square(int x)
+    {
+        return x*x;
+    }
+
+    static bool odd(int x)
+    {
+        return x%2==1;
+    }
+
+    static int Main()
+    {
+        var total = 0;
+        foreach (var x in take(filter(map(naturals(),square),odd),3))
+        {
+            total = total+x;
+        }
+        return square(total);
+    }
+}
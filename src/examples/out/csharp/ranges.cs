@@ -0,0 +1,75 @@
+static class Program
+{
+    static int sum(int n)
+    {
+        var total = 0;
+        for (var i = 0; i < n; i++)
+        {
+            total = total+i;
+        }
+        for (var i = 0; i <= n; i++)
+        {
+            total = total+i;
+        }
+        return total;
+    }
+
+    static int rounds(uint n)
+    {
+        var count = 0;
+        for (var i = n; i > 0;)
+        {
+            i--;
+            count = count+1;
+        }
+        for (var i = n+2; i >= 2;)
+        {
+            i -= 2;
+            count = count+1;
+        }
+        return count;
+    }
+
+    static int Main()
+    {
+        var total = sum(10);
+        for (var i = 0; i < 10; i += 3)
+        {
+            total = total+i;
+        }
+        var order = 0;
+        for (var i = 4; i > 1;)
+        {
+            i--;
+            order = order*10+i;
+        }
+        var evens = 0;
+        for (var i = 8; i >= 2;)
+        {
+            i -= 2;
+            evens = evens*10+i;
+        }
+        var odd = 0;
+        for (var i = 0; i < 10; i++)
+        {
+            if (i%2==0)
+            {
+                continue;
+            }
+            odd = odd+i;
+        }
+        {
+            var i_next = 0;
+            for (var x = 10; x < 20; x += 5)
+            {
+                var i = i_next++;
+                total = total+i*x;
+            }
+        }
+        if (order!=321||evens!=6420||odd!=25||rounds(0)!=1)
+        {
+            return 1;
+        }
+        return total;
+    }
+}
@@ -0,0 +1,29 @@
+struct Counter
+{
+    public int count;
+
+    public static Counter new_()
+    {
+        return new Counter { count = 1 };
+    }
+
+    public int this_()
+    {
+        var self = this;
+        return self.count;
+    }
+}
+
+static class Program
+{
+    static int end(int n)
+    {
+        return n*3;
+    }
+
+    static int Main()
+    {
+        var c = Counter.new_();
+        return end(c.this_());
+    }
+}
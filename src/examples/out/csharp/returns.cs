@@ -0,0 +1,36 @@
+static class Program
+{
+    static int sign(int n)
+    {
+        if (n>0)
+        {
+            return 1;
+        }
+        else if (n<0)
+        {
+            return 2;
+        }
+        else
+        {
+            return 0;
+        }
+    }
+
+    static int root(int above)
+    {
+        var i = 0;
+        while (true)
+        {
+            i = i+1;
+            if (i*i>above)
+            {
+                return i;
+            }
+        }
+    }
+
+    static int Main()
+    {
+        return sign(5)+root(10);
+    }
+}
@@ -0,0 +1,27 @@
+static class Program
+{
+    static int sum(int n)
+    {
+        var total = 0;
+        for (var x = 1; x <= n; x++)
+        {
+            if (x%2==0)
+            {
+                var part = x-1;
+                total = total+part;
+            }
+            else
+            {
+                var part = x;
+                total = total+part;
+            }
+        }
+        return total;
+    }
+
+    static int Main()
+    {
+        var total = sum(4);
+        return total;
+    }
+}
@@ -0,0 +1,42 @@
+using System;
+
+struct Point
+{
+    public int x;
+    public int y;
+}
+
+struct Rect
+{
+    public Point min;
+    public Point max;
+}
+
+static class Program
+{
+    static void print(params object[] values)
+    {
+        var words = Array.ConvertAll(values, v => v is bool b ? (b ? "true" : "false") : v);
+        Console.WriteLine(string.Join(" ", words));
+    }
+
+    static int area(Rect r)
+    {
+        return (r.max.x-r.min.x)*(r.max.y-r.min.y);
+    }
+
+    static Point corner(int x, int y)
+    {
+        return new Point { x = x, y = y };
+    }
+
+    static int Main()
+    {
+        var p = new Point { x = 3, y = 4 };
+        var label = "Point{x:3}";
+        print(label);
+        var r = new Rect { min = corner(1,1), max = new Point { x = p.x+5, y = p.y*2 } };
+        var moved = new Rect { min = r.min, max = corner(r.max.x,10) };
+        return area(r)+area(moved)+corner(2,7).y;
+    }
+}
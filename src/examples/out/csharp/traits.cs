@@ -0,0 +1,71 @@
+interface Shape
+{
+    int area();
+    int scaled(int k);
+}
+
+struct Square : Shape
+{
+    public int side;
+
+    public int area()
+    {
+        var self = this;
+        return self.side*self.side;
+    }
+
+    public int scaled(int k)
+    {
+        var self = this;
+        return self.area()*k*k;
+    }
+}
+
+struct Rect : Shape
+{
+    public int w;
+    public int h;
+
+    public int area()
+    {
+        var self = this;
+        return self.w*self.h;
+    }
+
+    public int scaled(int k)
+    {
+        var self = this;
+        return self.w*k*self.h*k;
+    }
+}
+
+struct Framed
+{
+    public Shape inner;
+    public int border;
+}
+
+static class Program
+{
+    static int total(Shape a, Shape b)
+    {
+        return a.area()+b.area();
+    }
+
+    static Shape larger(Shape a, Shape b)
+    {
+        if (a.area()>b.area())
+        {
+            return a;
+        }
+        return b;
+    }
+
+    static int Main()
+    {
+        var s = new Square { side = 3 };
+        var r = new Rect { w = 2, h = 5 };
+        var f = new Framed { inner = larger(s,r), border = 1 };
+        return total(s,r)+f.inner.scaled(2)+f.border;
+    }
+}
@@ -0,0 +1,34 @@
+struct Pixel
+{
+    public uint level;
+    public string name;
+}
+
+static class Program
+{
+    static string label(Pixel p)
+    {
+        return p.name;
+    }
+
+    static long area(long w, long h)
+    {
+        return w*h;
+    }
+
+    static double half(double x)
+    {
+        return x/2;
+    }
+
+    static int Main()
+    {
+        var p = new Pixel { level = 200, name = "red" };
+        var name = label(p);
+        if (p.level>100&&area(3,4)>10&&half(3.0)>1.0)
+        {
+            return 7;
+        }
+        return 0;
+    }
+}
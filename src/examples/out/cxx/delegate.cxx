@@ -0,0 +1,102 @@
+#include <coroutine>
+#include <exception>
+#include <utility>
+
+template <typename T>
+class generator
+{
+public:
+    struct promise_type
+    {
+        T value{};
+        std::exception_ptr exception;
+        generator get_return_object()
+        {
+            return generator(std::coroutine_handle<promise_type>::from_promise(*this));
+        }
+        std::suspend_always initial_suspend() noexcept { return {}; }
+        std::suspend_always final_suspend() noexcept { return {}; }
+        std::suspend_always yield_value(T v)
+        {
+            value = std::move(v);
+            return {};
+        }
+        void return_void() {}
+        void unhandled_exception() { exception = std::current_exception(); }
+    };
+    struct sentinel {};
+    struct iterator
+    {
+        generator *gen;
+        iterator &operator++()
+        {
+            gen->advance();
+            return *this;
+        }
+        T &operator*() const { return gen->handle.promise().value; }
+        bool operator!=(sentinel) const { return !gen->handle.done(); }
+    };
+    explicit generator(std::coroutine_handle<promise_type> handle) : handle(handle) {}
+    generator(generator &&other) noexcept : handle(std::exchange(other.handle, {})) {}
+    generator(const generator &) = delete;
+    ~generator()
+    {
+        if (handle)
+        {
+            handle.destroy();
+        }
+    }
+    iterator begin()
+    {
+        advance();
+        return iterator{this};
+    }
+    sentinel end() { return {}; }
+
+private:
+    std::coroutine_handle<promise_type> handle;
+    void advance()
+    {
+        handle.resume();
+        if (handle.promise().exception)
+        {
+            std::rethrow_exception(handle.promise().exception);
+        }
+    }
+};
+
+generator<int> count (int start, int stop)
+{
+    if (start>=stop)
+    {
+        co_return;
+    }
+    co_yield start;
+    for (auto &&_v : count(start+1,stop))
+    {
+        co_yield _v;
+    }
+    co_return;
+}
+generator<int> both (int n)
+{
+    for (auto &&_v : count(0,n))
+    {
+        co_yield _v;
+    }
+    for (auto &&_v : count(10,12))
+    {
+        co_yield _v;
+    }
+    co_yield n*100;
+    co_return;
+}
+int main ()
+{
+    auto total = 0;
+    for(auto x:both(3))
+    {
+        total = total+x;
+    }
+    return total;
+}
\ No newline at end of file
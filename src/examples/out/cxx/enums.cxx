@@ -0,0 +1,71 @@
+#include <variant>
+namespace Shape_
+{
+    struct Circle
+    {
+        double _0;
+    };
+    struct Rect
+    {
+        double _0;
+        double _1;
+    };
+    struct Empty
+    {
+    };
+}
+struct Shape : std::variant<Shape_::Circle, Shape_::Rect, Shape_::Empty>
+{
+    using variant::variant;
+    static Shape Circle(double _0)
+    {
+        return Shape_::Circle{_0};
+    }
+    static Shape Rect(double _0, double _1)
+    {
+        return Shape_::Rect{_0, _1};
+    }
+    static Shape Empty()
+    {
+        return Shape_::Empty{};
+    }
+};
+enum class Color
+{
+    Red,
+    Green,
+    Blue
+};
+struct Tile
+{
+    Color color;
+    Shape shape;
+};
+Color next (Color c)
+{
+    if (c==Color::Red)
+    {
+        return Color::Green;
+    }
+    else if (c==Color::Green)
+    {
+        return Color::Blue;
+    }
+    return Color::Red;
+}
+Shape square (double side)
+{
+    return Shape::Rect(side,side);
+}
+int main ()
+{
+    auto c = next(next(Color::Red));
+    auto t = Tile{.color = next(c), .shape = Shape::Empty()};
+    auto big = Tile{.color = c, .shape = square(2.0)};
+    auto round = Tile{.color = big.color, .shape = Shape::Circle(1.5)};
+    if (round.color==Color::Blue&&t.color==Color::Red)
+    {
+        return 42;
+    }
+    return 0;
+}
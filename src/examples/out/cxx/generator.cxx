@@ -1,17 +1,81 @@
-#include <vector>
+#include <coroutine>
+#include <exception>
+#include <utility>
 
-std::vector<int> test ()
+template <typename T>
+class generator
+{
+public:
+    struct promise_type
+    {
+        T value{};
+        std::exception_ptr exception;
+        generator get_return_object()
+        {
+            return generator(std::coroutine_handle<promise_type>::from_promise(*this));
+        }
+        std::suspend_always initial_suspend() noexcept { return {}; }
+        std::suspend_always final_suspend() noexcept { return {}; }
+        std::suspend_always yield_value(T v)
+        {
+            value = std::move(v);
+            return {};
+        }
+        void return_void() {}
+        void unhandled_exception() { exception = std::current_exception(); }
+    };
+    struct sentinel {};
+    struct iterator
+    {
+        generator *gen;
+        iterator &operator++()
+        {
+            gen->advance();
+            return *this;
+        }
+        T &operator*() const { return gen->handle.promise().value; }
+        bool operator!=(sentinel) const { return !gen->handle.done(); }
+    };
+    explicit generator(std::coroutine_handle<promise_type> handle) : handle(handle) {}
+    generator(generator &&other) noexcept : handle(std::exchange(other.handle, {})) {}
+    generator(const generator &) = delete;
+    ~generator()
+    {
+        if (handle)
+        {
+            handle.destroy();
+        }
+    }
+    iterator begin()
+    {
+        advance();
+        return iterator{this};
+    }
+    sentinel end() { return {}; }
+
+private:
+    std::coroutine_handle<promise_type> handle;
+    void advance()
+    {
+        handle.resume();
+        if (handle.promise().exception)
+        {
+            std::rethrow_exception(handle.promise().exception);
+        }
+    }
+};
+
+generator<int> test ()
 {
-    std::vector<int> dfjfjfdjfndjfnjd;
     if (true)
     {
-        dfjfjfdjfndjfnjd.push_back(10);
-        dfjfjfdjfndjfnjd.push_back(10*6+9);
+        co_yield 10;
+        co_yield 10*6+9;
     }
     else{
-        dfjfjfdjfndjfnjd.push_back(9+10);
+        co_yield 9+10;
     }
-    return dfjfjfdjfndjfnjd;
+    co_return;
 }
 int main ()
 {
@@ -0,0 +1,59 @@
+#include <concepts>
+#include <utility>
+template <typename T>
+concept Sized = requires(const T &self)
+{
+    { self.size() } -> std::convertible_to<int>;
+};
+struct Box
+{
+    int w;
+    int size() const;
+};
+template <typename T>
+struct Pair
+{
+    T first;
+    T second;
+};
+int Box::size () const
+{
+    const auto &self = *this;
+    return self.w;
+}
+template <typename T>
+T larger (T a, T b)
+{
+    if (a>b)
+    {
+        return a;
+    }
+    return b;
+}
+template <typename T>
+Pair<T> pair (T a, T b)
+{
+    return Pair{.first = a, .second = b};
+}
+template <typename T>
+Pair<T> swap (Pair<T> p)
+{
+    return Pair{.first = p.second, .second = p.first};
+}
+template <Sized T>
+int biggest (T a, T b)
+{
+    return larger(a.size(),b.size());
+}
+int main ()
+{
+    auto p = swap(pair(3,4));
+    auto small = Box{.w = 2};
+    auto big = Box{.w = 5};
+    auto total = larger(p.first,p.second)+biggest(small,big);
+    if (larger(1.5,0.5)>1.0)
+    {
+        return total;
+    }
+    return 0;
+}
@@ -1,4 +1,5 @@
 auto test = 69;
 int main ()
 {
+    return 0;
 }
\ No newline at end of file
@@ -5,4 +5,5 @@ int main ()
     {
         test = false;
     }
+    return 0;
 }
\ No newline at end of file
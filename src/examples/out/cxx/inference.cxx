@@ -0,0 +1,22 @@
+#include <cstdint>
+std::int64_t area (std::int64_t w, std::int64_t h)
+{
+    return w*h;
+}
+double scale (double x, double by)
+{
+    return x*by;
+}
+const auto SIDE = 4;
+const auto AREA = area(SIDE,3);
+auto ratio = scale(1.5,2.0);
+int main ()
+{
+    auto total = AREA+area(2,2);
+    auto half = ratio/2;
+    if (total>10&&half<4.0)
+    {
+        return 3;
+    }
+    return 0;
+}
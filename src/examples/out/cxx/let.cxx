@@ -1,4 +1,5 @@
 int main ()
 {
     auto test = 10;
+    return 0;
 }
\ No newline at end of file
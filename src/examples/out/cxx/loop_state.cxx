@@ -0,0 +1,36 @@
+auto ticks = 0;
+int count (int limit)
+{
+    auto n = 0;
+    auto total = 0;
+    do {
+        n = n+1;
+        if (n>limit)
+        {
+            return total;
+        }
+        if (n%2==1)
+        {
+            continue;
+        }
+        total = total+n;
+    } while (1);
+}
+#include <chrono>
+#include <thread>
+void tick ()
+{
+    do {
+        std::this_thread::sleep_for(std::chrono::milliseconds(1));
+        ticks = ticks+1;
+        if (ticks==3)
+        {
+            break;
+        }
+    } while (1);
+}
+int main ()
+{
+    tick ();
+    return count(10)+ticks;
+}
@@ -0,0 +1,77 @@
+auto seen = 0;
+int pairs ()
+{
+    auto found = 0;
+    auto a = 0;
+    while (a<5)
+    {
+        {
+            a = a+1;
+            auto b = 0;
+            while (b<3)
+            {
+                b = b+1;
+                if (b>a)
+                {
+                    goto outer_continue;
+                }
+                if (a*b>12)
+                {
+                    goto outer_break;
+                }
+                found = found+1;
+            }
+            auto row = a;
+            seen = seen+row;
+        }
+    outer_continue:;
+    }
+outer_break:;
+    return found;
+}
+int collatz (int n)
+{
+    auto steps = 0;
+    while (n!=1)
+    {
+        if (n%2==0)
+        {
+            n = n/2;
+        }
+        else{
+            n = 3*n+1;
+        }
+        steps = steps+1;
+    }
+    return steps;
+}
+int grid ()
+{
+    auto cells = 0;
+    auto row = 0;
+    while (true)
+    {
+        row = row+1;
+        auto col = 0;
+        while (true)
+        {
+            col = col+1;
+            if (col>row)
+            {
+                break;
+            }
+            if (row==4)
+            {
+                goto rows_break;
+            }
+            cells = cells+1;
+        }
+    }
+rows_break:;
+    return cells;
+}
+int main ()
+{
+    auto found = pairs();
+    return found*10+seen+collatz(6)+grid();
+}
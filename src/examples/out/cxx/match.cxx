@@ -0,0 +1,115 @@
+#include <variant>
+template <class... Ts>
+struct overloaded : Ts...
+{
+    using Ts::operator()...;
+};
+
+namespace Shape_
+{
+    struct Circle
+    {
+        double _0;
+    };
+    struct Rect
+    {
+        double _0;
+        double _1;
+    };
+    struct Empty
+    {
+    };
+}
+struct Shape : std::variant<Shape_::Circle, Shape_::Rect, Shape_::Empty>
+{
+    using variant::variant;
+    static Shape Circle(double _0)
+    {
+        return Shape_::Circle{_0};
+    }
+    static Shape Rect(double _0, double _1)
+    {
+        return Shape_::Rect{_0, _1};
+    }
+    static Shape Empty()
+    {
+        return Shape_::Empty{};
+    }
+};
+enum class Color
+{
+    Red,
+    Green,
+    Blue
+};
+double area (Shape s)
+{
+    return std::visit(overloaded{
+        [&](const Shape_::Circle &_v)
+        {
+            auto r = _v._0;
+            return 3.0*r*r;
+        },
+        [&](const Shape_::Rect &_v)
+        {
+            auto w = _v._0;
+            auto h = _v._1;
+            return w*h;
+        },
+        [&](const Shape_::Empty &)
+        {
+            return 0.0;
+        }}, s);
+}
+int digits (int n)
+{
+    auto count = [&](const auto &_m)
+    {
+        if (_m >= 0 && _m <= 9)
+        {
+            return 1;
+        }
+        if (_m >= 10 && _m <= 99)
+        {
+            return 2;
+        }
+        return 3;
+    }(n);
+    return count;
+}
+int code (Color c)
+{
+    return [&](const auto &_m)
+    {
+        switch (_m)
+        {
+            case Color::Red:
+            return 1;
+            case Color::Green:
+            return 2;
+            default:
+            return 3;
+        }
+    }(c);
+}
+int bit (bool on)
+{
+    return [&](const auto &_m)
+    {
+        if (_m)
+        {
+            return 1;
+        }
+        return 0;
+    }(on);
+}
+int main ()
+{
+    auto total = area(Shape::Rect(2.0,3.0))+area(Shape::Empty());
+    if (total!=6.0)
+    {
+        return 1;
+    }
+    auto sizes = digits(7)+digits(42)+digits(512);
+    return sizes*5+code(Color::Blue)*4+bit(false);
+}
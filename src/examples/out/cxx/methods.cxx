@@ -0,0 +1,51 @@
+struct Point
+{
+    int x;
+    int y;
+    static Point at(int x, int y);
+    static Point origin();
+    Point add(Point other) const;
+    int dot(Point other) const;
+    int len2() const;
+};
+struct Rect
+{
+    Point min;
+    Point max;
+    int area() const;
+};
+Point Point::at (int x, int y)
+{
+    return Point{.x = x, .y = y};
+}
+Point Point::origin ()
+{
+    return Point::at(0,0);
+}
+Point Point::add (Point other) const
+{
+    const auto &self = *this;
+    return Point{.x = self.x+other.x, .y = self.y+other.y};
+}
+int Point::dot (Point other) const
+{
+    const auto &self = *this;
+    return self.x*other.x+self.y*other.y;
+}
+int Point::len2 () const
+{
+    const auto &self = *this;
+    return self.dot(self);
+}
+int Rect::area () const
+{
+    const auto &self = *this;
+    auto size = self.max.add(Point::at(-self.min.x,-self.min.y));
+    return size.x*size.y;
+}
+int main ()
+{
+    auto p = Point::origin().add(Point::at(3,4));
+    auto r = Rect{.min = Point::at(1,1), .max = p.add(p)};
+    return p.len2()+r.area()+r.max.dot(Point::at(1,0));
+}
@@ -0,0 +1,134 @@
+#include <coroutine>
+#include <exception>
+#include <utility>
+
+template <typename T>
+class generator
+{
+public:
+    struct promise_type
+    {
+        T value{};
+        std::exception_ptr exception;
+        generator get_return_object()
+        {
+            return generator(std::coroutine_handle<promise_type>::from_promise(*this));
+        }
+        std::suspend_always initial_suspend() noexcept { return {}; }
+        std::suspend_always final_suspend() noexcept { return {}; }
+        std::suspend_always yield_value(T v)
+        {
+            value = std::move(v);
+            return {};
+        }
+        void return_void() {}
+        void unhandled_exception() { exception = std::current_exception(); }
+    };
+    struct sentinel {};
+    struct iterator
+    {
+        generator *gen;
+        iterator &operator++()
+        {
+            gen->advance();
+            return *this;
+        }
+        T &operator*() const { return gen->handle.promise().value; }
+        bool operator!=(sentinel) const { return !gen->handle.done(); }
+    };
+    explicit generator(std::coroutine_handle<promise_type> handle) : handle(handle) {}
+    generator(generator &&other) noexcept : handle(std::exchange(other.handle, {})) {}
+    generator(const generator &) = delete;
+    ~generator()
+    {
+        if (handle)
+        {
+            handle.destroy();
+        }
+    }
+    iterator begin()
+    {
+        advance();
+        return iterator{this};
+    }
+    sentinel end() { return {}; }
+
+private:
+    std::coroutine_handle<promise_type> handle;
+    void advance()
+    {
+        handle.resume();
+        if (handle.promise().exception)
+        {
+            std::rethrow_exception(handle.promise().exception);
+        }
+    }
+};
+
+#include <type_traits>
+
+template <typename R, typename F>
+auto map(R xs, F f) -> generator<std::decay_t<decltype(f(*xs.begin()))>>
+{
+    for (auto &&x : xs)
+    {
+        co_yield f(x);
+    }
+}
+
+template <typename R, typename F>
+auto filter(R xs, F p) -> generator<std::decay_t<decltype(*xs.begin())>>
+{
+    for (auto &&x : xs)
+    {
+        if (p(x))
+        {
+            co_yield x;
+        }
+    }
+}
+
+template <typename R>
+auto take(R xs, long n) -> generator<std::decay_t<decltype(*xs.begin())>>
+{
+    if (n <= 0)
+    {
+        co_return;
+    }
+    for (auto &&x : xs)
+    {
+        co_yield x;
+        if (--n <= 0)
+        {
+            co_return;
+        }
+    }
+}
+
+generator<int> naturals ()
+{
+    auto n = 1;
+    while (true)
+    {
+        co_yield n;
+        n = n+1;
+    }
+    co_return;
+}
+int square (int x)
+{
+    return x*x;
+}
+bool odd (int x)
+{
+    return x%2==1;
+}
+int main ()
+{
+    auto total = 0;
+    for(auto x:take(filter(map(naturals(),square),odd),3))
+    {
+        total = total+x;
+    }
+    return square(total);
+}
\ No newline at end of file
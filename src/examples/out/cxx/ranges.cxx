@@ -0,0 +1,71 @@
+#include <cstdint>
+int sum (int n)
+{
+    auto total = 0;
+    for (auto i = 0; i < n; ++i)
+    {
+        total = total+i;
+    }
+    for (auto i = 0; i <= n; ++i)
+    {
+        total = total+i;
+    }
+    return total;
+}
+int rounds (std::uint32_t n)
+{
+    auto count = 0;
+    for (auto i = n; i > 0;)
+    {
+        --i;
+        count = count+1;
+    }
+    for (auto i = n+2; i >= 2;)
+    {
+        i -= 2;
+        count = count+1;
+    }
+    return count;
+}
+int main ()
+{
+    auto total = sum(10);
+    for (auto i = 0; i < 10; i += 3)
+    {
+        total = total+i;
+    }
+    auto order = 0;
+    for (auto i = 4; i > 1;)
+    {
+        --i;
+        order = order*10+i;
+    }
+    auto evens = 0;
+    for (auto i = 8; i >= 2;)
+    {
+        i -= 2;
+        evens = evens*10+i;
+    }
+    auto odd = 0;
+    for (auto i = 0; i < 10; ++i)
+    {
+        if (i%2==0)
+        {
+            continue;
+        }
+        odd = odd+i;
+    }
+    {
+        auto i_next = 0;
+        for (auto x = 10; x < 20; x += 5)
+        {
+            auto i = i_next++;
+            total = total+i*x;
+        }
+    }
+    if (order!=321||evens!=6420||odd!=25||rounds(0)!=1)
+    {
+        return 1;
+    }
+    return total;
+}
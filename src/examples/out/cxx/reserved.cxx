@@ -0,0 +1,24 @@
+struct Counter
+{
+    int count;
+    static Counter new_();
+    int this_() const;
+};
+Counter Counter::new_ ()
+{
+    return Counter{.count = 1};
+}
+int Counter::this_ () const
+{
+    const auto &self = *this;
+    return self.count;
+}
+int end (int n)
+{
+    return n*3;
+}
+int main ()
+{
+    auto c = Counter::new_();
+    return end(c.this_());
+}
@@ -0,0 +1,30 @@
+int sign (int n)
+{
+    if (n>0)
+    {
+        return 1;
+    }
+    else if (n<0)
+    {
+        return 2;
+    }
+    else{
+        return 0;
+    }
+}
+int root (int above)
+{
+    auto i = 0;
+    while (true)
+    {
+        i = i+1;
+        if (i*i>above)
+        {
+            return i;
+        }
+    }
+}
+int main ()
+{
+    return sign(5)+root(10);
+}
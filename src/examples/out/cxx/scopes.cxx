@@ -0,0 +1,22 @@
+int sum (int n)
+{
+    auto total = 0;
+    for (auto x = 1; x <= n; ++x)
+    {
+        if (x%2==0)
+        {
+            auto part = x-1;
+            total = total+part;
+        }
+        else{
+            auto part = x;
+            total = total+part;
+        }
+    }
+    return total;
+}
+int main ()
+{
+    auto total = sum(4);
+    return total;
+}
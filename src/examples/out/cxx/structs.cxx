@@ -0,0 +1,36 @@
+#include <iostream>
+template <typename... Ts>
+void print(const Ts &...values)
+{
+    const char *separator = "";
+    ((std::cout << std::boolalpha << separator << values, separator = " "), ...);
+    std::cout << std::endl;
+}
+
+struct Point
+{
+    int x;
+    int y;
+};
+struct Rect
+{
+    Point min;
+    Point max;
+};
+int area (Rect r)
+{
+    return (r.max.x-r.min.x)*(r.max.y-r.min.y);
+}
+Point corner (int x, int y)
+{
+    return Point{.x = x, .y = y};
+}
+int main ()
+{
+    auto p = Point{.x = 3, .y = 4};
+    auto label = "Point{x:3}";
+    print (label );
+    auto r = Rect{.min = corner(1,1), .max = Point{.x = p.x+5, .y = p.y*2}};
+    auto moved = Rect{.min = r.min, .max = corner(r.max.x,10)};
+    return area(r)+area(moved)+corner(2,7).y;
+}
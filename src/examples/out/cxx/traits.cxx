@@ -0,0 +1,102 @@
+#include <concepts>
+#include <utility>
+template <typename T>
+concept Shape = requires(const T &self)
+{
+    { self.area() } -> std::convertible_to<int>;
+    { self.scaled(std::declval<int>()) } -> std::convertible_to<int>;
+};
+#include <memory>
+class DynShape
+{
+    struct Base
+    {
+        virtual ~Base() = default;
+        virtual int area() const = 0;
+        virtual int scaled(int k) const = 0;
+    };
+    template <typename T>
+    struct Model : Base
+    {
+        T self;
+        Model(T value) : self(value) {}
+        int area() const override
+        {
+            return self.area();
+        }
+        int scaled(int k) const override
+        {
+            return self.scaled(k);
+        }
+    };
+    std::shared_ptr<const Base> self;
+
+public:
+    template <Shape T>
+    DynShape(T value) : self(std::make_shared<Model<T>>(value)) {}
+    int area() const
+    {
+        return self->area();
+    }
+    int scaled(int k) const
+    {
+        return self->scaled(k);
+    }
+};
+struct Square
+{
+    int side;
+    int area() const;
+    int scaled(int k) const;
+};
+struct Rect
+{
+    int w;
+    int h;
+    int area() const;
+    int scaled(int k) const;
+};
+struct Framed
+{
+    DynShape inner;
+    int border;
+};
+int Square::area () const
+{
+    const auto &self = *this;
+    return self.side*self.side;
+}
+int Square::scaled (int k) const
+{
+    const auto &self = *this;
+    return self.area()*k*k;
+}
+int Rect::area () const
+{
+    const auto &self = *this;
+    return self.w*self.h;
+}
+int Rect::scaled (int k) const
+{
+    const auto &self = *this;
+    return self.w*k*self.h*k;
+}
+int total (Shape auto a, Shape auto b)
+{
+    return a.area()+b.area();
+}
+DynShape larger (DynShape a, DynShape b)
+{
+    if (a.area()>b.area())
+    {
+        return a;
+    }
+    return b;
+}
+int main ()
+{
+    auto s = Square{.side = 3};
+    auto r = Rect{.w = 2, .h = 5};
+    auto f = Framed{.inner = larger(s,r), .border = 1};
+    return total(s,r)+f.inner.scaled(2)+f.border;
+}
@@ -0,0 +1,29 @@
+#include <cstdint>
+#include <string>
+struct Pixel
+{
+    std::uint32_t level;
+    std::string name;
+};
+std::string label (Pixel p)
+{
+    return p.name;
+}
+std::int64_t area (std::int64_t w, std::int64_t h)
+{
+    return w*h;
+}
+double half (double x)
+{
+    return x/2;
+}
+int main ()
+{
+    auto p = Pixel{.level = 200, .name = "red"};
+    auto name = label(p);
+    if (p.level>100&&area(3,4)>10&&half(3.0)>1.0)
+    {
+        return 7;
+    }
+    return 0;
+}
@@ -0,0 +1,29 @@
+package main
+
+import "os"
+
+func count(start int, stop int) []int {
+	var _gen []int
+	if start >= stop {
+		return _gen
+	}
+	_gen = append(_gen, start)
+	_gen = append(_gen, count(start+1, stop)...)
+	return _gen
+}
+
+func both(n int) []int {
+	var _gen []int
+	_gen = append(_gen, count(0, n)...)
+	_gen = append(_gen, count(10, 12)...)
+	_gen = append(_gen, n*100)
+	return _gen
+}
+
+func main() {
+	total := 0
+	for _, x := range both(3) {
+		total = total + x
+	}
+	os.Exit(total)
+}
@@ -0,0 +1,26 @@
+package main
+
+import "os"
+
+func digits() []int {
+	var _gen []int
+	_gen = append(_gen, 7)
+	_gen = append(_gen, 8)
+	_gen = append(_gen, 9)
+	return _gen
+}
+
+func main() {
+	total := 0
+	for i, x := range digits() {
+		if i == 1 {
+			continue
+		}
+		total = total + i*x
+	}
+	for x, i := 8, 0; x > 5; i++ {
+		x--
+		total = total + i + x
+	}
+	os.Exit(total)
+}
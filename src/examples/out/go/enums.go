@@ -0,0 +1,61 @@
+package main
+
+import "os"
+
+type Shape interface {
+	isShape()
+}
+
+type ShapeCircle struct {
+	_0 float64
+}
+
+func (ShapeCircle) isShape() {}
+
+type ShapeRect struct {
+	_0 float64
+	_1 float64
+}
+
+func (ShapeRect) isShape() {}
+
+type ShapeEmpty struct{}
+
+func (ShapeEmpty) isShape() {}
+
+type Color int
+
+const (
+	ColorRed Color = iota
+	ColorGreen
+	ColorBlue
+)
+
+type Tile struct {
+	color Color
+	shape Shape
+}
+
+func next(c Color) Color {
+	if c == ColorRed {
+		return ColorGreen
+	} else if c == ColorGreen {
+		return ColorBlue
+	}
+	return ColorRed
+}
+
+func square(side float64) Shape {
+	return ShapeRect{side, side}
+}
+
+func main() {
+	c := next(next(ColorRed))
+	t := Tile{color: next(c), shape: ShapeEmpty{}}
+	big := Tile{color: c, shape: square(2.0)}
+	round := Tile{color: big.color, shape: ShapeCircle{1.5}}
+	if round.color == ColorBlue && t.color == ColorRed {
+		os.Exit(42)
+	}
+	os.Exit(0)
+}
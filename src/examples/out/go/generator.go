@@ -0,0 +1,20 @@
+package main
+
+import "os"
+
+func test() []int {
+	var _gen []int
+	if true {
+		_gen = append(_gen, 10)
+		_gen = append(_gen, 10*6+9)
+	} else {
+		_gen = append(_gen, 9+10)
+	}
+	return _gen
+}
+
+func main() {
+	temp := test()
+	_ = temp
+	os.Exit(0)
+}
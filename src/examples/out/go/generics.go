@@ -0,0 +1,57 @@
+package main
+
+import "os"
+
+type Sized interface {
+	size() int
+}
+
+type Box struct {
+	w int
+}
+
+type Pair_int struct {
+	first  int
+	second int
+}
+
+func (self Box) size() int {
+	return self.w
+}
+
+func larger_int(a int, b int) int {
+	if a > b {
+		return a
+	}
+	return b
+}
+
+func larger_f64(a float64, b float64) float64 {
+	if a > b {
+		return a
+	}
+	return b
+}
+
+func pair_int(a int, b int) Pair_int {
+	return Pair_int{first: a, second: b}
+}
+
+func swap_int(p Pair_int) Pair_int {
+	return Pair_int{first: p.second, second: p.first}
+}
+
+func biggest_Box(a Box, b Box) int {
+	return larger_int(a.size(), b.size())
+}
+
+func main() {
+	p := swap_int(pair_int(3, 4))
+	small := Box{w: 2}
+	big := Box{w: 5}
+	total := larger_int(p.first, p.second) + biggest_Box(small, big)
+	if larger_f64(1.5, 0.5) > 1.0 {
+		os.Exit(total)
+	}
+	os.Exit(0)
+}
@@ -0,0 +1,11 @@
+package main
+
+import "os"
+
+const (
+	test = 69
+)
+
+func main() {
+	os.Exit(0)
+}
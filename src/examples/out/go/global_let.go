@@ -0,0 +1,11 @@
+package main
+
+import "os"
+
+var (
+	test = 69
+)
+
+func main() {
+	os.Exit(0)
+}
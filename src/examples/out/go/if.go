@@ -0,0 +1,11 @@
+package main
+
+import "os"
+
+func main() {
+	test := true
+	if test {
+		test = false
+	}
+	os.Exit(0)
+}
@@ -0,0 +1,29 @@
+package main
+
+import "os"
+
+const (
+	SIDE = 4
+)
+
+var (
+	AREA  = area(SIDE, 3)
+	ratio = scale(1.5, 2.0)
+)
+
+func area(w int64, h int64) int64 {
+	return w * h
+}
+
+func scale(x float64, by float64) float64 {
+	return x * by
+}
+
+func main() {
+	total := AREA + area(2, 2)
+	half := ratio / 2
+	if total > 10 && half < 4.0 {
+		os.Exit(3)
+	}
+	os.Exit(0)
+}
@@ -0,0 +1,9 @@
+package main
+
+import "os"
+
+func main() {
+	test := 10
+	_ = test
+	os.Exit(0)
+}
@@ -0,0 +1,8 @@
+package main
+
+func main() {
+	for {
+		test := 10
+		_ = test
+	}
+}
@@ -0,0 +1,40 @@
+package main
+
+import (
+	"os"
+	"time"
+)
+
+var (
+	ticks = 0
+)
+
+func count(limit int) int {
+	n := 0
+	total := 0
+	for {
+		n = n + 1
+		if n > limit {
+			return total
+		}
+		if n%2 == 1 {
+			continue
+		}
+		total = total + n
+	}
+}
+
+func tick() {
+	for {
+		time.Sleep(1 * time.Millisecond)
+		ticks = ticks + 1
+		if ticks == 3 {
+			break
+		}
+	}
+}
+
+func main() {
+	tick()
+	os.Exit(count(10) + ticks)
+}
@@ -0,0 +1,69 @@
+package main
+
+import "os"
+
+var (
+	seen = 0
+)
+
+func pairs() int {
+	found := 0
+	a := 0
+outer:
+	for a < 5 {
+		a = a + 1
+		b := 0
+		for b < 3 {
+			b = b + 1
+			if b > a {
+				continue outer
+			}
+			if a*b > 12 {
+				break outer
+			}
+			found = found + 1
+		}
+		row := a
+		seen = seen + row
+	}
+	return found
+}
+
+func collatz(n int) int {
+	steps := 0
+	for n != 1 {
+		if n%2 == 0 {
+			n = n / 2
+		} else {
+			n = 3*n + 1
+		}
+		steps = steps + 1
+	}
+	return steps
+}
+
+func grid() int {
+	cells := 0
+	row := 0
+rows:
+	for {
+		row = row + 1
+		col := 0
+		for {
+			col = col + 1
+			if col > row {
+				break
+			}
+			if row == 4 {
+				break rows
+			}
+			cells = cells + 1
+		}
+	}
+	return cells
+}
+
+func main() {
+	found := pairs()
+	os.Exit(found*10 + seen + collatz(6) + grid())
+}
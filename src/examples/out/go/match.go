@@ -0,0 +1,95 @@
+package main
+
+import "os"
+
+type Shape interface {
+	isShape()
+}
+
+type ShapeCircle struct {
+	_0 float64
+}
+
+func (ShapeCircle) isShape() {}
+
+type ShapeRect struct {
+	_0 float64
+	_1 float64
+}
+
+func (ShapeRect) isShape() {}
+
+type ShapeEmpty struct{}
+
+func (ShapeEmpty) isShape() {}
+
+type Color int
+
+const (
+	ColorRed Color = iota
+	ColorGreen
+	ColorBlue
+)
+
+func area(s Shape) float64 {
+	return func() float64 {
+		switch _m := s.(type) {
+		case ShapeCircle:
+			r := _m._0
+			return 3.0 * r * r
+		case ShapeRect:
+			w := _m._0
+			h := _m._1
+			return w * h
+		default:
+			return 0.0
+		}
+	}()
+}
+
+func digits(n int) int {
+	count := func() int {
+		switch _m := n; {
+		case _m >= 0 && _m <= 9:
+			return 1
+		case _m >= 10 && _m <= 99:
+			return 2
+		default:
+			return 3
+		}
+	}()
+	return count
+}
+
+func code(c Color) int {
+	return func() int {
+		switch _m := c; {
+		case _m == ColorRed:
+			return 1
+		case _m == ColorGreen:
+			return 2
+		default:
+			return 3
+		}
+	}()
+}
+
+func bit(on bool) int {
+	return func() int {
+		switch _m := on; {
+		case _m:
+			return 1
+		default:
+			return 0
+		}
+	}()
+}
+
+func main() {
+	total := area(ShapeRect{2.0, 3.0}) + area(ShapeEmpty{})
+	if total != 6.0 {
+		os.Exit(1)
+	}
+	sizes := digits(7) + digits(42) + digits(512)
+	os.Exit(sizes*5 + code(ColorBlue)*4 + bit(false))
+}
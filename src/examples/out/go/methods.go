@@ -0,0 +1,44 @@
+package main
+
+import "os"
+
+type Point struct {
+	x int
+	y int
+}
+
+type Rect struct {
+	min Point
+	max Point
+}
+
+func PointAt(x int, y int) Point {
+	return Point{x: x, y: y}
+}
+
+func PointOrigin() Point {
+	return PointAt(0, 0)
+}
+
+func (self Point) add(other Point) Point {
+	return Point{x: self.x + other.x, y: self.y + other.y}
+}
+
+func (self Point) dot(other Point) int {
+	return self.x*other.x + self.y*other.y
+}
+
+func (self Point) len2() int {
+	return self.dot(self)
+}
+
+func (self Rect) area() int {
+	size := self.max.add(PointAt(-self.min.x, -self.min.y))
+	return size.x * size.y
+}
+
+func main() {
+	p := PointOrigin().add(PointAt(3, 4))
+	r := Rect{min: PointAt(1, 1), max: p.add(p)}
+	os.Exit(p.len2() + r.area() + r.max.dot(PointAt(1, 0)))
+}
@@ -0,0 +1,58 @@
+package main
+
+import "os"
+
+func sum(n int) int {
+	total := 0
+	for i := 0; i < n; i++ {
+		total = total + i
+	}
+	for i := 0; i <= n; i++ {
+		total = total + i
+	}
+	return total
+}
+
+func rounds(n uint32) int {
+	count := 0
+	for i := n; i > 0; {
+		i--
+		count = count + 1
+	}
+	for i := n + 2; i >= 2; {
+		i -= 2
+		count = count + 1
+	}
+	return count
+}
+
+func main() {
+	total := sum(10)
+	for i := 0; i < 10; i += 3 {
+		total = total + i
+	}
+	order := 0
+	for i := 4; i > 1; {
+		i--
+		order = order*10 + i
+	}
+	evens := 0
+	for i := 8; i >= 2; {
+		i -= 2
+		evens = evens*10 + i
+	}
+	odd := 0
+	for i := 0; i < 10; i++ {
+		if i%2 == 0 {
+			continue
+		}
+		odd = odd + i
+	}
+	for x, i := 10, 0; x < 20; x, i = x+5, i+1 {
+		total = total + i*x
+	}
+	if order != 321 || evens != 6420 || odd != 25 || rounds(0) != 1 {
+		os.Exit(1)
+	}
+	os.Exit(total)
+}
@@ -0,0 +1,24 @@
+package main
+
+import "os"
+
+type Counter struct {
+	count int
+}
+
+func CounterNew() Counter {
+	return Counter{count: 1}
+}
+
+func (self Counter) this() int {
+	return self.count
+}
+
+func end(n int) int {
+	return n * 3
+}
+
+func main() {
+	c := CounterNew()
+	os.Exit(end(c.this()))
+}
@@ -0,0 +1,27 @@
+package main
+
+import "os"
+
+func sign(n int) int {
+	if n > 0 {
+		return 1
+	} else if n < 0 {
+		return 2
+	} else {
+		return 0
+	}
+}
+
+func root(above int) int {
+	i := 0
+	for {
+		i = i + 1
+		if i*i > above {
+			return i
+		}
+	}
+}
+
+func main() {
+	os.Exit(sign(5) + root(10))
+}
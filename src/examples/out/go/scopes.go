@@ -0,0 +1,22 @@
+package main
+
+import "os"
+
+func sum(n int) int {
+	total := 0
+	for x := 1; x <= n; x++ {
+		if x%2 == 0 {
+			part := x - 1
+			total = total + part
+		} else {
+			part := x
+			total = total + part
+		}
+	}
+	return total
+}
+
+func main() {
+	total := sum(4)
+	os.Exit(total)
+}
@@ -0,0 +1,30 @@
+package main
+
+import "os"
+
+type Point struct {
+	x int
+	y int
+}
+
+type Rect struct {
+	min Point
+	max Point
+}
+
+func area(r Rect) int {
+	return (r.max.x - r.min.x) * (r.max.y - r.min.y)
+}
+
+func corner(x int, y int) Point {
+	return Point{x: x, y: y}
+}
+
+func main() {
+	p := Point{x: 3, y: 4}
+	label := "Point{x:3}"
+	print(label)
+	r := Rect{min: corner(1, 1), max: Point{x: p.x + 5, y: p.y * 2}}
+	moved := Rect{min: r.min, max: corner(r.max.x, 10)}
+	os.Exit(area(r) + area(moved) + corner(2, 7).y)
+}
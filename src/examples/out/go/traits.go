@@ -0,0 +1,56 @@
+package main
+
+import "os"
+
+type Shape interface {
+	area() int
+	scaled(k int) int
+}
+
+type Square struct {
+	side int
+}
+
+type Rect struct {
+	w int
+	h int
+}
+
+type Framed struct {
+	inner  Shape
+	border int
+}
+
+func (self Square) area() int {
+	return self.side * self.side
+}
+
+func (self Square) scaled(k int) int {
+	return self.area() * k * k
+}
+
+func (self Rect) area() int {
+	return self.w * self.h
+}
+
+func (self Rect) scaled(k int) int {
+	return self.w * k * self.h * k
+}
+
+func total(a Shape, b Shape) int {
+	return a.area() + b.area()
+}
+
+func larger(a Shape, b Shape) Shape {
+	if a.area() > b.area() {
+		return a
+	}
+	return b
+}
+
+func main() {
+	s := Square{side: 3}
+	r := Rect{w: 2, h: 5}
+	f := Framed{inner: larger(s, r), border: 1}
+	os.Exit(total(s, r) + f.inner.scaled(2) + f.border)
+}
@@ -0,0 +1,30 @@
+package main
+
+import "os"
+
+type Pixel struct {
+	level uint32
+	name  string
+}
+
+func label(p Pixel) string {
+	return p.name
+}
+
+func area(w int64, h int64) int64 {
+	return w * h
+}
+
+func half(x float64) float64 {
+	return x / 2
+}
+
+func main() {
+	p := Pixel{level: 200, name: "red"}
+	name := label(p)
+	_ = name
+	if p.level > 100 && area(3, 4) > 10 && half(3.0) > 1.0 {
+		os.Exit(7)
+	}
+	os.Exit(0)
+}
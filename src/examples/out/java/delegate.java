@@ -0,0 +1,36 @@
+import java.util.List;
+import java.util.ArrayList;
+
+public class Main {
+    static List<Integer> count(int start, int stop) {
+        List<Integer> _gen = new ArrayList<>();
+        if (start>=stop) {
+            return _gen;
+        }
+        _gen.add(start);
+        for (var _v : count(start+1,stop)) {
+            _gen.add(_v);
+        }
+        return _gen;
+    }
+
+    static List<Integer> both(int n) {
+        List<Integer> _gen = new ArrayList<>();
+        for (var _v : count(0,n)) {
+            _gen.add(_v);
+        }
+        for (var _v : count(10,12)) {
+            _gen.add(_v);
+        }
+        _gen.add(n*100);
+        return _gen;
+    }
+
+    public static void main(String[] args) {
+        var total = 0;
+        for (var x : both(3)) {
+            total = total+x;
+        }
+        System.exit(total);
+    }
+}
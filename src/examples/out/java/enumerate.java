@@ -0,0 +1,35 @@
+import java.util.List;
+import java.util.ArrayList;
+
+public class Main {
+    static List<Integer> digits() {
+        List<Integer> _gen = new ArrayList<>();
+        _gen.add(7);
+        _gen.add(8);
+        _gen.add(9);
+        return _gen;
+    }
+
+    public static void main(String[] args) {
+        var total = 0;
+        {
+            var i_next = 0;
+            for (var x : digits()) {
+                var i = i_next++;
+                if (i==1) {
+                    continue;
+                }
+                total = total+i*x;
+            }
+        }
+        {
+            var i_next = 0;
+            for (var x = 8; x > 5;) {
+                x--;
+                var i = i_next++;
+                total = total+i+x;
+            }
+        }
+        System.exit(total);
+    }
+}
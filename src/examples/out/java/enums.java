@@ -0,0 +1,45 @@
+public class Main {
+    sealed interface Shape {
+        record Circle(double _0) implements Shape {}
+        record Rect(double _0, double _1) implements Shape {}
+        record Empty() implements Shape {}
+    }
+
+    enum Color {
+        Red, Green, Blue
+    }
+
+    static class Tile {
+        Color color;
+        Shape shape;
+
+        Tile(Color color, Shape shape) {
+            this.color = color;
+            this.shape = shape;
+        }
+    }
+
+    static Color next(Color c) {
+        if (c==Color.Red) {
+            return Color.Green;
+        } else if (c==Color.Green) {
+            return Color.Blue;
+        }
+        return Color.Red;
+    }
+
+    static Shape square(double side) {
+        return new Shape.Rect(side,side);
+    }
+
+    public static void main(String[] args) {
+        var c = next(next(Color.Red));
+        var t = new Tile(next(c), new Shape.Empty());
+        var big = new Tile(c, square(2.0));
+        var round = new Tile(big.color, new Shape.Circle(1.5));
+        if (round.color==Color.Blue&&t.color==Color.Red) {
+            System.exit(42);
+        }
+        System.exit(0);
+    }
+}
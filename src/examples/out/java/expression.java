@@ -0,0 +1,6 @@
+public class Main {
+    public static void main(String[] args) {
+        var test = 10*9;
+        System.exit(0);
+    }
+}
@@ -0,0 +1,20 @@
+import java.util.List;
+import java.util.ArrayList;
+
+public class Main {
+    static List<Integer> test() {
+        List<Integer> _gen = new ArrayList<>();
+        if (true) {
+            _gen.add(10);
+            _gen.add(10*6+9);
+        } else {
+            _gen.add(9+10);
+        }
+        return _gen;
+    }
+
+    public static void main(String[] args) {
+        var temp = test();
+        System.exit(0);
+    }
+}
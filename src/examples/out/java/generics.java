@@ -0,0 +1,65 @@
+public class Main {
+    interface Sized {
+        int size();
+    }
+
+    static class Box implements Sized {
+        int w;
+
+        Box(int w) {
+            this.w = w;
+        }
+
+        public int size() {
+            Box self = this;
+            return self.w;
+        }
+    }
+
+    static class Pair_int {
+        int first;
+        int second;
+
+        Pair_int(int first, int second) {
+            this.first = first;
+            this.second = second;
+        }
+    }
+
+    static int larger_int(int a, int b) {
+        if (a>b) {
+            return a;
+        }
+        return b;
+    }
+
+    static double larger_f64(double a, double b) {
+        if (a>b) {
+            return a;
+        }
+        return b;
+    }
+
+    static Pair_int pair_int(int a, int b) {
+        return new Pair_int(a, b);
+    }
+
+    static Pair_int swap_int(Pair_int p) {
+        return new Pair_int(p.second, p.first);
+    }
+
+    static int biggest_Box(Box a, Box b) {
+        return larger_int(a.size(),b.size());
+    }
+
+    public static void main(String[] args) {
+        var p = swap_int(pair_int(3,4));
+        var small = new Box(2);
+        var big = new Box(5);
+        var total = larger_int(p.first,p.second)+biggest_Box(small,big);
+        if (larger_f64(1.5,0.5)>1.0) {
+            System.exit(total);
+        }
+        System.exit(0);
+    }
+}
@@ -0,0 +1,7 @@
+public class Main {
+    static final int test = 69;
+
+    public static void main(String[] args) {
+        System.exit(0);
+    }
+}
@@ -0,0 +1,9 @@
+public class Main {
+    public static void main(String[] args) {
+        var test = true;
+        if (test) {
+            test = false;
+        }
+        System.exit(0);
+    }
+}
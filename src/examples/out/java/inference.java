@@ -0,0 +1,22 @@
+public class Main {
+    static final int SIDE = 4;
+    static final long AREA = area(SIDE,3);
+    static double ratio = scale(1.5,2.0);
+
+    static long area(long w, long h) {
+        return w*h;
+    }
+
+    static double scale(double x, double by) {
+        return x*by;
+    }
+
+    public static void main(String[] args) {
+        var total = AREA+area(2,2);
+        var half = ratio/2;
+        if (total>10&&half<4.0) {
+            System.exit(3);
+        }
+        System.exit(0);
+    }
+}
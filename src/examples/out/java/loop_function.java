@@ -0,0 +1,7 @@
+public class Main {
+    public static void main(String[] args) {
+        while (true) {
+            var test = 10;
+        }
+    }
+}
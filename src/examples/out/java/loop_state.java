@@ -0,0 +1,37 @@
+public class Main {
+    static int ticks = 0;
+
+    static int count(int limit) {
+        var n = 0;
+        var total = 0;
+        while (true) {
+            n = n+1;
+            if (n>limit) {
+                return total;
+            }
+            if (n%2==1) {
+                continue;
+            }
+            total = total+n;
+        }
+    }
+
+    static void tick() {
+        while (true) {
+            try {
+                Thread.sleep(1);
+            } catch (InterruptedException e) {
+                throw new RuntimeException(e);
+            }
+            ticks = ticks+1;
+            if (ticks==3) {
+                break;
+            }
+        }
+    }
+
+    public static void main(String[] args) {
+        tick();
+        System.exit(count(10)+ticks);
+    }
+}
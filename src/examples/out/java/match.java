@@ -0,0 +1,37 @@
+public class Main {
+    sealed interface Shape {
+        record Circle(double _0) implements Shape {}
+        record Rect(double _0, double _1) implements Shape {}
+        record Empty() implements Shape {}
+    }
+
+    enum Color {
+        Red, Green, Blue
+    }
+
+    static double area(Shape s) {
+        return (s instanceof Shape.Circle _m ? 3.0*_m._0()*_m._0() : s instanceof Shape.Rect _m ? _m._0()*_m._1() : 0.0);
+    }
+
+    static int digits(int n) {
+        var count = (n>=0&&n<=9 ? 1 : n>=10&&n<=99 ? 2 : 3);
+        return count;
+    }
+
+    static int code(Color c) {
+        return (c==Color.Red ? 1 : c==Color.Green ? 2 : 3);
+    }
+
+    static int bit(boolean on) {
+        return (on ? 1 : 0);
+    }
+
+    public static void main(String[] args) {
+        var total = area(new Shape.Rect(2.0,3.0))+area(new Shape.Empty());
+        if (total!=6.0) {
+            System.exit(1);
+        }
+        var sizes = digits(7)+digits(42)+digits(512);
+        System.exit(sizes*5+code(Color.Blue)*4+bit(false));
+    }
+}
@@ -0,0 +1,56 @@
+public class Main {
+    static class Point {
+        int x;
+        int y;
+
+        Point(int x, int y) {
+            this.x = x;
+            this.y = y;
+        }
+
+        static Point at(int x, int y) {
+            return new Point(x, y);
+        }
+
+        static Point origin() {
+            return Point.at(0,0);
+        }
+
+        Point add(Point other) {
+            Point self = this;
+            return new Point(self.x+other.x, self.y+other.y);
+        }
+
+        int dot(Point other) {
+            Point self = this;
+            return self.x*other.x+self.y*other.y;
+        }
+
+        int len2() {
+            Point self = this;
+            return self.dot(self);
+        }
+    }
+
+    static class Rect {
+        Point min;
+        Point max;
+
+        Rect(Point min, Point max) {
+            this.min = min;
+            this.max = max;
+        }
+
+        int area() {
+            Rect self = this;
+            var size = self.max.add(Point.at(-self.min.x,-self.min.y));
+            return size.x*size.y;
+        }
+    }
+
+    public static void main(String[] args) {
+        var p = Point.origin().add(Point.at(3,4));
+        var r = new Rect(Point.at(1,1), p.add(p));
+        System.exit(p.len2()+r.area()+r.max.dot(Point.at(1,0)));
+    }
+}
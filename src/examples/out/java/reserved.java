@@ -0,0 +1,27 @@
+public class Main {
+    static class Counter {
+        int count;
+
+        Counter(int count) {
+            this.count = count;
+        }
+
+        static Counter new_() {
+            return new Counter(1);
+        }
+
+        int this_() {
+            Counter self = this;
+            return self.count;
+        }
+    }
+
+    static int end(int n) {
+        return n*3;
+    }
+
+    public static void main(String[] args) {
+        var c = Counter.new_();
+        System.exit(end(c.this_()));
+    }
+}
@@ -0,0 +1,25 @@
+public class Main {
+    static int sign(int n) {
+        if (n>0) {
+            return 1;
+        } else if (n<0) {
+            return 2;
+        } else {
+            return 0;
+        }
+    }
+
+    static int root(int above) {
+        var i = 0;
+        while (true) {
+            i = i+1;
+            if (i*i>above) {
+                return i;
+            }
+        }
+    }
+
+    public static void main(String[] args) {
+        System.exit(sign(5)+root(10));
+    }
+}
@@ -0,0 +1,48 @@
+import java.util.StringJoiner;
+
+public class Main {
+    static class Point {
+        int x;
+        int y;
+
+        Point(int x, int y) {
+            this.x = x;
+            this.y = y;
+        }
+    }
+
+    static class Rect {
+        Point min;
+        Point max;
+
+        Rect(Point min, Point max) {
+            this.min = min;
+            this.max = max;
+        }
+    }
+
+    static void print(Object... values) {
+        var line = new StringJoiner(" ");
+        for (var value : values) {
+            line.add(String.valueOf(value));
+        }
+        System.out.println(line);
+    }
+
+    static int area(Rect r) {
+        return (r.max.x-r.min.x)*(r.max.y-r.min.y);
+    }
+
+    static Point corner(int x, int y) {
+        return new Point(x, y);
+    }
+
+    public static void main(String[] args) {
+        var p = new Point(3, 4);
+        var label = "Point{x:3}";
+        print(label);
+        var r = new Rect(corner(1,1), new Point(p.x+5, p.y*2));
+        var moved = new Rect(r.min, corner(r.max.x,10));
+        System.exit(area(r)+area(moved)+corner(2,7).y);
+    }
+}
@@ -0,0 +1,72 @@
+public class Main {
+    interface Shape {
+        int area();
+        int scaled(int k);
+    }
+
+    static class Square implements Shape {
+        int side;
+
+        Square(int side) {
+            this.side = side;
+        }
+
+        public int area() {
+            Square self = this;
+            return self.side*self.side;
+        }
+
+        public int scaled(int k) {
+            Square self = this;
+            return self.area()*k*k;
+        }
+    }
+
+    static class Rect implements Shape {
+        int w;
+        int h;
+
+        Rect(int w, int h) {
+            this.w = w;
+            this.h = h;
+        }
+
+        public int area() {
+            Rect self = this;
+            return self.w*self.h;
+        }
+
+        public int scaled(int k) {
+            Rect self = this;
+            return self.w*k*self.h*k;
+        }
+    }
+
+    static class Framed {
+        Shape inner;
+        int border;
+
+        Framed(Shape inner, int border) {
+            this.inner = inner;
+            this.border = border;
+        }
+    }
+
+    static int total(Shape a, Shape b) {
+        return a.area()+b.area();
+    }
+
+    static Shape larger(Shape a, Shape b) {
+        if (a.area()>b.area()) {
+            return a;
+        }
+        return b;
+    }
+
+    public static void main(String[] args) {
+        var s = new Square(3);
+        var r = new Rect(2, 5);
+        var f = new Framed(larger(s,r), 1);
+        System.exit(total(s,r)+f.inner.scaled(2)+f.border);
+    }
+}
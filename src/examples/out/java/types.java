@@ -0,0 +1,32 @@
+public class Main {
+    static class Pixel {
+        long level;
+        String name;
+
+        Pixel(long level, String name) {
+            this.level = level;
+            this.name = name;
+        }
+    }
+
+    static String label(Pixel p) {
+        return p.name;
+    }
+
+    static long area(long w, long h) {
+        return w*h;
+    }
+
+    static double half(double x) {
+        return x/2;
+    }
+
+    public static void main(String[] args) {
+        var p = new Pixel(200, "red");
+        var name = label(p);
+        if (p.level>100&&area(3,4)>10&&half(3.0)>1.0) {
+            System.exit(7);
+        }
+        System.exit(0);
+    }
+}
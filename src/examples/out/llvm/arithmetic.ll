@@ -0,0 +1,76 @@
+@limit = constant i32 100
+@counter = global i32 0
+
+declare void @print(i32)
+
+define i32 @add(i32 %a, i32 %b) {
+entry:
+  %a.addr = alloca i32
+  %b.addr = alloca i32
+  store i32 %a, ptr %a.addr
+  store i32 %b, ptr %b.addr
+  %0 = load i32, ptr %a.addr
+  %1 = load i32, ptr %b.addr
+  %2 = add i32 %0, %1
+  ret i32 %2
+}
+
+define double @average(double %a, i32 %b) {
+entry:
+  %a.addr = alloca double
+  %b.addr = alloca i32
+  store double %a, ptr %a.addr
+  store i32 %b, ptr %b.addr
+  %0 = load double, ptr %a.addr
+  %1 = load i32, ptr %b.addr
+  %2 = sitofp i32 %1 to double
+  %3 = fadd double %0, %2
+  %4 = fdiv double %3, 0x4000000000000000
+  ret double %4
+}
+
+define i32 @main() {
+entry:
+  %x.addr = alloca i32
+  %0 = call i32 @add(i32 1, i32 2)
+  %1 = mul i32 %0, 3
+  store i32 %1, ptr %x.addr
+  %2 = load i32, ptr %x.addr
+  %3 = load i32, ptr @limit
+  %4 = icmp sgt i32 %2, %3
+  br i1 %4, label %if.then0, label %if.else0
+if.then0:
+  store i32 1, ptr @counter
+  br label %if.end0
+if.else0:
+  %5 = load i32, ptr %x.addr
+  %6 = icmp eq i32 %5, 9
+  br i1 %6, label %and.rhs2, label %and.end2
+and.rhs2:
+  %7 = load i32, ptr %x.addr
+  %8 = icmp ne i32 %7, 10
+  br label %and.end2
+and.end2:
+  %9 = phi i1 [ false, %if.else0 ], [ %8, %and.rhs2 ]
+  br i1 %9, label %if.then1, label %if.else1
+if.then1:
+  store i32 2, ptr @counter
+  br label %if.end1
+if.else1:
+  store i32 -3, ptr @counter
+  br label %if.end1
+if.end1:
+  br label %if.end0
+if.end0:
+  %10 = load i32, ptr %x.addr
+  call void @print(i32 %10)
+  br label %loop3
+loop3:
+  %11 = load i32, ptr %x.addr
+  %12 = sub i32 %11, 1
+  store i32 %12, ptr %x.addr
+  br label %loop3
+loop.end3:
+  %13 = load i32, ptr @counter
+  ret i32 %13
+}
@@ -0,0 +1,76 @@
+@.str.0 = private unnamed_addr constant [12 x i8] c"hello world\00"
+
+declare void @puts(ptr)
+
+define i32 @sum() {
+entry:
+  %total.addr = alloca i32
+  %array.addr = alloca [3 x i32]
+  %x.index.addr = alloca i64
+  %x.addr = alloca i32
+  store i32 0, ptr %total.addr
+  %0 = getelementptr inbounds [3 x i32], ptr %array.addr, i64 0, i64 0
+  store i32 1, ptr %0
+  %1 = getelementptr inbounds [3 x i32], ptr %array.addr, i64 0, i64 1
+  store i32 2, ptr %1
+  %2 = getelementptr inbounds [3 x i32], ptr %array.addr, i64 0, i64 2
+  store i32 3, ptr %2
+  store i64 0, ptr %x.index.addr
+  br label %for.cond0
+for.cond0:
+  %3 = load i64, ptr %x.index.addr
+  %4 = icmp slt i64 %3, 3
+  br i1 %4, label %for.body0, label %for.end0
+for.body0:
+  %5 = getelementptr inbounds [3 x i32], ptr %array.addr, i64 0, i64 %3
+  %6 = load i32, ptr %5
+  store i32 %6, ptr %x.addr
+  %7 = load i32, ptr %total.addr
+  %8 = load i32, ptr %x.addr
+  %9 = add i32 %7, %8
+  store i32 %9, ptr %total.addr
+  br label %for.inc0
+for.inc0:
+  %10 = load i64, ptr %x.index.addr
+  %11 = add i64 %10, 1
+  store i64 %11, ptr %x.index.addr
+  br label %for.cond0
+for.end0:
+  %12 = load i32, ptr %total.addr
+  ret i32 %12
+}
+
+define i32 @main() {
+entry:
+  %array.addr = alloca [3 x double]
+  %half.addr = alloca double
+  %0 = getelementptr inbounds [3 x double], ptr %array.addr, i64 0, i64 0
+  store double 0x4004000000000000, ptr %0
+  %1 = getelementptr inbounds [3 x double], ptr %array.addr, i64 0, i64 1
+  store double 0x4010000000000000, ptr %1
+  %2 = getelementptr inbounds [3 x double], ptr %array.addr, i64 0, i64 2
+  store double 0x4020000000000000, ptr %2
+  %3 = getelementptr inbounds [3 x double], ptr %array.addr, i64 0, i64 1
+  %4 = load double, ptr %3
+  %5 = fdiv double %4, 0x4000000000000000
+  store double %5, ptr %half.addr
+  call void @puts(ptr @.str.0)
+  %6 = load double, ptr %half.addr
+  %7 = fcmp ogt double %6, 0x3FF8000000000000
+  br i1 %7, label %or.end1, label %or.rhs1
+or.rhs1:
+  %8 = getelementptr inbounds [3 x double], ptr %array.addr, i64 0, i64 0
+  %9 = load double, ptr %8
+  %10 = fcmp oeq double %9, 0x4004000000000000
+  br label %or.end1
+or.end1:
+  %11 = phi i1 [ true, %entry ], [ %10, %or.rhs1 ]
+  br i1 %11, label %if.then0, label %if.end0
+if.then0:
+  %12 = call i32 @sum()
+  %13 = add i32 %12, 1
+  ret i32 %13
+if.end0:
+  %14 = call i32 @sum()
+  ret i32 %14
+}
@@ -0,0 +1,7 @@
+define i32 @main() {
+entry:
+  %test.addr = alloca i32
+  %0 = mul i32 10, 9
+  store i32 %0, ptr %test.addr
+  ret i32 0
+}
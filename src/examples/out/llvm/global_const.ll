@@ -0,0 +1,6 @@
+@test = constant i32 69
+
+define i32 @main() {
+entry:
+  ret i32 0
+}
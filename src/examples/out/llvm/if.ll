@@ -0,0 +1,12 @@
+define i32 @main() {
+entry:
+  %test.addr = alloca i1
+  store i1 true, ptr %test.addr
+  %0 = load i1, ptr %test.addr
+  br i1 %0, label %if.then0, label %if.end0
+if.then0:
+  store i1 false, ptr %test.addr
+  br label %if.end0
+if.end0:
+  ret i32 0
+}
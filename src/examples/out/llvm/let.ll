@@ -0,0 +1,6 @@
+define i32 @main() {
+entry:
+  %test.addr = alloca i32
+  store i32 10, ptr %test.addr
+  ret i32 0
+}
@@ -0,0 +1,8 @@
+define i32 @main() {
+entry:
+  %test.addr = alloca i32
+  br label %tick
+tick:
+  store i32 10, ptr %test.addr
+  br label %tick
+}
@@ -0,0 +1,155 @@
+@seen = global i32 0
+
+define i32 @pairs() {
+entry:
+  %found.addr = alloca i32
+  %a.addr = alloca i32
+  %b.addr = alloca i32
+  %row.addr = alloca i32
+  store i32 0, ptr %found.addr
+  store i32 0, ptr %a.addr
+  br label %while.cond0
+while.cond0:
+  %0 = load i32, ptr %a.addr
+  %1 = icmp slt i32 %0, 5
+  br i1 %1, label %while.body0, label %while.end0
+while.body0:
+  %2 = load i32, ptr %a.addr
+  %3 = add i32 %2, 1
+  store i32 %3, ptr %a.addr
+  store i32 0, ptr %b.addr
+  br label %while.cond1
+while.cond1:
+  %4 = load i32, ptr %b.addr
+  %5 = icmp slt i32 %4, 3
+  br i1 %5, label %while.body1, label %while.end1
+while.body1:
+  %6 = load i32, ptr %b.addr
+  %7 = add i32 %6, 1
+  store i32 %7, ptr %b.addr
+  %8 = load i32, ptr %b.addr
+  %9 = load i32, ptr %a.addr
+  %10 = icmp sgt i32 %8, %9
+  br i1 %10, label %if.then2, label %if.end2
+if.then2:
+  br label %while.cond0
+if.end2:
+  %11 = load i32, ptr %a.addr
+  %12 = load i32, ptr %b.addr
+  %13 = mul i32 %11, %12
+  %14 = icmp sgt i32 %13, 12
+  br i1 %14, label %if.then3, label %if.end3
+if.then3:
+  br label %while.end0
+if.end3:
+  %15 = load i32, ptr %found.addr
+  %16 = add i32 %15, 1
+  store i32 %16, ptr %found.addr
+  br label %while.cond1
+while.end1:
+  %17 = load i32, ptr %a.addr
+  store i32 %17, ptr %row.addr
+  %18 = load i32, ptr @seen
+  %19 = load i32, ptr %row.addr
+  %20 = add i32 %18, %19
+  store i32 %20, ptr @seen
+  br label %while.cond0
+while.end0:
+  %21 = load i32, ptr %found.addr
+  ret i32 %21
+}
+
+define i32 @collatz(i32 %n) {
+entry:
+  %n.addr = alloca i32
+  %steps.addr = alloca i32
+  store i32 %n, ptr %n.addr
+  store i32 0, ptr %steps.addr
+  br label %while.cond0
+while.cond0:
+  %0 = load i32, ptr %n.addr
+  %1 = icmp ne i32 %0, 1
+  br i1 %1, label %while.body0, label %while.end0
+while.body0:
+  %2 = load i32, ptr %n.addr
+  %3 = srem i32 %2, 2
+  %4 = icmp eq i32 %3, 0
+  br i1 %4, label %if.then1, label %if.else1
+if.then1:
+  %5 = load i32, ptr %n.addr
+  %6 = sdiv i32 %5, 2
+  store i32 %6, ptr %n.addr
+  br label %if.end1
+if.else1:
+  %7 = load i32, ptr %n.addr
+  %8 = mul i32 3, %7
+  %9 = add i32 %8, 1
+  store i32 %9, ptr %n.addr
+  br label %if.end1
+if.end1:
+  %10 = load i32, ptr %steps.addr
+  %11 = add i32 %10, 1
+  store i32 %11, ptr %steps.addr
+  br label %while.cond0
+while.end0:
+  %12 = load i32, ptr %steps.addr
+  ret i32 %12
+}
+
+define i32 @grid() {
+entry:
+  %cells.addr = alloca i32
+  %row.addr = alloca i32
+  %col.addr = alloca i32
+  store i32 0, ptr %cells.addr
+  store i32 0, ptr %row.addr
+  br label %loop0
+loop0:
+  %0 = load i32, ptr %row.addr
+  %1 = add i32 %0, 1
+  store i32 %1, ptr %row.addr
+  store i32 0, ptr %col.addr
+  br label %loop1
+loop1:
+  %2 = load i32, ptr %col.addr
+  %3 = add i32 %2, 1
+  store i32 %3, ptr %col.addr
+  %4 = load i32, ptr %col.addr
+  %5 = load i32, ptr %row.addr
+  %6 = icmp sgt i32 %4, %5
+  br i1 %6, label %if.then2, label %if.end2
+if.then2:
+  br label %loop.end1
+if.end2:
+  %7 = load i32, ptr %row.addr
+  %8 = icmp eq i32 %7, 4
+  br i1 %8, label %if.then3, label %if.end3
+if.then3:
+  br label %loop.end0
+if.end3:
+  %9 = load i32, ptr %cells.addr
+  %10 = add i32 %9, 1
+  store i32 %10, ptr %cells.addr
+  br label %loop1
+loop.end1:
+  br label %loop0
+loop.end0:
+  %11 = load i32, ptr %cells.addr
+  ret i32 %11
+}
+
+define i32 @main() {
+entry:
+  %found.addr = alloca i32
+  %0 = call i32 @pairs()
+  store i32 %0, ptr %found.addr
+  %1 = load i32, ptr %found.addr
+  %2 = mul i32 %1, 10
+  %3 = load i32, ptr @seen
+  %4 = add i32 %2, %3
+  %5 = call i32 @collatz(i32 6)
+  %6 = add i32 %4, %5
+  %7 = call i32 @grid()
+  %8 = add i32 %6, %7
+  ret i32 %8
+}
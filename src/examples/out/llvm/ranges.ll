@@ -0,0 +1,272 @@
+define i32 @sum(i32 %n) {
+entry:
+  %n.addr = alloca i32
+  %total.addr = alloca i32
+  %i.counter.addr = alloca i32
+  %i.addr = alloca i32
+  %i.counter.addr1 = alloca i32
+  %i.addr1 = alloca i32
+  store i32 %n, ptr %n.addr
+  store i32 0, ptr %total.addr
+  %0 = load i32, ptr %n.addr
+  store i32 0, ptr %i.counter.addr
+  br label %for.cond0
+for.cond0:
+  %1 = load i32, ptr %i.counter.addr
+  %2 = icmp slt i32 %1, %0
+  br i1 %2, label %for.body0, label %for.end0
+for.body0:
+  store i32 %1, ptr %i.addr
+  %3 = load i32, ptr %total.addr
+  %4 = load i32, ptr %i.addr
+  %5 = add i32 %3, %4
+  store i32 %5, ptr %total.addr
+  br label %for.inc0
+for.inc0:
+  %6 = load i32, ptr %i.counter.addr
+  %7 = add i32 %6, 1
+  store i32 %7, ptr %i.counter.addr
+  br label %for.cond0
+for.end0:
+  %8 = load i32, ptr %n.addr
+  store i32 0, ptr %i.counter.addr1
+  br label %for.cond1
+for.cond1:
+  %9 = load i32, ptr %i.counter.addr1
+  %10 = icmp sle i32 %9, %8
+  br i1 %10, label %for.body1, label %for.end1
+for.body1:
+  store i32 %9, ptr %i.addr1
+  %11 = load i32, ptr %total.addr
+  %12 = load i32, ptr %i.addr1
+  %13 = add i32 %11, %12
+  store i32 %13, ptr %total.addr
+  br label %for.inc1
+for.inc1:
+  %14 = load i32, ptr %i.counter.addr1
+  %15 = add i32 %14, 1
+  store i32 %15, ptr %i.counter.addr1
+  br label %for.cond1
+for.end1:
+  %16 = load i32, ptr %total.addr
+  ret i32 %16
+}
+
+define i32 @rounds(i32 %n) {
+entry:
+  %n.addr = alloca i32
+  %count.addr = alloca i32
+  %i.counter.addr = alloca i32
+  %i.addr = alloca i32
+  %i.counter.addr1 = alloca i32
+  %i.addr1 = alloca i32
+  store i32 %n, ptr %n.addr
+  store i32 0, ptr %count.addr
+  %0 = load i32, ptr %n.addr
+  %1 = sub i32 %0, 1
+  store i32 %1, ptr %i.counter.addr
+  br label %for.cond0
+for.cond0:
+  %2 = load i32, ptr %i.counter.addr
+  %3 = icmp sge i32 %2, 0
+  br i1 %3, label %for.body0, label %for.end0
+for.body0:
+  store i32 %2, ptr %i.addr
+  %4 = load i32, ptr %count.addr
+  %5 = add i32 %4, 1
+  store i32 %5, ptr %count.addr
+  br label %for.inc0
+for.inc0:
+  %6 = load i32, ptr %i.counter.addr
+  %7 = sub i32 %6, 1
+  store i32 %7, ptr %i.counter.addr
+  br label %for.cond0
+for.end0:
+  %8 = load i32, ptr %n.addr
+  store i32 %8, ptr %i.counter.addr1
+  br label %for.cond1
+for.cond1:
+  %9 = load i32, ptr %i.counter.addr1
+  %10 = icmp sge i32 %9, 0
+  br i1 %10, label %for.body1, label %for.end1
+for.body1:
+  store i32 %9, ptr %i.addr1
+  %11 = load i32, ptr %count.addr
+  %12 = add i32 %11, 1
+  store i32 %12, ptr %count.addr
+  br label %for.inc1
+for.inc1:
+  %13 = load i32, ptr %i.counter.addr1
+  %14 = sub i32 %13, 2
+  store i32 %14, ptr %i.counter.addr1
+  br label %for.cond1
+for.end1:
+  %15 = load i32, ptr %count.addr
+  ret i32 %15
+}
+
+define i32 @main() {
+entry:
+  %total.addr = alloca i32
+  %i.counter.addr = alloca i32
+  %i.addr = alloca i32
+  %order.addr = alloca i32
+  %i.counter.addr1 = alloca i32
+  %i.addr1 = alloca i32
+  %evens.addr = alloca i32
+  %i.counter.addr2 = alloca i32
+  %i.addr2 = alloca i32
+  %odd.addr = alloca i32
+  %i.counter.addr3 = alloca i32
+  %i.addr3 = alloca i32
+  %x.counter.addr = alloca i32
+  %i.count.addr = alloca i32
+  %x.addr = alloca i32
+  %i.addr4 = alloca i32
+  %0 = call i32 @sum(i32 10)
+  store i32 %0, ptr %total.addr
+  store i32 0, ptr %i.counter.addr
+  br label %for.cond0
+for.cond0:
+  %1 = load i32, ptr %i.counter.addr
+  %2 = icmp slt i32 %1, 10
+  br i1 %2, label %for.body0, label %for.end0
+for.body0:
+  store i32 %1, ptr %i.addr
+  %3 = load i32, ptr %total.addr
+  %4 = load i32, ptr %i.addr
+  %5 = add i32 %3, %4
+  store i32 %5, ptr %total.addr
+  br label %for.inc0
+for.inc0:
+  %6 = load i32, ptr %i.counter.addr
+  %7 = add i32 %6, 3
+  store i32 %7, ptr %i.counter.addr
+  br label %for.cond0
+for.end0:
+  store i32 0, ptr %order.addr
+  %8 = sub i32 4, 1
+  store i32 %8, ptr %i.counter.addr1
+  br label %for.cond1
+for.cond1:
+  %9 = load i32, ptr %i.counter.addr1
+  %10 = icmp sge i32 %9, 1
+  br i1 %10, label %for.body1, label %for.end1
+for.body1:
+  store i32 %9, ptr %i.addr1
+  %11 = load i32, ptr %order.addr
+  %12 = mul i32 %11, 10
+  %13 = load i32, ptr %i.addr1
+  %14 = add i32 %12, %13
+  store i32 %14, ptr %order.addr
+  br label %for.inc1
+for.inc1:
+  %15 = load i32, ptr %i.counter.addr1
+  %16 = sub i32 %15, 1
+  store i32 %16, ptr %i.counter.addr1
+  br label %for.cond1
+for.end1:
+  store i32 0, ptr %evens.addr
+  store i32 6, ptr %i.counter.addr2
+  br label %for.cond2
+for.cond2:
+  %17 = load i32, ptr %i.counter.addr2
+  %18 = icmp sge i32 %17, 0
+  br i1 %18, label %for.body2, label %for.end2
+for.body2:
+  store i32 %17, ptr %i.addr2
+  %19 = load i32, ptr %evens.addr
+  %20 = mul i32 %19, 10
+  %21 = load i32, ptr %i.addr2
+  %22 = add i32 %20, %21
+  store i32 %22, ptr %evens.addr
+  br label %for.inc2
+for.inc2:
+  %23 = load i32, ptr %i.counter.addr2
+  %24 = sub i32 %23, 2
+  store i32 %24, ptr %i.counter.addr2
+  br label %for.cond2
+for.end2:
+  store i32 0, ptr %odd.addr
+  store i32 0, ptr %i.counter.addr3
+  br label %for.cond3
+for.cond3:
+  %25 = load i32, ptr %i.counter.addr3
+  %26 = icmp slt i32 %25, 10
+  br i1 %26, label %for.body3, label %for.end3
+for.body3:
+  store i32 %25, ptr %i.addr3
+  %27 = load i32, ptr %i.addr3
+  %28 = srem i32 %27, 2
+  %29 = icmp eq i32 %28, 0
+  br i1 %29, label %if.then4, label %if.end4
+if.then4:
+  br label %for.inc3
+if.end4:
+  %30 = load i32, ptr %odd.addr
+  %31 = load i32, ptr %i.addr3
+  %32 = add i32 %30, %31
+  store i32 %32, ptr %odd.addr
+  br label %for.inc3
+for.inc3:
+  %33 = load i32, ptr %i.counter.addr3
+  %34 = add i32 %33, 1
+  store i32 %34, ptr %i.counter.addr3
+  br label %for.cond3
+for.end3:
+  store i32 10, ptr %x.counter.addr
+  store i32 0, ptr %i.count.addr
+  br label %for.cond5
+for.cond5:
+  %35 = load i32, ptr %x.counter.addr
+  %36 = icmp slt i32 %35, 20
+  br i1 %36, label %for.body5, label %for.end5
+for.body5:
+  store i32 %35, ptr %x.addr
+  %37 = load i32, ptr %i.count.addr
+  store i32 %37, ptr %i.addr4
+  %38 = add i32 %37, 1
+  store i32 %38, ptr %i.count.addr
+  %39 = load i32, ptr %total.addr
+  %40 = load i32, ptr %i.addr4
+  %41 = load i32, ptr %x.addr
+  %42 = mul i32 %40, %41
+  %43 = add i32 %39, %42
+  store i32 %43, ptr %total.addr
+  br label %for.inc5
+for.inc5:
+  %44 = load i32, ptr %x.counter.addr
+  %45 = add i32 %44, 5
+  store i32 %45, ptr %x.counter.addr
+  br label %for.cond5
+for.end5:
+  %46 = load i32, ptr %order.addr
+  %47 = icmp ne i32 %46, 321
+  br i1 %47, label %or.end9, label %or.rhs9
+or.rhs9:
+  %48 = load i32, ptr %evens.addr
+  %49 = icmp ne i32 %48, 6420
+  br label %or.end9
+or.end9:
+  %50 = phi i1 [ true, %for.end5 ], [ %49, %or.rhs9 ]
+  br i1 %50, label %or.end8, label %or.rhs8
+or.rhs8:
+  %51 = load i32, ptr %odd.addr
+  %52 = icmp ne i32 %51, 25
+  br label %or.end8
+or.end8:
+  %53 = phi i1 [ true, %or.end9 ], [ %52, %or.rhs8 ]
+  br i1 %53, label %or.end7, label %or.rhs7
+or.rhs7:
+  %54 = call i32 @rounds(i32 0)
+  %55 = icmp ne i32 %54, 1
+  br label %or.end7
+or.end7:
+  %56 = phi i1 [ true, %or.end8 ], [ %55, %or.rhs7 ]
+  br i1 %56, label %if.then6, label %if.end6
+if.then6:
+  ret i32 1
+if.end6:
+  %57 = load i32, ptr %total.addr
+  ret i32 %57
+}
@@ -0,0 +1,33 @@
+local function count(start, stop)
+    return coroutine.wrap(function()
+        if start >= stop then
+            return
+        end
+        coroutine.yield(start)
+        for _v in count(start + 1, stop) do
+            coroutine.yield(_v)
+        end
+    end)
+end
+
+local function both(n)
+    return coroutine.wrap(function()
+        for _v in count(0, n) do
+            coroutine.yield(_v)
+        end
+        for _v in count(10, 12) do
+            coroutine.yield(_v)
+        end
+        coroutine.yield(n * 100)
+    end)
+end
+
+local function main()
+    local total = 0
+    for x in both(3) do
+        total = total + x
+    end
+    return total
+end
+
+os.exit(main())
@@ -0,0 +1,19 @@
+local function _div(a, b)
+    local q = a // b
+    if q < 0 and q * b ~= a then
+        q = q + 1
+    end
+    return q
+end
+
+local function half(n)
+    local rounded = _div(n + 1, 2)
+    return _div(n, 2)
+end
+
+local function main()
+    local x = 7.0 / 2
+    return _div(half(9), 2) + math.fmod(half(-7), 2)
+end
+
+os.exit(main())
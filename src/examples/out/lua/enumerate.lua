@@ -0,0 +1,30 @@
+local function digits()
+    return coroutine.wrap(function()
+        coroutine.yield(7)
+        coroutine.yield(8)
+        coroutine.yield(9)
+    end)
+end
+
+local function main()
+    local total = 0
+    local i_next = 0
+    for x in digits() do
+        local i = i_next
+        i_next = i_next + 1
+        if i == 1 then
+            goto continue
+        end
+        total = total + i * x
+        ::continue::
+    end
+    local i_next = 0
+    for x = 7, 5, -1 do
+        local i = i_next
+        i_next = i_next + 1
+        total = total + i + x
+    end
+    return total
+end
+
+os.exit(main())
@@ -0,0 +1,33 @@
+local Shape <const> = {
+    Circle = function(_0) return { tag = "Circle", _0 } end,
+    Rect = function(_0, _1) return { tag = "Rect", _0, _1 } end,
+    Empty = function() return { tag = "Empty" } end,
+}
+
+local Color <const> = { Red = "Red", Green = "Green", Blue = "Blue" }
+
+local function next(c)
+    if c == Color.Red then
+        return Color.Green
+    elseif c == Color.Green then
+        return Color.Blue
+    end
+    return Color.Red
+end
+
+local function square(side)
+    return Shape.Rect(side, side)
+end
+
+local function main()
+    local c = next(next(Color.Red))
+    local t = { color = next(c), shape = Shape.Empty() }
+    local big = { color = c, shape = square(2.0) }
+    local round = { color = big.color, shape = Shape.Circle(1.5) }
+    if round.color == Color.Blue and t.color == Color.Red then
+        return 42
+    end
+    return 0
+end
+
+os.exit(main())
@@ -0,0 +1,17 @@
+local function test()
+    return coroutine.wrap(function()
+        if true then
+            coroutine.yield(10)
+            coroutine.yield(10 * 6 + 9)
+        else
+            coroutine.yield(9 + 10)
+        end
+    end)
+end
+
+local function main()
+    local temp = test()
+    return 0
+end
+
+os.exit(main())
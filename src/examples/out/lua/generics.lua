@@ -0,0 +1,38 @@
+local Box = {}
+Box.__index = Box
+
+function Box:size()
+    return self.w
+end
+
+local function larger(a, b)
+    if a > b then
+        return a
+    end
+    return b
+end
+
+local function pair(a, b)
+    return { first = a, second = b }
+end
+
+local function swap(p)
+    return { first = p.second, second = p.first }
+end
+
+local function biggest(a, b)
+    return larger(a:size(), b:size())
+end
+
+local function main()
+    local p = swap(pair(3, 4))
+    local small = setmetatable({ w = 2 }, Box)
+    local big = setmetatable({ w = 5 }, Box)
+    local total = larger(p.first, p.second) + biggest(small, big)
+    if larger(1.5, 0.5) > 1.0 then
+        return total
+    end
+    return 0
+end
+
+os.exit(main())
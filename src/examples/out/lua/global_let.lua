@@ -0,0 +1,7 @@
+local test = 69
+
+local function main()
+    return 0
+end
+
+os.exit(main())
@@ -0,0 +1,9 @@
+local function main()
+    local test = true
+    if test then
+        test = false
+    end
+    return 0
+end
+
+os.exit(main())
@@ -0,0 +1,21 @@
+local function area(w, h)
+    return w * h
+end
+
+local function scale(x, by)
+    return x * by
+end
+local SIDE <const> = 4
+local AREA <const> = area(SIDE, 3)
+local ratio = scale(1.5, 2.0)
+
+local function main()
+    local total = AREA + area(2, 2)
+    local half = ratio / 2
+    if total > 10 and half < 4.0 then
+        return 3
+    end
+    return 0
+end
+
+os.exit(main())
@@ -0,0 +1,6 @@
+local function main()
+    local test = 10
+    return 0
+end
+
+os.exit(main())
@@ -0,0 +1,7 @@
+local function main()
+    while true do
+        local test = 10
+    end
+end
+
+os.exit(main())
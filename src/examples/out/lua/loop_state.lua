@@ -0,0 +1,39 @@
+local ticks = 0
+
+local function count(limit)
+    local n = 0
+    local total = 0
+    while true do
+        n = n + 1
+        if n > limit then
+            return total
+        end
+        if math.fmod(n, 2) == 1 then
+            goto continue
+        end
+        total = total + n
+        ::continue::
+    end
+end
+
+local function sleep(ms)
+    local stop = os.clock() + ms / 1000
+    while os.clock() < stop do end
+end
+
+local function tick()
+    while true do
+        sleep(1)
+        ticks = ticks + 1
+        if ticks == 3 then
+            break
+        end
+    end
+end
+
+local function main()
+    tick()
+    return count(10) + ticks
+end
+
+os.exit(main())
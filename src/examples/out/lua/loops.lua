@@ -0,0 +1,74 @@
+local function _div(a, b)
+    local q = a // b
+    if q < 0 and q * b ~= a then
+        q = q + 1
+    end
+    return q
+end
+
+local seen = 0
+
+local function pairs_()
+    local found = 0
+    local a = 0
+    while a < 5 do
+        a = a + 1
+        local b = 0
+        while b < 3 do
+            b = b + 1
+            if b > a then
+                goto outer_continue
+            end
+            if a * b > 12 then
+                goto outer_break
+            end
+            found = found + 1
+        end
+        local row = a
+        seen = seen + row
+        ::outer_continue::
+    end
+    ::outer_break::
+    return found
+end
+
+local function collatz(n)
+    local steps = 0
+    while n ~= 1 do
+        if math.fmod(n, 2) == 0 then
+            n = _div(n, 2)
+        else
+            n = 3 * n + 1
+        end
+        steps = steps + 1
+    end
+    return steps
+end
+
+local function grid()
+    local cells = 0
+    local row = 0
+    while true do
+        row = row + 1
+        local col = 0
+        while true do
+            col = col + 1
+            if col > row then
+                break
+            end
+            if row == 4 then
+                goto rows_break
+            end
+            cells = cells + 1
+        end
+    end
+    ::rows_break::
+    return cells
+end
+
+local function main()
+    local found = pairs_()
+    return found * 10 + seen + collatz(6) + grid()
+end
+
+os.exit(main())
@@ -0,0 +1,68 @@
+local Shape <const> = {
+    Circle = function(_0) return { tag = "Circle", _0 } end,
+    Rect = function(_0, _1) return { tag = "Rect", _0, _1 } end,
+    Empty = function() return { tag = "Empty" } end,
+}
+
+local Color <const> = { Red = "Red", Green = "Green", Blue = "Blue" }
+
+local function area(s)
+    return (function(_m)
+    if _m.tag == "Circle" then
+        local r = _m[1]
+        return 3.0 * r * r
+    elseif _m.tag == "Rect" then
+        local w = _m[1]
+        local h = _m[2]
+        return w * h
+    else
+        return 0.0
+    end
+end)(s)
+end
+
+local function digits(n)
+    local count = (function(_m)
+    if _m >= 0 and _m <= 9 then
+        return 1
+    elseif _m >= 10 and _m <= 99 then
+        return 2
+    else
+        return 3
+    end
+end)(n)
+    return count
+end
+
+local function code(c)
+    return (function(_m)
+    if _m == Color.Red then
+        return 1
+    elseif _m == Color.Green then
+        return 2
+    else
+        return 3
+    end
+end)(c)
+end
+
+local function bit(on)
+    return (function(_m)
+    if _m then
+        return 1
+    else
+        return 0
+    end
+end)(on)
+end
+
+local function main()
+    local total = area(Shape.Rect(2.0, 3.0)) + area(Shape.Empty())
+    if total ~= 6.0 then
+        return 1
+    end
+    local sizes = digits(7) + digits(42) + digits(512)
+    return sizes * 5 + code(Color.Blue) * 4 + bit(false)
+end
+
+os.exit(main())
@@ -0,0 +1,38 @@
+local Point = {}
+Point.__index = Point
+
+local Rect = {}
+Rect.__index = Rect
+
+function Point.at(x, y)
+    return setmetatable({ x = x, y = y }, Point)
+end
+
+function Point.origin()
+    return Point.at(0, 0)
+end
+
+function Point:add(other)
+    return setmetatable({ x = self.x + other.x, y = self.y + other.y }, Point)
+end
+
+function Point:dot(other)
+    return self.x * other.x + self.y * other.y
+end
+
+function Point:len2()
+    return self:dot(self)
+end
+
+function Rect:area()
+    local size = self.max:add(Point.at(-self.min.x, -self.min.y))
+    return size.x * size.y
+end
+
+local function main()
+    local p = Point.origin():add(Point.at(3, 4))
+    local r = setmetatable({ min = Point.at(1, 1), max = p:add(p) }, Rect)
+    return p:len2() + r:area() + r.max:dot(Point.at(1, 0))
+end
+
+os.exit(main())
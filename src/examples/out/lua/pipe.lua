@@ -0,0 +1,71 @@
+local function _values(xs)
+    if type(xs) ~= "table" then
+        return xs
+    end
+    local i = 0
+    return function()
+        i = i + 1
+        return xs[i]
+    end
+end
+
+local function map(xs, f)
+    return coroutine.wrap(function()
+        for x in _values(xs) do
+            coroutine.yield(f(x))
+        end
+    end)
+end
+
+local function filter(xs, p)
+    return coroutine.wrap(function()
+        for x in _values(xs) do
+            if p(x) then
+                coroutine.yield(x)
+            end
+        end
+    end)
+end
+
+local function take(xs, n)
+    return coroutine.wrap(function()
+        if n <= 0 then
+            return
+        end
+        for x in _values(xs) do
+            coroutine.yield(x)
+            n = n - 1
+            if n <= 0 then
+                return
+            end
+        end
+    end)
+end
+
+local function naturals()
+    return coroutine.wrap(function()
+        local n = 1
+        while true do
+            coroutine.yield(n)
+            n = n + 1
+        end
+    end)
+end
+
+local function square(x)
+    return x * x
+end
+
+local function odd(x)
+    return math.fmod(x, 2) == 1
+end
+
+local function main()
+    local total = 0
+    for x in take(filter(map(naturals(), square), odd), 3) do
+        total = total + x
+    end
+    return square(total)
+end
+
+os.exit(main())
@@ -0,0 +1,56 @@
+local function sum(n)
+    local total = 0
+    for i = 0, n - 1 do
+        total = total + i
+    end
+    for i = 0, n do
+        total = total + i
+    end
+    return total
+end
+
+local function rounds(n)
+    local count = 0
+    for i = n - 1, 0, -1 do
+        count = count + 1
+    end
+    for i = n, 0, -2 do
+        count = count + 1
+    end
+    return count
+end
+
+local function main()
+    local total = sum(10)
+    for i = 0, 9, 3 do
+        total = total + i
+    end
+    local order = 0
+    for i = 3, 1, -1 do
+        order = order * 10 + i
+    end
+    local evens = 0
+    for i = 6, 0, -2 do
+        evens = evens * 10 + i
+    end
+    local odd = 0
+    for i = 0, 9 do
+        if math.fmod(i, 2) == 0 then
+            goto continue
+        end
+        odd = odd + i
+        ::continue::
+    end
+    local i_next = 0
+    for x = 10, 19, 5 do
+        local i = i_next
+        i_next = i_next + 1
+        total = total + i * x
+    end
+    if order ~= 321 or evens ~= 6420 or odd ~= 25 or rounds(0) ~= 1 then
+        return 1
+    end
+    return total
+end
+
+os.exit(main())
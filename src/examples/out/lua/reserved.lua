@@ -0,0 +1,21 @@
+local Counter = {}
+Counter.__index = Counter
+
+function Counter.new()
+    return setmetatable({ count = 1 }, Counter)
+end
+
+function Counter:this()
+    return self.count
+end
+
+local function end_(n)
+    return n * 3
+end
+
+local function main()
+    local c = Counter.new()
+    return end_(c:this())
+end
+
+os.exit(main())
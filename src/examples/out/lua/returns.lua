@@ -0,0 +1,25 @@
+local function sign(n)
+    if n > 0 then
+        return 1
+    elseif n < 0 then
+        return 2
+    else
+        return 0
+    end
+end
+
+local function root(above)
+    local i = 0
+    while true do
+        i = i + 1
+        if i * i > above then
+            return i
+        end
+    end
+end
+
+local function main()
+    return sign(5) + root(10)
+end
+
+os.exit(main())
@@ -0,0 +1,20 @@
+local function sum(n)
+    local total = 0
+    for x = 1, n do
+        if math.fmod(x, 2) == 0 then
+            local part = x - 1
+            total = total + part
+        else
+            local part = x
+            total = total + part
+        end
+    end
+    return total
+end
+
+local function main()
+    local total = sum(4)
+    return total
+end
+
+os.exit(main())
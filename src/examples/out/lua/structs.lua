@@ -0,0 +1,18 @@
+local function area(r)
+    return (r.max.x - r.min.x) * (r.max.y - r.min.y)
+end
+
+local function corner(x, y)
+    return { x = x, y = y }
+end
+
+local function main()
+    local p = { x = 3, y = 4 }
+    local label = "Point{x:3}"
+    print(label)
+    local r = { min = corner(1, 1), max = { x = p.x + 5, y = p.y * 2 } }
+    local moved = { min = r.min, max = corner(r.max.x, 10) }
+    return area(r) + area(moved) + corner(2, 7).y
+end
+
+os.exit(main())
@@ -0,0 +1,41 @@
+local Square = {}
+Square.__index = Square
+
+local Rect = {}
+Rect.__index = Rect
+
+function Square:area()
+    return self.side * self.side
+end
+
+function Square:scaled(k)
+    return self:area() * k * k
+end
+
+function Rect:area()
+    return self.w * self.h
+end
+
+function Rect:scaled(k)
+    return self.w * k * self.h * k
+end
+
+local function total(a, b)
+    return a:area() + b:area()
+end
+
+local function larger(a, b)
+    if a:area() > b:area() then
+        return a
+    end
+    return b
+end
+
+local function main()
+    local s = setmetatable({ side = 3 }, Square)
+    local r = setmetatable({ w = 2, h = 5 }, Rect)
+    local f = { inner = larger(s, r), border = 1 }
+    return total(s, r) + f.inner:scaled(2) + f.border
+end
+
+os.exit(main())
@@ -0,0 +1,22 @@
+local function label(p)
+    return p.name
+end
+
+local function area(w, h)
+    return w * h
+end
+
+local function half(x)
+    return x / 2
+end
+
+local function main()
+    local p = { level = 200, name = "red" }
+    local name = label(p)
+    if p.level > 100 and area(3, 4) > 10 and half(3.0) > 1.0 then
+        return 7
+    end
+    return 0
+end
+
+os.exit(main())
@@ -0,0 +1,38 @@
+(module
+  (import "env" "print" (func $print (param i32)))
+  (global $limit i32 (i32.const 100))
+  (global $counter (mut i32) (i32.const 0))
+  (func $add (export "add") (param $a i32) (param $b i32) (result i32)
+    (return (i32.add (local.get $a) (local.get $b)))
+  )
+  (func $average (export "average") (param $a f64) (param $b i32) (result f64)
+    (return (f64.div (f64.add (local.get $a) (f64.convert_i32_s (local.get $b))) (f64.const 2)))
+  )
+  (func $main (export "main") (result i32)
+    (local $x i32)
+    (local.set $x (i32.mul (call $add (i32.const 1) (i32.const 2)) (i32.const 3)))
+    (if (i32.gt_s (local.get $x) (global.get $limit))
+      (then
+        (global.set $counter (i32.const 1))
+      )
+      (else
+        (if (if (result i32) (i32.eq (local.get $x) (i32.const 9)) (then (i32.ne (local.get $x) (i32.const 10))) (else (i32.const 0)))
+          (then
+            (global.set $counter (i32.const 2))
+          )
+          (else
+            (global.set $counter (i32.const -3))
+          )
+        )
+      )
+    )
+    (call $print (local.get $x))
+    (block $break0
+      (loop $continue0
+        (local.set $x (i32.sub (local.get $x) (i32.const 1)))
+        (br $continue0)
+      )
+    )
+    (return (global.get $counter))
+  )
+)
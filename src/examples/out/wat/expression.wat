@@ -0,0 +1,7 @@
+(module
+  (func $main (export "main") (result i32)
+    (local $test i32)
+    (local.set $test (i32.mul (i32.const 10) (i32.const 9)))
+    (return (i32.const 0))
+  )
+)
@@ -0,0 +1,6 @@
+(module
+  (global $test i32 (i32.const 69))
+  (func $main (export "main") (result i32)
+    (return (i32.const 0))
+  )
+)
@@ -0,0 +1,12 @@
+(module
+  (func $main (export "main") (result i32)
+    (local $test i32)
+    (local.set $test (i32.const 1))
+    (if (local.get $test)
+      (then
+        (local.set $test (i32.const 0))
+      )
+    )
+    (return (i32.const 0))
+  )
+)
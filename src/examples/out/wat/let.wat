@@ -0,0 +1,7 @@
+(module
+  (func $main (export "main") (result i32)
+    (local $test i32)
+    (local.set $test (i32.const 10))
+    (return (i32.const 0))
+  )
+)
@@ -0,0 +1,10 @@
+(module
+  (func $main (export "main") (result i32)
+    (local $test i32)
+    (loop $tick
+      (local.set $test (i32.const 10))
+      (br $tick)
+    )
+    (unreachable)
+  )
+)
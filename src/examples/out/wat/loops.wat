@@ -0,0 +1,97 @@
+(module
+  (global $seen (mut i32) (i32.const 0))
+  (func $pairs (export "pairs") (result i32)
+    (local $found i32)
+    (local $a i32)
+    (local $b i32)
+    (local $row i32)
+    (local.set $found (i32.const 0))
+    (local.set $a (i32.const 0))
+    (block $break0
+      (loop $continue0
+        (br_if $break0 (i32.eqz (i32.lt_s (local.get $a) (i32.const 5))))
+        (local.set $a (i32.add (local.get $a) (i32.const 1)))
+        (local.set $b (i32.const 0))
+        (block $break1
+          (loop $continue1
+            (br_if $break1 (i32.eqz (i32.lt_s (local.get $b) (i32.const 3))))
+            (local.set $b (i32.add (local.get $b) (i32.const 1)))
+            (if (i32.gt_s (local.get $b) (local.get $a))
+              (then
+                (br $continue0)
+              )
+            )
+            (if (i32.gt_s (i32.mul (local.get $a) (local.get $b)) (i32.const 12))
+              (then
+                (br $break0)
+              )
+            )
+            (local.set $found (i32.add (local.get $found) (i32.const 1)))
+            (br $continue1)
+          )
+        )
+        (local.set $row (local.get $a))
+        (global.set $seen (i32.add (global.get $seen) (local.get $row)))
+        (br $continue0)
+      )
+    )
+    (return (local.get $found))
+  )
+  (func $collatz (export "collatz") (param $n i32) (result i32)
+    (local $steps i32)
+    (local.set $steps (i32.const 0))
+    (block $break0
+      (loop $continue0
+        (br_if $break0 (i32.eqz (i32.ne (local.get $n) (i32.const 1))))
+        (if (i32.eq (i32.rem_s (local.get $n) (i32.const 2)) (i32.const 0))
+          (then
+            (local.set $n (i32.div_s (local.get $n) (i32.const 2)))
+          )
+          (else
+            (local.set $n (i32.add (i32.mul (i32.const 3) (local.get $n)) (i32.const 1)))
+          )
+        )
+        (local.set $steps (i32.add (local.get $steps) (i32.const 1)))
+        (br $continue0)
+      )
+    )
+    (return (local.get $steps))
+  )
+  (func $grid (export "grid") (result i32)
+    (local $cells i32)
+    (local $row i32)
+    (local $col i32)
+    (local.set $cells (i32.const 0))
+    (local.set $row (i32.const 0))
+    (block $break0
+      (loop $continue0
+        (local.set $row (i32.add (local.get $row) (i32.const 1)))
+        (local.set $col (i32.const 0))
+        (block $break1
+          (loop $continue1
+            (local.set $col (i32.add (local.get $col) (i32.const 1)))
+            (if (i32.gt_s (local.get $col) (local.get $row))
+              (then
+                (br $break1)
+              )
+            )
+            (if (i32.eq (local.get $row) (i32.const 4))
+              (then
+                (br $break0)
+              )
+            )
+            (local.set $cells (i32.add (local.get $cells) (i32.const 1)))
+            (br $continue1)
+          )
+        )
+        (br $continue0)
+      )
+    )
+    (return (local.get $cells))
+  )
+  (func $main (export "main") (result i32)
+    (local $found i32)
+    (local.set $found (call $pairs))
+    (return (i32.add (i32.add (i32.add (i32.mul (local.get $found) (i32.const 10)) (global.get $seen)) (call $collatz (i32.const 6))) (call $grid)))
+  )
+)
@@ -0,0 +1,5 @@
+(module
+  (func $main (export "main") (result i32)
+    (return (i32.const 0))
+  )
+)
@@ -0,0 +1,179 @@
+(module
+  (func $sum (export "sum") (param $n i32) (result i32)
+    (local $total i32)
+    (local $i.counter i32)
+    (local $i.bound i32)
+    (local $i.step i32)
+    (local $i i32)
+    (local.set $total (i32.const 0))
+    (local.set $i.counter (i32.const 0))
+    (local.set $i.bound (local.get $n))
+    (local.set $i.step (i32.const 1))
+    (block $break0
+      (loop $for0
+        (br_if $break0 (i32.eqz (i32.lt_s (local.get $i.counter) (local.get $i.bound))))
+        (local.set $i (local.get $i.counter))
+        (block $continue0
+          (local.set $total (i32.add (local.get $total) (local.get $i)))
+        )
+        (local.set $i.counter (i32.add (local.get $i.counter) (local.get $i.step)))
+        (br $for0)
+      )
+    )
+    (local.set $i.counter (i32.const 0))
+    (local.set $i.bound (local.get $n))
+    (local.set $i.step (i32.const 1))
+    (block $break1
+      (loop $for1
+        (br_if $break1 (i32.eqz (i32.le_s (local.get $i.counter) (local.get $i.bound))))
+        (local.set $i (local.get $i.counter))
+        (block $continue1
+          (local.set $total (i32.add (local.get $total) (local.get $i)))
+        )
+        (local.set $i.counter (i32.add (local.get $i.counter) (local.get $i.step)))
+        (br $for1)
+      )
+    )
+    (return (local.get $total))
+  )
+  (func $rounds (export "rounds") (param $n i32) (result i32)
+    (local $count i32)
+    (local $i.counter i32)
+    (local $i.bound i32)
+    (local $i.step i32)
+    (local $i i32)
+    (local.set $count (i32.const 0))
+    (local.set $i.counter (i32.sub (local.get $n) (i32.const 1)))
+    (local.set $i.bound (i32.const 0))
+    (local.set $i.step (i32.const 1))
+    (block $break0
+      (loop $for0
+        (br_if $break0 (i32.eqz (i32.ge_s (local.get $i.counter) (local.get $i.bound))))
+        (local.set $i (local.get $i.counter))
+        (block $continue0
+          (local.set $count (i32.add (local.get $count) (i32.const 1)))
+        )
+        (local.set $i.counter (i32.sub (local.get $i.counter) (local.get $i.step)))
+        (br $for0)
+      )
+    )
+    (local.set $i.counter (local.get $n))
+    (local.set $i.bound (i32.const 0))
+    (local.set $i.step (i32.const 2))
+    (block $break1
+      (loop $for1
+        (br_if $break1 (i32.eqz (i32.ge_s (local.get $i.counter) (local.get $i.bound))))
+        (local.set $i (local.get $i.counter))
+        (block $continue1
+          (local.set $count (i32.add (local.get $count) (i32.const 1)))
+        )
+        (local.set $i.counter (i32.sub (local.get $i.counter) (local.get $i.step)))
+        (br $for1)
+      )
+    )
+    (return (local.get $count))
+  )
+  (func $main (export "main") (result i32)
+    (local $total i32)
+    (local $i.counter i32)
+    (local $i.bound i32)
+    (local $i.step i32)
+    (local $i i32)
+    (local $order i32)
+    (local $evens i32)
+    (local $odd i32)
+    (local $x.counter i32)
+    (local $x.bound i32)
+    (local $x.step i32)
+    (local $i.count i32)
+    (local $x i32)
+    (local.set $total (call $sum (i32.const 10)))
+    (local.set $i.counter (i32.const 0))
+    (local.set $i.bound (i32.const 10))
+    (local.set $i.step (i32.const 3))
+    (block $break0
+      (loop $for0
+        (br_if $break0 (i32.eqz (i32.lt_s (local.get $i.counter) (local.get $i.bound))))
+        (local.set $i (local.get $i.counter))
+        (block $continue0
+          (local.set $total (i32.add (local.get $total) (local.get $i)))
+        )
+        (local.set $i.counter (i32.add (local.get $i.counter) (local.get $i.step)))
+        (br $for0)
+      )
+    )
+    (local.set $order (i32.const 0))
+    (local.set $i.counter (i32.sub (i32.const 4) (i32.const 1)))
+    (local.set $i.bound (i32.const 1))
+    (local.set $i.step (i32.const 1))
+    (block $break1
+      (loop $for1
+        (br_if $break1 (i32.eqz (i32.ge_s (local.get $i.counter) (local.get $i.bound))))
+        (local.set $i (local.get $i.counter))
+        (block $continue1
+          (local.set $order (i32.add (i32.mul (local.get $order) (i32.const 10)) (local.get $i)))
+        )
+        (local.set $i.counter (i32.sub (local.get $i.counter) (local.get $i.step)))
+        (br $for1)
+      )
+    )
+    (local.set $evens (i32.const 0))
+    (local.set $i.counter (i32.const 6))
+    (local.set $i.bound (i32.const 0))
+    (local.set $i.step (i32.const 2))
+    (block $break2
+      (loop $for2
+        (br_if $break2 (i32.eqz (i32.ge_s (local.get $i.counter) (local.get $i.bound))))
+        (local.set $i (local.get $i.counter))
+        (block $continue2
+          (local.set $evens (i32.add (i32.mul (local.get $evens) (i32.const 10)) (local.get $i)))
+        )
+        (local.set $i.counter (i32.sub (local.get $i.counter) (local.get $i.step)))
+        (br $for2)
+      )
+    )
+    (local.set $odd (i32.const 0))
+    (local.set $i.counter (i32.const 0))
+    (local.set $i.bound (i32.const 10))
+    (local.set $i.step (i32.const 1))
+    (block $break3
+      (loop $for3
+        (br_if $break3 (i32.eqz (i32.lt_s (local.get $i.counter) (local.get $i.bound))))
+        (local.set $i (local.get $i.counter))
+        (block $continue3
+          (if (i32.eq (i32.rem_s (local.get $i) (i32.const 2)) (i32.const 0))
+            (then
+              (br $continue3)
+            )
+          )
+          (local.set $odd (i32.add (local.get $odd) (local.get $i)))
+        )
+        (local.set $i.counter (i32.add (local.get $i.counter) (local.get $i.step)))
+        (br $for3)
+      )
+    )
+    (local.set $x.counter (i32.const 10))
+    (local.set $x.bound (i32.const 20))
+    (local.set $x.step (i32.const 5))
+    (local.set $i.count (i32.const 0))
+    (block $break4
+      (loop $for4
+        (br_if $break4 (i32.eqz (i32.lt_s (local.get $x.counter) (local.get $x.bound))))
+        (local.set $x (local.get $x.counter))
+        (local.set $i (local.get $i.count))
+        (local.set $i.count (i32.add (local.get $i.count) (i32.const 1)))
+        (block $continue4
+          (local.set $total (i32.add (local.get $total) (i32.mul (local.get $i) (local.get $x))))
+        )
+        (local.set $x.counter (i32.add (local.get $x.counter) (local.get $x.step)))
+        (br $for4)
+      )
+    )
+    (if (if (result i32) (if (result i32) (if (result i32) (i32.ne (local.get $order) (i32.const 321)) (then (i32.const 1)) (else (i32.ne (local.get $evens) (i32.const 6420)))) (then (i32.const 1)) (else (i32.ne (local.get $odd) (i32.const 25)))) (then (i32.const 1)) (else (i32.ne (call $rounds (i32.const 0)) (i32.const 1))))
+      (then
+        (return (i32.const 1))
+      )
+    )
+    (return (local.get $total))
+  )
+)
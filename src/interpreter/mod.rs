@@ -0,0 +1,1047 @@
+//! Runs lang programs without transpiling them first, by walking the
+//! statement tree of [`crate::lexer::tree`].
+//!
+//! Function bodies are executed by a [`Frame`] that keeps its position in
+//! every block it is in, instead of recursing into nested blocks. That way a
+//! generator can stop at a `yield` and continue from there once the next
+//! value is needed.
+use {
+    crate::{
+        lexer::{
+            check, flow, resolve,
+            token::{expression::Operator, Function, Variant},
+            tree::{self, Expr, Iterable, Pattern, Stmt},
+            types::Type,
+            FunctionType, Lexer, Token,
+        },
+        log, printx, PrintT,
+    },
+    std::{
+        cell::RefCell, collections::HashMap, fs::read_to_string, path::Path, rc::Rc, thread,
+        time::Duration,
+    },
+};
+
+pub mod value;
+pub use value::Value;
+
+/// The most calls running at once, recursing deeper is an error instead of
+/// overflowing the native stack.
+pub const MAX_DEPTH: usize = 1000;
+
+/// The native stack [`MAX_DEPTH`] calls fit in, see [`with_stack`].
+pub const STACK_SIZE: usize = 256 << 20;
+
+/// Runs `f` on a thread with a native stack of [`STACK_SIZE`] bytes, like
+/// `lang run` and the REPL run the interpreter.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn with_stack<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    thread::scope(|scope| {
+        let thread = thread::Builder::new().stack_size(STACK_SIZE);
+        match thread.spawn_scoped(scope, f).unwrap().join() {
+            Ok(value) => value,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    })
+}
+
+/// WebAssembly has no threads, `f` runs on the stack of the caller.
+#[cfg(target_arch = "wasm32")]
+pub fn with_stack<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    f()
+}
+
+/// Functions callable without defining them.
+pub const BUILTINS: [&str; 3] = ["print", "len", "sleep"];
+
+/// Lazy generator adapters for `|>` pipelines like `xs |> map(f) |> take(3)`,
+/// written in lang itself. A program's own functions replace them.
+pub const PRELUDE: &str = "gen fn map(xs: any, f: any): any {
+    for x in xs {
+        yield f(x)
+    }
+}
+
+gen fn filter(xs: any, p: any): any {
+    for x in xs {
+        if p(x) {
+            yield x
+        }
+    }
+}
+
+gen fn take(xs: any, n: int): any {
+    if n <= 0 {
+        return
+    }
+    for x in xs {
+        yield x
+        n = n - 1
+        if n <= 0 {
+            return
+        }
+    }
+}
+";
+
+/// The names a program can use without declaring them, the builtins and the
+/// functions of the prelude.
+pub fn predefined() -> Vec<String> {
+    let mut lexer = Lexer::quiet();
+    lexer.parse(PRELUDE.to_string());
+    let functions = lexer.ast.into_iter().filter_map(|nt| match nt {
+        Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) => Some(x.name),
+        _ => None,
+    });
+    BUILTINS
+        .iter()
+        .map(|b| b.to_string())
+        .chain(functions)
+        .collect()
+}
+
+struct Callable {
+    arguments: Vec<(String, Type)>,
+    return_type: Type,
+    body: Rc<Vec<Stmt>>,
+    type_: FunctionType,
+}
+
+enum BlockKind {
+    Plain,
+    /// restarts at its end
+    Loop,
+    /// restarts at its end while the condition is true
+    While(Rc<Expr>),
+    /// restarts at its end while the iterator has values left
+    For(ForLoop),
+    /// the top level of a [`Session`], waits for more statements at its end
+    Session,
+}
+
+enum Iter {
+    Array(Vec<Value>, usize),
+    Generator(Rc<RefCell<Frame>>),
+    /// the next value, the comparison with the bound it has to pass and the
+    /// step, which is negative for reversed ranges
+    Range(i64, Operator, i64, i64),
+}
+
+/// A running `for`, with the variables it sets every iteration.
+struct ForLoop {
+    index: Option<String>,
+    var_name: String,
+    iter: Iter,
+    /// the value of `index` in the next iteration
+    count: i64,
+}
+
+struct Block {
+    stmts: Rc<Vec<Stmt>>,
+    /// index of the next statement
+    pc: usize,
+    kind: BlockKind,
+    vars: HashMap<String, Value>,
+    /// of a loop, for `break 'label` and `continue 'label`
+    label: Option<String>,
+}
+
+impl Block {
+    fn new(stmts: &[Stmt], kind: BlockKind) -> Block {
+        Block {
+            stmts: Rc::new(stmts.to_vec()),
+            pc: 0,
+            kind,
+            vars: HashMap::new(),
+            label: None,
+        }
+    }
+}
+
+/// The state of a running function call.
+pub struct Frame {
+    name: String,
+    return_type: Type,
+    generator: bool,
+    /// the innermost block is last
+    blocks: Vec<Block>,
+}
+
+impl std::fmt::Debug for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Frame({})", self.name)
+    }
+}
+
+/// The variables of a REPL, they stay defined between inputs.
+pub struct Session {
+    frame: Frame,
+}
+
+enum Flow {
+    Return(Value),
+    Yield(Value),
+}
+
+#[derive(Default)]
+pub struct Interpreter {
+    functions: HashMap<String, Rc<Callable>>,
+    /// the fields of each struct with their types
+    structs: HashMap<String, Vec<(String, Type)>>,
+    enums: HashMap<String, Vec<Variant>>,
+    /// the declarations the traits are checked against
+    signatures: tree::Signatures,
+    /// value and mutability
+    globals: HashMap<String, (Value, bool)>,
+    /// output of `print`, written to stdout when `None`
+    pub output: Option<String>,
+    /// the calls and generators running, up to [`MAX_DEPTH`]
+    depth: usize,
+}
+
+impl Interpreter {
+    pub fn new() -> Interpreter {
+        let mut interpreter = Interpreter::default();
+        let mut lexer = Lexer::quiet();
+        lexer.parse(PRELUDE.to_string());
+        // the tests run with the prelude, so it always loads
+        interpreter.load(&lexer.ast).unwrap();
+        interpreter
+    }
+
+    /// An interpreter collecting everything printed in `output`.
+    pub fn capturing() -> Interpreter {
+        Interpreter {
+            output: Some(String::new()),
+            ..Interpreter::new()
+        }
+    }
+
+    /// Runs the `main` function of a file, its return value is the exit code.
+    pub fn run<P>(&mut self, file: P) -> Result<i32, String>
+    where
+        P: AsRef<Path>,
+    {
+        let code = match read_to_string(file) {
+            Ok(code) => code,
+            Err(e) => return Err(format!("Unable to read file: {e}")),
+        };
+        let mut lexer = Lexer::quiet();
+        lexer.parse(code);
+        if lexer.errors > 0 {
+            return Err("Not running a program with lexer errors".to_string());
+        }
+        self.load(&lexer.ast)?;
+        self.main()
+    }
+
+    /// Defines the functions and globals of a program.
+    pub fn load(&mut self, ast: &[Token]) -> Result<(), String> {
+        // globals can use structs and enums declared after them
+        for nt in ast {
+            match nt {
+                Token::Struct(x) => {
+                    let fields = x
+                        .fields
+                        .iter()
+                        .map(|field| (field.name.clone(), field.type_.clone()))
+                        .collect();
+                    self.structs.insert(x.name.clone(), fields);
+                }
+                Token::Enum(x) => {
+                    self.enums.insert(x.name.clone(), x.variants.clone());
+                }
+                _ => {}
+            }
+        }
+        // the names loaded before, the prelude among them
+        let known: Vec<String> = (BUILTINS.iter().map(|b| b.to_string()))
+            .chain(self.functions.keys().cloned())
+            .chain(self.globals.keys().cloned())
+            .chain(self.structs.keys().cloned())
+            .chain(self.enums.keys().cloned())
+            .collect();
+        let resolved = resolve::program(ast, &known);
+        for warning in &resolved.warnings {
+            log!(Warning, f("{warning}"));
+        }
+        if !resolved.errors.is_empty() {
+            return Err(resolved.errors.join("\n"));
+        }
+        self.signatures.extend(ast)?;
+        let mut errors = check::program(&self.signatures, ast);
+        errors.extend(flow::program(ast));
+        if !errors.is_empty() {
+            return Err(errors.join("\n"));
+        }
+        for nt in ast {
+            match nt {
+                Token::Struct(_) | Token::Enum(_) | Token::Trait(_) => {}
+                Token::Impl(x) => {
+                    if !self.structs.contains_key(&x.name) {
+                        return Err(format!("`impl {}` needs a struct named `{0}`", x.name));
+                    }
+                    for function in &x.functions {
+                        let function = Function {
+                            name: format!("{}::{}", x.name, function.name),
+                            ..function.clone()
+                        };
+                        self.define(&function, FunctionType::Function)?;
+                    }
+                }
+                Token::Function(x) => self.define(x, FunctionType::Function)?,
+                Token::LoopFunction(x) => self.define(x, FunctionType::Loop)?,
+                Token::GeneratorFunction(x) => self.define(x, FunctionType::Generator)?,
+                Token::Const(x) | Token::Global(x) => {
+                    let exp = tree::expression(&x.exp)
+                        .and_then(|exp| {
+                            tree::walk_expr(&exp, &mut |exp| self.exhaustive(exp))?;
+                            Ok(exp)
+                        })
+                        .map_err(|e| format!("{e} in `{}`", x.name))?;
+                    let mut frame = Frame {
+                        name: x.name.clone(),
+                        return_type: Type::VOID,
+                        generator: false,
+                        blocks: vec![],
+                    };
+                    let value = self.eval(&mut frame, &exp)?;
+                    self.globals
+                        .insert(x.name.clone(), (value, matches!(nt, Token::Global(_))));
+                }
+                Token::Import(i) => {
+                    log!(Interpreter, f("Skipping import {i}"));
+                }
+                Token::CImport(ci) => {
+                    log!(Interpreter, f("Skipping C include {ci}"));
+                }
+                Token::Comment(_) => {}
+                _ => {
+                    log!(Error, f("unexpected Token {nt:?}"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn define(&mut self, x: &Function, type_: FunctionType) -> Result<(), String> {
+        let body = match type_ {
+            FunctionType::Loop => tree::loop_function(x),
+            _ => tree::function(x),
+        }
+        .and_then(|body| {
+            tree::walk(&body, &mut |exp| self.exhaustive(exp))?;
+            self.signatures.bounds(&x.generics, &x.arguments, &body)?;
+            Ok(body)
+        })
+        .map_err(|e| format!("{e} in `{}`", x.name))?;
+        self.functions.insert(
+            x.name.clone(),
+            Rc::new(Callable {
+                arguments: x
+                    .arguments
+                    .iter()
+                    .map(|arg| (arg.name.clone(), arg.type_.clone()))
+                    .collect(),
+                return_type: x.return_type.clone(),
+                body: Rc::new(body),
+                type_,
+            }),
+        );
+        Ok(())
+    }
+
+    /// Calls `main`, like in C++ it returns 0 when it ends without `return`.
+    pub fn main(&mut self) -> Result<i32, String> {
+        if !self.functions.contains_key("main") {
+            return Err("There is no `main` function".to_string());
+        }
+        match self.call("main", vec![])? {
+            Value::Int(code) => Ok(code as i32),
+            _ => Ok(0),
+        }
+    }
+
+    pub fn session(&self) -> Session {
+        Session {
+            frame: Frame {
+                name: "repl".to_string(),
+                return_type: Type::VOID,
+                generator: false,
+                blocks: vec![Block::new(&[], BlockKind::Session)],
+            },
+        }
+    }
+
+    /// Runs statements at the top level of a session, the value of a
+    /// trailing expression is returned.
+    pub fn execute(
+        &mut self,
+        session: &mut Session,
+        mut stmts: Vec<Stmt>,
+    ) -> Result<Value, String> {
+        tree::walk(&stmts, &mut |exp| self.exhaustive(exp))?;
+        self.signatures.bounds(&[], &[], &stmts)?;
+        let last = match stmts.last() {
+            Some(Stmt::Expr(_)) => match stmts.pop() {
+                Some(Stmt::Expr(exp)) => Some(exp),
+                _ => unreachable!(),
+            },
+            _ => None,
+        };
+        let frame = &mut session.frame;
+        // `return` drops every block, the variables are restored from here
+        let vars = frame.blocks[0].vars.clone();
+        frame.blocks[0].stmts = Rc::new(stmts);
+        frame.blocks[0].pc = 0;
+        let result = match self.resume(frame) {
+            Ok(_) if frame.blocks.is_empty() => {
+                Err("`return` is only allowed in functions".to_string())
+            }
+            Ok(_) => match last {
+                Some(exp) => self.eval(frame, &exp),
+                None => Ok(Value::Void),
+            },
+            Err(e) => Err(e),
+        };
+        if frame.blocks.is_empty() {
+            let mut block = Block::new(&[], BlockKind::Session);
+            block.vars = vars;
+            frame.blocks.push(block);
+        }
+        // statements after an error are skipped
+        frame.blocks.truncate(1);
+        frame.blocks[0].pc = frame.blocks[0].stmts.len();
+        result
+    }
+
+    pub fn call(&mut self, name: &str, args: Vec<Value>) -> Result<Value, String> {
+        let function = match self.functions.get(name) {
+            Some(function) => function.clone(),
+            None if BUILTINS.contains(&name) => return self.builtin(name, args),
+            None => return Err(format!("Unknown function `{name}`")),
+        };
+        if function.arguments.len() != args.len() {
+            return Err(format!(
+                "`{name}` takes {} arguments but got {}",
+                function.arguments.len(),
+                args.len()
+            ));
+        }
+        let mut vars = HashMap::new();
+        for ((arg, type_), value) in function.arguments.iter().zip(args) {
+            let value = value
+                .coerce(type_)
+                .map_err(|e| format!("{e} for `{arg}`"))?;
+            vars.insert(arg.clone(), value);
+        }
+        let mut frame = Frame {
+            name: name.to_string(),
+            return_type: function.return_type.clone(),
+            generator: function.type_ == FunctionType::Generator,
+            blocks: vec![Block {
+                stmts: function.body.clone(),
+                pc: 0,
+                kind: BlockKind::Plain,
+                vars,
+                label: None,
+            }],
+        };
+        // nothing runs until the first value is asked for, the body of a
+        // `loop fn` already is a `loop` and runs like any other function
+        if function.type_ == FunctionType::Generator {
+            return Ok(Value::Generator(Rc::new(RefCell::new(frame))));
+        }
+        let value = match self.enter(&mut frame)? {
+            Flow::Return(value) => value,
+            Flow::Yield(_) => unreachable!(),
+        };
+        match value {
+            Value::Void if name == "main" => Ok(Value::Int(0)),
+            Value::Void if !function.return_type.is_void() => Err(format!(
+                "`{name}` ended without returning a `{}`",
+                function.return_type
+            )),
+            value => value.coerce(&function.return_type),
+        }
+    }
+
+    fn builtin(&mut self, name: &str, args: Vec<Value>) -> Result<Value, String> {
+        match name {
+            "print" => {
+                let line: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                let line = line.join(" ");
+                match &mut self.output {
+                    Some(output) => {
+                        output.push_str(&line);
+                        output.push('\n');
+                    }
+                    None => println!("{line}"),
+                }
+                Ok(Value::Void)
+            }
+            "len" => match args.as_slice() {
+                [Value::Array(items)] => Ok(Value::Int(items.len() as i64)),
+                [Value::Str(s)] => Ok(Value::Int(s.chars().count() as i64)),
+                _ => Err("`len` takes an array or a string".to_string()),
+            },
+            "sleep" => match args.as_slice() {
+                [Value::Int(ms)] if *ms >= 0 => {
+                    thread::sleep(Duration::from_millis(*ms as u64));
+                    Ok(Value::Void)
+                }
+                _ => Err("`sleep` takes a number of milliseconds".to_string()),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Resumes a frame one call deeper, adding it to the trace of the errors.
+    /// A function recursing into itself is in the trace once.
+    fn enter(&mut self, frame: &mut Frame) -> Result<Flow, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(format!(
+                "More than {MAX_DEPTH} calls running at once\n    in `{}`",
+                frame.name
+            ));
+        }
+        self.depth += 1;
+        let flow = self.resume(frame);
+        self.depth -= 1;
+        flow.map_err(|e| {
+            let line = format!("\n    in `{}`", frame.name);
+            match e.ends_with(&line) {
+                true => e,
+                false => e + &line,
+            }
+        })
+    }
+
+    /// Runs a frame until it returns or yields.
+    fn resume(&mut self, frame: &mut Frame) -> Result<Flow, String> {
+        loop {
+            let block = match frame.blocks.last_mut() {
+                Some(block) => block,
+                None => return Ok(Flow::Return(Value::Void)),
+            };
+            if block.pc == block.stmts.len() {
+                match &mut block.kind {
+                    BlockKind::Plain => {
+                        frame.blocks.pop();
+                    }
+                    BlockKind::Loop => {
+                        block.pc = 0;
+                        block.vars.clear();
+                    }
+                    BlockKind::While(condition) => {
+                        let condition = condition.clone();
+                        block.vars.clear();
+                        if self.eval(frame, &condition)?.truthy()? {
+                            if let Some(block) = frame.blocks.last_mut() {
+                                block.pc = 0;
+                            }
+                        } else {
+                            frame.blocks.pop();
+                        }
+                    }
+                    BlockKind::Session => return Ok(Flow::Return(Value::Void)),
+                    BlockKind::For(for_) => {
+                        block.vars.clear();
+                        if self.advance(for_, &mut block.vars)? {
+                            block.pc = 0;
+                        } else {
+                            frame.blocks.pop();
+                        }
+                    }
+                }
+                continue;
+            }
+            let (stmts, pc) = (block.stmts.clone(), block.pc);
+            block.pc += 1;
+            match &stmts[pc] {
+                Stmt::Let(name, exp) => {
+                    let value = self.eval(frame, exp)?;
+                    if let Some(block) = frame.blocks.last_mut() {
+                        block.vars.insert(name.clone(), value);
+                    }
+                }
+                Stmt::Assign(name, exp) => {
+                    let value = self.eval(frame, exp)?;
+                    self.assign(frame, name, value)?;
+                }
+                Stmt::If(branches, otherwise) => {
+                    let mut taken = None;
+                    for (condition, stmts) in branches {
+                        if self.eval(frame, condition)?.truthy()? {
+                            taken = Some(stmts);
+                            break;
+                        }
+                    }
+                    if let Some(stmts) = taken.or(otherwise.as_ref()) {
+                        frame.blocks.push(Block::new(stmts, BlockKind::Plain));
+                    }
+                }
+                stmt @ (Stmt::Loop(_) | Stmt::While(..) | Stmt::For(..)) => {
+                    self.loop_(frame, stmt, None)?;
+                }
+                Stmt::Labeled(label, stmt) => {
+                    self.loop_(frame, stmt, Some(label))?;
+                }
+                stmt @ (Stmt::Break(label) | Stmt::Continue(label)) => {
+                    let index = frame.blocks.iter().rposition(|block| {
+                        matches!(
+                            block.kind,
+                            BlockKind::Loop | BlockKind::While(_) | BlockKind::For(..)
+                        ) && (label.is_none() || block.label == *label)
+                    });
+                    // the tree is checked for jumps out of loops
+                    let index = match index {
+                        Some(index) => index,
+                        None => return Err("There is no loop to leave".to_string()),
+                    };
+                    frame.blocks.truncate(index + 1);
+                    if let Stmt::Break(_) = stmt {
+                        frame.blocks.pop();
+                    } else {
+                        // the end of the loop decides whether it runs again
+                        let block = &mut frame.blocks[index];
+                        block.pc = block.stmts.len();
+                    }
+                }
+                Stmt::Return(Some(_)) if frame.generator => {
+                    return Err("`return` in a generator can't have a value".to_string());
+                }
+                Stmt::Return(exp) => {
+                    let value = match exp {
+                        Some(exp) => self.eval(frame, exp)?,
+                        None => Value::Void,
+                    };
+                    frame.blocks.clear();
+                    return Ok(Flow::Return(value));
+                }
+                Stmt::Yield(exp) => {
+                    if !frame.generator {
+                        return Err("`yield` is only allowed in generator functions".to_string());
+                    }
+                    let value = self.eval(frame, exp)?.coerce(&frame.return_type)?;
+                    return Ok(Flow::Yield(value));
+                }
+                Stmt::YieldFrom(exp) => {
+                    if !frame.generator {
+                        return Err(
+                            "`yield from` is only allowed in generator functions".to_string()
+                        );
+                    }
+                    // a `for` loop yielding every value, the name can't be used by the code
+                    let var = "yield from".to_string();
+                    let for_ = tree::For {
+                        index: None,
+                        var_name: var.clone(),
+                        iter: Iterable::Expr(exp.clone()),
+                        body: vec![Stmt::Yield(Expr::Var(var))],
+                    };
+                    self.for_(frame, &for_)?;
+                }
+                Stmt::Expr(exp) => {
+                    self.eval(frame, exp)?;
+                }
+            }
+        }
+    }
+
+    /// Enters a `loop`, `while` or `for`, unless it doesn't run at all.
+    fn loop_(
+        &mut self,
+        frame: &mut Frame,
+        stmt: &Stmt,
+        label: Option<&String>,
+    ) -> Result<(), String> {
+        let depth = frame.blocks.len();
+        match stmt {
+            Stmt::Loop(stmts) => frame.blocks.push(Block::new(stmts, BlockKind::Loop)),
+            Stmt::While(condition, stmts) => {
+                if self.eval(frame, condition)?.truthy()? {
+                    let kind = BlockKind::While(Rc::new(condition.clone()));
+                    frame.blocks.push(Block::new(stmts, kind));
+                }
+            }
+            Stmt::For(for_) => self.for_(frame, for_)?,
+            _ => unreachable!("only loops have labels"),
+        }
+        if let Some(block) = frame.blocks.get_mut(depth) {
+            block.label = label.cloned();
+        }
+        Ok(())
+    }
+
+    /// Enters a `for` loop, unless there are no values to iterate over.
+    fn for_(&mut self, frame: &mut Frame, x: &tree::For) -> Result<(), String> {
+        let iter = match &x.iter {
+            Iterable::Expr(exp) => match self.eval(frame, exp)? {
+                Value::Array(items) => Iter::Array(items, 0),
+                Value::Generator(gen) => Iter::Generator(gen),
+                v => return Err(format!("Can't iterate over `{}`", v.type_name())),
+            },
+            Iterable::Range(range) => {
+                let (first, cmp, bound) = range.counted();
+                let mut int = |exp: &Expr| match self.eval(frame, exp)? {
+                    Value::Int(i) => Ok(i),
+                    v => Err(format!("Ranges need `int`s, not `{}`", v.type_name())),
+                };
+                let (first, bound, step) = (int(&first)?, int(&bound)?, int(&range.step())?);
+                if step <= 0 {
+                    return Err(format!("The step of a range must be positive, not {step}"));
+                }
+                let step = if range.rev { -step } else { step };
+                Iter::Range(first, cmp, bound, step)
+            }
+        };
+        let mut for_ = ForLoop {
+            index: x.index.clone(),
+            var_name: x.var_name.clone(),
+            iter,
+            count: 0,
+        };
+        let mut vars = HashMap::new();
+        if self.advance(&mut for_, &mut vars)? {
+            let mut block = Block::new(&x.body, BlockKind::For(for_));
+            block.vars = vars;
+            frame.blocks.push(block);
+        }
+        Ok(())
+    }
+
+    /// Sets the variables of the next iteration of a `for`, `false` when
+    /// there are no values left.
+    fn advance(
+        &mut self,
+        for_: &mut ForLoop,
+        vars: &mut HashMap<String, Value>,
+    ) -> Result<bool, String> {
+        let Some(value) = self.next(&mut for_.iter)? else {
+            return Ok(false);
+        };
+        if let Some(index) = &for_.index {
+            vars.insert(index.clone(), Value::Int(for_.count));
+        }
+        for_.count += 1;
+        vars.insert(for_.var_name.clone(), value);
+        Ok(true)
+    }
+
+    /// The next value of an iterator, runs generators up to their next `yield`.
+    fn next(&mut self, iter: &mut Iter) -> Result<Option<Value>, String> {
+        match iter {
+            Iter::Array(items, index) => {
+                *index += 1;
+                Ok(items.get(*index - 1).cloned())
+            }
+            Iter::Generator(gen) => {
+                let mut frame = match gen.try_borrow_mut() {
+                    Ok(frame) => frame,
+                    Err(_) => return Err("A generator can't iterate over itself".to_string()),
+                };
+                match self.enter(&mut frame)? {
+                    Flow::Yield(value) => Ok(Some(value)),
+                    // generators end when they return or reach their end
+                    Flow::Return(_) => Ok(None),
+                }
+            }
+            Iter::Range(next, cmp, bound, step) => {
+                let more = match cmp {
+                    Operator::Less => *next < *bound,
+                    Operator::LessEquals => *next <= *bound,
+                    _ => *next >= *bound,
+                };
+                if !more {
+                    return Ok(None);
+                }
+                let value = *next;
+                // a range ending at the last `int` stops instead of overflowing
+                match next.checked_add(*step) {
+                    Some(n) => *next = n,
+                    None => *cmp = Operator::Less,
+                }
+                Ok(Some(Value::Int(value)))
+            }
+        }
+    }
+
+    /// Checks the arms of a `match` against the declared enums.
+    fn exhaustive(&self, exp: &Expr) -> Result<(), String> {
+        let Expr::Match(_, arms) = exp else {
+            return Ok(());
+        };
+        tree::exhaustive(arms.iter().map(|(pattern, _)| pattern), |name| {
+            let variants = self.enums.get(name)?;
+            Some(
+                variants
+                    .iter()
+                    .map(|v| (v.name.clone(), v.fields.len()))
+                    .collect(),
+            )
+        })
+    }
+
+    fn assign(&mut self, frame: &mut Frame, name: &str, value: Value) -> Result<(), String> {
+        for block in frame.blocks.iter_mut().rev() {
+            if let Some(var) = block.vars.get_mut(name) {
+                *var = value;
+                return Ok(());
+            }
+        }
+        match self.globals.get_mut(name) {
+            Some((var, true)) => {
+                *var = value;
+                Ok(())
+            }
+            Some((_, false)) => Err(format!("Can't assign to const `{name}`")),
+            None => Err(format!("Unknown variable `{name}`")),
+        }
+    }
+
+    fn var(&self, frame: &Frame, name: &str) -> Result<Value, String> {
+        for block in frame.blocks.iter().rev() {
+            if let Some(value) = block.vars.get(name) {
+                return Ok(value.clone());
+            }
+        }
+        match self.globals.get(name) {
+            Some((value, _)) => Ok(value.clone()),
+            None if self.functions.contains_key(name) || BUILTINS.contains(&name) => {
+                Ok(Value::Function(name.to_string()))
+            }
+            None => Err(format!("Unknown variable `{name}`")),
+        }
+    }
+
+    fn eval(&mut self, frame: &mut Frame, exp: &Expr) -> Result<Value, String> {
+        Ok(match exp {
+            Expr::Int(i) => Value::Int(*i),
+            Expr::Float(f) => Value::Float(*f),
+            Expr::Char(c) => Value::Int(*c as i64),
+            Expr::Bool(b) => Value::Bool(*b),
+            Expr::Str(s) => Value::Str(s.clone()),
+            Expr::Var(name) => self.var(frame, name)?,
+            Expr::Array(items) => {
+                let mut values = vec![];
+                for item in items {
+                    values.push(self.eval(frame, item)?);
+                }
+                Value::Array(values)
+            }
+            Expr::Call(name, args) => {
+                let mut values = vec![];
+                for arg in args {
+                    values.push(self.eval(frame, arg)?);
+                }
+                // variables holding functions are called like functions
+                match self.var(frame, name) {
+                    Ok(Value::Function(f)) => self.call(&f, values)?,
+                    Ok(v) => {
+                        return Err(format!("`{name}` is a `{}`, not a function", v.type_name()))
+                    }
+                    Err(_) => self.call(name, values)?,
+                }
+            }
+            Expr::Index(exp, index) => {
+                let (value, index) = (self.eval(frame, exp)?, self.eval(frame, index)?);
+                match (value, index) {
+                    (Value::Array(items), Value::Int(i)) => match items.get(i as usize) {
+                        Some(item) if i >= 0 => item.clone(),
+                        _ => {
+                            return Err(format!(
+                                "Index {i} is out of bounds for an array of length {}",
+                                items.len()
+                            ))
+                        }
+                    },
+                    (Value::Str(s), Value::Int(i)) => match s.chars().nth(i as usize) {
+                        Some(c) if i >= 0 => Value::Str(c.to_string()),
+                        _ => return Err(format!("Index {i} is out of bounds for `{s}`")),
+                    },
+                    (value, index) => {
+                        return Err(format!(
+                            "Can't index `{}` with `{}`",
+                            value.type_name(),
+                            index.type_name()
+                        ))
+                    }
+                }
+            }
+            Expr::Struct(name, fields) => {
+                let declared = match self.structs.get(name) {
+                    Some(declared) => declared.clone(),
+                    None => return Err(format!("Unknown struct `{name}`")),
+                };
+                if let Some((field, _)) = fields
+                    .iter()
+                    .find(|(field, _)| !declared.iter().any(|(f, _)| f == field))
+                {
+                    return Err(format!("The struct `{name}` has no field `{field}`"));
+                }
+                // in the order of the declaration, like in C++
+                let mut values = vec![];
+                for (field, type_) in declared {
+                    let exp = match fields.iter().find(|(f, _)| *f == field) {
+                        Some((_, exp)) => exp,
+                        None => {
+                            return Err(format!("Missing field `{field}` in the `{name}` literal"))
+                        }
+                    };
+                    let value = self
+                        .eval(frame, exp)?
+                        .coerce(&type_)
+                        .map_err(|e| format!("{e} for `{field}`"))?;
+                    values.push((field, value));
+                }
+                Value::Struct(name.clone(), values)
+            }
+            Expr::Field(exp, field) => match self.eval(frame, exp)? {
+                Value::Struct(name, fields) => match fields.into_iter().find(|(f, _)| f == field) {
+                    Some((_, value)) => value,
+                    None => return Err(format!("The struct `{name}` has no field `{field}`")),
+                },
+                v => return Err(format!("`{}` has no field `{field}`", v.type_name())),
+            },
+            Expr::Variant(name, variant, args) if !self.enums.contains_key(name) => {
+                let function = format!("{name}::{variant}");
+                match self.functions.get(&function) {
+                    Some(f) if f.arguments.first().is_some_and(|(arg, _)| arg == "self") => {
+                        return Err(format!(
+                            "`{function}` takes `self`, call it like `value.{variant}()`"
+                        ))
+                    }
+                    Some(_) => {}
+                    None if self.structs.contains_key(name) => {
+                        return Err(format!("The struct `{name}` has no function `{variant}`"))
+                    }
+                    None => return Err(format!("Unknown enum `{name}`")),
+                }
+                let mut values = vec![];
+                for arg in args {
+                    values.push(self.eval(frame, arg)?);
+                }
+                self.call(&function, values)?
+            }
+            Expr::Method(receiver, method, args) => {
+                let value = self.eval(frame, receiver)?;
+                let Value::Struct(name, _) = &value else {
+                    return Err(format!("`{}` has no method `{method}`", value.type_name()));
+                };
+                let function = format!("{name}::{method}");
+                let count = match self.functions.get(&function) {
+                    Some(f) if f.arguments.first().is_some_and(|(arg, _)| arg == "self") => {
+                        f.arguments.len() - 1
+                    }
+                    Some(_) => {
+                        return Err(format!(
+                            "`{function}` doesn't take `self`, call it like `{function}()`"
+                        ))
+                    }
+                    None => return Err(format!("The struct `{name}` has no method `{method}`")),
+                };
+                if args.len() != count {
+                    return Err(format!(
+                        "`{function}` takes {count} arguments but got {}",
+                        args.len()
+                    ));
+                }
+                let mut values = vec![value];
+                for arg in args {
+                    values.push(self.eval(frame, arg)?);
+                }
+                self.call(&function, values)?
+            }
+            Expr::Variant(name, variant, values) => {
+                let fields = match self.enums.get(name) {
+                    Some(variants) => match variants.iter().find(|v| v.name == *variant) {
+                        Some(v) => v.fields.clone(),
+                        None => {
+                            return Err(format!("The enum `{name}` has no variant `{variant}`"))
+                        }
+                    },
+                    None => return Err(format!("Unknown enum `{name}`")),
+                };
+                if values.len() != fields.len() {
+                    return Err(format!(
+                        "`{name}::{variant}` holds {} values but got {}",
+                        fields.len(),
+                        values.len()
+                    ));
+                }
+                let mut held = vec![];
+                for (exp, type_) in values.iter().zip(&fields) {
+                    held.push(self.eval(frame, exp)?.coerce(type_)?);
+                }
+                Value::Enum(name.clone(), variant.clone(), held)
+            }
+            Expr::Match(value, arms) => {
+                let value = self.eval(frame, value)?;
+                for (pattern, exp) in arms {
+                    let Some(vars) = bind(pattern, &value) else {
+                        continue;
+                    };
+                    if vars.is_empty() {
+                        return self.eval(frame, exp);
+                    }
+                    let mut block = Block::new(&[], BlockKind::Plain);
+                    block.vars = vars;
+                    frame.blocks.push(block);
+                    let result = self.eval(frame, exp);
+                    frame.blocks.pop();
+                    return result;
+                }
+                return Err(format!("No arm of the `match` matches `{value}`"));
+            }
+            Expr::Neg(exp) => match self.eval(frame, exp)? {
+                Value::Int(i) => Value::Int(i.wrapping_neg()),
+                Value::Float(f) => Value::Float(-f),
+                v => return Err(format!("Can't negate `{}`", v.type_name())),
+            },
+            Expr::Binary(lhs, o, rhs) => match o {
+                // the right side is only evaluated when needed, like in C
+                Operator::And => Value::Bool(
+                    self.eval(frame, lhs)?.truthy()? && self.eval(frame, rhs)?.truthy()?,
+                ),
+                Operator::Or => Value::Bool(
+                    self.eval(frame, lhs)?.truthy()? || self.eval(frame, rhs)?.truthy()?,
+                ),
+                _ => {
+                    let l = self.eval(frame, lhs)?;
+                    let r = self.eval(frame, rhs)?;
+                    value::binary(l, o, r)?
+                }
+            },
+        })
+    }
+}
+
+/// The variables an arm of a `match` sets when `pattern` matches `value`.
+fn bind(pattern: &Pattern, value: &Value) -> Option<HashMap<String, Value>> {
+    let mut vars = HashMap::new();
+    let matched = match (pattern, value) {
+        (Pattern::Wildcard, _) => true,
+        (Pattern::Binding(name), _) => {
+            vars.insert(name.clone(), value.clone());
+            true
+        }
+        (Pattern::Int(a), Value::Int(b)) => a == b,
+        (Pattern::Range(start, end), Value::Int(i)) => start <= i && i <= end,
+        (Pattern::Bool(a), Value::Bool(b)) => a == b,
+        (Pattern::Str(a), Value::Str(b)) => a == b,
+        (Pattern::Variant(name, variant, names), Value::Enum(e, v, held)) => {
+            if name != e || variant != v {
+                return None;
+            }
+            for (name, value) in names.iter().zip(held) {
+                if let Some(name) = name {
+                    vars.insert(name.clone(), value.clone());
+                }
+            }
+            true
+        }
+        _ => false,
+    };
+    matched.then_some(vars)
+}
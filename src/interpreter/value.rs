@@ -0,0 +1,240 @@
+use {
+    super::Frame,
+    crate::lexer::{
+        token::expression::Operator,
+        types::{Primitive, Type},
+    },
+    std::{cell::RefCell, fmt, rc::Rc},
+};
+
+#[derive(Clone, Debug)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    Array(Vec<Value>),
+    /// the name of the struct and its fields in declaration order
+    Struct(String, Vec<(String, Value)>),
+    /// the name of the enum, the variant and the values it holds
+    Enum(String, String, Vec<Value>),
+    /// a suspended `gen fn` call, it only runs when the next value is needed
+    Generator(Rc<RefCell<Frame>>),
+    /// a function used by name without calling it, e.g. `map(f)`
+    Function(String),
+    Void,
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Str(a), Value::Str(b)) => a == b,
+            (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Struct(a, x), Value::Struct(b, y)) => a == b && x == y,
+            (Value::Enum(a, v, x), Value::Enum(b, w, y)) => a == b && v == w && x == y,
+            (Value::Generator(a), Value::Generator(b)) => Rc::ptr_eq(a, b),
+            (Value::Function(a), Value::Function(b)) => a == b,
+            (Value::Void, Value::Void) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(i) => write!(f, "{i}"),
+            Value::Float(x) => write!(f, "{x}"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Str(s) => write!(f, "{s}"),
+            Value::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+            Value::Struct(name, fields) => {
+                write!(f, "{name} {{")?;
+                for (i, (field, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, " {field}: {value}")?;
+                }
+                write!(f, " }}")
+            }
+            Value::Enum(name, variant, values) => {
+                write!(f, "{name}::{variant}")?;
+                if !values.is_empty() {
+                    write!(f, "(")?;
+                    for (i, value) in values.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{value}")?;
+                    }
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            Value::Generator(_) => write!(f, "<generator>"),
+            Value::Function(name) => write!(f, "<fn {name}>"),
+            Value::Void => write!(f, "void"),
+        }
+    }
+}
+
+impl Value {
+    /// The name of the type of the value, the way the type checker writes
+    /// it.
+    pub fn type_name(&self) -> String {
+        let primitive = |p: Primitive| p.name().to_string();
+        match self {
+            Value::Int(_) => primitive(Primitive::Int),
+            Value::Float(_) => primitive(Primitive::F64),
+            Value::Bool(_) => primitive(Primitive::Bool),
+            Value::Str(_) => primitive(Primitive::Str),
+            Value::Array(items) => match items.first() {
+                Some(item) => format!("[{}]", item.type_name()),
+                None => "array".to_string(),
+            },
+            Value::Struct(name, _) | Value::Enum(name, ..) => name.clone(),
+            Value::Generator(_) => "generator".to_string(),
+            Value::Function(_) => "function".to_string(),
+            Value::Void => primitive(Primitive::Void),
+        }
+    }
+
+    /// Conditions work like in C, numbers are true unless they are 0.
+    pub fn truthy(&self) -> Result<bool, String> {
+        match self {
+            Value::Bool(b) => Ok(*b),
+            Value::Int(i) => Ok(*i != 0),
+            Value::Float(f) => Ok(*f != 0.0),
+            _ => Err(format!(
+                "`{}` can't be used as a condition",
+                self.type_name()
+            )),
+        }
+    }
+
+    /// Converts a value to a declared lang type, numbers convert into each
+    /// other like in C and integers wrap around to the size of their type.
+    pub fn coerce(self, type_: &Type) -> Result<Value, String> {
+        let Some(p) = type_.primitive() else {
+            return match self {
+                Value::Void => Err(format!("Expected a value of type `{type_}`")),
+                v => Ok(v),
+            };
+        };
+        let int = p.integer() || p == Primitive::Char;
+        Ok(match self {
+            Value::Int(i) if p.float() => Value::Float(i as f64),
+            Value::Bool(b) if p.float() => Value::Float(b as i64 as f64),
+            Value::Float(f) if int => wrap(f as i64, p),
+            Value::Bool(b) if int => Value::Int(b as i64),
+            Value::Int(i) if int => wrap(i, p),
+            Value::Int(i) if p == Primitive::Bool => Value::Bool(i != 0),
+            Value::Float(f) if p == Primitive::Bool => Value::Bool(f != 0.0),
+            Value::Void if p == Primitive::Void => Value::Void,
+            Value::Void => return Err(format!("Expected a value of type `{type_}`")),
+            v => v,
+        })
+    }
+}
+
+/// `i` as an integer of the size of `p`, `int` is 64 bits.
+fn wrap(i: i64, p: Primitive) -> Value {
+    Value::Int(match p {
+        Primitive::I8 => i as i8 as i64,
+        Primitive::I16 => i as i16 as i64,
+        Primitive::I32 => i as i32 as i64,
+        Primitive::U8 => i as u8 as i64,
+        Primitive::U16 => i as u16 as i64,
+        Primitive::U32 => i as u32 as i64,
+        _ => i,
+    })
+}
+
+/// Applies a binary operator, `&&`, `||` and `|>` are handled by the
+/// interpreter as they don't evaluate both sides up front.
+pub fn binary(l: Value, o: &Operator, r: Value) -> Result<Value, String> {
+    // booleans take part in arithmetic as 0 and 1, like in C
+    let number = |v: Value| match v {
+        Value::Bool(b) => Value::Int(b as i64),
+        v => v,
+    };
+    Ok(match (number(l), number(r)) {
+        (Value::Int(a), Value::Int(b)) => match o {
+            Operator::Plus => Value::Int(a.wrapping_add(b)),
+            Operator::Minus => Value::Int(a.wrapping_sub(b)),
+            Operator::Mul => Value::Int(a.wrapping_mul(b)),
+            Operator::Div | Operator::Mod if b == 0 => {
+                return Err("Division by zero".to_string());
+            }
+            Operator::Div => Value::Int(a.wrapping_div(b)),
+            Operator::Mod => Value::Int(a.wrapping_rem(b)),
+            Operator::BitAnd => Value::Int(a & b),
+            Operator::BitOr => Value::Int(a | b),
+            Operator::BitShiftLeft => Value::Int(a.wrapping_shl(b as u32)),
+            Operator::BitShiftRight => Value::Int(a.wrapping_shr(b as u32)),
+            _ => compare(o, a.cmp(&b))?,
+        },
+        (Value::Int(a), Value::Float(b)) => float(a as f64, o, b)?,
+        (Value::Float(a), Value::Int(b)) => float(a, o, b as f64)?,
+        (Value::Float(a), Value::Float(b)) => float(a, o, b)?,
+        (Value::Str(a), Value::Str(b)) => match o {
+            Operator::Plus => Value::Str(a + &b),
+            _ => compare(o, a.cmp(&b))?,
+        },
+        (l @ Value::Enum(..), r @ Value::Enum(..)) => match o {
+            Operator::Equals => Value::Bool(l == r),
+            Operator::NotEquals => Value::Bool(l != r),
+            _ => return Err(format!("{o:?} can't be used with enums")),
+        },
+        (l, r) => {
+            return Err(format!(
+                "{o:?} can't be used with `{}` and `{}`",
+                l.type_name(),
+                r.type_name()
+            ))
+        }
+    })
+}
+
+pub fn float(a: f64, o: &Operator, b: f64) -> Result<Value, String> {
+    Ok(match o {
+        Operator::Plus => Value::Float(a + b),
+        Operator::Minus => Value::Float(a - b),
+        Operator::Mul => Value::Float(a * b),
+        Operator::Div => Value::Float(a / b),
+        Operator::Mod => Value::Float(a % b),
+        Operator::Equals => Value::Bool(a == b),
+        Operator::NotEquals => Value::Bool(a != b),
+        Operator::Less => Value::Bool(a < b),
+        Operator::Greater => Value::Bool(a > b),
+        Operator::LessEquals => Value::Bool(a <= b),
+        Operator::GreaterEquals => Value::Bool(a >= b),
+        _ => return Err(format!("{o:?} needs integer operands")),
+    })
+}
+
+pub fn compare(o: &Operator, ordering: std::cmp::Ordering) -> Result<Value, String> {
+    use std::cmp::Ordering::*;
+    Ok(Value::Bool(match o {
+        Operator::Equals => ordering == Equal,
+        Operator::NotEquals => ordering != Equal,
+        Operator::Less => ordering == Less,
+        Operator::Greater => ordering == Greater,
+        Operator::LessEquals => ordering != Greater,
+        Operator::GreaterEquals => ordering != Less,
+        _ => return Err(format!("{o:?} can't be used with strings")),
+    }))
+}
@@ -0,0 +1,379 @@
+//! Type checking of the function bodies.
+//!
+//! The types of the `let`s are inferred from their values, see
+//! [`Signatures::type_of`]. The values passed to functions, methods and
+//! struct literals, the returned, yielded and assigned ones and the operands
+//! of the operators are checked against what they are used as. Values whose
+//! type can't be told, or that mention type parameters or enums, are left to
+//! the target.
+
+use {
+    super::{
+        token::{expression::Operator, Expression, Function},
+        tree::{self, Expr, Signatures},
+        types::{Primitive, Type},
+        Token,
+    },
+    std::collections::HashMap,
+};
+
+/// The types of the globals and consts of `ast` that can be told, each one
+/// can use the ones declared before it.
+pub fn globals(signatures: &Signatures, ast: &[Token]) -> HashMap<String, Type> {
+    let mut checker = Checker::new(signatures);
+    checker.globals(ast);
+    checker.globals
+}
+
+/// The type errors in `ast`, with the line and the function they are in.
+pub fn program(signatures: &Signatures, ast: &[Token]) -> Vec<String> {
+    let mut checker = Checker::new(signatures);
+    checker.globals(ast);
+    for nt in ast {
+        match nt {
+            Token::Function(x) | Token::LoopFunction(x) => checker.function(x, &x.name, false),
+            Token::GeneratorFunction(x) => checker.function(x, &x.name, true),
+            Token::Impl(x) => {
+                for function in &x.functions {
+                    let name = format!("{}::{}", x.name, function.name);
+                    checker.function(function, &name, false);
+                }
+            }
+            _ => {}
+        }
+    }
+    checker.errors
+}
+
+struct Checker<'a> {
+    signatures: &'a Signatures,
+    globals: HashMap<String, Type>,
+    errors: Vec<String>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(signatures: &'a Signatures) -> Self {
+        Checker {
+            signatures,
+            globals: HashMap::new(),
+            errors: vec![],
+        }
+    }
+
+    fn globals(&mut self, ast: &[Token]) {
+        for nt in ast {
+            if let Token::Const(x) | Token::Global(x) = nt {
+                let vars = self.globals.clone();
+                let at = format!("in `{}`", x.name);
+                if let Some(type_) = self.expression(&x.exp, &vars, &at) {
+                    self.globals.insert(x.name.clone(), type_);
+                }
+            }
+        }
+    }
+
+    /// Checks the body of `x`, named `name`. The values a generator yields
+    /// have its return type.
+    fn function(&mut self, x: &Function, name: &str, generator: bool) {
+        let mut arguments = HashMap::new();
+        for arg in &x.arguments {
+            arguments.insert(arg.name.clone(), Some(arg.type_.clone()));
+        }
+        // the variables of each block, `None` for the ones of unknown type
+        let mut scopes: Vec<HashMap<String, Option<Type>>> = vec![arguments];
+        // the depth of the `state` block of a `loop fn`, its variables outlive it
+        let mut state = None;
+        for (ln, token) in tree::tokens(&x.lines, x.start_ln) {
+            let at = format!("at line {ln} in `{name}`");
+            let vars = self.vars(&scopes);
+            match &token {
+                Token::If(if_) | Token::ElseIf(if_) => {
+                    self.condition(&if_.condition, &vars, &at);
+                    scopes.push(HashMap::new());
+                }
+                Token::While(while_) => {
+                    self.condition(&while_.condition, &vars, &at);
+                    scopes.push(HashMap::new());
+                }
+                Token::Else(_) | Token::Loop(_) => scopes.push(HashMap::new()),
+                Token::State(_) => {
+                    state = Some(scopes.len());
+                    scopes.push(HashMap::new());
+                }
+                Token::For(for_) => {
+                    let mut scope = HashMap::new();
+                    let type_ = match &for_.range {
+                        Some(range) => {
+                            let mut bounds = vec![&range.start, &range.end];
+                            bounds.extend(&range.step);
+                            for exp in bounds {
+                                let type_ = self.expression(exp, &vars, &at);
+                                match type_ {
+                                    Some(t) if !self.opaque(&t) && !integer(&t) => {
+                                        self.errors.push(format!(
+                                            "A range needs integers but got `{t}` {at}"
+                                        ));
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            Some(Type::INT)
+                        }
+                        None => match self.expression(&for_.iter_exp, &vars, &at) {
+                            Some(Type::Array(item)) => Some(*item),
+                            _ => None,
+                        },
+                    };
+                    scope.insert(for_.var_name.clone(), type_);
+                    if let Some(index) = &for_.index {
+                        scope.insert(index.clone(), Some(Type::INT));
+                    }
+                    scopes.push(scope);
+                }
+                Token::End(_) if scopes.len() > 1 => {
+                    let scope = scopes.pop().unwrap();
+                    if state == Some(scopes.len()) {
+                        state = None;
+                        scopes.last_mut().unwrap().extend(scope);
+                    }
+                }
+                Token::Var(let_) => {
+                    let type_ = self.expression(&let_.exp, &vars, &at);
+                    scopes.last_mut().unwrap().insert(let_.name.clone(), type_);
+                }
+                Token::Assign(assign) => {
+                    let found = self.expression(&assign.exp, &vars, &at);
+                    if let (Some(type_), Some(found)) = (vars.get(&assign.var), found) {
+                        if !self.assignable(type_, &found) {
+                            self.errors.push(format!(
+                                "`{}` is `{type_}` but is assigned `{found}` {at}",
+                                assign.var
+                            ));
+                        }
+                    }
+                }
+                Token::Return(exp) if !exp.is_empty() => {
+                    let found = self.expression(exp, &vars, &at);
+                    match found {
+                        Some(found)
+                            if !generator
+                                && !x.return_type.is_void()
+                                && !self.assignable(&x.return_type, &found) =>
+                        {
+                            self.errors.push(format!(
+                                "`{name}` returns `{}` but got `{found}` {at}",
+                                x.return_type
+                            ));
+                        }
+                        _ => {}
+                    }
+                }
+                Token::Yield(exp) => match self.expression(exp, &vars, &at) {
+                    Some(found) if generator && !self.assignable(&x.return_type, &found) => {
+                        self.errors.push(format!(
+                            "`{name}` yields `{}` but got `{found}` {at}",
+                            x.return_type
+                        ));
+                    }
+                    _ => {}
+                },
+                Token::YieldFrom(exp) | Token::Expression(exp) => {
+                    self.expression(exp, &vars, &at);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The types of the variables in `scopes`, the inner ones hide the outer
+    /// ones.
+    fn vars(&self, scopes: &[HashMap<String, Option<Type>>]) -> HashMap<String, Type> {
+        let mut vars = self.globals.clone();
+        for scope in scopes {
+            for (name, type_) in scope {
+                match type_ {
+                    Some(type_) => vars.insert(name.clone(), type_.clone()),
+                    None => vars.remove(name),
+                };
+            }
+        }
+        vars
+    }
+
+    /// Checks the values in `exp` and gives its type, when it can be told.
+    /// Expressions the tree can't be built for are left to the target.
+    fn expression(
+        &mut self,
+        exp: &Expression,
+        vars: &HashMap<String, Type>,
+        at: &str,
+    ) -> Option<Type> {
+        let exp = tree::expression(exp).ok()?;
+        let mut errors = vec![];
+        let _ = tree::walk_expr(&exp, &mut |exp| {
+            errors.extend(self.operands(exp, vars));
+            errors.extend(self.passed(exp, vars));
+            Ok(())
+        });
+        self.errors
+            .extend(errors.into_iter().map(|e| format!("{e} {at}")));
+        self.signatures.type_of(&exp, vars)
+    }
+
+    fn condition(&mut self, exp: &Expression, vars: &HashMap<String, Type>, at: &str) {
+        match self.expression(exp, vars, at) {
+            Some(t) if !self.opaque(&t) && !number(&t) && t != Type::BOOL => {
+                self.errors
+                    .push(format!("A condition needs a `bool` but got `{t}` {at}"));
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks the operands of an operator.
+    fn operands(&self, exp: &Expr, vars: &HashMap<String, Type>) -> Option<String> {
+        let type_of = |exp| {
+            let type_ = self.signatures.type_of(exp, vars)?;
+            (!self.opaque(&type_)).then_some(type_)
+        };
+        match exp {
+            Expr::Neg(value) => {
+                let t = type_of(value)?;
+                (!number(&t)).then(|| format!("`-` can't be used with `{t}`"))
+            }
+            Expr::Binary(l, o, r) => {
+                let (l, r) = (type_of(l)?, type_of(r)?);
+                let str_ = Type::Primitive(Primitive::Str);
+                let valid = match o {
+                    Operator::Plus => (number(&l) && number(&r)) || (l == str_ && r == str_),
+                    Operator::Minus | Operator::Mul | Operator::Div | Operator::Mod => {
+                        number(&l) && number(&r)
+                    }
+                    Operator::BitAnd
+                    | Operator::BitOr
+                    | Operator::BitShiftLeft
+                    | Operator::BitShiftRight => {
+                        let bits = |t: &Type| integer(t) || *t == Type::BOOL;
+                        bits(&l) && bits(&r)
+                    }
+                    Operator::Less
+                    | Operator::Greater
+                    | Operator::LessEquals
+                    | Operator::GreaterEquals => {
+                        (number(&l) && number(&r)) || (l == str_ && r == str_)
+                    }
+                    Operator::Equals | Operator::NotEquals => (number(&l) && number(&r)) || l == r,
+                    Operator::And | Operator::Or => {
+                        let truth = |t: &Type| number(t) || *t == Type::BOOL;
+                        truth(&l) && truth(&r)
+                    }
+                    Operator::Pipe => true,
+                };
+                (!valid).then(|| format!("`{}` can't be used with `{l}` and `{r}`", symbol(o)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks the number and the types of the values passed to a function,
+    /// a method or a struct literal.
+    fn passed(&self, exp: &Expr, vars: &HashMap<String, Type>) -> Vec<String> {
+        let Some((name, params, values)) = self.signatures.parameters(exp, vars) else {
+            return vec![];
+        };
+        if !matches!(exp, Expr::Struct(..)) && params.len() != values.len() {
+            return vec![format!(
+                "`{name}` takes {} arguments but got {}",
+                params.len(),
+                values.len()
+            )];
+        }
+        // the type parameters that can be told, the others are left out
+        let types = (self.signatures.type_arguments(exp, vars)).unwrap_or_default();
+        let mut errors = vec![];
+        for (param, value) in params.iter().zip(values) {
+            let expected = param.type_.substitute(&types);
+            let Some(found) = self.signatures.type_of(value, vars) else {
+                continue;
+            };
+            if !self.assignable(&expected, &found) {
+                errors.push(format!(
+                    "`{}` of `{name}` is `{expected}` but got `{found}`",
+                    param.name
+                ));
+            }
+        }
+        errors
+    }
+
+    /// Whether a value of type `found` can be used as a `expected`. Numbers
+    /// are converted into each other, `T?` takes a `T`. Traits are checked
+    /// by [`Signatures::bounds`].
+    fn assignable(&self, expected: &Type, found: &Type) -> bool {
+        if expected == found || self.opaque(expected) || self.opaque(found) {
+            return true;
+        }
+        match (expected, found) {
+            (Type::Primitive(_), Type::Primitive(_)) => number(expected) && number(found),
+            (Type::Optional(expected), Type::Optional(found))
+            | (Type::Array(expected), Type::Array(found)) => self.assignable(expected, found),
+            (Type::Optional(expected), found) => self.assignable(expected, found),
+            (Type::Impl(_) | Type::Dyn(_), _) => true,
+            (Type::Function(params, result), Type::Function(args, returned)) => {
+                params.len() == args.len()
+                    && (params.iter().zip(args)).all(|(p, a)| self.assignable(a, p))
+                    && self.assignable(result, returned)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether `type_` mentions a type parameter, an enum or a type that
+    /// isn't known, which aren't checked.
+    fn opaque(&self, type_: &Type) -> bool {
+        let mut opaque = false;
+        type_.visit(&mut |t| {
+            if let Type::Named(name, _) = t {
+                opaque |= self.signatures.struct_(name).is_none();
+            }
+        });
+        opaque
+    }
+}
+
+/// Integers, floats and `char`s, which convert into each other.
+fn number(type_: &Type) -> bool {
+    type_
+        .primitive()
+        .is_some_and(|p| p.integer() || p.float() || p == Primitive::Char)
+}
+
+fn integer(type_: &Type) -> bool {
+    type_
+        .primitive()
+        .is_some_and(|p| p.integer() || p == Primitive::Char)
+}
+
+/// How `o` is written.
+pub fn symbol(o: &Operator) -> &'static str {
+    match o {
+        Operator::Plus => "+",
+        Operator::Minus => "-",
+        Operator::Mul => "*",
+        Operator::Div => "/",
+        Operator::Mod => "%",
+        Operator::BitShiftLeft => "<<",
+        Operator::BitShiftRight => ">>",
+        Operator::BitAnd => "&",
+        Operator::BitOr => "|",
+        Operator::Equals => "==",
+        Operator::NotEquals => "!=",
+        Operator::Less => "<",
+        Operator::Greater => ">",
+        Operator::LessEquals => "<=",
+        Operator::GreaterEquals => ">=",
+        Operator::And => "&&",
+        Operator::Or => "||",
+        Operator::Pipe => "|>",
+    }
+}
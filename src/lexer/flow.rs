@@ -0,0 +1,164 @@
+//! Control flow of the function bodies.
+//!
+//! A function returning a value has to return on every path through it, the
+//! statements after a `return`, `break` or `continue` of the same block never
+//! run, `yield` only goes in a `gen fn` and a `void` function returns no
+//! value.
+
+use super::{
+    token::Function,
+    tree::{self, Expr, Stmt},
+    FunctionType, Token,
+};
+
+/// The control flow errors of the functions in `ast`.
+pub fn program(ast: &[Token]) -> Vec<String> {
+    let mut errors = vec![];
+    for nt in ast {
+        match nt {
+            Token::Function(x) => function(x, &x.name, FunctionType::Function, &mut errors),
+            Token::LoopFunction(x) => function(x, &x.name, FunctionType::Loop, &mut errors),
+            Token::GeneratorFunction(x) => {
+                function(x, &x.name, FunctionType::Generator, &mut errors);
+            }
+            Token::Impl(x) => {
+                for f in &x.functions {
+                    let name = format!("{}::{}", x.name, f.name);
+                    function(f, &name, FunctionType::Function, &mut errors);
+                }
+            }
+            _ => {}
+        }
+    }
+    errors
+}
+
+/// Where a block is at: still running, right after a jump, or past the
+/// statement already reported as unreachable.
+#[derive(Clone, Copy, PartialEq)]
+enum Block {
+    Running,
+    Jumped(&'static str),
+    Reported,
+}
+
+/// Checks the function `x` named `name`. The return type of a generator is
+/// the one of the values it yields, it ends with a bare `return` or its last
+/// statement.
+fn function(x: &Function, name: &str, type_: FunctionType, errors: &mut Vec<String>) {
+    let generator = type_ == FunctionType::Generator;
+    statements(x, name, generator, errors);
+    if generator || x.return_type.is_void() {
+        return;
+    }
+    let body = match type_ {
+        FunctionType::Loop => tree::loop_function(x),
+        _ => tree::function(x),
+    };
+    // a body that doesn't build is reported by the other passes
+    if body.is_ok_and(|body| !ends(&body)) {
+        errors.push(format!(
+            "`{name}` returns `{}` but can reach its end without a `return` at line {} in `{name}`",
+            x.return_type,
+            x.start_ln + x.lines.len() as i32 + 1
+        ));
+    }
+}
+
+/// Checks the statements of `x` in the order they are written: the ones
+/// following a jump in the same block, the `yield`s and the `return`s.
+fn statements(x: &Function, name: &str, generator: bool, errors: &mut Vec<String>) {
+    let mut blocks = vec![Block::Running];
+    for (ln, token) in tree::tokens(&x.lines, x.start_ln) {
+        let at = format!("at line {ln} in `{name}`");
+        match &token {
+            Token::End(_) => {
+                if blocks.len() > 1 {
+                    blocks.pop();
+                }
+                continue;
+            }
+            // the branch after the `}` of the one before
+            Token::ElseIf(_) | Token::Else(_) => {
+                blocks.push(Block::Running);
+                continue;
+            }
+            _ => {}
+        }
+        let block = blocks.last_mut().unwrap();
+        if let Block::Jumped(jump) = *block {
+            errors.push(format!("Unreachable code after `{jump}` {at}"));
+            *block = Block::Reported;
+        }
+        match &token {
+            Token::If(_) | Token::While(_) | Token::For(_) | Token::Loop(_) | Token::State(_) => {
+                blocks.push(Block::Running);
+            }
+            Token::Return(exp) => {
+                match (x.return_type.is_void(), exp.is_empty()) {
+                    _ if generator => {}
+                    (true, false) => {
+                        errors.push(format!("`{name}` returns `void` but got a value {at}"));
+                    }
+                    (false, true) => errors.push(format!(
+                        "`{name}` returns `{}` but got no value {at}",
+                        x.return_type
+                    )),
+                    _ => {}
+                }
+                jumped(&mut blocks, "return");
+            }
+            Token::Break(_) => jumped(&mut blocks, "break"),
+            Token::Continue(_) => jumped(&mut blocks, "continue"),
+            Token::Yield(_) | Token::YieldFrom(_) if !generator => {
+                errors.push(format!("`yield` outside of a `gen fn` {at}"));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn jumped(blocks: &mut [Block], jump: &'static str) {
+    let block = blocks.last_mut().unwrap();
+    if *block == Block::Running {
+        *block = Block::Jumped(jump);
+    }
+}
+
+/// Whether running `stmts` never gets past their end: one of them returns,
+/// jumps, or is a loop nothing breaks out of.
+fn ends(stmts: &[Stmt]) -> bool {
+    stmts.iter().any(|stmt| match stmt {
+        Stmt::Return(_) | Stmt::Break(_) | Stmt::Continue(_) => true,
+        Stmt::If(branches, Some(otherwise)) => {
+            branches.iter().all(|(_, body)| ends(body)) && ends(otherwise)
+        }
+        Stmt::Loop(body) | Stmt::While(Expr::Bool(true), body) => !breaks(body, None, false),
+        Stmt::Labeled(label, stmt) => match &**stmt {
+            Stmt::Loop(body) | Stmt::While(Expr::Bool(true), body) => {
+                !breaks(body, Some(label), false)
+            }
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+/// Whether a `break` in `stmts` leaves the loop labelled `label`, `nested` in
+/// a loop inside of it only the `break`s with its label do.
+fn breaks(stmts: &[Stmt], label: Option<&str>, nested: bool) -> bool {
+    stmts.iter().any(|stmt| match stmt {
+        Stmt::Break(None) => !nested,
+        Stmt::Break(Some(to)) => Some(to.as_str()) == label,
+        Stmt::If(branches, otherwise) => {
+            (branches.iter()).any(|(_, body)| breaks(body, label, nested))
+                || otherwise
+                    .as_ref()
+                    .is_some_and(|body| breaks(body, label, nested))
+        }
+        Stmt::Loop(body) | Stmt::While(_, body) => breaks(body, label, true),
+        Stmt::For(for_) => breaks(&for_.body, label, true),
+        Stmt::Labeled(_, stmt) => breaks(std::slice::from_ref(&**stmt), label, true),
+        _ => false,
+    })
+}
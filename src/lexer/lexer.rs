@@ -9,7 +9,7 @@ use {
     std::fmt::Write,
 };
 use {
-    crate::{lexer::*, lexer_error, log, printx, PrintT, notwasm},
+    crate::{lexer::{types::Type, *}, lexer_error, lexer_errors, log, printx, PrintT, notwasm},
     rand::Rng,
     std::vec,
 };
@@ -32,8 +32,19 @@ impl Lexer {
                 square: 0,
                 braces: 0,
             },
+            quiet: false,
+            errors: 0,
         };
     }
+    /// A lexer that parses without logging or drawing a progress bar.
+    pub fn quiet() -> Lexer {
+        Lexer {
+            #[cfg(not(target_arch = "wasm32"))]
+            progress_bar: ProgressBar::hidden(),
+            quiet: true,
+            ..Lexer::new()
+        }
+    }
     // --------------------------------
     // Progressbar setup
     // --------------------------------
@@ -80,8 +91,7 @@ impl Lexer {
                                 temp_string = String::new();
                             }
                         }
-                        '(' | ')' | '{' | '}' | '[' | ']' | ',' | '=' | '+' | '-' | '*' | '"'
-                        | '#' => {
+                        '(' | ')' | '{' | '}' | '[' | ']' | ',' | '+' | '-' | '*' | '%' | '#' => {
                             if temp_string.len() > 0 {
                                 self.strings[i].push(temp_string);
                                 temp_string = String::new();
@@ -89,6 +99,91 @@ impl Lexer {
 
                             self.strings[i].push(char.to_string());
                         }
+                        '=' => {
+                            if !temp_string.is_empty() {
+                                self.strings[i].push(temp_string);
+                                temp_string = String::new();
+                            }
+
+                            if chars.peek() == Some(&'=') {
+                                chars.next();
+                                self.strings[i].push("==".to_string());
+                            } else if chars.peek() == Some(&'>') {
+                                // the arrow of `match` arms
+                                chars.next();
+                                self.strings[i].push("=>".to_string());
+                            } else {
+                                self.strings[i].push(char.to_string());
+                            }
+                        }
+                        // a string keeps its text as it is, quotes included
+                        '"' => {
+                            if !temp_string.is_empty() {
+                                self.strings[i].push(temp_string);
+                                temp_string = String::new();
+                            }
+
+                            let mut literal = char.to_string();
+                            while let Some(c) = chars.next() {
+                                literal.push(c);
+                                match c {
+                                    '\\' => literal.extend(chars.next()),
+                                    '"' => break,
+                                    _ => {}
+                                }
+                            }
+                            self.strings[i].push(literal);
+                        }
+                        // a char like `'<'` or `'\n'`, other quotes start labels
+                        '\'' if temp_string.is_empty() && char_literal(chars.clone()) > 0 => {
+                            temp_string.push(char);
+                            for _ in 0..char_literal(chars.clone()) {
+                                temp_string.extend(chars.next());
+                            }
+                            self.strings[i].push(temp_string);
+                            temp_string = String::new();
+                        }
+                        // `<`, `<=` and `<<`, and the same for `>`, `|` and `&`
+                        '<' | '>' | '|' | '&' => {
+                            if !temp_string.is_empty() {
+                                self.strings[i].push(temp_string);
+                                temp_string = String::new();
+                            }
+
+                            let mut operator = char.to_string();
+                            operator.extend(chars.next_if(|&c| {
+                                c == char
+                                    || (c == '=' && ['<', '>'].contains(&char))
+                                    || (c == '>' && char == '|')
+                            }));
+                            self.strings[i].push(operator);
+                        }
+                        // `!=`, a lone `!` stays in the word
+                        '!' if chars.peek() == Some(&'=') => {
+                            if !temp_string.is_empty() {
+                                self.strings[i].push(temp_string);
+                                temp_string = String::new();
+                            }
+
+                            chars.next();
+                            self.strings[i].push("!=".to_string());
+                        }
+                        // `..` and `..=` of ranges, a single `.` stays in numbers and
+                        // method names
+                        '.' if chars.peek() == Some(&'.') => {
+                            if !temp_string.is_empty() {
+                                self.strings[i].push(temp_string);
+                                temp_string = String::new();
+                            }
+
+                            chars.next();
+                            if chars.peek() == Some(&'=') {
+                                chars.next();
+                                self.strings[i].push("..=".to_string());
+                            } else {
+                                self.strings[i].push("..".to_string());
+                            }
+                        }
                         '/' => {
                             if temp_string.len() > 0 {
                                 self.strings[i].push(temp_string);
@@ -120,12 +215,15 @@ impl Lexer {
     // --------------------------------
     pub fn parse(&mut self, code: String) {
         use super::token::*;
-        
-        log!(Lexer, "Started parsing tokens...");
+
+        if !self.quiet {
+            log!(Lexer, "Started parsing tokens...");
+        }
         notwasm! {
             self.setup_pb();
         }
 
+        let before = lexer_errors();
         let mut rng = rand::thread_rng();
         notwasm! {self.progress_bar.set_position(0);}
         self.top_level(code);
@@ -172,14 +270,53 @@ impl Lexer {
                     });
                     self.ast.push(f);
                 }
+                Token::Impl(x) => {
+                    let functions = (x.functions.iter())
+                        .map(|func| Function {
+                            lines: self.low_level(
+                                func.tmp_lines.clone(),
+                                func.start_ln,
+                                rng.gen_range(0..=i32::MAX),
+                            ),
+                            ..func.clone()
+                        })
+                        .collect();
+                    self.ast.push(Token::Impl(Impl { functions, ..x }));
+                }
                 _ => {
                     self.ast.push(node.to_owned());
                 }
             }
         }
         notwasm! {self.progress_bar.set_position(10000);}
+        self.errors = lexer_errors() - before;
         if self.brackets.braces > 0 {}
     }
+    /// Counts the brackets left open at the end of `code`, ignoring strings
+    /// and comments. Negative counts mean there are more closing brackets.
+    pub fn count_brackets(&mut self, code: String) -> Brackets {
+        self.generate_strings(code);
+        self.brackets = Brackets {
+            round: 0,
+            square: 0,
+            braces: 0,
+        };
+        for line in &self.strings {
+            for s in line {
+                match s.as_str() {
+                    "//" => break,
+                    "(" => self.brackets.round += 1,
+                    ")" => self.brackets.round -= 1,
+                    "[" => self.brackets.square += 1,
+                    "]" => self.brackets.square -= 1,
+                    "{" => self.brackets.braces += 1,
+                    "}" => self.brackets.braces -= 1,
+                    _ => {}
+                }
+            }
+        }
+        self.brackets.clone()
+    }
     pub fn top_level(&mut self, code: String) {
         use super::token::*;
         self.generate_strings(code); // generates a 2D string vector
@@ -190,6 +327,7 @@ impl Lexer {
         let mut line_number = 0;
         let mut p = 1;
         let mut fn_type = FunctionType::Function;
+        let mut impl_: Option<Open> = None;
         let len = self.strings.len();
         while line_iter.peek().is_some() {
             notwasm! {
@@ -225,9 +363,13 @@ impl Lexer {
                                 log!(Syntax, "\ngen fn `name` (`arguments`) {\n`code`\n}");
                             }
                         };
-                        if let (Some(fn_name), Some(fn_op_br)) =
-                            (string_iter.next(), string_iter.next())
-                        {
+                        let fn_name = (string_iter.next())
+                            .map(|first| type_name(&angled(first, &mut string_iter)));
+                        if let (Some(fn_name), Some(fn_op_br)) = (fn_name, string_iter.next()) {
+                            let Some((fn_name, generics)) = generics(&fn_name, line_number) else {
+                                syntax_fn();
+                                continue;
+                            };
                             if fn_name == "(" {
                                 log!(
                                     LexerError,
@@ -243,17 +385,26 @@ impl Lexer {
                                  *  Parse Function Arguments
                                  * --------------------------------------------------*/
                                 let mut arguments: Vec<String> = vec![];
+                                // the brackets of function types like `fn(int): int`
+                                let mut depth = 0;
                                 while string_iter.peek().is_some() {
                                     if string_iter.peek().unwrap().as_str() == ")" {
                                         self.brackets.round -= 1;
-                                        string_iter.next();
-                                        break;
+                                        if depth == 0 {
+                                            string_iter.next();
+                                            break;
+                                        }
+                                        depth -= 1;
                                     } else if string_iter.peek().unwrap().as_str() == "(" {
                                         self.brackets.round += 1;
-                                        log!(
-                                            LexerError,
-                                            f("Unexpected opening bracket at line {line_number}")
-                                        );
+                                        if arguments.last().is_some_and(|s| s == "fn") {
+                                            depth += 1;
+                                        } else {
+                                            log!(
+                                                LexerError,
+                                                f("Unexpected opening bracket at line {line_number}")
+                                            );
+                                        }
                                     }
                                     arguments.push(string_iter.next().unwrap().to_string());
                                 }
@@ -265,7 +416,25 @@ impl Lexer {
                                 let mut arg_iter = arguments.iter().peekable();
                                 while arg_iter.peek().is_some() {
                                     let x = arg_iter.next().unwrap();
-                                    if x.is_empty() {
+                                    if x == "self" {
+                                        match &impl_ {
+                                            Some(open) if args.is_empty() => {
+                                                args.push(Arg {
+                                                    name: x.to_owned(),
+                                                    type_: Type::named(&open.name),
+                                                });
+                                            }
+                                            Some(_) => {
+                                                log!(LexerError, f("`self` has to be the first argument, at line {line_number}"));
+                                            }
+                                            None => {
+                                                log!(LexerError, f("`self` only goes in the functions of an `impl`, at line {line_number}"));
+                                            }
+                                        }
+                                        if arg_iter.peek() == Some(&&",".to_string()) {
+                                            arg_iter.next();
+                                        }
+                                    } else if x.is_empty() {
                                         log!(
                                             LexerError,
                                             f("Expected argument `name` at line {line_number}")
@@ -274,7 +443,7 @@ impl Lexer {
                                         let mut tmp = x.chars();
                                         tmp.next_back();
                                         let name = tmp.collect();
-                                        let mut type_ = String::new();
+                                        let mut type_ = vec![];
                                         if !x.ends_with(":") {
                                             log!(
                                                 LexerError,
@@ -284,44 +453,126 @@ impl Lexer {
                                                 )
                                             );
                                         } else {
-                                            while arg_iter.peek() != Some(&&",".to_string())
-                                                && arg_iter.peek().is_some()
+                                            // the commas of `pair<A, B>` are part of it
+                                            let mut depth = 0;
+                                            while let Some(s) =
+                                                arg_iter.next_if(|s| depth > 0 || s.as_str() != ",")
                                             {
-                                                type_.push_str(arg_iter.next().unwrap());
+                                                depth += angles(s);
+                                                type_.push(s);
                                             }
                                             if type_.is_empty() {
                                                 log!(LexerError, f("Expected argument `type` at line {line_number}"));
                                             } else {
                                                 arg_iter.next();
-                                                args.push(Arg { name, type_ })
+                                                match Type::parse(&type_name(&type_)) {
+                                                    Ok(type_) => args.push(Arg { name, type_ }),
+                                                    Err(e) => {
+                                                        log!(
+                                                            LexerError,
+                                                            f("{e} at line {line_number}")
+                                                        );
+                                                    }
+                                                }
                                             }
                                         }
                                     }
                                 }
 
-                                if let Some(op_braces) = string_iter.next() {
-                                    if op_braces != "{" && op_braces != ":" {
+                                // the functions of a trait end after their
+                                // return type
+                                let signature = impl_.as_ref().is_some_and(|open| open.trait_body);
+                                let op_braces =
+                                    string_iter.next().filter(|s| !(signature && s.is_empty()));
+                                if op_braces.is_none() && signature {
+                                    self.tmp_ast.push(Token::Function(Function {
+                                        name: fn_name.to_owned(),
+                                        arguments: args,
+                                        return_type: Type::VOID,
+                                        lines: vec![],
+                                        tmp_lines: vec![],
+                                        start_ln: line_number,
+                                        every: None,
+                                        generics,
+                                    }));
+                                } else if let Some(op_braces) = op_braces {
+                                    if op_braces != "{" && op_braces != ":" && op_braces != "every"
+                                    {
                                         log!(LexerError, f("Expected opening braces or colon but found `{op_braces}` at line {line_number}"));
                                     } else {
                                         /* --------------------------------------------------
                                          *  Parse Function Return Type
                                          * --------------------------------------------------*/
-                                        let mut return_type = "void".to_string();
-                                        if op_braces == ":" {
+                                        let mut return_type = Type::VOID;
+                                        let mut op_braces = Some(op_braces);
+                                        if op_braces.is_some_and(|s| s == ":") {
                                             // Check for return type
-                                            if let Some(type_) = string_iter.next() {
-                                                return_type = type_.to_owned();
-                                            } else {
+                                            let type_: Vec<&String> = std::iter::from_fn(|| {
+                                                string_iter.next_if(|s| {
+                                                    !["{", "every", ""].contains(&s.as_str())
+                                                })
+                                            })
+                                            .collect();
+                                            if type_.is_empty() {
                                                 log!(
                                                     LexerError,
                                                     f("Expected return type at line {line_number}")
                                                 );
+                                            } else {
+                                                match Type::parse(&type_name(&type_)) {
+                                                    Ok(type_) => return_type = type_,
+                                                    Err(e) => {
+                                                        log!(
+                                                            LexerError,
+                                                            f("{e} at line {line_number}")
+                                                        );
+                                                    }
+                                                }
                                             }
-                                            if let Some(op_braces_) = string_iter.next() {
-                                                if op_braces_ != "{" {
-                                                    log!(LexerError, f("Expected opening braces found `{op_braces_}` at line {line_number}"));
+                                            op_braces = string_iter.next();
+                                        }
+                                        if signature {
+                                            match op_braces {
+                                                Some(_) => {
+                                                    log!(LexerError, f("The functions of a `trait` have no body, at line {line_number}"));
+                                                }
+                                                None => {
+                                                    self.tmp_ast.push(Token::Function(Function {
+                                                        name: fn_name.to_owned(),
+                                                        arguments: args,
+                                                        return_type,
+                                                        lines: vec![],
+                                                        tmp_lines: vec![],
+                                                        start_ln: line_number,
+                                                        every: None,
+                                                        generics: generics.clone(),
+                                                    }));
                                                 }
                                             }
+                                            continue;
+                                        }
+                                        /* --------------------------------------------------
+                                         *  Parse the period of `loop fn ... every 16ms`
+                                         * --------------------------------------------------*/
+                                        let mut every = None;
+                                        if op_braces.is_some_and(|s| s == "every") {
+                                            match string_iter.next().and_then(|s| period(s)) {
+                                                Some(ms) if fn_type == FunctionType::Loop => {
+                                                    every = Some(ms);
+                                                }
+                                                Some(_) => {
+                                                    log!(LexerError, f("Only a `loop fn` can run `every` period, at line {line_number}"));
+                                                }
+                                                None => {
+                                                    log!(LexerError, f("Expected a period like `16ms` or `1s` after `every` at line {line_number}"));
+                                                }
+                                            }
+                                            op_braces = string_iter.next();
+                                        }
+                                        if let Some(op_braces_) = op_braces {
+                                            if op_braces_ != "{" {
+                                                log!(LexerError, f("Expected opening braces found `{op_braces_}` at line {line_number}"));
+                                            }
                                         }
                                         /* --------------------------------------------------
                                          *  Parse Function Body
@@ -329,20 +580,58 @@ impl Lexer {
                                         self.brackets.braces += 1;
                                         let mut fn_body: Vec<Vec<String>> = vec![];
                                         let mut function_parsed: bool = false;
+                                        let start_ln = line_number;
                                         while line_iter.peek().is_some() {
                                             if function_parsed {
                                                 break;
                                             }
                                             string_iter =
                                                 line_iter.next().unwrap().iter().peekable();
+                                            line_number += 1;
                                             let mut temp = vec![];
-                                            while string_iter.peek().is_some() {
+                                            // braces opened on this line, like the
+                                            // ones of a struct literal or `match`
+                                            let mut open = 0;
+                                            // lines joined to this one while the
+                                            // arms of its `match` are open
+                                            let mut joined = 0;
+                                            loop {
+                                                if string_iter.peek().is_none() {
+                                                    let arms = open > 0
+                                                        && temp.iter().any(|s| s == "match");
+                                                    match line_iter.peek() {
+                                                        Some(_) if arms => {
+                                                            string_iter = line_iter
+                                                                .next()
+                                                                .unwrap()
+                                                                .iter()
+                                                                .peekable();
+                                                            joined += 1;
+                                                            line_number += 1;
+                                                            // the empty value left by a line
+                                                            // ending in a bracket
+                                                            if temp.last().is_some_and(
+                                                                |s: &String| s.is_empty(),
+                                                            ) {
+                                                                temp.pop();
+                                                            }
+                                                            continue;
+                                                        }
+                                                        _ => break,
+                                                    }
+                                                }
                                                 let current_string = string_iter.next().unwrap();
                                                 match current_string.as_str() {
                                                     "{" => {
                                                         self.brackets.braces += 1;
+                                                        open += 1;
                                                         temp.push("{".to_string());
                                                     }
+                                                    "}" if open > 0 => {
+                                                        self.brackets.braces -= 1;
+                                                        open -= 1;
+                                                        temp.push("}".to_string());
+                                                    }
                                                     "}" => {
                                                         self.brackets.braces -= 1;
                                                         if self.brackets.braces == 0 {
@@ -352,7 +641,9 @@ impl Lexer {
                                                                 return_type: return_type.clone(),
                                                                 lines: vec![],
                                                                 tmp_lines: fn_body.clone(),
-                                                                start_ln: line_number,
+                                                                start_ln,
+                                                                every,
+                                                                generics: generics.clone(),
                                                             };
                                                             match fn_type {
                                                                 FunctionType::Function => {
@@ -379,7 +670,7 @@ impl Lexer {
                                                             fn_type = FunctionType::Function;
                                                             break;
                                                         } else {
-                                                            fn_body.push(vec!["}".to_string()]);
+                                                            temp.push("}".to_string());
                                                         }
                                                     }
                                                     _ => {
@@ -388,6 +679,8 @@ impl Lexer {
                                                 }
                                             }
                                             fn_body.push(temp);
+                                            // keeps the line numbers of the lines after
+                                            fn_body.extend((0..joined).map(|_| vec![]));
                                         }
                                         if !function_parsed {
                                             log!(
@@ -408,6 +701,204 @@ impl Lexer {
                             syntax_fn();
                         }
                     }
+                    "struct" | "enum" => {
+                        let syntax = || {
+                            if _str == "struct" {
+                                log!(Syntax, "\nstruct `name` {\n    `field`: `type`,\n}");
+                            } else {
+                                log!(Syntax, "\nenum `name` {\n    `variant`,\n    `variant`(`type`, ...),\n}");
+                            }
+                        };
+                        let name = (string_iter.next())
+                            .map(|first| type_name(&angled(first, &mut string_iter)));
+                        let (Some(name), Some(open)) = (name, string_iter.next()) else {
+                            log!(LexerError, f("Expected {_str} name at line {line_number}"));
+                            syntax();
+                            continue;
+                        };
+                        let Some((name, generics)) = generics(&name, line_number) else {
+                            syntax();
+                            continue;
+                        };
+                        if _str == "enum" && !generics.is_empty() {
+                            log!(LexerError, f("Only functions and structs have type parameters, not the enum `{name}`, at line {line_number}"));
+                            continue;
+                        }
+                        if open != "{" {
+                            log!(LexerError, f("Expected opening braces after the {_str} name but got `{open}` at line {line_number}"));
+                            syntax();
+                            continue;
+                        }
+                        let start_ln = line_number;
+                        /* --------------------------------------------------
+                         *  Collect the fields or variants up to the closing
+                         *  braces, which may be on a later line
+                         * --------------------------------------------------*/
+                        let mut strings: Vec<&String> = vec![];
+                        let mut closed = false;
+                        loop {
+                            for s in string_iter.by_ref() {
+                                match s.as_str() {
+                                    "}" => {
+                                        closed = true;
+                                        break;
+                                    }
+                                    // the rest of the line is a comment
+                                    "//" => break,
+                                    "" => {}
+                                    _ => strings.push(s),
+                                }
+                            }
+                            if closed {
+                                break;
+                            }
+                            match line_iter.next() {
+                                Some(line) => {
+                                    line_number += 1;
+                                    string_iter = line.iter().peekable();
+                                }
+                                None => break,
+                            }
+                        }
+                        if !closed {
+                            log!(
+                                LexerError,
+                                f("Missing `}}` for the {_str} `{name}` at line {start_ln}")
+                            );
+                            continue;
+                        }
+                        if _str == "enum" {
+                            match variants(&name, &strings, start_ln) {
+                                Some(variants) => self.tmp_ast.push(Token::Enum(Enum {
+                                    name: name.to_owned(),
+                                    variants,
+                                    line: start_ln,
+                                })),
+                                None => syntax(),
+                            }
+                            continue;
+                        }
+                        let mut fields: Vec<Arg> = vec![];
+                        // a trailing comma leaves an empty field behind
+                        for field in split(&strings).into_iter().filter(|f| !f.is_empty()) {
+                            let field_name = match field[0].strip_suffix(':') {
+                                Some(field_name) if !field_name.is_empty() && field.len() > 1 => {
+                                    field_name
+                                }
+                                _ => {
+                                    log!(LexerError, f("Expected `field: type` in the struct `{name}` at line {start_ln}"));
+                                    syntax();
+                                    continue;
+                                }
+                            };
+                            if fields.iter().any(|f| f.name == field_name) {
+                                log!(LexerError, f("The struct `{name}` has two fields named `{field_name}`, line {start_ln}"));
+                                continue;
+                            }
+                            match Type::parse(&type_name(&field[1..])) {
+                                Ok(type_) => fields.push(Arg {
+                                    name: field_name.to_string(),
+                                    type_,
+                                }),
+                                Err(e) => {
+                                    log!(
+                                        LexerError,
+                                        f("{e} in the struct `{name}` at line {start_ln}")
+                                    );
+                                }
+                            }
+                        }
+                        self.tmp_ast.push(Token::Struct(Struct {
+                            name: name.to_owned(),
+                            fields,
+                            line: start_ln,
+                            generics,
+                        }));
+                    }
+                    "impl" | "trait" => {
+                        let syntax = || {
+                            if _str == "impl" {
+                                log!(Syntax, "\nimpl [`trait` for] `name` {\n    fn `name`(self, `arguments`) {\n    `code`\n    }\n}");
+                            } else {
+                                log!(
+                                    Syntax,
+                                    "\ntrait `name` {\n    fn `name`(self, `arguments`): `type`\n}"
+                                );
+                            }
+                        };
+                        let (Some(mut name), Some(mut open)) =
+                            (string_iter.next(), string_iter.next())
+                        else {
+                            log!(LexerError, f("Expected {_str} name at line {line_number}"));
+                            syntax();
+                            continue;
+                        };
+                        let mut trait_ = None;
+                        if _str == "impl" && open == "for" {
+                            let (Some(type_), Some(open_)) =
+                                (string_iter.next(), string_iter.next())
+                            else {
+                                log!(LexerError, f("Expected the type implementing `{name}` at line {line_number}"));
+                                syntax();
+                                continue;
+                            };
+                            trait_ = Some(name.to_owned());
+                            (name, open) = (type_, open_);
+                        }
+                        let item = Open {
+                            name: name.to_owned(),
+                            trait_,
+                            trait_body: _str == "trait",
+                            start: self.tmp_ast.len(),
+                            line: line_number,
+                        };
+                        if open != "{" {
+                            log!(LexerError, f("Expected opening braces but found `{open}` at line {line_number}"));
+                            syntax();
+                        } else if let Some(outer) = &impl_ {
+                            log!(
+                                LexerError,
+                                f("{item} is inside {outer}, at line {line_number}")
+                            );
+                        } else {
+                            impl_ = Some(item);
+                        }
+                    }
+                    "}" if impl_.is_some() => {
+                        let open = impl_.take().unwrap();
+                        let mut functions = vec![];
+                        for nt in self.tmp_ast.drain(open.start..) {
+                            match nt {
+                                Token::Function(x) if open.trait_body && !x.method() => {
+                                    log!(LexerError, f("The functions of {open} take `self`, `{}` doesn't, at line {}", x.name, x.start_ln));
+                                }
+                                Token::Function(x) => functions.push(x),
+                                Token::Comment(_) => {}
+                                _ => {
+                                    log!(
+                                        LexerError,
+                                        f("Only `fn`s go in {open}, at line {}", open.line)
+                                    );
+                                }
+                            }
+                        }
+                        let Open {
+                            name, trait_, line, ..
+                        } = open;
+                        self.tmp_ast.push(match open.trait_body {
+                            true => Token::Trait(Trait {
+                                name,
+                                functions,
+                                line,
+                            }),
+                            false => Token::Impl(Impl {
+                                name,
+                                trait_,
+                                functions,
+                                line,
+                            }),
+                        });
+                    }
                     "//" => {
                         let comment = next_line.join(" ");
                         self.tmp_ast.push(Token::Comment(comment));
@@ -439,11 +930,13 @@ impl Lexer {
                                         self.tmp_ast.push(Token::Const(Let {
                                             name: name.to_owned(),
                                             exp,
+                                            line: line_number,
                                         }));
                                     } else {
                                         self.tmp_ast.push(Token::Global(Let {
                                             name: name.to_owned(),
                                             exp,
+                                            line: line_number,
                                         }));
                                     }
                                 }
@@ -459,8 +952,11 @@ impl Lexer {
                     "#" => {
                         if let Some(two) = string_iter.next() {
                             if two == "include" {
-                                if let Some(path) = string_iter.next() {
-                                    self.tmp_ast.push(Token::CImport(format!("{path}")));
+                                // `<path>` is split like comparisons
+                                let path: String =
+                                    string_iter.by_ref().map(String::as_str).collect();
+                                if !path.is_empty() {
+                                    self.tmp_ast.push(Token::CImport(path));
                                 } else {
                                     log!(LexerError, f("Expected `path` at line {line_number}"));
                                 }
@@ -471,6 +967,16 @@ impl Lexer {
                             log!(LexerError, f("Expected `something` at line {line_number}"));
                         }
                     }
+                    "import" => {
+                        let path: Vec<&String> = string_iter.by_ref().collect();
+                        if path.is_empty() {
+                            log!(LexerError, f("Expected `path` at line {line_number}"));
+                        } else {
+                            self.tmp_ast.push(Token::Import(
+                                path.iter().map(|s| s.as_str()).collect(),
+                            ));
+                        }
+                    }
                     "" => {}
                     _ => {
                         self.tmp_ast.push(Token::Unknown(_str.to_string()));
@@ -479,6 +985,12 @@ impl Lexer {
                 }
             }
         }
+        if let Some(open) = impl_ {
+            log!(
+                LexerError,
+                f("{open} at line {} is never closed", open.line)
+            );
+        }
         // ------------------------------
     }
 
@@ -520,6 +1032,7 @@ impl Lexer {
                                     tokens.push(Token::Var(Let {
                                         name: name.to_owned(),
                                         exp: generate_expression(expression, line_number),
+                                        line: line_number,
                                     }));
                                 }
                             } else {
@@ -615,13 +1128,30 @@ impl Lexer {
                         let syntax = || {
                             log!(
                                 Syntax,
-                                "\nfor `var_name` in `iterator/Expression` {\n   `code`\n}"
+                                "\nfor `var_name` in `iterator/Expression` {\n   `code`\n}\nfor `index`, `var_name` in `start`..`end` {\n   `code`\n}"
                             );
                         };
-                        if let Some(var_name) = string_iter.next() {
+                        if let Some(mut var_name) = string_iter.next() {
+                            // `for i, x in items` counts the iterations in `i`
+                            let mut index = None;
+                            if string_iter.peek().is_some_and(|s| *s == ",") {
+                                string_iter.next();
+                                match string_iter.next() {
+                                    Some(value) => {
+                                        index = Some(var_name.to_owned());
+                                        var_name = value;
+                                    }
+                                    None => {
+                                        log!(
+                                            LexerError,
+                                            f("Expected var_name after `,` at line {line_number}")
+                                        );
+                                        syntax();
+                                    }
+                                }
+                            }
                             let mut iter_exp: Vec<&String> = vec![];
                             let mut then: bool = false;
-                            let mut _in: bool = false;
                             if string_iter.peek().is_some()
                                 && string_iter.next().unwrap() == &"in".to_string()
                             {
@@ -646,12 +1176,16 @@ impl Lexer {
                                         );
                                         syntax();
                                     } else {
-                                        tokens.push(Token::For(For::new(
+                                        let range = range(&iter_exp, line_number);
+                                        let mut _for = For::new(
                                             var_name.to_owned(),
                                             generate_expression(iter_exp, line_number),
                                             id,
                                             self.brackets.braces,
-                                        )));
+                                        );
+                                        _for.index = index;
+                                        _for.range = range;
+                                        tokens.push(Token::For(_for));
                                     }
                                 }
                             } else {
@@ -663,6 +1197,17 @@ impl Lexer {
                             syntax();
                         }
                     }
+                    // `match` is an expression, its arms are values
+                    "match" => {
+                        log!(LexerError, f("`match` only goes in `let`, assignments, `return` and `yield`, line {line_number}"));
+                        while string_iter.next().is_some() {}
+                    }
+                    // blocks open at the end of `if`, `for`, ..., so this is a struct
+                    // literal in a statement that isn't lexed as an expression
+                    "{" => {
+                        log!(LexerError, f("Struct literals only go in `let`, assignments, `return` and `yield`, line {line_number}"));
+                        while string_iter.next().is_some() {}
+                    }
                     "}" => {
                         tokens.push(Token::End(Br::new(id, self.brackets.braces)));
                         self.brackets.braces -= 1;
@@ -686,12 +1231,75 @@ impl Lexer {
                     "," => {
                         tokens.push(Token::Comma);
                     }
+                    // only a keyword when a block follows, `state` stays a valid name
+                    "state" if string_iter.peek().is_some_and(|s| *s == "{") => {
+                        string_iter.next();
+                        self.brackets.braces += 1;
+                        tokens.push(Token::State(Br::new(id, self.brackets.braces)));
+                    }
+                    "break" | "continue" => {
+                        let label = match string_iter.next() {
+                            Some(label) if label.len() > 1 && label.starts_with('\'') => {
+                                Some(label[1..].to_string())
+                            }
+                            Some(nt) => {
+                                log!(LexerError, f("Expected a label like `'outer` after `{string}` but found `{nt}` at line {line_number}"));
+                                None
+                            }
+                            None => None,
+                        };
+                        if string == "break" {
+                            tokens.push(Token::Break(label));
+                        } else {
+                            tokens.push(Token::Continue(label));
+                        }
+                    }
+                    label if label.len() > 2 && label.starts_with('\'') && label.ends_with(':') => {
+                        match string_iter.peek().map(|s| s.as_str()) {
+                            Some("loop" | "while" | "for") => {
+                                tokens.push(Token::Label(label[1..label.len() - 1].to_string()));
+                            }
+                            _ => {
+                                log!(LexerError, f("Expected `loop`, `while` or `for` after the label `{label}` at line {line_number}"));
+                            }
+                        }
+                    }
+                    "while" => {
+                        let syntax = || {
+                            log!(Syntax, "\nwhile `condition` {\n   `code`\n}");
+                        };
+                        let mut condition_v: Vec<&String> = vec![];
+                        let mut then: bool = false;
+                        while string_iter.peek().is_some() {
+                            let nt = string_iter.next().unwrap();
+                            if nt == "{" {
+                                then = true;
+                                self.brackets.braces += 1;
+                                break;
+                            }
+                            condition_v.push(nt);
+                        }
+                        if !then {
+                            log!(LexerError, f("Expected `{{` at line {line_number}"));
+                            syntax();
+                        } else if condition_v.is_empty() {
+                            log!(LexerError, f("Expected condition at line {line_number}"));
+                            syntax();
+                        } else {
+                            tokens.push(Token::While(While::new(
+                                generate_expression(condition_v, line_number),
+                                id,
+                                self.brackets.braces,
+                            )));
+                        }
+                    }
                     "loop" => {
                         if let Some(nt) = string_iter.next() {
                             if nt == "{" {
                                 self.brackets.braces += 1;
                                 tokens.push(Token::Loop(Loop {
-                                    id: self.brackets.braces,
+                                    // like the `End` closing it
+                                    id: id + self.brackets.braces,
                                 }));
                             } else {
                                 log!(LexerError, f("Expected `{{` at line {line_number}"));
@@ -701,11 +1309,17 @@ impl Lexer {
                     "" => {}
                     "yield" => {
                         if let Some(_) = string_iter.peek() {
-                            let exp = string_iter.clone().collect();
+                            let mut exp: Vec<&String> = string_iter.clone().collect();
                             while string_iter.peek().is_some() {
                                 string_iter.next();
                             }
-                            tokens.push(Token::Yield(generate_expression(exp, line_number)));
+                            // `yield from` is only a keyword when followed by an expression
+                            if exp.len() > 1 && exp[0] == "from" {
+                                exp.remove(0);
+                                tokens.push(Token::YieldFrom(generate_expression(exp, line_number)));
+                            } else {
+                                tokens.push(Token::Yield(generate_expression(exp, line_number)));
+                            }
                         } else {
                             log!(
                                 LexerError,
@@ -714,17 +1328,12 @@ impl Lexer {
                         }
                     }
                     "return" => {
-                        if let Some(_) = string_iter.peek() {
-                            let exp = string_iter.clone().collect();
-                            while string_iter.peek().is_some() {
-                                string_iter.next();
-                            }
-                            tokens.push(Token::Return(generate_expression(exp, line_number)));
+                        let exp: Vec<&String> = string_iter.by_ref().filter(|s| !s.is_empty()).collect();
+                        if exp.is_empty() {
+                            // a bare `return`
+                            tokens.push(Token::Return(vec![]));
                         } else {
-                            log!(
-                                LexerError,
-                                f("Expected expression after `return` at line {line_number}")
-                            );
+                            tokens.push(Token::Return(generate_expression(exp, line_number)));
                         }
                     }
                     _ => {
@@ -764,6 +1373,301 @@ impl Lexer {
     }
 }
 
+/// The `impl` or `trait` whose functions are being collected.
+struct Open {
+    name: String,
+    /// `Trait` in `impl Trait for Name`
+    trait_: Option<String>,
+    /// in a `trait`, the functions have no body
+    trait_body: bool,
+    /// where the functions start in `tmp_ast`
+    start: usize,
+    line: i32,
+}
+
+impl std::fmt::Display for Open {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match (&self.trait_, self.trait_body) {
+            (_, true) => write!(f, "`trait {}`", self.name),
+            (Some(trait_), _) => write!(f, "`impl {trait_} for {}`", self.name),
+            (None, _) => write!(f, "`impl {}`", self.name),
+        }
+    }
+}
+
+/// Joins the strings of a type like `vec<dyn Shape>` or `pair<A, B>`, with a
+/// space only between two words and after commas.
+fn type_name<S: AsRef<str>>(strings: &[S]) -> String {
+    let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let mut type_ = String::new();
+    for s in strings {
+        let s = s.as_ref();
+        if word(type_.chars().last()) && word(s.chars().next()) {
+            type_.push(' ');
+        }
+        type_.push_str(s);
+        if s == "," {
+            type_.push(' ');
+        }
+    }
+    type_
+}
+
+/// The strings of a name like `pair<A, B>` starting with `first`, the ones
+/// after it up to the `>` closing its type parameters.
+fn angled<'a, I: Iterator<Item = &'a String>>(
+    first: &'a String,
+    strings: &mut std::iter::Peekable<I>,
+) -> Vec<&'a String> {
+    let mut name = vec![first];
+    let mut depth = 0;
+    if let Some(open) = strings.next_if(|s| *s == "<") {
+        name.push(open);
+        depth = 1;
+    }
+    while depth > 0 {
+        let Some(s) = strings.next() else {
+            break;
+        };
+        depth += angles(s);
+        name.push(s);
+    }
+    name
+}
+
+/// How many more opening than closing brackets a string has, `<`, `(` and `[`.
+fn angles(s: &str) -> i32 {
+    let opening = s.matches(['<', '(', '[']).count() as i32;
+    opening - s.matches(['>', ')', ']']).count() as i32
+}
+
+/// Splits types separated by commas, keeping the ones inside `<...>`.
+fn split<S: AsRef<str>>(strings: &[S]) -> Vec<&[S]> {
+    let mut types = vec![];
+    let (mut start, mut depth) = (0, 0);
+    for (i, s) in strings.iter().enumerate() {
+        match s.as_ref() {
+            "," if depth == 0 => {
+                types.push(&strings[start..i]);
+                start = i + 1;
+            }
+            s => depth += angles(s),
+        }
+    }
+    types.push(&strings[start..]);
+    types
+}
+
+/// Splits `name<T, U: Trait>` into the name and its type parameters.
+fn generics(name: &str, line_number: i32) -> Option<(String, Vec<token::Generic>)> {
+    let Some((base, params)) = name.split_once('<') else {
+        return Some((name.to_string(), vec![]));
+    };
+    let word = |s: &str| {
+        s.starts_with(|c: char| c.is_alphabetic() || c == '_')
+            && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+    };
+    let mut generics: Vec<token::Generic> = vec![];
+    for param in params.strip_suffix('>').unwrap_or("").split(',') {
+        let (name, bound) = match param.split_once(':') {
+            Some((name, bound)) => (name.trim(), Some(bound.trim().to_string())),
+            None => (param.trim(), None),
+        };
+        if !word(base) || !word(name) || bound.as_deref().is_some_and(|b| !word(b)) {
+            log!(LexerError, f("Expected type parameters like `{base}<T>` or `{base}<T: Trait>` at line {line_number}"));
+            return None;
+        }
+        if generics.iter().any(|g| g.name == name) {
+            log!(
+                LexerError,
+                f("`{base}` has two type parameters named `{name}`, line {line_number}")
+            );
+            return None;
+        }
+        generics.push(token::Generic {
+            name: name.to_string(),
+            bound,
+        });
+    }
+    Some((base.to_string(), generics))
+}
+
+/// How many of the characters after a `'` belong to a char literal like
+/// `'a'` or `'\n'`, none for labels like `'outer`.
+fn char_literal(mut chars: impl Iterator<Item = char>) -> usize {
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some('\\'), Some(_), Some('\'')) => 3,
+        (Some(c), Some('\''), _) if c != '\\' && c != '\'' => 2,
+        _ => 0,
+    }
+}
+
+/// The milliseconds of a period like `16ms` or `2s`.
+fn period(s: &str) -> Option<u64> {
+    match s.strip_suffix("ms") {
+        Some(ms) => ms.parse().ok(),
+        None => s.strip_suffix('s')?.parse::<u64>().ok()?.checked_mul(1000),
+    }
+}
+
+/// The range a `for` iterates over, `None` when the expression isn't one.
+/// `.step(n)` and `.rev()` need the range in brackets.
+fn range(strings: &[&String], line_number: i32) -> Option<token::Range> {
+    // the `..` outside of brackets
+    let split = |strings: &[&String]| {
+        let mut depth = 0;
+        for (i, s) in strings.iter().enumerate() {
+            match s.as_str() {
+                "(" | "[" => depth += 1,
+                ")" | "]" => depth -= 1,
+                ".." | "..=" if depth == 0 => return Some(i),
+                _ => {}
+            }
+        }
+        None
+    };
+    let (inner, mut methods) = match split(strings) {
+        Some(_) => (strings, &[][..]),
+        None if strings.first().is_some_and(|s| *s == "(") => {
+            let close = closing(strings, 0)?;
+            (&strings[1..close], &strings[close + 1..])
+        }
+        None => return None,
+    };
+    let at = split(inner)?;
+    let (start, end) = (&inner[..at], &inner[at + 1..]);
+    if start.is_empty() || end.is_empty() {
+        log!(
+            LexerError,
+            f("Ranges need a start and an end at line {line_number}")
+        );
+        return None;
+    }
+    let mut range = token::Range {
+        start: generate_expression(start.to_vec(), line_number),
+        end: generate_expression(end.to_vec(), line_number),
+        inclusive: inner[at] == "..=",
+        step: None,
+        rev: false,
+    };
+    while let Some(method) = methods.first() {
+        let close = match methods.get(1) {
+            Some(open) if *open == "(" => closing(methods, 1),
+            _ => None,
+        };
+        let Some(close) = close else {
+            log!(
+                LexerError,
+                f("Expected `(` after `{method}` at line {line_number}")
+            );
+            return None;
+        };
+        let args = &methods[2..close];
+        match method.as_str() {
+            ".step" if range.step.is_some() => {
+                log!(
+                    LexerError,
+                    f("A range can only have one `.step` at line {line_number}")
+                );
+            }
+            ".step" if !args.is_empty() => {
+                range.step = Some(generate_expression(args.to_vec(), line_number));
+            }
+            ".rev" if args.is_empty() && range.step.is_some() => {
+                log!(
+                    LexerError,
+                    f("`.rev()` goes before `.step(n)` at line {line_number}")
+                );
+            }
+            ".rev" if args.is_empty() => range.rev = true,
+            _ => {
+                log!(LexerError, f("Ranges only have `.step(n)` and `.rev()`, found `{method}` at line {line_number}"));
+                return None;
+            }
+        }
+        methods = &methods[close + 1..];
+    }
+    Some(range)
+}
+
+/// The index of the `)` closing the `(` at `open`.
+fn closing(strings: &[&String], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, s) in strings.iter().enumerate().skip(open) {
+        match s.as_str() {
+            "(" => depth += 1,
+            ")" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The variants of the enum `name`, `Variant` or `Variant(type, ...)` separated
+/// by commas. `None` once a malformed variant was logged.
+fn variants(name: &str, strings: &[&String], line_number: i32) -> Option<Vec<token::Variant>> {
+    let mut variants: Vec<token::Variant> = vec![];
+    let mut i = 0;
+    while let Some(variant) = strings.get(i) {
+        if !variant.starts_with(|c: char| c.is_alphabetic() || c == '_')
+            || !variant.chars().all(|c| c.is_alphanumeric() || c == '_')
+        {
+            log!(LexerError, f("Expected a variant of the enum `{name}` but got `{variant}` at line {line_number}"));
+            return None;
+        }
+        if variants.iter().any(|v| v.name == **variant) {
+            log!(
+                LexerError,
+                f("The enum `{name}` has two variants named `{variant}`, line {line_number}")
+            );
+            return None;
+        }
+        i += 1;
+        let mut fields = vec![];
+        if strings.get(i).is_some_and(|s| *s == "(") {
+            let Some(close) = closing(strings, i) else {
+                log!(LexerError, f("Missing `)` after the variant `{variant}` of the enum `{name}` at line {line_number}"));
+                return None;
+            };
+            // `Variant()` holds nothing, like `Variant`
+            if close > i + 1 {
+                for field in split(&strings[i + 1..close]) {
+                    if field.is_empty() {
+                        log!(LexerError, f("Expected a type in the variant `{variant}` of the enum `{name}` at line {line_number}"));
+                        return None;
+                    }
+                    match Type::parse(&type_name(field)) {
+                        Ok(type_) => fields.push(type_),
+                        Err(e) => {
+                            log!(LexerError, f("{e} in the variant `{variant}` of the enum `{name}` at line {line_number}"));
+                            return None;
+                        }
+                    }
+                }
+            }
+            i = close + 1;
+        }
+        variants.push(token::Variant {
+            name: variant.to_string(),
+            fields,
+        });
+        match strings.get(i) {
+            Some(s) if *s == "," => i += 1,
+            Some(s) => {
+                log!(LexerError, f("Expected `,` after the variant `{variant}` of the enum `{name}` but got `{s}` at line {line_number}"));
+                return None;
+            }
+            None => {}
+        }
+    }
+    Some(variants)
+}
+
 pub fn generate_expression(strings: Vec<&String>, _line_number: i32) -> token::Expression {
     let mut expression = vec![];
 
@@ -789,9 +1693,27 @@ pub fn generate_expression(strings: Vec<&String>, _line_number: i32) -> token::E
             ">>" => {
                 expression.push(Token::Operator(Operator::BitShiftRight));
             }
+            "%" => {
+                expression.push(Token::Operator(Operator::Mod));
+            }
             "==" => {
                 expression.push(Token::Operator(Operator::Equals));
             }
+            "!=" => {
+                expression.push(Token::Operator(Operator::NotEquals));
+            }
+            "<" => {
+                expression.push(Token::Operator(Operator::Less));
+            }
+            ">" => {
+                expression.push(Token::Operator(Operator::Greater));
+            }
+            "<=" => {
+                expression.push(Token::Operator(Operator::LessEquals));
+            }
+            ">=" => {
+                expression.push(Token::Operator(Operator::GreaterEquals));
+            }
             "|>" => {
                 expression.push(Token::Operator(Operator::Pipe));
             }
@@ -1,7 +1,12 @@
 #[cfg(not(target_arch = "wasm32"))]
 use indicatif::ProgressBar;
 use serde::{Deserialize, Serialize};
+pub mod check;
+pub mod flow;
 mod lexer;
+pub mod resolve;
+pub mod tree;
+pub mod types;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum Token {
@@ -11,7 +16,24 @@ pub enum Token {
     Function(token::Function),
     LoopFunction(token::Function),
     GeneratorFunction(token::Function),
+    /// `struct Name { field: type, ... }` at the top level
+    Struct(token::Struct),
+    /// `enum Name { Variant, Variant(type, ...), ... }` at the top level
+    Enum(token::Enum),
+    /// `impl Name { fn ... }` or `impl Trait for Name { fn ... }` at the top
+    /// level
+    Impl(token::Impl),
+    /// `trait Name { fn name(self, ...): type ... }` at the top level
+    Trait(token::Trait),
     Loop(token::Loop),
+    While(token::While),
+    /// `'name:` in front of a `loop`, `while` or `for`
+    Label(String),
+    /// `state { ... }` at the start of a `loop fn`, runs once before the loop
+    State(token::Br),
+    /// `break` or `break 'name`
+    Break(Option<String>),
+    Continue(Option<String>),
     Const(token::Let),
     Global(token::Let),
     Var(token::Let),
@@ -25,7 +47,9 @@ pub enum Token {
     OpenRoBr(token::Br),
     CloseRoBr(token::Br),
     Yield(token::Expression),
+    YieldFrom(token::Expression),
     Assign(token::Assign),
+    /// the expression is empty for a bare `return`
     Return(token::Expression),
     /**
      * Standard Types
@@ -36,6 +60,7 @@ pub enum Token {
     Port(String),
     Comment(String),
     CImport(String),
+    Import(String),
     Generic(String),
     Line(Vec<Token>),
     Unknown(String),
@@ -71,41 +96,155 @@ pub enum FunctionType {
 pub mod token {
     use serde::{Deserialize, Serialize};
 
-    use super::Token;
+    use super::{types::Type, Token};
     #[derive(Clone, Debug, Deserialize, Serialize)]
     pub struct Function {
         pub name: String,
         pub arguments: Vec<Arg>,
-        pub return_type: String,
+        pub return_type: Type,
         pub lines: Vec<super::Line>,
         pub tmp_lines: Vec<Vec<String>>,
         pub start_ln: i32,
+        /// milliseconds between the iterations of `loop fn ... every 16ms`
+        #[serde(default)]
+        pub every: Option<u64>,
+        /// `T` in `fn name<T>(...)`
+        #[serde(default)]
+        pub generics: Vec<Generic>,
+    }
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Struct {
+        pub name: String,
+        pub fields: Vec<Arg>,
+        pub line: i32,
+        /// `T` in `struct Name<T>`
+        #[serde(default)]
+        pub generics: Vec<Generic>,
+    }
+    /// A type parameter of a generic function or struct, `T` or `T: Trait`.
+    /// The type it stands for is inferred from the values passed for it and
+    /// has to implement the trait.
+    #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+    pub struct Generic {
+        pub name: String,
+        pub bound: Option<String>,
+    }
+    impl std::fmt::Display for Generic {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            match &self.bound {
+                Some(bound) => write!(f, "{}: {bound}", self.name),
+                None => write!(f, "{}", self.name),
+            }
+        }
+    }
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Enum {
+        pub name: String,
+        pub variants: Vec<Variant>,
+        pub line: i32,
+    }
+    impl Enum {
+        /// Whether no variant holds values, like a C enum.
+        pub fn c_like(&self) -> bool {
+            self.variants.iter().all(|v| v.fields.is_empty())
+        }
+    }
+    /// A variant of an enum with the types of the values it holds, which
+    /// have no names.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Variant {
+        pub name: String,
+        pub fields: Vec<Type>,
+    }
+    /// The functions of a struct. Methods take `self` as their first
+    /// argument, which has the type of the struct, the others are called
+    /// like `Name::function()`.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Impl {
+        pub name: String,
+        /// `Trait` in `impl Trait for Name`
+        #[serde(default)]
+        pub trait_: Option<String>,
+        pub functions: Vec<Function>,
+        pub line: i32,
+    }
+    /// The methods a type needs to implement the trait. The functions have
+    /// no body, they all take `self`, which has the type of the trait. Types
+    /// like `impl Name` and `dyn Name` take any value implementing it.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Trait {
+        pub name: String,
+        pub functions: Vec<Function>,
+        pub line: i32,
+    }
+    impl Function {
+        /// Whether the function takes `self`, so it's called like
+        /// `value.name()`.
+        pub fn method(&self) -> bool {
+            self.arguments.first().is_some_and(|arg| arg.name == "self")
+        }
     }
     #[derive(Clone, Debug, Deserialize, Serialize)]
     pub struct Loop {
         pub id: i32,
     }
     #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct While {
+        pub condition: Expression,
+        pub id: i32,
+        pub level: i32,
+    }
+    impl While {
+        pub fn new(condition: Expression, id: i32, level: i32) -> Self {
+            Self {
+                condition,
+                level,
+                id: id + level,
+            }
+        }
+    }
+    #[derive(Clone, Debug, Deserialize, Serialize)]
     pub struct For {
         pub id: i32,
         pub level: i32,
         pub var_name: String,
         pub iter_exp: Expression,
+        /// `i` in `for i, x in items`, counts the iterations from 0
+        #[serde(default)]
+        pub index: Option<String>,
+        /// set when `iter_exp` is a range like `0..n`
+        #[serde(default)]
+        pub range: Option<Range>,
     }
     impl For {
         pub fn new(var_name: String, iter_exp: Expression, id: i32, level: i32) -> Self {
             Self {
                 var_name,
                 iter_exp,
+                index: None,
+                range: None,
                 level,
                 id: id + level,
             }
         }
     }
+    /// `start..end`, `start..=end`, `(start..end).step(n)` or
+    /// `(start..end).rev()`. Counts up from `start`, or down to it when
+    /// reversed.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Range {
+        pub start: Expression,
+        pub end: Expression,
+        pub inclusive: bool,
+        pub step: Option<Expression>,
+        pub rev: bool,
+    }
     #[derive(Clone, Debug, Deserialize, Serialize)]
     pub struct Let {
         pub name: String,
         pub exp: Expression,
+        #[serde(default)]
+        pub line: i32,
     }
     #[derive(Clone, Debug, Deserialize, Serialize)]
     pub struct If {
@@ -154,7 +293,7 @@ pub mod token {
     #[derive(Clone, Debug, Deserialize, Serialize)]
     pub struct Arg {
         pub name: String,
-        pub type_: String,
+        pub type_: Type,
     }
 
     #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -165,7 +304,7 @@ pub mod token {
 
     pub mod expression {
         use serde::{Deserialize, Serialize};
-        #[derive(Clone, Debug, Deserialize, Serialize)]
+        #[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
         pub enum Operator {
             BitShiftRight,
             BitShiftLeft,
@@ -177,7 +316,13 @@ pub mod token {
             Minus,
             Mul,
             Div,
+            Mod,
             Equals,
+            NotEquals,
+            Less,
+            Greater,
+            LessEquals,
+            GreaterEquals,
             Pipe,
         }
     }
@@ -205,4 +350,8 @@ pub struct Lexer {
     #[cfg(not(target_arch = "wasm32"))]
     progress: usize,
     brackets: Brackets,
+    /// don't announce parsing, for the REPL
+    quiet: bool,
+    /// the errors logged by the last `parse`
+    pub errors: usize,
 }
@@ -0,0 +1,396 @@
+//! Name resolution of the function bodies.
+//!
+//! Each name used in a body has to be a variable of one of the blocks around
+//! it, an argument, a global, a const, a function or one of the names the
+//! target provides. Unknown names are reported with the declared name
+//! closest to them, names declared twice in the same scope are errors and
+//! variables hiding one of an outer scope are warnings.
+
+use {
+    super::{
+        token::{Enum, Expression, Function, Struct, Trait},
+        tree::{self, Expr, Pattern},
+        Token,
+    },
+    std::collections::HashMap,
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Resolved {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Resolves the names in `ast`. `known` are the names declared outside of
+/// it, like the builtins or the items loaded before.
+pub fn program(ast: &[Token], known: &[String]) -> Resolved {
+    resolve(ast, known, false)
+}
+
+/// Resolves the names in `ast` for a target that links the functions it
+/// doesn't define, like the C ones LLVM declares, so calling them is fine.
+pub fn linked(ast: &[Token], known: &[String]) -> Resolved {
+    resolve(ast, known, true)
+}
+
+fn resolve(ast: &[Token], known: &[String], linked: bool) -> Resolved {
+    let mut resolver = Resolver {
+        known,
+        values: HashMap::new(),
+        functions: vec![],
+        types: vec![],
+        // the names of imported code can't be told
+        imports: (ast.iter()).any(|nt| matches!(nt, Token::Import(_) | Token::CImport(_))),
+        linked,
+        resolved: Resolved::default(),
+    };
+    resolver.items(ast);
+    for nt in ast {
+        match nt {
+            Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) => {
+                resolver.function(x, &x.name);
+            }
+            Token::Impl(x) => {
+                for function in &x.functions {
+                    resolver.function(function, &format!("{}::{}", x.name, function.name));
+                }
+            }
+            Token::Const(x) | Token::Global(x) => {
+                resolver.expression(&x.exp, &mut vec![], &format!("in `{}`", x.name));
+            }
+            _ => {}
+        }
+    }
+    resolver.resolved
+}
+
+/// What a name stands for, to tell what a variable hides.
+#[derive(Clone, Copy, Debug)]
+enum Kind {
+    Global,
+    Const,
+    Argument,
+    /// a `let` or a `for` variable, with the line it is declared at
+    Variable(i32),
+    /// a name bound by a `match` arm
+    Binding,
+}
+
+impl Kind {
+    fn describe(self, name: &str) -> String {
+        match self {
+            Kind::Global => format!("the global `{name}`"),
+            Kind::Const => format!("the const `{name}`"),
+            Kind::Argument => format!("the argument `{name}`"),
+            Kind::Variable(ln) => format!("the variable `{name}` of line {ln}"),
+            Kind::Binding => format!("the binding `{name}`"),
+        }
+    }
+}
+
+type Scope = Vec<(String, Kind)>;
+
+struct Resolver<'a> {
+    known: &'a [String],
+    /// the globals and consts
+    values: HashMap<String, Kind>,
+    /// the functions, `Name::function` for the ones of an `impl`
+    functions: Vec<String>,
+    /// the structs, enums and traits
+    types: Vec<String>,
+    imports: bool,
+    /// the functions not defined are linked
+    linked: bool,
+    resolved: Resolved,
+}
+
+impl Resolver<'_> {
+    /// Collects the top-level names, each one can be declared once.
+    fn items(&mut self, ast: &[Token]) {
+        let mut twice = vec![];
+        for nt in ast {
+            let (name, kind, line) = match nt {
+                Token::Struct(Struct { name, line, .. })
+                | Token::Enum(Enum { name, line, .. })
+                | Token::Trait(Trait { name, line, .. }) => {
+                    if self.types.contains(name) {
+                        twice.push((name.clone(), *line));
+                    }
+                    self.types.push(name.clone());
+                    continue;
+                }
+                Token::Impl(x) => {
+                    for function in &x.functions {
+                        let name = format!("{}::{}", x.name, function.name);
+                        if self.functions.contains(&name) {
+                            twice.push((name.clone(), function.start_ln));
+                        }
+                        self.functions.push(name);
+                    }
+                    continue;
+                }
+                Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) => {
+                    (&x.name, None, x.start_ln)
+                }
+                Token::Const(x) => (&x.name, Some(Kind::Const), x.line),
+                Token::Global(x) => (&x.name, Some(Kind::Global), x.line),
+                _ => continue,
+            };
+            if self.functions.contains(name) || self.values.contains_key(name) {
+                twice.push((name.clone(), line));
+            }
+            match kind {
+                Some(kind) => {
+                    self.values.insert(name.clone(), kind);
+                }
+                None => self.functions.push(name.clone()),
+            }
+        }
+        let errors = twice
+            .iter()
+            .map(|(name, line)| format!("`{name}` is defined twice at line {line}"));
+        self.resolved.errors.extend(errors);
+    }
+
+    /// Resolves the names in the body of `x`, named `name`.
+    fn function(&mut self, x: &Function, name: &str) {
+        let mut arguments: Scope = vec![];
+        for arg in &x.arguments {
+            if arguments.iter().any(|(a, _)| *a == arg.name) {
+                self.resolved.errors.push(format!(
+                    "The argument `{}` of `{name}` is defined twice",
+                    arg.name
+                ));
+            }
+            arguments.push((arg.name.clone(), Kind::Argument));
+        }
+        // the arguments, the body and the blocks in it
+        let mut scopes = vec![arguments, vec![]];
+        // the depth of the `state` block of a `loop fn`, its variables outlive it
+        let mut state = None;
+        for (ln, token) in tree::tokens(&x.lines, x.start_ln) {
+            let at = format!("at line {ln} in `{name}`");
+            match &token {
+                Token::If(if_) | Token::ElseIf(if_) => {
+                    self.expression(&if_.condition, &mut scopes, &at);
+                    scopes.push(vec![]);
+                }
+                Token::While(while_) => {
+                    self.expression(&while_.condition, &mut scopes, &at);
+                    scopes.push(vec![]);
+                }
+                Token::Else(_) | Token::Loop(_) => scopes.push(vec![]),
+                Token::State(_) => {
+                    state = Some(scopes.len());
+                    scopes.push(vec![]);
+                }
+                Token::For(for_) => {
+                    match &for_.range {
+                        Some(range) => {
+                            let mut bounds = vec![&range.start, &range.end];
+                            bounds.extend(&range.step);
+                            for exp in bounds {
+                                self.expression(exp, &mut scopes, &at);
+                            }
+                        }
+                        None => self.expression(&for_.iter_exp, &mut scopes, &at),
+                    }
+                    scopes.push(vec![]);
+                    for var in for_.index.iter().chain([&for_.var_name]) {
+                        self.declare(var, Kind::Variable(ln), &mut scopes, &at);
+                    }
+                }
+                Token::End(_) if scopes.len() > 2 => {
+                    let scope = scopes.pop().unwrap();
+                    if state == Some(scopes.len()) {
+                        state = None;
+                        scopes.last_mut().unwrap().extend(scope);
+                    }
+                }
+                Token::Var(let_) => {
+                    // the value can use a variable the `let` hides
+                    self.expression(&let_.exp, &mut scopes, &at);
+                    self.declare(&let_.name, Kind::Variable(ln), &mut scopes, &at);
+                }
+                Token::Assign(assign) => {
+                    self.expression(&assign.exp, &mut scopes, &at);
+                    if !self.is_variable(&assign.var, &scopes) {
+                        let error = unknown("variable", &assign.var, &self.variables(&scopes), &at);
+                        self.resolved.errors.push(error);
+                    }
+                }
+                Token::Return(exp)
+                | Token::Yield(exp)
+                | Token::YieldFrom(exp)
+                | Token::Expression(exp) => self.expression(exp, &mut scopes, &at),
+                _ => {}
+            }
+        }
+    }
+
+    /// Adds a variable to the innermost scope.
+    fn declare(&mut self, name: &str, kind: Kind, scopes: &mut [Scope], at: &str) {
+        let (scope, outer) = scopes.split_last_mut().unwrap();
+        if scope.iter().any(|(n, _)| n == name) {
+            (self.resolved.errors)
+                .push(format!("`{name}` is defined twice in the same block {at}"));
+        } else if let Some(hidden) = (outer.iter().rev())
+            .find_map(|scope| scope.iter().rev().find(|(n, _)| n == name))
+            .map(|(_, kind)| *kind)
+            .or_else(|| self.values.get(name).copied())
+        {
+            (self.resolved.warnings).push(format!("`{name}` hides {} {at}", hidden.describe(name)));
+        }
+        scope.push((name.to_string(), kind));
+    }
+
+    fn expression(&mut self, exp: &Expression, scopes: &mut Vec<Scope>, at: &str) {
+        // expressions the tree can't be built for are left to the target
+        if let Ok(exp) = tree::expression(exp) {
+            self.expr(&exp, scopes, at);
+        }
+    }
+
+    fn expr(&mut self, exp: &Expr, scopes: &mut Vec<Scope>, at: &str) {
+        match exp {
+            Expr::Int(_) | Expr::Float(_) | Expr::Bool(_) | Expr::Char(_) | Expr::Str(_) => {}
+            Expr::Var(name) => {
+                // functions are values too
+                if !self.is_variable(name, scopes) && !self.is_function(name) {
+                    let mut names = self.variables(scopes);
+                    names.extend(self.functions.iter().map(String::as_str));
+                    let error = unknown("variable", name, &names, at);
+                    self.resolved.errors.push(error);
+                }
+            }
+            Expr::Call(name, args) => {
+                if !self.is_function(name)
+                    && !self.is_variable(name, scopes)
+                    && !self.imports
+                    && !self.linked
+                {
+                    let mut names: Vec<&str> = self.functions.iter().map(String::as_str).collect();
+                    names.extend(self.known.iter().map(String::as_str));
+                    names.extend(self.variables(scopes));
+                    let error = unknown("function", name, &names, at);
+                    self.resolved.errors.push(error);
+                }
+                args.iter().for_each(|arg| self.expr(arg, scopes, at));
+            }
+            Expr::Struct(name, fields) => {
+                if !self.is_type(name) {
+                    let names: Vec<&str> = self.types.iter().map(String::as_str).collect();
+                    let error = unknown("struct", name, &names, at);
+                    self.resolved.errors.push(error);
+                }
+                (fields.iter()).for_each(|(_, value)| self.expr(value, scopes, at));
+            }
+            Expr::Variant(name, _, args) => {
+                if !self.is_type(name) && !self.imports {
+                    let names: Vec<&str> = self.types.iter().map(String::as_str).collect();
+                    let error = unknown("type", name, &names, at);
+                    self.resolved.errors.push(error);
+                }
+                args.iter().for_each(|arg| self.expr(arg, scopes, at));
+            }
+            Expr::Array(items) => items.iter().for_each(|item| self.expr(item, scopes, at)),
+            Expr::Index(value, other) | Expr::Binary(value, _, other) => {
+                self.expr(value, scopes, at);
+                self.expr(other, scopes, at);
+            }
+            Expr::Field(value, _) | Expr::Neg(value) => self.expr(value, scopes, at),
+            Expr::Method(value, _, args) => {
+                self.expr(value, scopes, at);
+                args.iter().for_each(|arg| self.expr(arg, scopes, at));
+            }
+            Expr::Match(value, arms) => {
+                self.expr(value, scopes, at);
+                for (pattern, exp) in arms {
+                    // the names the arm binds are only known in its value
+                    let bound: Scope = match pattern {
+                        Pattern::Binding(name) => vec![(name.clone(), Kind::Binding)],
+                        Pattern::Variant(.., values) => (values.iter().flatten())
+                            .map(|name| (name.clone(), Kind::Binding))
+                            .collect(),
+                        _ => vec![],
+                    };
+                    scopes.push(bound);
+                    self.expr(exp, scopes, at);
+                    scopes.pop();
+                }
+            }
+        }
+    }
+
+    /// Whether `name` is a variable, an argument, a global or a const.
+    fn is_variable(&self, name: &str, scopes: &[Scope]) -> bool {
+        scopes.iter().flatten().any(|(n, _)| n == name)
+            || self.values.contains_key(name)
+            || self.known.iter().any(|n| n == name)
+    }
+
+    fn is_function(&self, name: &str) -> bool {
+        self.functions.iter().any(|f| f == name) || self.known.iter().any(|n| n == name)
+    }
+
+    fn is_type(&self, name: &str) -> bool {
+        self.types.iter().any(|t| t == name) || self.known.iter().any(|n| n == name)
+    }
+
+    /// The variables, arguments, globals and consts `scopes` can use.
+    fn variables<'s>(&'s self, scopes: &'s [Scope]) -> Vec<&'s str> {
+        let mut names: Vec<&str> = scopes.iter().flatten().map(|(n, _)| n.as_str()).collect();
+        let mut values: Vec<&str> = self.values.keys().map(String::as_str).collect();
+        // in the same order on every run, for the suggestions
+        values.sort();
+        names.extend(values);
+        names
+    }
+}
+
+/// The error for a `name` that isn't declared, with the one of `names` it
+/// is likely a typo of.
+fn unknown(what: &str, name: &str, names: &[&str], at: &str) -> String {
+    match closest(name, names) {
+        Some(closest) => format!("Unknown {what} `{name}` {at}, did you mean `{closest}`?"),
+        None => format!("Unknown {what} `{name}` {at}"),
+    }
+}
+
+/// The name in `names` that is the fewest edits away from `name`, when it is
+/// close enough to be a typo of it.
+fn closest<'n>(name: &str, names: &[&'n str]) -> Option<&'n str> {
+    // every name of one character is one edit away from the others
+    let most = match name.chars().count() {
+        0 | 1 => return None,
+        count => (count / 3).max(1),
+    };
+    (names.iter())
+        .map(|n| (distance(name, n), *n))
+        .filter(|(d, _)| *d <= most)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, n)| n)
+}
+
+/// The number of characters to insert, remove, replace or swap with the next
+/// one to turn `a` into `b`.
+fn distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    // the distances from the start of `a` up to the last two characters
+    let mut before: Vec<usize> = vec![];
+    let mut last: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            row[j] = match a[i - 1] == b[j - 1] {
+                true => last[j - 1],
+                false => 1 + last[j - 1].min(last[j]).min(row[j - 1]),
+            };
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(before[j - 2] + 1);
+            }
+        }
+        before = std::mem::replace(&mut last, row);
+    }
+    last[b.len()]
+}
@@ -0,0 +1,1799 @@
+//! Structured view of the lexer output.
+//!
+//! The lexer keeps expressions as flat token lists and function bodies as
+//! lines of tokens, which is all the text based transpilers need. Backends
+//! that have to understand the code (typed targets, interpreters) build an
+//! [`Expr`] tree per expression and nested [`Stmt`]s per function body here.
+
+use {
+    super::{
+        lexer::generate_expression,
+        token::{expression::Operator, Arg, Expression, Function, Generic, Impl},
+        types::{Primitive, Type},
+        Line, Token,
+    },
+    std::collections::HashMap,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// `'a'`, a number when the program runs like in C
+    Char(char),
+    Str(String),
+    Var(String),
+    Array(Vec<Expr>),
+    Call(String, Vec<Expr>),
+    Index(Box<Expr>, Box<Expr>),
+    /// `Name { field: value, ... }`, the fields in the order they are written
+    Struct(String, Vec<(String, Expr)>),
+    /// `value.field`
+    Field(Box<Expr>, String),
+    /// `Enum::Variant` or `Enum::Variant(value, ...)`, also the calls of the
+    /// functions of an `impl` like `Point::origin()`
+    Variant(String, String, Vec<Expr>),
+    /// `value.method(argument, ...)`
+    Method(Box<Expr>, String, Vec<Expr>),
+    Neg(Box<Expr>),
+    Binary(Box<Expr>, Operator, Box<Expr>),
+    /// `match value { pattern => value, ... }`, the arms in the order they
+    /// are tried
+    Match(Box<Expr>, Vec<(Pattern, Expr)>),
+}
+
+/// What a `match` arm compares its value with.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pattern {
+    /// `_`
+    Wildcard,
+    /// a name that holds the value in the arm
+    Binding(String),
+    Int(i64),
+    Bool(bool),
+    Str(String),
+    /// `start..end` or `start..=end`, both bounds are inclusive here
+    Range(i64, i64),
+    /// `Enum::Variant` or `Enum::Variant(a, _)`, the values it holds are
+    /// bound to names or ignored with `None`
+    Variant(String, String, Vec<Option<String>>),
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Pattern::Wildcard => write!(f, "_"),
+            Pattern::Binding(name) => write!(f, "{name}"),
+            Pattern::Int(i) => write!(f, "{i}"),
+            Pattern::Bool(b) => write!(f, "{b}"),
+            Pattern::Str(s) => write!(f, "\"{s}\""),
+            Pattern::Range(start, end) => write!(f, "{start}..={end}"),
+            Pattern::Variant(name, variant, values) if values.is_empty() => {
+                write!(f, "{name}::{variant}")
+            }
+            Pattern::Variant(name, variant, values) => {
+                let values: Vec<&str> =
+                    values.iter().map(|v| v.as_deref().unwrap_or("_")).collect();
+                write!(f, "{name}::{variant}({})", values.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Stmt {
+    Let(String, Expr),
+    Assign(String, Expr),
+    /// `if`/`else if` branches in order, followed by the `else` block
+    If(Vec<(Expr, Vec<Stmt>)>, Option<Vec<Stmt>>),
+    For(For),
+    Loop(Vec<Stmt>),
+    While(Expr, Vec<Stmt>),
+    /// a `loop`, `while` or `for` named `'label`
+    Labeled(String, Box<Stmt>),
+    /// leaves the innermost loop, or the one with the label
+    Break(Option<String>),
+    /// starts the next iteration of the innermost loop, or the one with the
+    /// label
+    Continue(Option<String>),
+    /// `None` for a bare `return`
+    Return(Option<Expr>),
+    Yield(Expr),
+    /// yields every value of a generator or array
+    YieldFrom(Expr),
+    Expr(Expr),
+}
+
+/// `for [index,] var_name in iter`
+#[derive(Clone, Debug, PartialEq)]
+pub struct For {
+    /// counts the iterations from 0
+    pub index: Option<String>,
+    pub var_name: String,
+    pub iter: Iterable,
+    pub body: Vec<Stmt>,
+}
+
+/// What a `for` iterates over.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Iterable {
+    /// an array or a generator
+    Expr(Expr),
+    Range(Range),
+}
+
+/// A range of integers, see [`super::token::Range`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Range {
+    pub start: Expr,
+    pub end: Expr,
+    pub inclusive: bool,
+    pub step: Option<Expr>,
+    pub rev: bool,
+}
+
+impl Range {
+    /// The first value of a counted loop over the range, and the comparison
+    /// with the bound it runs while. The counter goes up by the step, or down
+    /// when the range is reversed.
+    pub fn counted(&self) -> (Expr, Operator, Expr) {
+        if !self.rev {
+            let cmp = match self.inclusive {
+                true => Operator::LessEquals,
+                false => Operator::Less,
+            };
+            return (self.start.clone(), cmp, self.end.clone());
+        }
+        let first = match self.inclusive {
+            true => self.end.clone(),
+            false => Expr::Binary(
+                Box::new(self.end.clone()),
+                Operator::Minus,
+                Box::new(Expr::Int(1)),
+            ),
+        };
+        (first, Operator::GreaterEquals, self.start.clone())
+    }
+
+    pub fn step(&self) -> Expr {
+        self.step.clone().unwrap_or(Expr::Int(1))
+    }
+}
+
+/// Binding power of a binary operator, higher binds stronger.
+pub fn precedence(o: &Operator) -> u8 {
+    match o {
+        Operator::Pipe => 1,
+        Operator::Or => 2,
+        Operator::And => 3,
+        Operator::BitOr => 4,
+        Operator::BitAnd => 5,
+        Operator::Equals | Operator::NotEquals => 6,
+        Operator::Less | Operator::Greater | Operator::LessEquals | Operator::GreaterEquals => 7,
+        Operator::BitShiftLeft | Operator::BitShiftRight => 8,
+        Operator::Plus | Operator::Minus => 9,
+        Operator::Mul | Operator::Div | Operator::Mod => 10,
+    }
+}
+
+// -----------------------------------------------------------------------
+// Expressions
+// -----------------------------------------------------------------------
+
+pub fn expression(exp: &Expression) -> Result<Expr, String> {
+    let tokens = pipes(exp)?;
+    let mut parser = ExpressionParser {
+        tokens: &tokens,
+        pos: 0,
+        literals: true,
+    };
+    let tree = parser.binary(0)?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(tree),
+        Some(t) => Err(format!("Unexpected `{}` in expression", describe(t))),
+    }
+}
+
+/// The pattern of a `match` arm, for the text based transpilers that keep
+/// the values of the arms as tokens.
+pub fn pattern(tokens: &[Token]) -> Result<Pattern, String> {
+    let tokens: Vec<Token> = tokens
+        .iter()
+        .filter(|t| !matches!(t, Token::ExpVal(s) if s.is_empty()))
+        .cloned()
+        .collect();
+    let mut parser = ExpressionParser {
+        tokens: &tokens,
+        pos: 0,
+        literals: true,
+    };
+    let pattern = parser.pattern()?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(pattern),
+        Some(t) => Err(format!(
+            "Unexpected `{}` after the pattern `{pattern}`",
+            describe(t)
+        )),
+    }
+}
+
+/// Rewrites `x |> f(a)` into `f(x, a)` and `x |> f` into `f(x)`. `|>` binds
+/// weaker than every other operator, so the expression is split at the
+/// pipes outside of brackets and each part is passed on as first argument.
+/// The arguments and indices in round and square brackets are rewritten on
+/// their own first. Working on tokens lets the text based transpilers use
+/// this as well.
+pub fn pipes(exp: &[Token]) -> Result<Expression, String> {
+    // lines ending in a bracket leave an empty value behind
+    let tokens: Vec<Token> = exp
+        .iter()
+        .filter(|t| !matches!(t, Token::ExpVal(s) if s.is_empty()))
+        .cloned()
+        .collect();
+    let mut pos = 0;
+    let mut piped = group(&tokens, &mut pos)?;
+    // a `)` or `]` nothing opened, the parser reports it
+    while let Some(t) = tokens.get(pos) {
+        piped.push(t.clone());
+        pos += 1;
+        piped.append(&mut group(&tokens, &mut pos)?);
+    }
+    Ok(piped)
+}
+
+/// The tokens from `pos` up to the `)` or `]` closing the group they are in,
+/// which `pos` is left at, with the pipes rewritten in each of the values
+/// separated by commas.
+fn group(tokens: &[Token], pos: &mut usize) -> Result<Expression, String> {
+    let mut values: Vec<Expression> = vec![vec![]];
+    let mut depth = 0;
+    while let Some(t) = tokens.get(*pos) {
+        let value = values.last_mut().unwrap();
+        match t {
+            Token::ExpVal(s) if s == "(" || s == "[" => {
+                value.push(t.clone());
+                *pos += 1;
+                value.append(&mut group(tokens, pos)?);
+                if let Some(close) = tokens.get(*pos) {
+                    value.push(close.clone());
+                    *pos += 1;
+                }
+                continue;
+            }
+            Token::ExpVal(s) if s == ")" || s == "]" => break,
+            Token::ExpVal(s) if s == "{" => depth += 1,
+            Token::ExpVal(s) if s == "}" => depth -= 1,
+            Token::ExpVal(s) if s == "," && depth == 0 => {
+                values.push(vec![]);
+                *pos += 1;
+                continue;
+            }
+            _ => {}
+        }
+        value.push(t.clone());
+        *pos += 1;
+    }
+    let mut piped = vec![];
+    for (i, value) in values.into_iter().enumerate() {
+        if i > 0 {
+            piped.push(Token::ExpVal(",".to_string()));
+        }
+        piped.append(&mut calls(value)?);
+    }
+    Ok(piped)
+}
+
+/// Rewrites the pipes of `exp` outside of brackets into calls.
+fn calls(exp: Expression) -> Result<Expression, String> {
+    let mut parts: Vec<Vec<Token>> = vec![vec![]];
+    let mut depth = 0;
+    for t in exp {
+        match &t {
+            Token::ExpVal(s) if ["(", "[", "{"].contains(&s.as_str()) => depth += 1,
+            Token::ExpVal(s) if [")", "]", "}"].contains(&s.as_str()) => depth -= 1,
+            Token::Operator(Operator::Pipe) if depth == 0 => {
+                parts.push(vec![]);
+                continue;
+            }
+            _ => {}
+        }
+        parts.last_mut().unwrap().push(t);
+    }
+    let mut parts = parts.into_iter();
+    let mut piped = parts.next().unwrap();
+    for part in parts {
+        let value = |s: &str| Token::ExpVal(s.to_string());
+        let (name, args) = match part.as_slice() {
+            [Token::ExpVal(name)] => (name.clone(), &[][..]),
+            [Token::ExpVal(name), Token::ExpVal(open), args @ .., Token::ExpVal(close)]
+                if open == "(" && close == ")" =>
+            {
+                (name.clone(), args)
+            }
+            _ => return Err("The right side of `|>` must be a function".to_string()),
+        };
+        let mut call = vec![value(&name), value("(")];
+        call.append(&mut piped);
+        if !args.is_empty() {
+            call.push(value(","));
+            call.extend_from_slice(args);
+        }
+        call.push(value(")"));
+        piped = call;
+    }
+    Ok(piped)
+}
+
+fn describe(t: &Token) -> String {
+    match t {
+        Token::ExpVal(s) => s.clone(),
+        Token::Operator(o) => format!("{o:?}"),
+        _ => format!("{t:?}"),
+    }
+}
+
+struct ExpressionParser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    /// whether `name {` starts a struct literal, it doesn't in the value
+    /// of a `match`, where the `{` opens the arms
+    literals: bool,
+}
+
+impl ExpressionParser<'_> {
+    fn peek_val(&self) -> Option<&str> {
+        match self.tokens.get(self.pos) {
+            Some(Token::ExpVal(s)) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+    fn expect(&mut self, s: &str) -> Result<(), String> {
+        if self.peek_val() == Some(s) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            match self.tokens.get(self.pos) {
+                Some(t) => Err(format!("Expected `{s}` but found `{}`", describe(t))),
+                None => Err(format!("Expected `{s}` at the end of the expression")),
+            }
+        }
+    }
+    fn binary(&mut self, min: u8) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        while let Some(Token::Operator(o)) = self.tokens.get(self.pos) {
+            let prec = precedence(o);
+            if prec < min {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.binary(prec + 1)?;
+            lhs = Expr::Binary(Box::new(lhs), *o, Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+    fn unary(&mut self) -> Result<Expr, String> {
+        if let Some(Token::Operator(Operator::Minus)) = self.tokens.get(self.pos) {
+            self.pos += 1;
+            return Ok(match self.unary()? {
+                Expr::Int(i) => Expr::Int(-i),
+                Expr::Float(f) => Expr::Float(-f),
+                e => Expr::Neg(Box::new(e)),
+            });
+        }
+        let mut exp = self.primary()?;
+        loop {
+            match self.peek_val() {
+                Some("[") => {
+                    self.pos += 1;
+                    let index = self.binary(0)?;
+                    self.expect("]")?;
+                    exp = Expr::Index(Box::new(exp), Box::new(index));
+                }
+                // the lexer keeps `.field` after a bracket in one value
+                Some(s) if s.len() > 1 && s.starts_with('.') => {
+                    let s = s.to_string();
+                    self.pos += 1;
+                    exp = fields(exp, &s[1..]);
+                    if self.peek_val() == Some("(") {
+                        exp = self.method(exp)?;
+                    }
+                }
+                _ => break,
+            }
+        }
+        Ok(exp)
+    }
+    /// Turns the last field read from `exp` into a method called with the
+    /// arguments after it.
+    fn method(&mut self, exp: Expr) -> Result<Expr, String> {
+        let Expr::Field(receiver, name) = exp else {
+            return Err("Expected a method before `(`".to_string());
+        };
+        self.pos += 1;
+        Ok(Expr::Method(receiver, name, self.list(")")?))
+    }
+    /// Comma separated expressions up to `close`, which is consumed.
+    fn list(&mut self, close: &str) -> Result<Vec<Expr>, String> {
+        let mut items = vec![];
+        while self.peek_val() != Some(close) {
+            items.push(self.binary(0)?);
+            if self.peek_val() == Some(",") {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        self.expect(close)?;
+        Ok(items)
+    }
+    /// The fields of a struct literal up to its `}`, which is consumed.
+    fn literal(&mut self, name: String) -> Result<Expr, String> {
+        let mut fields: Vec<(String, Expr)> = vec![];
+        while self.peek_val() != Some("}") {
+            let field = match self.peek_val().and_then(|s| s.strip_suffix(':')) {
+                Some(field) if !field.is_empty() => field.to_string(),
+                _ => return Err(format!("Expected `field: value` in the `{name}` literal")),
+            };
+            if fields.iter().any(|(f, _)| *f == field) {
+                return Err(format!("`{field}` is set twice in the `{name}` literal"));
+            }
+            self.pos += 1;
+            fields.push((field, self.binary(0)?));
+            if self.peek_val() == Some(",") {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        self.expect("}")?;
+        Ok(Expr::Struct(name, fields))
+    }
+    /// The value and arms of a `match` up to its `}`, which is consumed.
+    fn match_(&mut self) -> Result<Expr, String> {
+        self.literals = false;
+        let value = self.binary(0);
+        self.literals = true;
+        let value = value?;
+        self.expect("{")?;
+        let mut arms = vec![];
+        while self.peek_val() != Some("}") {
+            let pattern = self.pattern()?;
+            self.expect("=>")?;
+            arms.push((pattern, self.binary(0)?));
+            if self.peek_val() == Some(",") {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        self.expect("}")?;
+        if arms.is_empty() {
+            return Err("A `match` needs at least one arm".to_string());
+        }
+        Ok(Expr::Match(Box::new(value), arms))
+    }
+    /// An integer with an optional `-`, nothing is consumed when there is
+    /// none.
+    fn int(&mut self) -> Option<i64> {
+        let at = match self.tokens.get(self.pos) {
+            Some(Token::Operator(Operator::Minus)) => self.pos + 1,
+            _ => self.pos,
+        };
+        let i = match self.tokens.get(at) {
+            Some(Token::ExpVal(s)) => s.parse::<i64>().ok()?,
+            _ => return None,
+        };
+        let negative = at > self.pos;
+        self.pos = at + 1;
+        Some(if negative { -i } else { i })
+    }
+    fn pattern(&mut self) -> Result<Pattern, String> {
+        if let Some(start) = self.int() {
+            let inclusive = match self.peek_val() {
+                Some("..") => false,
+                Some("..=") => true,
+                _ => return Ok(Pattern::Int(start)),
+            };
+            self.pos += 1;
+            let end = self
+                .int()
+                .ok_or("Expected an integer at the end of the range pattern")?;
+            let (last, dots) = match inclusive {
+                true => (end, "..="),
+                false => (end.saturating_sub(1), ".."),
+            };
+            if start > last {
+                return Err(format!("The range `{start}{dots}{end}` matches no value"));
+            }
+            return Ok(Pattern::Range(start, last));
+        }
+        let s = match self.tokens.get(self.pos) {
+            Some(Token::ExpVal(s)) => s.clone(),
+            Some(t) => return Err(format!("Expected a pattern but found `{}`", describe(t))),
+            None => return Err("Expected a pattern at the end of the `match`".to_string()),
+        };
+        self.pos += 1;
+        let name = |s: &str| {
+            s.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+        };
+        match s.as_str() {
+            "_" => Ok(Pattern::Wildcard),
+            "true" => Ok(Pattern::Bool(true)),
+            "false" => Ok(Pattern::Bool(false)),
+            _ if s.starts_with('"') => Ok(Pattern::Str(string(&s)?)),
+            _ if s.parse::<f64>().is_ok() => Err(format!(
+                "Only integers, bools, strings and enum variants can be matched, not `{s}`"
+            )),
+            _ => {
+                let Some((enum_, variant)) = s.split_once("::") else {
+                    return match name(&s) {
+                        true => Ok(Pattern::Binding(s)),
+                        false => Err(format!("Expected a pattern but found `{s}`")),
+                    };
+                };
+                if !name(enum_) || !name(variant) {
+                    return Err(format!("Expected a pattern but found `{s}`"));
+                }
+                let mut values: Vec<Option<String>> = vec![];
+                if self.peek_val() == Some("(") {
+                    self.pos += 1;
+                    while self.peek_val() != Some(")") {
+                        match self.peek_val() {
+                            Some("_") => values.push(None),
+                            Some(value) if name(value) => {
+                                if values.iter().flatten().any(|v| v == value) {
+                                    return Err(format!("`{value}` is bound twice in `{s}`"));
+                                }
+                                values.push(Some(value.to_string()));
+                            }
+                            _ => {
+                                return Err(format!(
+                                    "The values of `{s}` can only be bound to names or `_`"
+                                ))
+                            }
+                        }
+                        self.pos += 1;
+                        if self.peek_val() == Some(",") {
+                            self.pos += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    self.expect(")")?;
+                }
+                Ok(Pattern::Variant(
+                    enum_.to_string(),
+                    variant.to_string(),
+                    values,
+                ))
+            }
+        }
+    }
+    fn primary(&mut self) -> Result<Expr, String> {
+        let s = match self.tokens.get(self.pos) {
+            Some(Token::ExpVal(s)) => s.clone(),
+            Some(t) => return Err(format!("Unexpected `{}` in expression", describe(t))),
+            None => return Err("Expected a value at the end of the expression".to_string()),
+        };
+        self.pos += 1;
+        match s.as_str() {
+            "(" => {
+                let exp = self.binary(0)?;
+                self.expect(")")?;
+                Ok(exp)
+            }
+            "[" => Ok(Expr::Array(self.list("]")?)),
+            _ if s.starts_with('"') => Ok(Expr::Str(string(&s)?)),
+            "true" => Ok(Expr::Bool(true)),
+            "false" => Ok(Expr::Bool(false)),
+            "match" => self.match_(),
+            _ => {
+                if let Ok(i) = s.parse::<i64>() {
+                    Ok(Expr::Int(i))
+                } else if let Ok(f) = s.parse::<f64>() {
+                    Ok(Expr::Float(f))
+                } else if s.len() > 1 && s.starts_with('\'') && s.ends_with('\'') {
+                    char_(&s[1..s.len() - 1]).map(Expr::Char)
+                } else if [")", "]", ",", "{", "}"].contains(&s.as_str()) {
+                    Err(format!("Unexpected `{s}` in expression"))
+                } else if let Some((name, variant)) = s
+                    .split_once("::")
+                    .filter(|(name, variant)| !name.is_empty() && !variant.is_empty())
+                {
+                    let values = match self.peek_val() {
+                        Some("(") => {
+                            self.pos += 1;
+                            self.list(")")?
+                        }
+                        _ => vec![],
+                    };
+                    Ok(Expr::Variant(name.to_string(), variant.to_string(), values))
+                } else if let Some((name, path)) = s
+                    .split_once('.')
+                    .filter(|(name, path)| !name.is_empty() && !path.split('.').any(str::is_empty))
+                    .filter(|_| self.peek_val() == Some("("))
+                {
+                    self.method(fields(Expr::Var(name.to_string()), path))
+                } else if self.peek_val() == Some("(") {
+                    self.pos += 1;
+                    Ok(Expr::Call(s, self.list(")")?))
+                } else if self.literals && self.peek_val() == Some("{") {
+                    self.pos += 1;
+                    self.literal(s)
+                } else if let Some((name, path)) = s
+                    .split_once('.')
+                    .filter(|(name, path)| !name.is_empty() && !path.split('.').any(str::is_empty))
+                {
+                    Ok(fields(Expr::Var(name.to_string()), path))
+                } else {
+                    Ok(Expr::Var(s))
+                }
+            }
+        }
+    }
+}
+
+/// Accesses the fields of a path like `a.b` one after the other.
+fn fields(exp: Expr, path: &str) -> Expr {
+    path.split('.').fold(exp, |exp, field| {
+        Expr::Field(Box::new(exp), field.to_string())
+    })
+}
+
+/// The character an escape like `\n` stands for.
+fn escape(escaped: char) -> Option<char> {
+    match escaped {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        '0' => Some('\0'),
+        '\\' | '\'' | '"' => Some(escaped),
+        _ => None,
+    }
+}
+
+/// The text of a string literal, which the lexer keeps in one piece with its
+/// quotes.
+fn string(literal: &str) -> Result<String, String> {
+    let Some(s) = literal[1..]
+        .strip_suffix('"')
+        .filter(|s| !s.ends_with('\\') || s.ends_with("\\\\"))
+    else {
+        return Err(format!("The string {literal} is never closed"));
+    };
+    let mut text = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next().and_then(escape) {
+                Some(c) => text.push(c),
+                None => return Err(format!("Unknown escape in the string {literal}")),
+            },
+            c => text.push(c),
+        }
+    }
+    Ok(text)
+}
+
+/// The character of a char literal from what is between its quotes, which
+/// can be an escape like `\n`.
+fn char_(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    let c = match (chars.next(), chars.next()) {
+        (Some('\\'), Some(escaped)) => match escape(escaped) {
+            Some(c) => c,
+            None => return Err(format!("Unknown escape `\\{escaped}` in `'{s}'`")),
+        },
+        (Some(c), None) => return Ok(c),
+        _ => return Err(format!("`'{s}'` has to be a single character")),
+    };
+    match chars.next() {
+        None => Ok(c),
+        Some(_) => Err(format!("`'{s}'` has to be a single character")),
+    }
+}
+
+// -----------------------------------------------------------------------
+// Statements
+// -----------------------------------------------------------------------
+
+/// Builds the statements of a function body, errors name the line they
+/// occurred at.
+pub fn function(x: &Function) -> Result<Vec<Stmt>, String> {
+    let stmts = block(&x.lines, x.start_ln)?;
+    jumps(&stmts, &mut vec![])?;
+    Ok(stmts)
+}
+
+/// Builds the body of a `loop fn`: the statements of its `state` block run
+/// once, followed by a `loop` over the rest. With `every 16ms` each iteration
+/// starts with `sleep(16)`, so `continue` waits as well.
+pub fn loop_function(x: &Function) -> Result<Vec<Stmt>, String> {
+    let tokens = tokens(&x.lines, x.start_ln);
+    let mut iter = tokens.iter().peekable();
+    let mut stmts = match iter.peek() {
+        Some((ln, Token::State(_))) => {
+            iter.next();
+            body(&mut iter, *ln)?
+        }
+        _ => vec![],
+    };
+    let mut tick = vec![];
+    if let Some(ms) = x.every {
+        tick.push(Stmt::Expr(Expr::Call(
+            "sleep".to_string(),
+            vec![Expr::Int(ms as i64)],
+        )));
+    }
+    tick.extend(statements(&mut iter)?);
+    if let Some((ln, _)) = iter.next() {
+        return Err(format!("Unexpected `}}` at line {ln}"));
+    }
+    stmts.push(Stmt::Loop(tick));
+    jumps(&stmts, &mut vec![])?;
+    Ok(stmts)
+}
+
+/// Checks that every `break` and `continue` is in a loop, and that their
+/// labels name one of the loops they are in. `loops` holds the labels of
+/// the loops around `stmts`.
+fn jumps<'a>(stmts: &'a [Stmt], loops: &mut Vec<Option<&'a str>>) -> Result<(), String> {
+    for stmt in stmts {
+        let (label, stmt) = match stmt {
+            Stmt::Labeled(label, stmt) => (Some(label.as_str()), stmt.as_ref()),
+            stmt => (None, stmt),
+        };
+        match stmt {
+            Stmt::Loop(stmts) | Stmt::While(_, stmts) | Stmt::For(For { body: stmts, .. }) => {
+                loops.push(label);
+                jumps(stmts, loops)?;
+                loops.pop();
+            }
+            Stmt::If(branches, otherwise) => {
+                for (_, stmts) in branches {
+                    jumps(stmts, loops)?;
+                }
+                if let Some(stmts) = otherwise {
+                    jumps(stmts, loops)?;
+                }
+            }
+            Stmt::Break(label) | Stmt::Continue(label) => {
+                let name = match stmt {
+                    Stmt::Break(_) => "break",
+                    _ => "continue",
+                };
+                match label {
+                    None if loops.is_empty() => {
+                        return Err(format!("`{name}` outside of a loop"));
+                    }
+                    Some(label) if !loops.contains(&Some(label.as_str())) => {
+                        return Err(format!(
+                            "`{name} '{label}` isn't in a loop named `'{label}`"
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Whether a `break` in the body of a loop leaves that loop, and not one
+/// nested in it.
+pub fn breaks(stmts: &[Stmt]) -> bool {
+    stmts.iter().any(|stmt| match stmt {
+        Stmt::Break(None) => true,
+        Stmt::If(branches, otherwise) => {
+            branches.iter().any(|(_, stmts)| breaks(stmts))
+                || otherwise.as_deref().is_some_and(breaks)
+        }
+        _ => false,
+    })
+}
+
+/// Calls `f` with every expression in `stmts`, each one before the
+/// expressions it contains. Stops at the first error.
+pub fn walk(stmts: &[Stmt], f: &mut impl FnMut(&Expr) -> Result<(), String>) -> Result<(), String> {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Let(_, exp)
+            | Stmt::Assign(_, exp)
+            | Stmt::Return(Some(exp))
+            | Stmt::Yield(exp)
+            | Stmt::YieldFrom(exp)
+            | Stmt::Expr(exp) => walk_expr(exp, f)?,
+            Stmt::If(branches, otherwise) => {
+                for (condition, stmts) in branches {
+                    walk_expr(condition, f)?;
+                    walk(stmts, f)?;
+                }
+                if let Some(stmts) = otherwise {
+                    walk(stmts, f)?;
+                }
+            }
+            Stmt::For(x) => {
+                match &x.iter {
+                    Iterable::Expr(exp) => walk_expr(exp, f)?,
+                    Iterable::Range(range) => {
+                        walk_expr(&range.start, f)?;
+                        walk_expr(&range.end, f)?;
+                        if let Some(step) = &range.step {
+                            walk_expr(step, f)?;
+                        }
+                    }
+                }
+                walk(&x.body, f)?;
+            }
+            Stmt::While(condition, stmts) => {
+                walk_expr(condition, f)?;
+                walk(stmts, f)?;
+            }
+            Stmt::Loop(stmts) => walk(stmts, f)?,
+            Stmt::Labeled(_, stmt) => walk(std::slice::from_ref(stmt), f)?,
+            Stmt::Break(_) | Stmt::Continue(_) | Stmt::Return(None) => {}
+        }
+    }
+    Ok(())
+}
+
+/// [`walk`] for a single expression.
+pub fn walk_expr(
+    exp: &Expr,
+    f: &mut impl FnMut(&Expr) -> Result<(), String>,
+) -> Result<(), String> {
+    f(exp)?;
+    match exp {
+        Expr::Int(_)
+        | Expr::Float(_)
+        | Expr::Bool(_)
+        | Expr::Char(_)
+        | Expr::Str(_)
+        | Expr::Var(_) => Ok(()),
+        Expr::Array(items) | Expr::Call(_, items) | Expr::Variant(_, _, items) => {
+            items.iter().try_for_each(|exp| walk_expr(exp, f))
+        }
+        Expr::Method(exp, _, args) => {
+            walk_expr(exp, f)?;
+            args.iter().try_for_each(|exp| walk_expr(exp, f))
+        }
+        Expr::Struct(_, fields) => fields.iter().try_for_each(|(_, exp)| walk_expr(exp, f)),
+        Expr::Index(exp, index) | Expr::Binary(exp, _, index) => {
+            walk_expr(exp, f)?;
+            walk_expr(index, f)
+        }
+        Expr::Field(exp, _) | Expr::Neg(exp) => walk_expr(exp, f),
+        Expr::Match(value, arms) => {
+            walk_expr(value, f)?;
+            arms.iter().try_for_each(|(_, exp)| walk_expr(exp, f))
+        }
+    }
+}
+
+/// Checks that the patterns of a `match` cover every value, and that each
+/// one matches a value the patterns before it don't. `variants` gives the variants
+/// of an enum with the number of values they hold.
+pub fn exhaustive<'a>(
+    patterns: impl IntoIterator<Item = &'a Pattern>,
+    variants: impl Fn(&str) -> Option<Vec<(String, usize)>>,
+) -> Result<(), String> {
+    let mut first: Option<&Pattern> = None;
+    let mut rest = false;
+    // integers as sorted, disjoint inclusive ranges
+    let mut ints: Vec<(i64, i64)> = vec![];
+    let mut bools = vec![];
+    let mut strings = vec![];
+    let mut enum_: Option<(&str, Vec<(String, usize)>)> = None;
+    let mut covered = vec![];
+    for pattern in patterns {
+        if rest {
+            return Err(format!(
+                "The arm `{pattern}` is unreachable, the arms before it match everything"
+            ));
+        }
+        let same = match (first, pattern) {
+            (_, Pattern::Wildcard | Pattern::Binding(_)) | (None, _) => true,
+            (Some(Pattern::Int(_) | Pattern::Range(..)), Pattern::Int(_) | Pattern::Range(..)) => {
+                true
+            }
+            (Some(Pattern::Variant(a, ..)), Pattern::Variant(b, ..)) => a == b,
+            (Some(a), b) => std::mem::discriminant(a) == std::mem::discriminant(b),
+        };
+        if !same {
+            return Err(format!(
+                "`{pattern}` can't be matched in the same `match` as `{}`",
+                first.unwrap()
+            ));
+        }
+        if !matches!(pattern, Pattern::Wildcard | Pattern::Binding(_)) {
+            first.get_or_insert(pattern);
+        }
+        let new = match pattern {
+            Pattern::Wildcard | Pattern::Binding(_) => {
+                rest = true;
+                true
+            }
+            Pattern::Int(i) => add(&mut ints, *i, *i),
+            Pattern::Range(start, end) => add(&mut ints, *start, *end),
+            Pattern::Bool(b) => {
+                !bools.contains(b) && {
+                    bools.push(*b);
+                    true
+                }
+            }
+            Pattern::Str(s) => {
+                !strings.contains(&s) && {
+                    strings.push(s);
+                    true
+                }
+            }
+            Pattern::Variant(name, variant, values) => {
+                let (_, declared) = match &enum_ {
+                    Some(x) => x,
+                    None => enum_.insert((
+                        name,
+                        variants(name).ok_or(format!("Unknown enum `{name}`"))?,
+                    )),
+                };
+                match declared.iter().find(|(v, _)| v == variant) {
+                    Some((_, count)) if *count != values.len() => {
+                        return Err(format!(
+                            "`{name}::{variant}` holds {count} values but got {}",
+                            values.len()
+                        ))
+                    }
+                    Some(_) => {}
+                    None => return Err(format!("The enum `{name}` has no variant `{variant}`")),
+                }
+                !covered.contains(&variant) && {
+                    covered.push(variant);
+                    true
+                }
+            }
+        };
+        if !new {
+            return Err(format!(
+                "The arm `{pattern}` is unreachable, the arms before it match its values"
+            ));
+        }
+    }
+    if rest {
+        return Ok(());
+    }
+    let missing: Vec<String> = match (first, enum_) {
+        (Some(Pattern::Variant(name, ..)), Some((_, declared))) => declared
+            .iter()
+            .filter(|(v, _)| !covered.contains(&v))
+            .map(|(v, _)| format!("{name}::{v}"))
+            .collect(),
+        (Some(Pattern::Bool(_)), _) => [true, false]
+            .iter()
+            .filter(|b| !bools.contains(b))
+            .map(|b| b.to_string())
+            .collect(),
+        (Some(Pattern::Int(_) | Pattern::Range(..)), _) if ints == [(i64::MIN, i64::MAX)] => {
+            vec![]
+        }
+        _ => return Err("The `match` doesn't cover every value, add a `_` arm".to_string()),
+    };
+    match missing.is_empty() {
+        true => Ok(()),
+        false => Err(format!(
+            "The `match` doesn't cover `{}`",
+            missing.join("`, `")
+        )),
+    }
+}
+
+/// Adds the integers `start..=end` to `ints`, false when they are all in it
+/// already.
+fn add(ints: &mut Vec<(i64, i64)>, start: i64, end: i64) -> bool {
+    if ints.iter().any(|&(s, e)| s <= start && end <= e) {
+        return false;
+    }
+    ints.push((start, end));
+    ints.sort();
+    let mut merged: Vec<(i64, i64)> = vec![];
+    for &(s, e) in ints.iter() {
+        match merged.last_mut() {
+            Some(last) if s <= last.1.saturating_add(1) => last.1 = last.1.max(e),
+            _ => merged.push((s, e)),
+        }
+    }
+    *ints = merged;
+    true
+}
+
+/// Builds nested statements from the lines produced by `Lexer::low_level`.
+pub fn block(lines: &[Line], start_ln: i32) -> Result<Vec<Stmt>, String> {
+    let tokens = tokens(lines, start_ln);
+    let mut iter = tokens.iter().peekable();
+    let stmts = statements(&mut iter)?;
+    if let Some((ln, _)) = iter.next() {
+        return Err(format!("Unexpected `}}` at line {ln}"));
+    }
+    Ok(stmts)
+}
+
+/// The tokens of all lines with their line number.
+pub fn tokens(lines: &[Line], start_ln: i32) -> Vec<(i32, Token)> {
+    // Calls like `print(x)` reach us as `Generic` and bracket tokens, those
+    // are joined back into an expression per line.
+    let mut tokens: Vec<(i32, Token)> = vec![];
+    for (i, line) in lines.iter().enumerate() {
+        let line_number = start_ln + i as i32 + 1;
+        let mut generic: Vec<String> = vec![];
+        for token in &line.tokens {
+            let s = match token {
+                Token::Generic(s) => s.as_str(),
+                Token::OpenRoBr(_) => "(",
+                Token::CloseRoBr(_) => ")",
+                Token::OpenSqBr(_) => "[",
+                Token::CloseSqBr(_) => "]",
+                Token::Comma => ",",
+                _ => {
+                    flush(&mut generic, &mut tokens, line_number);
+                    tokens.push((line_number, token.clone()));
+                    continue;
+                }
+            };
+            generic.push(s.to_string());
+        }
+        flush(&mut generic, &mut tokens, line_number);
+    }
+    tokens
+}
+
+fn flush(generic: &mut Vec<String>, tokens: &mut Vec<(i32, Token)>, line_number: i32) {
+    if !generic.is_empty() {
+        let exp = generate_expression(generic.iter().collect(), line_number);
+        tokens.push((line_number, Token::Expression(exp)));
+        generic.clear();
+    }
+}
+
+type TokenIter<'a> = std::iter::Peekable<std::slice::Iter<'a, (i32, Token)>>;
+
+fn at(ln: i32) -> impl Fn(String) -> String {
+    move |e| format!("{e} at line {ln}")
+}
+
+/// Statements up to (not including) the `End` closing the current block.
+fn statements(iter: &mut TokenIter) -> Result<Vec<Stmt>, String> {
+    let mut stmts = vec![];
+    // the label of the loop that follows
+    let mut label = None;
+    while let Some((ln, token)) = iter.peek() {
+        let ln = *ln;
+        let stmt = match token {
+            Token::Label(name) => {
+                iter.next();
+                label = Some(name.clone());
+                continue;
+            }
+            Token::End(_) => break,
+            Token::If(_if) => {
+                iter.next();
+                let mut branches =
+                    vec![(expression(&_if.condition).map_err(at(ln))?, body(iter, ln)?)];
+                let mut otherwise = None;
+                loop {
+                    match iter.peek() {
+                        Some((ln, Token::ElseIf(_if))) => {
+                            iter.next();
+                            branches.push((
+                                expression(&_if.condition).map_err(at(*ln))?,
+                                body(iter, *ln)?,
+                            ));
+                        }
+                        Some((ln, Token::Else(_))) => {
+                            iter.next();
+                            otherwise = Some(body(iter, *ln)?);
+                            break;
+                        }
+                        _ => break,
+                    }
+                }
+                Stmt::If(branches, otherwise)
+            }
+            Token::ElseIf(_) | Token::Else(_) => {
+                return Err(format!("`else` without `if` at line {ln}"));
+            }
+            Token::For(_for) => {
+                iter.next();
+                let iter_ = match &_for.range {
+                    Some(range) => Iterable::Range(Range {
+                        start: expression(&range.start).map_err(at(ln))?,
+                        end: expression(&range.end).map_err(at(ln))?,
+                        inclusive: range.inclusive,
+                        step: match &range.step {
+                            Some(step) => Some(expression(step).map_err(at(ln))?),
+                            None => None,
+                        },
+                        rev: range.rev,
+                    }),
+                    None => Iterable::Expr(expression(&_for.iter_exp).map_err(at(ln))?),
+                };
+                Stmt::For(For {
+                    index: _for.index.clone(),
+                    var_name: _for.var_name.clone(),
+                    iter: iter_,
+                    body: body(iter, ln)?,
+                })
+            }
+            Token::Loop(_) => {
+                iter.next();
+                Stmt::Loop(body(iter, ln)?)
+            }
+            Token::While(_while) => {
+                iter.next();
+                let condition = expression(&_while.condition).map_err(at(ln))?;
+                Stmt::While(condition, body(iter, ln)?)
+            }
+            Token::Break(label) => {
+                iter.next();
+                Stmt::Break(label.clone())
+            }
+            Token::Continue(label) => {
+                iter.next();
+                Stmt::Continue(label.clone())
+            }
+            Token::State(_) => {
+                return Err(format!(
+                    "`state` blocks only go at the start of a `loop fn`, line {ln}"
+                ));
+            }
+            Token::Var(_var) => {
+                iter.next();
+                Stmt::Let(_var.name.clone(), expression(&_var.exp).map_err(at(ln))?)
+            }
+            Token::Assign(_assign) => {
+                iter.next();
+                Stmt::Assign(
+                    _assign.var.clone(),
+                    expression(&_assign.exp).map_err(at(ln))?,
+                )
+            }
+            Token::Return(exp) if exp.is_empty() => {
+                iter.next();
+                Stmt::Return(None)
+            }
+            Token::Return(exp) => {
+                iter.next();
+                Stmt::Return(Some(expression(exp).map_err(at(ln))?))
+            }
+            Token::Yield(exp) => {
+                iter.next();
+                Stmt::Yield(expression(exp).map_err(at(ln))?)
+            }
+            Token::YieldFrom(exp) => {
+                iter.next();
+                Stmt::YieldFrom(expression(exp).map_err(at(ln))?)
+            }
+            Token::Expression(exp) => {
+                iter.next();
+                Stmt::Expr(expression(exp).map_err(at(ln))?)
+            }
+            Token::Empty | Token::Comment(_) => {
+                iter.next();
+                continue;
+            }
+            t => return Err(format!("Unexpected `{}` at line {ln}", describe(t))),
+        };
+        match label.take() {
+            Some(label) => match stmt {
+                Stmt::Loop(_) | Stmt::While(..) | Stmt::For(..) => {
+                    stmts.push(Stmt::Labeled(label, Box::new(stmt)));
+                }
+                _ => return Err(format!("Only loops can have a label, line {ln}")),
+            },
+            None => stmts.push(stmt),
+        }
+    }
+    Ok(stmts)
+}
+
+/// The statements of a block and its closing `End`.
+fn body(iter: &mut TokenIter, ln: i32) -> Result<Vec<Stmt>, String> {
+    let stmts = statements(iter)?;
+    match iter.next() {
+        Some((_, Token::End(_))) => Ok(stmts),
+        _ => Err(format!("Missing `}}` for the block at line {ln}")),
+    }
+}
+
+// -----------------------------------------------------------------------
+// Traits
+// -----------------------------------------------------------------------
+
+/// `fn name(self, argument: type): type`, how a function of a trait is
+/// declared.
+pub fn signature(x: &Function) -> String {
+    let args: Vec<String> = (x.arguments.iter())
+        .map(|arg| match arg.name.as_str() {
+            "self" => "self".to_string(),
+            name => format!("{name}: {}", arg.type_),
+        })
+        .collect();
+    let mut signature = format!("fn {}({})", x.name, args.join(", "));
+    if !x.return_type.is_void() {
+        signature.push_str(&format!(": {}", x.return_type));
+    }
+    signature
+}
+
+/// What the checks of traits and generics know about a program: the types
+/// the values of expressions have where they can be told without running it.
+#[derive(Clone, Debug, Default)]
+pub struct Signatures {
+    /// the arguments, return type and type parameters of each function,
+    /// `Name::function` for the ones of an `impl`
+    functions: HashMap<String, (Vec<Arg>, Type, Vec<Generic>)>,
+    /// the fields and type parameters of each struct
+    structs: HashMap<String, (Vec<Arg>, Vec<Generic>)>,
+    traits: HashMap<String, Vec<Function>>,
+    /// `(trait, type)` of each `impl Trait for Type`
+    implemented: Vec<(String, String)>,
+    /// calling these gives the values they yield, not a value of their type
+    generators: Vec<String>,
+}
+
+impl Signatures {
+    /// Adds the items of `ast`. Every `impl Trait for Type` has to have the
+    /// functions of the trait, traits used in types have to exist and the
+    /// type parameters have to be inferable.
+    pub fn extend(&mut self, ast: &[Token]) -> Result<(), String> {
+        let mut types = vec![];
+        let mut fields = vec![];
+        for nt in ast {
+            match nt {
+                Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) => {
+                    self.insert(x.name.clone(), x);
+                    types.push((x.name.clone(), x));
+                    if let Token::GeneratorFunction(_) = nt {
+                        self.generators.push(x.name.clone());
+                    }
+                }
+                Token::Impl(x) => {
+                    for function in &x.functions {
+                        let name = format!("{}::{}", x.name, function.name);
+                        self.insert(name.clone(), function);
+                        types.push((name, function));
+                    }
+                }
+                Token::Struct(x) => {
+                    let generics = x.generics.clone();
+                    self.structs
+                        .insert(x.name.clone(), (x.fields.clone(), generics));
+                    fields.extend(x.fields.iter().map(|field| (&x.name, field)));
+                }
+                Token::Trait(x) => {
+                    self.traits.insert(x.name.clone(), x.functions.clone());
+                    for function in &x.functions {
+                        types.push((format!("{}::{}", x.name, function.name), function));
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (name, x) in types {
+            if !x.generics.is_empty() && name.contains("::") {
+                return Err(format!(
+                    "`{name}` can't have type parameters, only functions outside of an `impl` or `trait` can"
+                ));
+            }
+            for arg in &x.arguments {
+                self.known(&arg.type_, &name)?;
+            }
+            self.known(&x.return_type, &name)?;
+            let types: Vec<&Type> = x.arguments.iter().map(|arg| &arg.type_).collect();
+            self.inferable(&x.generics, &types, &name)?;
+        }
+        for (name, field) in fields {
+            if let Type::Impl(trait_) = &field.type_ {
+                return Err(format!(
+                    "The field `{}` of `{name}` can't be `impl {trait_}`, only `dyn {trait_}`",
+                    field.name
+                ));
+            }
+            self.known(&field.type_, name)?;
+        }
+        for (name, (fields, generics)) in &self.structs {
+            let types: Vec<&Type> = fields.iter().map(|field| &field.type_).collect();
+            self.inferable(generics, &types, name)?;
+        }
+        for nt in ast {
+            match nt {
+                Token::Impl(x) if self.generic(&x.name) => {
+                    let name = x.name.split('<').next().unwrap_or_default();
+                    return Err(format!(
+                        "The generic struct `{name}` can't have an `impl`, functions like `fn name<T>(value: {name}<T>)` can take it"
+                    ));
+                }
+                Token::Impl(
+                    x @ Impl {
+                        trait_: Some(trait_),
+                        ..
+                    },
+                ) => {
+                    self.conforms(x, trait_)?;
+                    self.implemented.push((trait_.clone(), x.name.clone()));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, name: String, x: &Function) {
+        let signature = (
+            x.arguments.clone(),
+            x.return_type.clone(),
+            x.generics.clone(),
+        );
+        self.functions.insert(name, signature);
+    }
+
+    /// The fields and type parameters of a struct.
+    pub fn struct_(&self, name: &str) -> Option<&(Vec<Arg>, Vec<Generic>)> {
+        self.structs.get(name)
+    }
+
+    /// Checks that the traits named by `impl` and `dyn` in `type_` exist.
+    fn known(&self, type_: &Type, used_by: &str) -> Result<(), String> {
+        let mut unknown = None;
+        type_.visit(&mut |type_| match type_.bound() {
+            Some(trait_) if !self.traits.contains_key(trait_) => unknown = Some(trait_),
+            _ => {}
+        });
+        match unknown {
+            Some(trait_) => Err(format!("Unknown trait `{trait_}` in `{used_by}`")),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that the bounds of `generics` are traits and that each one is
+    /// used by `types`, the ones of the values it is inferred from.
+    fn inferable(&self, generics: &[Generic], types: &[&Type], name: &str) -> Result<(), String> {
+        for generic in generics {
+            if let Some(trait_) = &generic.bound {
+                self.known(&Type::Impl(trait_.clone()), name)?;
+            }
+            if !types
+                .iter()
+                .any(|type_| type_.names().contains(&generic.name.as_str()))
+            {
+                return Err(format!(
+                    "The type parameter `{}` of `{name}` isn't used by its {}, which it is inferred from",
+                    generic.name,
+                    match self.structs.contains_key(name) {
+                        true => "fields",
+                        false => "arguments",
+                    }
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `name` is a generic struct, or one with type arguments.
+    fn generic(&self, name: &str) -> bool {
+        match Type::parse(name) {
+            Ok(Type::Named(name, arguments)) => {
+                !arguments.is_empty()
+                    || (self.structs.get(&name)).is_some_and(|(_, generics)| !generics.is_empty())
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks that `x` has every function of `trait_`, declared like in the
+    /// trait, and nothing else.
+    fn conforms(&self, x: &Impl, trait_: &str) -> Result<(), String> {
+        let item = format!("impl {trait_} for {}", x.name);
+        let Some(required) = self.traits.get(trait_) else {
+            return Err(format!("Unknown trait `{trait_}` in `{item}`"));
+        };
+        if !self.structs.contains_key(&x.name) {
+            return Err(format!("`{item}` needs a struct named `{}`", x.name));
+        }
+        for function in required {
+            let Some(found) = x.functions.iter().find(|f| f.name == function.name) else {
+                return Err(format!("`{item}` is missing `{}`", signature(function)));
+            };
+            let types = |f: &Function| -> Vec<Type> {
+                (f.arguments.iter().skip(1))
+                    .map(|arg| arg.type_.clone())
+                    .chain([f.return_type.clone()])
+                    .collect()
+            };
+            if !found.method() || types(found) != types(function) {
+                return Err(format!(
+                    "`{}::{}` has to be `{}` like in the trait `{trait_}`",
+                    x.name,
+                    found.name,
+                    signature(function)
+                ));
+            }
+        }
+        match x
+            .functions
+            .iter()
+            .find(|f| !required.iter().any(|function| function.name == f.name))
+        {
+            Some(f) => Err(format!(
+                "`{}` is not a function of the trait `{trait_}`, it goes in `impl {}`",
+                f.name, x.name
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether values of `type_` can be used as `impl trait_`.
+    pub fn implements(&self, type_: &Type, trait_: &str) -> bool {
+        type_.bound() == Some(trait_)
+            || (self.implemented.iter()).any(|(t, name)| t == trait_ && *name == type_.to_string())
+    }
+
+    /// Checks the values passed for arguments and fields like `s: impl
+    /// Shape` or `value: T`, and the methods called on them in a function
+    /// body. In the body, a value of type `T: Shape` is an `impl Shape`.
+    /// Values whose type isn't known are left to run time.
+    pub fn bounds(
+        &self,
+        generics: &[Generic],
+        arguments: &[Arg],
+        stmts: &[Stmt],
+    ) -> Result<(), String> {
+        let bounded: HashMap<String, Type> = (generics.iter())
+            .filter_map(|g| Some((g.name.clone(), Type::Impl(g.bound.clone()?))))
+            .collect();
+        let arguments: Vec<Arg> = (arguments.iter())
+            .map(|arg| Arg {
+                name: arg.name.clone(),
+                type_: arg.type_.substitute(&bounded),
+            })
+            .collect();
+        let vars = self.vars(&arguments, stmts);
+        walk(stmts, &mut |exp| self.passed(exp, &vars, generics))
+    }
+
+    /// The types of the arguments and of the variables declared in `stmts`
+    /// that can be told.
+    pub fn vars(&self, arguments: &[Arg], stmts: &[Stmt]) -> HashMap<String, Type> {
+        let mut vars: HashMap<String, Type> = (arguments.iter())
+            .map(|arg| (arg.name.clone(), arg.type_.clone()))
+            .collect();
+        self.lets(stmts, &mut vars);
+        vars
+    }
+
+    /// The types of the variables declared in `stmts`, the ones that can
+    /// have values of different types are forgotten.
+    fn lets(&self, stmts: &[Stmt], vars: &mut HashMap<String, Type>) {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Let(name, exp) => match self.type_of(exp, vars) {
+                    Some(type_) => {
+                        vars.insert(name.clone(), type_);
+                    }
+                    None => {
+                        vars.remove(name);
+                    }
+                },
+                Stmt::If(branches, otherwise) => {
+                    for (_, stmts) in branches {
+                        self.lets(stmts, vars);
+                    }
+                    if let Some(stmts) = otherwise {
+                        self.lets(stmts, vars);
+                    }
+                }
+                Stmt::For(x) => {
+                    vars.remove(&x.var_name);
+                    self.lets(&x.body, vars);
+                }
+                Stmt::Loop(stmts) | Stmt::While(_, stmts) => self.lets(stmts, vars),
+                Stmt::Labeled(_, stmt) => self.lets(std::slice::from_ref(stmt), vars),
+                _ => {}
+            }
+        }
+    }
+
+    /// The type of the value of `exp`, when it can be told.
+    pub fn type_of(&self, exp: &Expr, vars: &HashMap<String, Type>) -> Option<Type> {
+        // impl functions have no type parameters, `exp` is a call when they
+        // are inferred
+        let returns = |name: &str| {
+            if self.generators.iter().any(|g| g == name) {
+                return None;
+            }
+            let (_, type_, generics) = self.functions.get(name)?;
+            let types = self.type_arguments(exp, vars).ok()?;
+            let known = generics.iter().all(|g| types.contains_key(&g.name));
+            known.then(|| type_.substitute(&types))
+        };
+        match exp {
+            Expr::Int(_) => Some(Type::INT),
+            Expr::Float(_) => Some(Type::Primitive(Primitive::F64)),
+            Expr::Char(_) => Some(Type::Primitive(Primitive::Char)),
+            Expr::Bool(_) => Some(Type::BOOL),
+            Expr::Str(_) => Some(Type::Primitive(Primitive::Str)),
+            Expr::Var(name) => vars.get(name).cloned(),
+            Expr::Array(items) => {
+                let mut types = items.iter().map(|item| self.type_of(item, vars));
+                let first = types.next()??;
+                types
+                    .all(|type_| type_.as_ref() == Some(&first))
+                    .then(|| Type::Array(Box::new(first)))
+            }
+            Expr::Index(value, _) => match self.type_of(value, vars)? {
+                Type::Array(item) => Some(*item),
+                _ => None,
+            },
+            Expr::Struct(name, _) => match self.structs.get(name)? {
+                (_, generics) if generics.is_empty() => Some(Type::named(name)),
+                (_, generics) => {
+                    let types = self.type_arguments(exp, vars).ok()?;
+                    let types: Option<Vec<Type>> = (generics.iter())
+                        .map(|g| types.get(&g.name).cloned())
+                        .collect();
+                    Some(Type::Named(name.clone(), types?))
+                }
+            },
+            Expr::Call(name, _) => returns(name),
+            Expr::Variant(name, function, _) => returns(&format!("{name}::{function}")),
+            Expr::Method(value, method, _) => {
+                let type_ = self.type_of(value, vars)?;
+                match type_.bound() {
+                    Some(trait_) => (self.traits.get(trait_)?.iter())
+                        .find(|x| x.name == *method)
+                        .map(|x| x.return_type.clone()),
+                    None => returns(&format!("{type_}::{method}")),
+                }
+            }
+            Expr::Field(value, field) => {
+                let Type::Named(name, arguments) = self.type_of(value, vars)? else {
+                    return None;
+                };
+                let (fields, generics) = self.structs.get(&name)?;
+                let types = (generics.iter().map(|g| g.name.clone()))
+                    .zip(arguments)
+                    .collect();
+                (fields.iter())
+                    .find(|f| f.name == *field)
+                    .map(|f| f.type_.substitute(&types))
+            }
+            Expr::Neg(value) => self.type_of(value, vars),
+            Expr::Binary(l, o, r) => match o {
+                Operator::Equals
+                | Operator::NotEquals
+                | Operator::Less
+                | Operator::Greater
+                | Operator::LessEquals
+                | Operator::GreaterEquals
+                | Operator::And
+                | Operator::Or => Some(Type::BOOL),
+                Operator::Pipe => None,
+                _ => match (self.type_of(l, vars)?, self.type_of(r, vars)?) {
+                    (l, r) if l == r => Some(l),
+                    // an `int` literal is turned into the float it is used with
+                    (Type::INT, float) | (float, Type::INT)
+                        if float.primitive().is_some_and(Primitive::float) =>
+                    {
+                        Some(float)
+                    }
+                    _ => None,
+                },
+            },
+            _ => None,
+        }
+    }
+
+    /// The types the type parameters stand for in a call of a generic
+    /// function or in a literal of a generic struct, inferred from the
+    /// values passed for them. Parameters whose values have no known type
+    /// are left out.
+    pub fn type_arguments(
+        &self,
+        exp: &Expr,
+        vars: &HashMap<String, Type>,
+    ) -> Result<HashMap<String, Type>, String> {
+        let (name, params, generics, values): (String, Vec<Arg>, &[Generic], Vec<&Expr>) = match exp
+        {
+            Expr::Call(name, args) => match self.functions.get(name) {
+                Some((params, _, generics)) => (
+                    name.clone(),
+                    params.clone(),
+                    generics,
+                    args.iter().collect(),
+                ),
+                None => return Ok(HashMap::new()),
+            },
+            Expr::Struct(name, fields) => match self.structs.get(name) {
+                Some((declared, generics)) => {
+                    let (params, values) = (fields.iter())
+                        .filter_map(|(field, value)| {
+                            let declared = declared.iter().find(|f| f.name == *field)?;
+                            Some((declared.clone(), value))
+                        })
+                        .unzip();
+                    (name.clone(), params, generics, values)
+                }
+                None => return Ok(HashMap::new()),
+            },
+            _ => return Ok(HashMap::new()),
+        };
+        // the type each parameter stands for and the argument it is from
+        let mut types: HashMap<String, (Type, &str)> = HashMap::new();
+        for (param, value) in params.iter().zip(values) {
+            let Some(type_) = self.type_of(value, vars) else {
+                continue;
+            };
+            let mut found = vec![];
+            unify(&param.type_, &type_, generics, &mut found);
+            for (generic, type_) in found {
+                match types.get(&generic) {
+                    Some((other, from)) if *other != type_ => {
+                        return Err(format!(
+                            "The type `{generic}` of `{name}` is `{other}` for `{from}` but `{type_}` for `{}`",
+                            param.name
+                        ));
+                    }
+                    Some(_) => {}
+                    None => {
+                        types.insert(generic, (type_, &param.name));
+                    }
+                }
+            }
+        }
+        for generic in generics {
+            let (Some(trait_), Some((type_, _))) = (&generic.bound, types.get(&generic.name))
+            else {
+                continue;
+            };
+            if !self.implements(type_, trait_) {
+                return Err(format!(
+                    "`{type_}` doesn't implement the trait `{trait_}`, which `{}` of `{name}` needs",
+                    generic.name
+                ));
+            }
+        }
+        Ok(types
+            .into_iter()
+            .map(|(generic, (type_, _))| (generic, type_))
+            .collect())
+    }
+
+    /// Checks the values `exp` passes on, when it is a call, a method call or
+    /// a struct literal. `generics` are the type parameters of the function
+    /// `exp` is in.
+    fn passed(
+        &self,
+        exp: &Expr,
+        vars: &HashMap<String, Type>,
+        generics: &[Generic],
+    ) -> Result<(), String> {
+        self.type_arguments(exp, vars)?;
+        if let Expr::Method(value, method, _) = exp {
+            if let Some(type_) = self.type_of(value, vars) {
+                if generics.iter().any(|g| type_ == Type::named(&g.name)) {
+                    return Err(format!(
+                        "`{type_}` has no method `{method}`, it needs a trait bound like `<{type_}: Trait>`"
+                    ));
+                }
+                if let Some(trait_) = type_.bound() {
+                    let mut functions = self.traits.get(trait_).into_iter().flatten();
+                    if !functions.any(|x| x.name == *method) {
+                        return Err(format!("The trait `{trait_}` has no method `{method}`"));
+                    }
+                }
+            }
+        }
+        let Some((name, params, values)) = self.parameters(exp, vars) else {
+            return Ok(());
+        };
+        for (param, value) in params.iter().zip(values) {
+            let Some(trait_) = param.type_.bound() else {
+                continue;
+            };
+            match self.type_of(value, vars) {
+                Some(type_) if !self.implements(&type_, trait_) => {
+                    return Err(format!(
+                        "`{type_}` doesn't implement the trait `{trait_}`, which `{}` of `{name}` needs",
+                        param.name
+                    ));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// The name of what `exp` calls or builds, its parameters and the values
+    /// passed for them, when it is a call of a known function or method or a
+    /// struct literal. The `self` of methods is left out, and the fields of
+    /// a literal are the ones it sets.
+    pub fn parameters<'e>(
+        &self,
+        exp: &'e Expr,
+        vars: &HashMap<String, Type>,
+    ) -> Option<(String, Vec<Arg>, Vec<&'e Expr>)> {
+        match exp {
+            Expr::Call(name, args) => {
+                let (params, ..) = self.functions.get(name)?;
+                Some((name.clone(), params.clone(), args.iter().collect()))
+            }
+            Expr::Variant(name, function, args) => {
+                let name = format!("{name}::{function}");
+                let (params, ..) = self.functions.get(&name)?;
+                Some((name, params.clone(), args.iter().collect()))
+            }
+            Expr::Method(value, method, args) => {
+                let type_ = self.type_of(value, vars)?;
+                let params = match type_.bound() {
+                    Some(trait_) => {
+                        let functions = self.traits.get(trait_)?;
+                        let found = functions.iter().find(|x| x.name == *method)?;
+                        found.arguments.clone()
+                    }
+                    None => self.functions.get(&format!("{type_}::{method}"))?.0.clone(),
+                };
+                let name = format!("{type_}::{method}");
+                let params = params.into_iter().skip(1).collect();
+                Some((name, params, args.iter().collect()))
+            }
+            Expr::Struct(name, fields) => {
+                let (declared, _) = self.structs.get(name)?;
+                let (params, values) = (fields.iter())
+                    .filter_map(|(field, value)| {
+                        let declared = declared.iter().find(|f| f.name == *field)?;
+                        Some((declared.clone(), value))
+                    })
+                    .unzip();
+                Some((name.clone(), params, values))
+            }
+            _ => None,
+        }
+    }
+}
+
+// -----------------------------------------------------------------------
+// Generics
+// -----------------------------------------------------------------------
+
+/// The types the type parameters in `param` stand for when a value of
+/// `type_` is passed for it, like `int` for `T` when `[int]` is passed for
+/// `[T]`.
+fn unify(param: &Type, type_: &Type, generics: &[Generic], found: &mut Vec<(String, Type)>) {
+    match (param, type_) {
+        (Type::Named(name, params), _)
+            if params.is_empty() && generics.iter().any(|g| g.name == *name) =>
+        {
+            found.push((name.clone(), type_.clone()));
+        }
+        (Type::Array(param), Type::Array(type_))
+        | (Type::Optional(param), Type::Optional(type_)) => {
+            unify(param, type_, generics, found);
+        }
+        (Type::Named(name, params), Type::Named(other, types))
+            if name == other && params.len() == types.len() =>
+        {
+            for (param, type_) in params.iter().zip(types) {
+                unify(param, type_, generics, found);
+            }
+        }
+        (Type::Function(params, param), Type::Function(types, type_))
+            if params.len() == types.len() =>
+        {
+            for (param, type_) in params.iter().zip(types) {
+                unify(param, type_, generics, found);
+            }
+            unify(param, type_, generics, found);
+        }
+        _ => {}
+    }
+}
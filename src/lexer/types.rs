@@ -0,0 +1,339 @@
+//! The types of arguments, fields and return values.
+//!
+//! Types are parsed once by the lexer, the backends map them onto the types
+//! of their target, see `transpiler::PRIMITIVES`.
+
+use {
+    serde::{Deserialize, Serialize},
+    std::collections::HashMap,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Type {
+    Primitive(Primitive),
+    /// `[T]`, also written `vec<T>`
+    Array(Box<Type>),
+    /// `T?`, a value or none
+    Optional(Box<Type>),
+    /// `fn(A, B): R`, the return type is `void` when it is left out
+    Function(Vec<Type>, Box<Type>),
+    /// `impl Trait`, any value of a type implementing the trait
+    Impl(String),
+    /// `dyn Trait`, holds any value of a type implementing the trait
+    Dyn(String),
+    /// a struct, enum, trait or type parameter, `Pair<int>` with the types
+    /// of its type parameters
+    Named(String, Vec<Type>),
+}
+
+/// The types built into lang. `int` is the integer of the target, 32 bits
+/// or more, the others have the same size everywhere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Primitive {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+    Int,
+    Bool,
+    Char,
+    Str,
+    Void,
+}
+
+impl Primitive {
+    pub const ALL: [Primitive; 15] = [
+        Primitive::I8,
+        Primitive::I16,
+        Primitive::I32,
+        Primitive::I64,
+        Primitive::U8,
+        Primitive::U16,
+        Primitive::U32,
+        Primitive::U64,
+        Primitive::F32,
+        Primitive::F64,
+        Primitive::Int,
+        Primitive::Bool,
+        Primitive::Char,
+        Primitive::Str,
+        Primitive::Void,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Primitive::I8 => "i8",
+            Primitive::I16 => "i16",
+            Primitive::I32 => "i32",
+            Primitive::I64 => "i64",
+            Primitive::U8 => "u8",
+            Primitive::U16 => "u16",
+            Primitive::U32 => "u32",
+            Primitive::U64 => "u64",
+            Primitive::F32 => "f32",
+            Primitive::F64 => "f64",
+            Primitive::Int => "int",
+            Primitive::Bool => "bool",
+            Primitive::Char => "char",
+            Primitive::Str => "str",
+            Primitive::Void => "void",
+        }
+    }
+
+    /// The primitive called `name`, also by the C names the language used
+    /// before it had its own.
+    pub fn from_name(name: &str) -> Option<Primitive> {
+        let alias = match name {
+            "short" => Primitive::I16,
+            "long" => Primitive::I64,
+            "float" => Primitive::F32,
+            "double" => Primitive::F64,
+            "string" | "std::string" => Primitive::Str,
+            _ => return Primitive::ALL.into_iter().find(|p| p.name() == name),
+        };
+        Some(alias)
+    }
+
+    pub fn integer(self) -> bool {
+        !self.float()
+            && !matches!(
+                self,
+                Primitive::Bool | Primitive::Char | Primitive::Str | Primitive::Void
+            )
+    }
+
+    pub fn float(self) -> bool {
+        matches!(self, Primitive::F32 | Primitive::F64)
+    }
+}
+
+impl Type {
+    pub const VOID: Type = Type::Primitive(Primitive::Void);
+    pub const INT: Type = Type::Primitive(Primitive::Int);
+    pub const BOOL: Type = Type::Primitive(Primitive::Bool);
+
+    /// A struct, enum or type parameter without type arguments.
+    pub fn named(name: &str) -> Type {
+        Type::Named(name.to_string(), vec![])
+    }
+
+    pub fn parse(s: &str) -> Result<Type, String> {
+        let mut parser = Parser {
+            tokens: tokenize(s),
+            pos: 0,
+        };
+        let type_ = parser.type_()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(type_),
+            Some(t) => Err(format!("Unexpected `{t}` in the type `{s}`")),
+        }
+    }
+
+    pub fn primitive(&self) -> Option<Primitive> {
+        match self {
+            Type::Primitive(p) => Some(*p),
+            _ => None,
+        }
+    }
+
+    pub fn is_void(&self) -> bool {
+        *self == Type::VOID
+    }
+
+    /// The trait a value has to implement to have this type, for `impl
+    /// Trait` and `dyn Trait`.
+    pub fn bound(&self) -> Option<&str> {
+        match self {
+            Type::Impl(trait_) | Type::Dyn(trait_) => Some(trait_),
+            _ => None,
+        }
+    }
+
+    /// The names of the structs, enums, traits and type parameters in the
+    /// type.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names = vec![];
+        self.visit(&mut |type_| match type_ {
+            Type::Named(name, _) | Type::Impl(name) | Type::Dyn(name) => names.push(name.as_str()),
+            _ => {}
+        });
+        names
+    }
+
+    /// Calls `f` with the type and each type in it.
+    pub fn visit<'a>(&'a self, f: &mut impl FnMut(&'a Type)) {
+        f(self);
+        match self {
+            Type::Array(item) | Type::Optional(item) => item.visit(f),
+            Type::Function(args, result) => {
+                args.iter().for_each(|arg| arg.visit(f));
+                result.visit(f);
+            }
+            Type::Named(_, arguments) => arguments.iter().for_each(|arg| arg.visit(f)),
+            Type::Primitive(_) | Type::Impl(_) | Type::Dyn(_) => {}
+        }
+    }
+
+    /// The type with the type parameters in it replaced by the types they
+    /// stand for.
+    pub fn substitute(&self, types: &HashMap<String, Type>) -> Type {
+        let all = |types_: &[Type]| types_.iter().map(|t| t.substitute(types)).collect();
+        match self {
+            Type::Named(name, arguments) if arguments.is_empty() => {
+                types.get(name).cloned().unwrap_or_else(|| self.clone())
+            }
+            Type::Named(name, arguments) => Type::Named(name.clone(), all(arguments)),
+            Type::Array(item) => Type::Array(Box::new(item.substitute(types))),
+            Type::Optional(item) => Type::Optional(Box::new(item.substitute(types))),
+            Type::Function(args, result) => {
+                Type::Function(all(args), Box::new(result.substitute(types)))
+            }
+            Type::Primitive(_) | Type::Impl(_) | Type::Dyn(_) => self.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let list = |types: &[Type]| {
+            let types: Vec<String> = types.iter().map(Type::to_string).collect();
+            types.join(", ")
+        };
+        match self {
+            Type::Primitive(p) => write!(f, "{}", p.name()),
+            Type::Array(item) => write!(f, "[{item}]"),
+            Type::Optional(item) => write!(f, "{item}?"),
+            Type::Function(args, result) if result.is_void() => write!(f, "fn({})", list(args)),
+            Type::Function(args, result) => write!(f, "fn({}): {result}", list(args)),
+            Type::Impl(trait_) => write!(f, "impl {trait_}"),
+            Type::Dyn(trait_) => write!(f, "dyn {trait_}"),
+            Type::Named(name, arguments) if arguments.is_empty() => write!(f, "{name}"),
+            Type::Named(name, arguments) => write!(f, "{name}<{}>", list(arguments)),
+        }
+    }
+}
+
+/// Splits a type into names like `std::vector` and the characters between
+/// them.
+fn tokenize(s: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = vec![];
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            let mut name = c.to_string();
+            while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == ':') {
+                name.push(c);
+            }
+            // `std::string`, but not the colon of a return type
+            let colons = name.len() - name.trim_end_matches(':').len();
+            name.truncate(name.len() - colons);
+            tokens.push(name);
+            tokens.extend((0..colons).map(|_| ":".to_string()));
+        } else if !c.is_whitespace() {
+            tokens.push(c.to_string());
+        }
+    }
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<&str> {
+        self.pos += 1;
+        self.tokens.get(self.pos - 1).map(String::as_str)
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        let found = self.tokens.get(self.pos).is_some_and(|t| t == s);
+        self.pos += found as usize;
+        found
+    }
+
+    fn expect(&mut self, s: &str) -> Result<(), String> {
+        match self.eat(s) {
+            true => Ok(()),
+            false => Err(match self.tokens.get(self.pos) {
+                Some(t) => format!("Expected `{s}` in a type but got `{t}`"),
+                None => format!("Expected `{s}` at the end of a type"),
+            }),
+        }
+    }
+
+    /// Types separated by commas up to `close`.
+    fn list(&mut self, close: &str) -> Result<Vec<Type>, String> {
+        let mut types = vec![];
+        while !self.eat(close) {
+            if !types.is_empty() {
+                self.expect(",")?;
+            }
+            types.push(self.type_()?);
+        }
+        Ok(types)
+    }
+
+    fn type_(&mut self) -> Result<Type, String> {
+        let mut type_ = match self.next() {
+            Some("[") => {
+                let item = self.type_()?;
+                self.expect("]")?;
+                Type::Array(Box::new(item))
+            }
+            Some("fn") => {
+                self.expect("(")?;
+                let args = self.list(")")?;
+                let result = match self.eat(":") {
+                    true => self.type_()?,
+                    false => Type::VOID,
+                };
+                Type::Function(args, Box::new(result))
+            }
+            Some(bound @ ("impl" | "dyn")) => {
+                let dyn_ = bound == "dyn";
+                match self.next() {
+                    Some(name) if word(name) => match dyn_ {
+                        true => Type::Dyn(name.to_string()),
+                        false => Type::Impl(name.to_string()),
+                    },
+                    _ => {
+                        return Err("Expected the name of a trait after `impl` or `dyn`".to_string())
+                    }
+                }
+            }
+            Some(name) if word(name) => {
+                let name = name.to_string();
+                let arguments = match self.eat("<") {
+                    true => self.list(">")?,
+                    false => vec![],
+                };
+                match (Primitive::from_name(&name), arguments.as_slice()) {
+                    (Some(p), []) => Type::Primitive(p),
+                    (_, [item]) if name == "vec" || name == "std::vector" => {
+                        Type::Array(Box::new(item.clone()))
+                    }
+                    (Some(_), _) => return Err(format!("`{name}` has no type parameters")),
+                    (None, _) => Type::Named(name, arguments),
+                }
+            }
+            Some(t) => return Err(format!("Unexpected `{t}` in a type")),
+            None => return Err("Expected a type".to_string()),
+        };
+        while self.eat("?") {
+            type_ = Type::Optional(Box::new(type_));
+        }
+        Ok(type_)
+    }
+}
+
+fn word(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_alphabetic() || c == '_')
+}
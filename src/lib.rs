@@ -3,9 +3,13 @@
 use colored::Colorize;
 use std::cell::RefCell;
 
+pub mod interpreter;
 pub mod lexer;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod repl;
 mod test;
 pub mod transpiler;
+pub mod vm;
 
 thread_local! {
     pub static LEXER_ERROR_COUNT: RefCell<usize> = RefCell::new(0usize);
@@ -17,8 +21,14 @@ pub fn lexer_error() {
     });
 }
 
+/// The lexer errors logged so far on this thread.
+pub fn lexer_errors() -> usize {
+    LEXER_ERROR_COUNT.with(|count| *count.borrow())
+}
+
 pub enum PrintT {
     Error,
+    Warning,
     LexerError,
     Lexer,
     Syntax,
@@ -26,12 +36,21 @@ pub enum PrintT {
     Cpu,
     Clear,
     Test,
+    Interpreter,
+    VM,
     CXX,
+    GO,
+    JAVA,
+    CSHARP,
+    LUA,
+    WAT,
+    LLVM,
 }
 
 pub fn printx(type_: PrintT, message: &str) {
     let prefix = match type_ {
         PrintT::Error => String::from("ERROR: ").red(),
+        PrintT::Warning => String::from("WARNING: ").yellow(),
         PrintT::LexerError => String::from("LEXER: ").red(),
         PrintT::Info => format!("INFO: ").green(),
         PrintT::Syntax => format!("SYNTAX: ").yellow(),
@@ -39,16 +58,24 @@ pub fn printx(type_: PrintT, message: &str) {
         PrintT::Cpu => format!("CPU: ").yellow(),
         PrintT::Test => format!("TEST: ").yellow(),
         PrintT::Clear => "".to_string().white(),
+        PrintT::Interpreter => "INTERPRETER: ".to_string().yellow(),
+        PrintT::VM => "VM: ".to_string().yellow(),
         // --------------
         // languages
         // --------------
         PrintT::CXX => format!("CXX: ").yellow(),
+        PrintT::GO => "GO: ".to_string().yellow(),
+        PrintT::JAVA => "JAVA: ".to_string().yellow(),
+        PrintT::CSHARP => "C#: ".to_string().yellow(),
+        PrintT::LUA => "LUA: ".to_string().yellow(),
+        PrintT::WAT => "WAT: ".to_string().yellow(),
+        PrintT::LLVM => "LLVM: ".to_string().yellow(),
     };
     match type_ {
         PrintT::Clear => {
             print!("{}{}", prefix, message);
         }
-        PrintT::Error | PrintT::LexerError => {
+        PrintT::Error | PrintT::Warning | PrintT::LexerError => {
             eprintln!("{}{}", prefix, message)
         }
         _ => {
@@ -64,6 +91,12 @@ macro_rules! log {
     (Error, $($str:tt)*) => {
         printx(PrintT::Error, $($str)*);
     };
+    (Warning, f($($format:tt)*)) => {
+        printx(PrintT::Warning, format!($($format)*).as_str());
+    };
+    (Warning, $($str:tt)*) => {
+        printx(PrintT::Warning, $($str)*);
+    };
     (LexerError, f($($format:tt)*)) => {
         printx(PrintT::LexerError, format!($($format)*).as_str());
         lexer_error();
@@ -109,6 +142,54 @@ macro_rules! log {
 
         printx(PrintT::CXX, $($str)*);
     };
+    (GO, f($($format:tt)*)) => {
+        printx(PrintT::GO, format!($($format)*).as_str());
+    };
+    (GO, $($str:tt)*) => {
+        printx(PrintT::GO, $($str)*);
+    };
+    (JAVA, f($($format:tt)*)) => {
+        printx(PrintT::JAVA, format!($($format)*).as_str());
+    };
+    (JAVA, $($str:tt)*) => {
+        printx(PrintT::JAVA, $($str)*);
+    };
+    (CSHARP, f($($format:tt)*)) => {
+        printx(PrintT::CSHARP, format!($($format)*).as_str());
+    };
+    (CSHARP, $($str:tt)*) => {
+        printx(PrintT::CSHARP, $($str)*);
+    };
+    (LUA, f($($format:tt)*)) => {
+        printx(PrintT::LUA, format!($($format)*).as_str());
+    };
+    (LUA, $($str:tt)*) => {
+        printx(PrintT::LUA, $($str)*);
+    };
+    (WAT, f($($format:tt)*)) => {
+        printx(PrintT::WAT, format!($($format)*).as_str());
+    };
+    (WAT, $($str:tt)*) => {
+        printx(PrintT::WAT, $($str)*);
+    };
+    (LLVM, f($($format:tt)*)) => {
+        printx(PrintT::LLVM, format!($($format)*).as_str());
+    };
+    (LLVM, $($str:tt)*) => {
+        printx(PrintT::LLVM, $($str)*);
+    };
+    (Interpreter, f($($format:tt)*)) => {
+        printx(PrintT::Interpreter, format!($($format)*).as_str());
+    };
+    (Interpreter, $($str:tt)*) => {
+        printx(PrintT::Interpreter, $($str)*);
+    };
+    (VM, f($($format:tt)*)) => {
+        printx(PrintT::VM, format!($($format)*).as_str());
+    };
+    (VM, $($str:tt)*) => {
+        printx(PrintT::VM, $($str)*);
+    };
     (Test, f($($format:tt)*)) => {
         printx(PrintT::Test, format!($($format)*).as_str());
     };
@@ -1,3 +1,65 @@
+#[cfg(not(target_arch = "wasm32"))]
+use lang::repl::Repl;
+use {
+    lang::{
+        interpreter::{with_stack, Interpreter},
+        log, printx,
+        vm::{
+            bytecode::{Program, MAGIC},
+            compiler, Vm,
+        },
+        PrintT,
+    },
+    std::{env, fs, path::Path, process},
+};
+
+const USAGE: &str = "usage:
+    lang run <file.lang | file.lbc>
+    lang repl
+    lang compile <file.lang> [out.lbc]";
+
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["run", file] => run(file),
+        #[cfg(not(target_arch = "wasm32"))]
+        ["repl"] => with_stack(|| Repl::new().run()),
+        ["compile", file] => compile(
+            file,
+            &Path::new(file).with_extension("lbc").to_string_lossy(),
+        ),
+        ["compile", file, out] => compile(file, out),
+        _ => {
+            log!(Error, USAGE);
+            process::exit(2);
+        }
+    };
+    match result {
+        Ok(code) => process::exit(code),
+        Err(e) => {
+            log!(Error, f("{e}"));
+            process::exit(1);
+        }
+    }
+}
+
+/// Runs compiled files on the VM and source files with the interpreter.
+fn run(file: &str) -> Result<i32, String> {
+    let bytes = fs::read(file).map_err(|e| format!("Unable to read file: {e}"))?;
+    if bytes.starts_with(MAGIC) {
+        Vm::new(Program::decode(&bytes)?).run()
+    } else {
+        with_stack(|| Interpreter::new().run(file))
+    }
+}
+
+fn compile(file: &str, out: &str) -> Result<i32, String> {
+    let program = compiler::compile_file(file)?;
+    fs::write(out, program.encode()).map_err(|e| format!("Unable to write {out}: {e}"))?;
+    Ok(0)
 }
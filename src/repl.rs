@@ -0,0 +1,249 @@
+//! `lang repl`, an interactive session on top of the interpreter.
+//!
+//! Top-level items (`fn`, `struct`, `enum`, `const`, `global`, ...) are loaded
+//! into the interpreter, everything else is run as statements of a
+//! [`Session`], so variables declared with `let` stay defined for the next
+//! input.
+use {
+    crate::{
+        interpreter::{Interpreter, Session, Value},
+        lexer::{tree, Lexer, Token},
+        log, printx,
+        transpiler::{cxx, transpile},
+        PrintT,
+    },
+    rustyline::{error::ReadlineError, DefaultEditor},
+    std::{env, path::PathBuf},
+};
+
+const HELP: &str = "enter items or statements, a trailing expression is printed
+    :ast          the parse tree of the last input
+    :cxx          the last input transpiled to C++, after the items and `let`s before it
+    :type <expr>  the type of the value of an expression
+    :help         this message
+    :quit         leave the REPL";
+
+/// The last complete input, for `:ast` and `:cxx`.
+enum Input {
+    Items(Vec<Token>),
+    /// statements are parsed as the body of a function called `repl`
+    Stmts(Vec<Token>, Vec<tree::Stmt>),
+}
+
+pub struct Repl {
+    pub interpreter: Interpreter,
+    session: Session,
+    last: Option<Input>,
+    /// the items loaded so far and the `let`s outside of blocks by name,
+    /// which `:cxx` needs to resolve the names in the last input
+    items: Vec<Token>,
+    lets: Vec<(String, String)>,
+}
+
+impl Default for Repl {
+    fn default() -> Repl {
+        Repl::new()
+    }
+}
+
+impl Repl {
+    pub fn new() -> Repl {
+        Repl::with(Interpreter::new())
+    }
+
+    /// A REPL collecting everything printed in `interpreter.output`.
+    pub fn capturing() -> Repl {
+        Repl::with(Interpreter::capturing())
+    }
+
+    fn with(interpreter: Interpreter) -> Repl {
+        Repl {
+            session: interpreter.session(),
+            interpreter,
+            last: None,
+            items: vec![],
+            lets: vec![],
+        }
+    }
+
+    /// Whether all brackets of `code` are closed, otherwise the REPL asks
+    /// for more lines.
+    pub fn complete(code: &str) -> bool {
+        let brackets = Lexer::quiet().count_brackets(code.to_string());
+        brackets.round <= 0 && brackets.square <= 0 && brackets.braces <= 0
+    }
+
+    /// Reads inputs until `:quit` or the end of the input, with the history
+    /// kept in `~/.lang_history`.
+    pub fn run(&mut self) -> Result<i32, String> {
+        let mut editor =
+            DefaultEditor::new().map_err(|e| format!("Unable to start the REPL: {e}"))?;
+        let history = env::var_os("HOME").map(|home| PathBuf::from(home).join(".lang_history"));
+        if let Some(history) = &history {
+            // there is none the first time
+            let _ = editor.load_history(history);
+        }
+        log!(Info, "lang repl, `:help` lists the commands");
+        let mut input = String::new();
+        loop {
+            let prompt = if input.is_empty() { "> " } else { ". " };
+            match editor.readline(prompt) {
+                Ok(line) => {
+                    input.push_str(&line);
+                    input.push('\n');
+                    if !Repl::complete(&input) {
+                        continue;
+                    }
+                    let _ = editor.add_history_entry(input.trim_end());
+                    let code = std::mem::take(&mut input);
+                    if matches!(code.trim(), ":quit" | ":q") {
+                        break;
+                    }
+                    match self.eval(&code) {
+                        Ok(out) if out.is_empty() => {}
+                        Ok(out) => println!("{out}"),
+                        Err(e) => {
+                            log!(Error, f("{e}"));
+                        }
+                    }
+                }
+                // Ctrl-C drops the lines typed so far
+                Err(ReadlineError::Interrupted) => input.clear(),
+                Err(ReadlineError::Eof) => break,
+                Err(e) => return Err(format!("Unable to read the input: {e}")),
+            }
+        }
+        if let Some(history) = &history {
+            let _ = editor.save_history(history);
+        }
+        Ok(0)
+    }
+
+    /// Runs one complete input and returns the text to show for it.
+    pub fn eval(&mut self, code: &str) -> Result<String, String> {
+        let code = code.trim();
+        if let Some(command) = code.strip_prefix(':') {
+            return self.command(command);
+        }
+        if code.is_empty() {
+            return Ok(String::new());
+        }
+        if is_item(code) {
+            let mut lexer = Lexer::quiet();
+            lexer.parse(code.to_string());
+            if lexer.errors > 0 {
+                return Err(LEXER_ERRORS.to_string());
+            }
+            self.interpreter.load(&lexer.ast)?;
+            self.items.extend(lexer.ast.iter().cloned());
+            self.last = Some(Input::Items(lexer.ast));
+            return Ok(String::new());
+        }
+        let (_, stmts) = statements(code)?;
+        let earlier: String = self.lets.iter().map(|(_, line)| line.as_str()).collect();
+        let (ast, _) = statements(&format!("{earlier}{code}"))?;
+        self.last = Some(Input::Stmts(ast, stmts.clone()));
+        let value = self.interpreter.execute(&mut self.session, stmts)?;
+        // a `let` of a name defined before replaces that one in place
+        for (name, line) in lets(code) {
+            match self.lets.iter_mut().find(|(other, _)| *other == name) {
+                Some(earlier) => earlier.1 = line,
+                None => self.lets.push((name, line)),
+            }
+        }
+        match value {
+            Value::Void => Ok(String::new()),
+            value => Ok(value.to_string()),
+        }
+    }
+
+    fn command(&mut self, command: &str) -> Result<String, String> {
+        let (name, arg) = match command.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, arg.trim()),
+            None => (command, ""),
+        };
+        match name {
+            "ast" => match &self.last {
+                Some(Input::Items(ast)) => Ok(format!("{ast:#?}")),
+                Some(Input::Stmts(_, stmts)) => Ok(format!("{stmts:#?}")),
+                None => Err("Nothing was entered yet".to_string()),
+            },
+            "cxx" => {
+                let ast = match &self.last {
+                    Some(Input::Items(_)) => self.items.clone(),
+                    Some(Input::Stmts(ast, _)) => [self.items.clone(), ast.clone()].concat(),
+                    None => return Err("Nothing was entered yet".to_string()),
+                };
+                let mut t = cxx::new();
+                transpile(&mut t, ast);
+                Ok(t.buffer.trim().to_string())
+            }
+            // the expression is evaluated, there are no static types yet
+            "type" if !arg.is_empty() => {
+                let (_, stmts) = statements(arg)?;
+                match stmts.as_slice() {
+                    [tree::Stmt::Expr(_)] => {}
+                    _ => return Err("`:type` takes a single expression".to_string()),
+                }
+                let value = self.interpreter.execute(&mut self.session, stmts)?;
+                Ok(value.type_name().to_string())
+            }
+            "type" => Err("Usage: `:type <expr>`".to_string()),
+            "help" => Ok(HELP.to_string()),
+            _ => Err(format!("Unknown command `:{name}`, try `:help`")),
+        }
+    }
+}
+
+const LEXER_ERRORS: &str = "Not running input with lexer errors";
+
+fn is_item(code: &str) -> bool {
+    let mut words = code.split_whitespace();
+    match (words.next(), words.next()) {
+        (Some("loop" | "gen"), Some(second)) => second == "fn",
+        (Some("fn" | "struct" | "enum" | "const" | "global" | "import" | "impl" | "trait"), _) => {
+            true
+        }
+        (Some(first), _) => first.starts_with('#') || first.starts_with("//"),
+        (None, _) => false,
+    }
+}
+
+/// Parses statements as the body of a function, which is what the lexer
+/// expects them in.
+fn statements(code: &str) -> Result<(Vec<Token>, Vec<tree::Stmt>), String> {
+    let mut lexer = Lexer::quiet();
+    lexer.parse(format!("fn repl() {{\n{code}\n}}"));
+    if lexer.errors > 0 {
+        return Err(LEXER_ERRORS.to_string());
+    }
+    let stmts = match lexer.ast.first() {
+        Some(Token::Function(x)) => tree::function(x)?,
+        _ => return Err("Unable to parse the input".to_string()),
+    };
+    Ok((lexer.ast, stmts))
+}
+
+/// The `let`s of `code` outside of blocks by name, with all the lines of a
+/// value spanning several.
+fn lets(code: &str) -> Vec<(String, String)> {
+    let mut lets: Vec<(String, String)> = vec![];
+    // brackets open before the line, and whether it is part of a `let`
+    let mut depth = 0;
+    let mut in_let = false;
+    for line in code.lines() {
+        if depth == 0 {
+            let name = (line.trim_start().strip_prefix("let "))
+                .and_then(|rest| rest.split([' ', ':', '=']).find(|s| !s.is_empty()));
+            in_let = name.is_some();
+            lets.extend(name.map(|name| (name.to_string(), String::new())));
+        }
+        if let Some((_, lines)) = lets.last_mut().filter(|_| in_let) {
+            lines.push_str(line);
+            lines.push('\n');
+        }
+        let brackets = Lexer::quiet().count_brackets(line.to_string());
+        depth += brackets.round + brackets.square + brackets.braces;
+    }
+    lets
+}
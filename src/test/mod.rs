@@ -24,8 +24,582 @@ mod general {
     }
 }
 
+mod tree {
+    use crate::lexer::{
+        check, flow, resolve,
+        token::{expression::Operator, Expression},
+        tree::{self, *},
+        types::{Primitive, Type},
+        Lexer, Token,
+    };
+
+    fn lexed(exp: &str) -> Expression {
+        let mut lexer = Lexer::quiet();
+        lexer.parse(format!("const x = {exp}"));
+        match &lexer.ast[0] {
+            Token::Const(x) => x.exp.clone(),
+            _ => unreachable!(),
+        }
+    }
+    fn parse(exp: &str) -> Expr {
+        expression(&lexed(exp)).unwrap()
+    }
+    #[test]
+    fn pipe() {
+        assert_eq!(parse("a + 1 |> f"), parse("f(a + 1)"));
+        assert_eq!(
+            parse("g() |> map(f) |> take(2)"),
+            parse("take(map(g(), f), 2)")
+        );
+        assert_eq!(parse("(4 |> f) + 1"), parse("f(4) + 1"));
+        assert_eq!(parse("g(x |> f, [y |> f][0])"), parse("g(f(x), [f(y)][0])"));
+        assert_eq!(parse("\"x |> y\""), Expr::Str("x |> y".to_string()));
+    }
+    #[test]
+    fn comparisons() {
+        assert_eq!(parse("a<=5"), parse("a <= 5"));
+        assert_eq!(parse("a>=b"), parse("a >= b"));
+        assert_eq!(parse("a!=b"), parse("a != b"));
+        assert_eq!(parse("a<b"), parse("a < b"));
+        assert_eq!(parse("a>b"), parse("a > b"));
+        assert_eq!(parse("a%2==1"), parse("a % 2 == 1"));
+        let spaced = function("if a <= 5 {\n}");
+        assert!(spaced.is_ok());
+        assert_eq!(function("if a<=5 {\n}"), spaced);
+    }
+    #[test]
+    fn strings() {
+        let text = |s: &str| Expr::Str(s.to_string());
+        assert_eq!(parse("\"a  b,c (d)  e\""), text("a  b,c (d)  e"));
+        assert_eq!(parse("\"x//y\""), text("x//y"));
+        assert_eq!(parse(r#""say \"hi\"\n""#), text("say \"hi\"\n"));
+        assert!(expression(&lexed("\"open")).is_err());
+    }
+    #[test]
+    fn loop_function() {
+        let mut lexer = Lexer::quiet();
+        lexer.parse(
+            "loop fn f(): void every 2s {\nstate {\nlet n = 0\n}\nn = n + 1\nbreak\n}".to_string(),
+        );
+        let stmts = match &lexer.ast[0] {
+            Token::LoopFunction(x) => tree::loop_function(x).unwrap(),
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            stmts,
+            [
+                Stmt::Let("n".to_string(), Expr::Int(0)),
+                Stmt::Loop(vec![
+                    Stmt::Expr(Expr::Call("sleep".to_string(), vec![Expr::Int(2000)])),
+                    Stmt::Assign("n".to_string(), parse("n + 1")),
+                    Stmt::Break(None),
+                ]),
+            ]
+        );
+    }
+    fn function(body: &str) -> Result<Vec<Stmt>, String> {
+        let mut lexer = Lexer::quiet();
+        lexer.parse(format!("fn f(): void {{\n{body}\n}}"));
+        match &lexer.ast[0] {
+            Token::Function(x) => tree::function(x),
+            _ => unreachable!(),
+        }
+    }
+    #[test]
+    fn labels() {
+        assert_eq!(
+            function("'outer: loop {\nwhile x {\nbreak 'outer\n}\n}"),
+            Ok(vec![Stmt::Labeled(
+                "outer".to_string(),
+                Box::new(Stmt::Loop(vec![Stmt::While(
+                    parse("x"),
+                    vec![Stmt::Break(Some("outer".to_string()))],
+                )])),
+            )])
+        );
+        assert!(function("break").is_err());
+        assert!(function("'outer: loop {\ncontinue 'inner\n}").is_err());
+    }
+    #[test]
+    fn range() {
+        let stmts = function("for i, x in (0..=n).rev().step(2) {\n}").unwrap();
+        assert_eq!(
+            stmts,
+            [Stmt::For(For {
+                index: Some("i".to_string()),
+                var_name: "x".to_string(),
+                iter: Iterable::Range(Range {
+                    start: Expr::Int(0),
+                    end: parse("n"),
+                    inclusive: true,
+                    step: Some(Expr::Int(2)),
+                    rev: true,
+                }),
+                body: vec![],
+            })]
+        );
+        match function("for x in xs {\n}").unwrap().as_slice() {
+            [Stmt::For(x)] => assert_eq!(x.iter, Iterable::Expr(parse("xs"))),
+            stmts => panic!("{stmts:?}"),
+        }
+    }
+    #[test]
+    fn struct_() {
+        let field = |exp, name: &str| Expr::Field(Box::new(exp), name.to_string());
+        assert_eq!(
+            parse("Point { x: 1, y: a.b }"),
+            Expr::Struct(
+                "Point".to_string(),
+                vec![
+                    ("x".to_string(), Expr::Int(1)),
+                    ("y".to_string(), field(Expr::Var("a".to_string()), "b")),
+                ]
+            )
+        );
+        assert_eq!(
+            parse("f(p).x + 1"),
+            Expr::Binary(
+                Box::new(field(parse("f(p)"), "x")),
+                Operator::Plus,
+                Box::new(Expr::Int(1))
+            )
+        );
+        assert!(expression(&lexed("Point { x: 1, x: 2 }")).is_err());
+    }
+    #[test]
+    fn char_() {
+        assert_eq!(expression(&lexed("'a'")), Ok(Expr::Char('a')));
+        assert_eq!(expression(&lexed("'\\n'")), Ok(Expr::Char('\n')));
+        assert!(expression(&lexed("'ab'")).is_err());
+    }
+    #[test]
+    fn enum_() {
+        let mut lexer = Lexer::quiet();
+        lexer.parse("enum Shape {\n    Circle(float),\n    Rect(float, float), // w, h\n    Empty,\n}\nenum E { A B }".to_string());
+        let f32 = Type::Primitive(Primitive::F32);
+        let variants: Vec<(&str, Vec<Type>)> = match lexer.ast.as_slice() {
+            [Token::Enum(x)] => (x.variants.iter())
+                .map(|v| (v.name.as_str(), v.fields.clone()))
+                .collect(),
+            ast => panic!("{ast:?}"),
+        };
+        assert_eq!(
+            variants,
+            [
+                ("Circle", vec![f32.clone()]),
+                ("Rect", vec![f32.clone(), f32]),
+                ("Empty", vec![])
+            ]
+        );
+        assert_eq!(
+            parse("Shape::Rect(1, w)"),
+            Expr::Variant(
+                "Shape".to_string(),
+                "Rect".to_string(),
+                vec![Expr::Int(1), Expr::Var("w".to_string())]
+            )
+        );
+        assert_eq!(
+            parse("Shape::Empty"),
+            Expr::Variant("Shape".to_string(), "Empty".to_string(), vec![])
+        );
+    }
+    #[test]
+    fn match_() {
+        let stmts = function("let a = match s {\n    Shape::Rect(w, _) => w,\n    -1..=1 => 0,\n    x => x,\n}\nreturn a");
+        let arms = vec![
+            (
+                Pattern::Variant(
+                    "Shape".to_string(),
+                    "Rect".to_string(),
+                    vec![Some("w".to_string()), None],
+                ),
+                parse("w"),
+            ),
+            (Pattern::Range(-1, 1), Expr::Int(0)),
+            (Pattern::Binding("x".to_string()), parse("x")),
+        ];
+        assert_eq!(
+            stmts,
+            Ok(vec![
+                Stmt::Let("a".to_string(), Expr::Match(Box::new(parse("s")), arms)),
+                Stmt::Return(Some(parse("a"))),
+            ])
+        );
+        // the `{` of the arms doesn't start a struct literal
+        assert_eq!(
+            parse("match p { _ => P { x: 1 } }"),
+            Expr::Match(
+                Box::new(parse("p")),
+                vec![(Pattern::Wildcard, parse("P { x: 1 }"))]
+            )
+        );
+        assert!(expression(&lexed("match n { 1.5 => 0 }")).is_err());
+        assert!(expression(&lexed("match n { 3..3 => 0 }")).is_err());
+
+        let variants = |name: &str| {
+            (name == "Color").then(|| vec![("Red".to_string(), 0), ("Blue".to_string(), 0)])
+        };
+        let check = |exp: &str| match parse(exp) {
+            Expr::Match(_, arms) => exhaustive(arms.iter().map(|(p, _)| p), variants),
+            exp => panic!("{exp:?}"),
+        };
+        assert_eq!(
+            check("match c { Color::Red => 1, Color::Blue => 2 }"),
+            Ok(())
+        );
+        assert_eq!(check("match n { 0..5 => 1, 5 => 2, _ => 3 }"), Ok(()));
+        assert_eq!(check("match b { false => 0, true => 1 }"), Ok(()));
+        assert!(check("match c { Color::Red => 1 }").is_err());
+        assert!(check("match c { Color::Red => 1, Color::Red => 2, _ => 3 }").is_err());
+        assert!(check("match c { Color::Green => 1, _ => 2 }").is_err());
+        assert!(check("match n { 0..=9 => 1, 5 => 2, _ => 3 }").is_err());
+        assert!(check("match n { x => 1, 0 => 2 }").is_err());
+        assert!(check("match n { 0 => 1, true => 2, _ => 3 }").is_err());
+        assert!(check("match s { \"a\" => 1 }").is_err());
+    }
+    #[test]
+    fn methods() {
+        let method = |receiver: Expr, name: &str, args: Vec<Expr>| {
+            Expr::Method(Box::new(receiver), name.to_string(), args)
+        };
+        assert_eq!(parse("p.len()"), method(parse("p"), "len", vec![]));
+        assert_eq!(
+            parse("f(p).add(q)"),
+            method(parse("f(p)"), "add", vec![parse("q")])
+        );
+        assert_eq!(
+            parse("r.max.dot(x)"),
+            method(parse("r.max"), "dot", vec![parse("x")])
+        );
+        assert_eq!(
+            parse("Point::origin()"),
+            Expr::Variant("Point".to_string(), "origin".to_string(), vec![])
+        );
+
+        let mut lexer = Lexer::quiet();
+        lexer.parse("struct P { x: int }\nimpl P {\n    fn get(self, n: int): int {\n        return self.x + n\n    }\n    fn new(): P {\n        return P { x: 0 }\n    }\n}".to_string());
+        let functions: Vec<(&str, bool, Vec<Type>)> = match lexer.ast.as_slice() {
+            [Token::Struct(_), Token::Impl(x)] if x.name == "P" => (x.functions.iter())
+                .map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.method(),
+                        f.arguments.iter().map(|a| a.type_.clone()).collect(),
+                    )
+                })
+                .collect(),
+            ast => panic!("{ast:?}"),
+        };
+        assert_eq!(
+            functions,
+            [
+                ("get", true, vec![Type::named("P"), Type::INT]),
+                ("new", false, vec![])
+            ]
+        );
+    }
+    #[test]
+    fn traits() {
+        let mut lexer = Lexer::quiet();
+        lexer.parse("trait Shape {\n    fn area(self): int\n    fn grow(self, by: vec<dyn Shape>)\n}\nstruct Sq { side: int }\nimpl Shape for Sq {\n    fn area(self): int {\n        return self.side\n    }\n}".to_string());
+        let signatures: Vec<String> = match lexer.ast.as_slice() {
+            [Token::Trait(x), Token::Struct(_), Token::Impl(y)]
+                if y.trait_.as_deref() == Some("Shape") && y.name == "Sq" =>
+            {
+                x.functions.iter().map(signature).collect()
+            }
+            ast => panic!("{ast:?}"),
+        };
+        assert_eq!(
+            signatures,
+            ["fn area(self): int", "fn grow(self, by: [dyn Shape])"]
+        );
+
+        let check = |code: &str| {
+            let mut lexer = Lexer::quiet();
+            lexer.parse(format!("trait Shape {{\n    fn area(self): int\n}}\nstruct Sq {{ side: int }}\nstruct Dot {{ x: int }}\nimpl Shape for Sq {{\n    fn area(self): int {{\n        return 1\n    }}\n}}\nfn f(s: impl Shape): int {{\n    return s.area()\n}}\n{code}"));
+            let mut signatures = Signatures::default();
+            signatures.extend(&lexer.ast)?;
+            for nt in &lexer.ast {
+                if let Token::Function(x) = nt {
+                    signatures.bounds(&x.generics, &x.arguments, &tree::function(x)?)?;
+                }
+            }
+            Ok::<(), String>(())
+        };
+        assert_eq!(
+            check("fn g(): int {\n    let s = Sq { side: 2 }\n    return f(s)\n}"),
+            Ok(())
+        );
+        assert_eq!(
+            check("fn g(d: dyn Shape): int {\n    return f(d)\n}"),
+            Ok(())
+        );
+        assert!(check("fn g(): int {\n    return f(Dot { x: 1 })\n}").is_err());
+        assert!(check("fn g(): int {\n    return f(1)\n}").is_err());
+        assert!(check("fn g(s: dyn Shape): int {\n    return s.len()\n}").is_err());
+        assert!(check("fn g(s: dyn Solid) {\n}").is_err());
+        assert!(check("struct Box { s: impl Shape }").is_err());
+        assert!(check("impl Shape for Dot {\n}").is_err());
+        assert!(check(
+            "impl Shape for Dot {\n    fn area(self): bool {\n        return true\n    }\n}"
+        )
+        .is_err());
+    }
+    #[test]
+    fn generics() {
+        let mut lexer = Lexer::quiet();
+        lexer.parse("struct Entry<K, V: Shape> {\n    key: K,\n    values: pair<K, vec<V>>,\n}\nfn get<T>(e: Entry<T, int>, i: int): T {\n    return e.key\n}".to_string());
+        match lexer.ast.as_slice() {
+            [Token::Struct(x), Token::Function(y)] => {
+                let generics: Vec<String> = x.generics.iter().map(|g| g.to_string()).collect();
+                assert_eq!(generics, ["K", "V: Shape"]);
+                assert_eq!(x.fields[1].type_.to_string(), "pair<K, [V]>");
+                assert_eq!((y.name.as_str(), y.generics.len()), ("get", 1));
+                assert_eq!(y.arguments[0].type_.to_string(), "Entry<T, int>");
+            }
+            ast => panic!("{ast:?}"),
+        }
+
+        let check = |code: &str| {
+            let mut lexer = Lexer::quiet();
+            lexer.parse(format!("trait Shape {{\n    fn area(self): int\n}}\nstruct Sq {{ side: int }}\nimpl Shape for Sq {{\n    fn area(self): int {{\n        return 1\n    }}\n}}\nstruct Pair<T> {{ a: T, b: T }}\nfn first<T>(p: Pair<T>): T {{\n    return p.a\n}}\nfn area<T: Shape>(s: T): int {{\n    return s.area()\n}}\n{code}"));
+            let mut signatures = Signatures::default();
+            signatures.extend(&lexer.ast)?;
+            for nt in &lexer.ast {
+                if let Token::Function(x) = nt {
+                    signatures.bounds(&x.generics, &x.arguments, &tree::function(x)?)?;
+                }
+            }
+            Ok::<(), String>(())
+        };
+        assert_eq!(
+            check("fn g(): int {\n    let p = Pair { a: Sq { side: 1 }, b: Sq { side: 2 } }\n    return area(first(p))\n}"),
+            Ok(())
+        );
+        assert!(
+            check("fn g(): int {\n    let p = Pair { a: 1, b: 2.5 }\n    return 0\n}").is_err()
+        );
+        assert!(check(
+            "fn g(): int {\n    let p = Pair { a: 1, b: 2 }\n    return area(first(p))\n}"
+        )
+        .is_err());
+        assert!(check("fn h<T>(s: T): int {\n    return s.area()\n}").is_err());
+        assert!(check("fn h<T>(): int {\n    return 0\n}").is_err());
+        assert!(check("fn h<T: Solid>(s: T): int {\n    return 0\n}").is_err());
+        assert!(check("impl Pair {\n}").is_err());
+    }
+    #[test]
+    fn types() {
+        for type_ in [
+            "i8",
+            "u64",
+            "str",
+            "[i32]",
+            "f64?",
+            "[Pair<int, str>]?",
+            "fn(int, [str]): bool",
+            "fn()",
+            "impl Shape",
+            "dyn Shape",
+        ] {
+            assert_eq!(
+                Type::parse(type_).map(|t| t.to_string()),
+                Ok(type_.to_string())
+            );
+        }
+        assert_eq!(Type::parse("double"), Ok(Type::Primitive(Primitive::F64)));
+        assert_eq!(
+            Type::parse("vec<std::string>").unwrap().to_string(),
+            "[str]"
+        );
+        for type_ in ["", "[int", "int<str>", "fn(int", "impl", "int int"] {
+            assert!(Type::parse(type_).is_err(), "{type_}");
+        }
+
+        let mut lexer = Lexer::quiet();
+        lexer.parse("fn apply(f: fn(i32): i32, xs: [i32]?): str {\n    return \"\"\n}".to_string());
+        match lexer.ast.as_slice() {
+            [Token::Function(x)] => {
+                let arguments: Vec<String> = (x.arguments.iter())
+                    .map(|a| format!("{}: {}", a.name, a.type_))
+                    .collect();
+                assert_eq!(arguments, ["f: fn(i32): i32", "xs: [i32]?"]);
+                assert_eq!(x.return_type, Type::Primitive(Primitive::Str));
+            }
+            ast => panic!("{ast:?}"),
+        }
+    }
+    #[test]
+    fn inference() {
+        let check = |code: &str| {
+            let mut lexer = Lexer::quiet();
+            lexer.parse(format!("struct P {{ x: int }}\nfn area(w: i64, h: i64): i64 {{\n    return w * h\n}}\n{code}"));
+            let mut signatures = Signatures::default();
+            signatures.extend(&lexer.ast).unwrap();
+            (
+                check::globals(&signatures, &lexer.ast),
+                check::program(&signatures, &lexer.ast),
+            )
+        };
+        let (globals, errors) =
+            check("const A = area(2, 3)\nglobal s = \"a\" + \"b\"\nconst B = A > 2");
+        assert_eq!(globals["A"], Type::Primitive(Primitive::I64));
+        assert_eq!(globals["s"], Type::Primitive(Primitive::Str));
+        assert_eq!(globals["B"], Type::BOOL);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let errors = |code: &str| check(code).1;
+        assert_eq!(
+            errors("fn f(): int {\n    let n = area(1, 2)\n    let s = \"a\"\n    return n + s\n}"),
+            ["`+` can't be used with `i64` and `str` at line 8 in `f`"]
+        );
+        assert_eq!(
+            errors("fn f(): str {\n    return 1\n}"),
+            ["`f` returns `str` but got `int` at line 6 in `f`"]
+        );
+        assert_eq!(
+            errors("fn f() {\n    let p = P { x: \"1\" }\n}"),
+            ["`x` of `P` is `int` but got `str` at line 6 in `f`"]
+        );
+        assert_eq!(
+            errors("fn f(xs: [str]) {\n    for x in xs {\n        area(x, 1)\n    }\n}"),
+            ["`w` of `area` is `i64` but got `str` at line 7 in `f`"]
+        );
+        assert_eq!(
+            errors("fn f() {\n    area(1)\n}"),
+            ["`area` takes 2 arguments but got 1 at line 6 in `f`"]
+        );
+        assert_eq!(
+            errors("fn f() {\n    let n = 1\n    n = \"a\"\n    while \"x\" {\n    }\n}"),
+            [
+                "`n` is `int` but is assigned `str` at line 7 in `f`",
+                "A condition needs a `bool` but got `str` at line 8 in `f`"
+            ]
+        );
+        // the `let` in the block hides `n` until its end
+        assert!(errors("fn f() {\n    let n = 1\n    if n > 0 {\n        let n = \"a\"\n        print(n + \"b\")\n    }\n    n = n * 2\n}").is_empty());
+        assert_eq!(
+            errors("fn f(): str {\n    return 'a'\n}"),
+            ["`f` returns `str` but got `char` at line 6 in `f`"]
+        );
+        // type parameters are left to the target
+        assert!(errors("fn f<T>(a: T): T {\n    return a + 1\n}").is_empty());
+        assert_eq!(
+            errors("fn f() {\n    print(1.5 & 2)\n}"),
+            ["`&` can't be used with `f64` and `int` at line 6 in `f`"]
+        );
+    }
+    #[test]
+    fn resolve() {
+        let resolved = |code: &str| {
+            let mut lexer = Lexer::quiet();
+            lexer.parse(format!("struct P {{ x: int }}\nfn area(w: int, h: int): int {{\n    return w * h\n}}\n{code}"));
+            resolve::program(&lexer.ast, &["print".to_string()])
+        };
+        let errors = |code: &str| resolved(code).errors;
+        assert_eq!(
+            errors("fn f(): int {\n    let total = 1\n    return totl\n}"),
+            ["Unknown variable `totl` at line 7 in `f`, did you mean `total`?"]
+        );
+        assert_eq!(
+            errors("fn f(): int {\n    return aera(1, 2) + size\n}"),
+            [
+                "Unknown function `aera` at line 6 in `f`, did you mean `area`?",
+                "Unknown variable `size` at line 6 in `f`"
+            ]
+        );
+        assert_eq!(
+            errors("fn f() {\n    let p = Pt { x: 1 }\n}"),
+            ["Unknown struct `Pt` at line 6 in `f`, did you mean `P`?"]
+        );
+        // a `let` ends with its block
+        assert_eq!(
+            errors("fn f() {\n    if true {\n        let n = 1\n    }\n    print(n)\n}"),
+            ["Unknown variable `n` at line 9 in `f`"]
+        );
+        assert_eq!(
+            errors("fn f(a: int, a: int) {\n    print(a)\n}\nconst area = 2"),
+            [
+                "`area` is defined twice at line 8",
+                "The argument `a` of `f` is defined twice"
+            ]
+        );
+        assert_eq!(
+            errors("fn area() {\n    print(1)\n}"),
+            ["`area` is defined twice at line 5"]
+        );
+        // the functions LLVM declares
+        let mut lexer = Lexer::quiet();
+        lexer.parse("fn f() {\n    puts(\"hi\")\n}".to_string());
+        assert!(resolve::linked(&lexer.ast, &[]).errors.is_empty());
+        assert_eq!(
+            errors("fn f() {\n    let n = 1\n    let n = 2\n}"),
+            ["`n` is defined twice in the same block at line 7 in `f`"]
+        );
+        let hidden = resolved("fn f(n: int) {\n    if n > 0 {\n        let n = 2\n    }\n}");
+        assert!(hidden.errors.is_empty(), "{:?}", hidden.errors);
+        assert_eq!(
+            hidden.warnings,
+            ["`n` hides the argument `n` at line 7 in `f`"]
+        );
+        // the bindings of an arm, the `state` of a `loop fn` for all of it
+        assert!(errors("enum E { A(int), B }\nfn f(e: E): int {\n    return match e { E::A(n) => n, E::B => 0 }\n}").is_empty());
+        assert!(errors(
+            "loop fn f(): int {\n    state {\n        let n = 0\n    }\n    n = n + 1\n}"
+        )
+        .is_empty());
+        assert!(errors("fn f(): char {\n    let c = 'a'\n    return c\n}").is_empty());
+        // the names of imported code can't be told
+        assert!(errors("import java.io.File\nfn f() {\n    read(File::open(\"a\"))\n}").is_empty());
+    }
+    #[test]
+    fn flow() {
+        let errors = |code: &str| {
+            let mut lexer = Lexer::quiet();
+            lexer.parse(code.to_string());
+            flow::program(&lexer.ast)
+        };
+        assert_eq!(
+            errors("fn f(n: int): int {\n    if n > 0 {\n        return 1\n    }\n}"),
+            ["`f` returns `int` but can reach its end without a `return` at line 5 in `f`"]
+        );
+        assert_eq!(
+            errors("fn f(n: int): int {\n    return n\n    print(n)\n    print(n)\n}"),
+            ["Unreachable code after `return` at line 3 in `f`"]
+        );
+        assert_eq!(
+            errors("fn f() {\n    while true {\n        break\n        print(1)\n    }\n    return 1\n}"),
+            [
+                "Unreachable code after `break` at line 4 in `f`",
+                "`f` returns `void` but got a value at line 6 in `f`"
+            ]
+        );
+        assert_eq!(
+            errors("fn f(): int {\n    yield 1\n    return\n}"),
+            [
+                "`yield` outside of a `gen fn` at line 2 in `f`",
+                "`f` returns `int` but got no value at line 3 in `f`"
+            ]
+        );
+        // every branch returns, or the loop is only left by returning
+        assert!(errors("fn f(n: int): int {\n    if n > 0 {\n        return 1\n    } else if n < 0 {\n        return 2\n    } else {\n        return 3\n    }\n}").is_empty());
+        assert!(errors("fn f(n: int): int {\n    'outer: loop {\n        for i in 0..n {\n            break\n        }\n        return n\n    }\n}").is_empty());
+        assert_eq!(
+            errors("fn f(n: int): int {\n    'outer: loop {\n        loop {\n            break 'outer\n        }\n    }\n}"),
+            ["`f` returns `int` but can reach its end without a `return` at line 7 in `f`"]
+        );
+        assert!(errors("gen fn f(n: int): int {\n    yield n\n    return\n}").is_empty());
+        assert!(
+            errors("loop fn f(n: int): int {\n    if n > 2 {\n        return n\n    }\n}")
+                .is_empty()
+        );
+    }
+}
+
 mod cxx {
-    use crate::test::utils::test_cxx;
+    use crate::{lexer::types::Type, test::utils::test_cxx, transpiler::cxx};
     #[test]
     fn expression() {
         test_cxx("expression");
@@ -51,6 +625,50 @@ mod cxx {
         test_cxx("loop_function");
     }
     #[test]
+    fn loop_state() {
+        test_cxx("loop_state");
+    }
+    #[test]
+    fn loops() {
+        test_cxx("loops");
+    }
+    #[test]
+    fn ranges() {
+        test_cxx("ranges");
+    }
+    #[test]
+    fn enumerate() {
+        test_cxx("enumerate");
+    }
+    #[test]
+    fn structs() {
+        test_cxx("structs");
+    }
+    #[test]
+    fn enums() {
+        test_cxx("enums");
+    }
+    #[test]
+    fn match_() {
+        test_cxx("match");
+    }
+    #[test]
+    fn methods() {
+        test_cxx("methods");
+    }
+    #[test]
+    fn reserved() {
+        test_cxx("reserved");
+    }
+    #[test]
+    fn traits() {
+        test_cxx("traits");
+    }
+    #[test]
+    fn generics() {
+        test_cxx("generics");
+    }
+    #[test]
     fn function() {
         test_cxx("main");
     }
@@ -58,6 +676,1028 @@ mod cxx {
     fn generator() {
         test_cxx("generator");
     }
+    #[test]
+    fn delegate() {
+        test_cxx("delegate");
+    }
+    #[test]
+    fn pipe() {
+        test_cxx("pipe");
+    }
+    #[test]
+    fn lazy() {
+        test_cxx("lazy");
+    }
+    #[test]
+    fn types() {
+        test_cxx("types");
+        let type_ = Type::parse("fn([i32]?): str").unwrap();
+        assert_eq!(
+            cxx::type_name(&type_),
+            "std::function<std::string(std::optional<std::vector<std::int32_t>>)>"
+        );
+    }
+    #[test]
+    fn inference() {
+        test_cxx("inference");
+    }
+    #[test]
+    fn scopes() {
+        test_cxx("scopes");
+    }
+    #[test]
+    fn returns() {
+        test_cxx("returns");
+    }
+}
+
+mod go {
+    use crate::{
+        lexer::{types::Type, Lexer, Token},
+        test::utils::test_go,
+        transpiler::go,
+    };
+    #[test]
+    fn expression() {
+        test_go("expression");
+    }
+    #[test]
+    fn global_const() {
+        test_go("global_const");
+    }
+    #[test]
+    fn global_let() {
+        test_go("global_let");
+    }
+    #[test]
+    fn if_() {
+        test_go("if");
+    }
+    #[test]
+    fn let_() {
+        test_go("let");
+    }
+    #[test]
+    fn loop_function() {
+        test_go("loop_function");
+    }
+    #[test]
+    fn loop_state() {
+        test_go("loop_state");
+    }
+    #[test]
+    fn loops() {
+        test_go("loops");
+    }
+    #[test]
+    fn ranges() {
+        test_go("ranges");
+    }
+    #[test]
+    fn enumerate() {
+        test_go("enumerate");
+    }
+    #[test]
+    fn structs() {
+        test_go("structs");
+    }
+    #[test]
+    fn enums() {
+        test_go("enums");
+    }
+    #[test]
+    fn match_() {
+        test_go("match");
+    }
+    #[test]
+    fn methods() {
+        test_go("methods");
+    }
+    #[test]
+    fn reserved() {
+        test_go("reserved");
+    }
+    #[test]
+    fn traits() {
+        test_go("traits");
+    }
+    #[test]
+    fn generics() {
+        test_go("generics");
+    }
+    #[test]
+    fn function() {
+        test_go("main");
+    }
+    #[test]
+    fn generator() {
+        test_go("generator");
+    }
+    #[test]
+    fn delegate() {
+        test_go("delegate");
+    }
+    #[test]
+    fn types() {
+        test_go("types");
+        let type_ = Type::parse("fn([i32]?): str").unwrap();
+        assert_eq!(go::type_name(&type_), "func(*[]int32) string");
+    }
+    #[test]
+    fn gofmt() {
+        let go = |exp: &str| {
+            let mut lexer = Lexer::quiet();
+            lexer.parse(format!("const x = {exp}"));
+            match &lexer.ast[0] {
+                Token::Const(x) => go::expression(&x.exp, 1),
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(go("n % 2 == 1"), "n%2 == 1");
+        assert_eq!(go("a + b * c"), "a + b*c");
+        assert_eq!(go("f(a + b, c) * f(a + b)"), "f(a+b, c) * f(a+b)");
+        assert_eq!(go("f(a + b)"), "f(a + b)");
+        assert_eq!(go("(a + b) * c"), "(a + b) * c");
+        assert_eq!(go("a - -b"), "a - -b");
+        assert_eq!(go("a == b && c == d"), "a == b && c == d");
+        assert_eq!(go("a * b * c - d % 2 << 1"), "a*b*c - d%2<<1");
+    }
+    #[test]
+    fn inference() {
+        test_go("inference");
+    }
+    #[test]
+    fn scopes() {
+        test_go("scopes");
+    }
+    #[test]
+    fn returns() {
+        test_go("returns");
+    }
+}
+
+mod java {
+    use crate::{lexer::types::Type, test::utils::test_java, transpiler::java};
+    #[test]
+    fn expression() {
+        test_java("expression");
+    }
+    #[test]
+    fn global_const() {
+        test_java("global_const");
+    }
+    #[test]
+    fn global_let() {
+        test_java("global_let");
+    }
+    #[test]
+    fn if_() {
+        test_java("if");
+    }
+    #[test]
+    fn let_() {
+        test_java("let");
+    }
+    #[test]
+    fn loop_function() {
+        test_java("loop_function");
+    }
+    #[test]
+    fn loop_state() {
+        test_java("loop_state");
+    }
+    #[test]
+    fn loops() {
+        test_java("loops");
+    }
+    #[test]
+    fn ranges() {
+        test_java("ranges");
+    }
+    #[test]
+    fn enumerate() {
+        test_java("enumerate");
+    }
+    #[test]
+    fn structs() {
+        test_java("structs");
+    }
+    #[test]
+    fn enums() {
+        test_java("enums");
+    }
+    #[test]
+    fn match_() {
+        test_java("match");
+    }
+    #[test]
+    fn methods() {
+        test_java("methods");
+    }
+    #[test]
+    fn reserved() {
+        test_java("reserved");
+    }
+    #[test]
+    fn traits() {
+        test_java("traits");
+    }
+    #[test]
+    fn generics() {
+        test_java("generics");
+    }
+    #[test]
+    fn function() {
+        test_java("main");
+    }
+    #[test]
+    fn generator() {
+        test_java("generator");
+    }
+    #[test]
+    fn delegate() {
+        test_java("delegate");
+    }
+    #[test]
+    fn types() {
+        test_java("types");
+        let this = &mut java::new();
+        let type_ = Type::parse("fn([i32]?): str").unwrap();
+        assert_eq!(
+            java::type_name(this, &type_),
+            "Function<List<Integer>, String>"
+        );
+        let type_ = Type::parse("fn(u8, bool)").unwrap();
+        assert_eq!(java::type_name(this, &type_), "BiConsumer<Short, Boolean>");
+    }
+    #[test]
+    fn inference() {
+        test_java("inference");
+    }
+    #[test]
+    fn scopes() {
+        test_java("scopes");
+    }
+    #[test]
+    fn returns() {
+        test_java("returns");
+    }
+}
+
+mod csharp {
+    use crate::{lexer::types::Type, test::utils::test_csharp, transpiler::csharp};
+    #[test]
+    fn expression() {
+        test_csharp("expression");
+    }
+    #[test]
+    fn global_const() {
+        test_csharp("global_const");
+    }
+    #[test]
+    fn global_let() {
+        test_csharp("global_let");
+    }
+    #[test]
+    fn if_() {
+        test_csharp("if");
+    }
+    #[test]
+    fn let_() {
+        test_csharp("let");
+    }
+    #[test]
+    fn loop_function() {
+        test_csharp("loop_function");
+    }
+    #[test]
+    fn loop_state() {
+        test_csharp("loop_state");
+    }
+    #[test]
+    fn loops() {
+        test_csharp("loops");
+    }
+    #[test]
+    fn ranges() {
+        test_csharp("ranges");
+    }
+    #[test]
+    fn enumerate() {
+        test_csharp("enumerate");
+    }
+    #[test]
+    fn structs() {
+        test_csharp("structs");
+    }
+    #[test]
+    fn pipe() {
+        test_csharp("pipe");
+    }
+    #[test]
+    fn enums() {
+        test_csharp("enums");
+    }
+    #[test]
+    fn match_() {
+        test_csharp("match");
+    }
+    #[test]
+    fn methods() {
+        test_csharp("methods");
+    }
+    #[test]
+    fn reserved() {
+        test_csharp("reserved");
+    }
+    #[test]
+    fn traits() {
+        test_csharp("traits");
+    }
+    #[test]
+    fn generics() {
+        test_csharp("generics");
+    }
+    #[test]
+    fn function() {
+        test_csharp("main");
+    }
+    #[test]
+    fn generator() {
+        test_csharp("generator");
+    }
+    #[test]
+    fn delegate() {
+        test_csharp("delegate");
+    }
+    #[test]
+    fn types() {
+        test_csharp("types");
+        let this = &mut csharp::new();
+        let type_ = Type::parse("fn([i32]?): str").unwrap();
+        assert_eq!(csharp::type_name(this, &type_), "Func<List<int>?, string>");
+    }
+    #[test]
+    fn inference() {
+        test_csharp("inference");
+    }
+    #[test]
+    fn scopes() {
+        test_csharp("scopes");
+    }
+    #[test]
+    fn returns() {
+        test_csharp("returns");
+    }
+    #[test]
+    fn imports() {
+        test_csharp("imports");
+    }
+}
+
+mod lua {
+    use crate::test::utils::test_lua;
+    #[test]
+    fn expression() {
+        test_lua("expression");
+    }
+    #[test]
+    fn global_const() {
+        test_lua("global_const");
+    }
+    #[test]
+    fn global_let() {
+        test_lua("global_let");
+    }
+    #[test]
+    fn if_() {
+        test_lua("if");
+    }
+    #[test]
+    fn let_() {
+        test_lua("let");
+    }
+    #[test]
+    fn loop_function() {
+        test_lua("loop_function");
+    }
+    #[test]
+    fn loop_state() {
+        test_lua("loop_state");
+    }
+    #[test]
+    fn loops() {
+        test_lua("loops");
+    }
+    #[test]
+    fn ranges() {
+        test_lua("ranges");
+    }
+    #[test]
+    fn enumerate() {
+        test_lua("enumerate");
+    }
+    #[test]
+    fn structs() {
+        test_lua("structs");
+    }
+    #[test]
+    fn pipe() {
+        test_lua("pipe");
+    }
+    #[test]
+    fn enums() {
+        test_lua("enums");
+    }
+    #[test]
+    fn match_() {
+        test_lua("match");
+    }
+    #[test]
+    fn methods() {
+        test_lua("methods");
+    }
+    #[test]
+    fn reserved() {
+        test_lua("reserved");
+    }
+    #[test]
+    fn traits() {
+        test_lua("traits");
+    }
+    #[test]
+    fn generics() {
+        test_lua("generics");
+    }
+    #[test]
+    fn function() {
+        test_lua("main");
+    }
+    #[test]
+    fn generator() {
+        test_lua("generator");
+    }
+    #[test]
+    fn delegate() {
+        test_lua("delegate");
+    }
+    #[test]
+    fn division() {
+        test_lua("division");
+    }
+    #[test]
+    fn types() {
+        test_lua("types");
+    }
+    #[test]
+    fn inference() {
+        test_lua("inference");
+    }
+    #[test]
+    fn scopes() {
+        test_lua("scopes");
+    }
+    #[test]
+    fn returns() {
+        test_lua("returns");
+    }
+    #[test]
+    fn imports() {
+        test_lua("imports");
+    }
+}
+
+mod wat {
+    use crate::test::utils::test_wat;
+    #[test]
+    fn arithmetic() {
+        test_wat("arithmetic");
+    }
+    #[test]
+    fn expression() {
+        test_wat("expression");
+    }
+    #[test]
+    fn global_const() {
+        test_wat("global_const");
+    }
+    #[test]
+    fn global_let() {
+        test_wat("global_let");
+    }
+    #[test]
+    fn if_() {
+        test_wat("if");
+    }
+    #[test]
+    fn let_() {
+        test_wat("let");
+    }
+    #[test]
+    fn loop_function() {
+        test_wat("loop_function");
+    }
+    #[test]
+    fn loops() {
+        test_wat("loops");
+    }
+    #[test]
+    fn ranges() {
+        test_wat("ranges");
+    }
+    #[test]
+    fn function() {
+        test_wat("main");
+    }
+}
+
+mod llvm {
+    use crate::test::utils::test_llvm;
+    #[test]
+    fn arithmetic() {
+        test_llvm("arithmetic");
+    }
+    #[test]
+    fn array() {
+        test_llvm("array");
+    }
+    #[test]
+    fn expression() {
+        test_llvm("expression");
+    }
+    #[test]
+    fn global_const() {
+        test_llvm("global_const");
+    }
+    #[test]
+    fn global_let() {
+        test_llvm("global_let");
+    }
+    #[test]
+    fn if_() {
+        test_llvm("if");
+    }
+    #[test]
+    fn let_() {
+        test_llvm("let");
+    }
+    #[test]
+    fn loop_function() {
+        test_llvm("loop_function");
+    }
+    #[test]
+    fn loops() {
+        test_llvm("loops");
+    }
+    #[test]
+    fn ranges() {
+        test_llvm("ranges");
+    }
+    #[test]
+    fn function() {
+        test_llvm("main");
+    }
+}
+
+mod interpreter {
+    use crate::{interpreter::Interpreter, test::utils::test_interpreter};
+    #[test]
+    fn division() {
+        test_interpreter("division", "", 1);
+    }
+    #[test]
+    fn generator() {
+        test_interpreter("generator", "", 0);
+    }
+    #[test]
+    fn delegate() {
+        test_interpreter("delegate", "", 324);
+    }
+    #[test]
+    fn pipe() {
+        test_interpreter("pipe", "", 1225);
+    }
+    #[test]
+    fn loop_state() {
+        test_interpreter("loop_state", "", 33);
+    }
+    #[test]
+    fn loops() {
+        test_interpreter("loops", "", 131);
+    }
+    #[test]
+    fn ranges() {
+        test_interpreter("ranges", "", 133);
+    }
+    #[test]
+    fn enumerate() {
+        test_interpreter("enumerate", "", 39);
+    }
+    #[test]
+    fn structs() {
+        test_interpreter("structs", "Point{x:3}\n", 119);
+    }
+    #[test]
+    fn enums() {
+        test_interpreter("enums", "", 42);
+    }
+    #[test]
+    fn match_() {
+        test_interpreter("match", "", 42);
+    }
+    #[test]
+    fn methods() {
+        test_interpreter("methods", "", 66);
+    }
+    #[test]
+    fn traits() {
+        test_interpreter("traits", "", 60);
+    }
+    #[test]
+    fn generics() {
+        test_interpreter("generics", "", 9);
+    }
+    #[test]
+    fn if_() {
+        test_interpreter("if", "", 0);
+    }
+    #[test]
+    fn function() {
+        test_interpreter("main", "", 0);
+    }
+    #[test]
+    fn run() {
+        test_interpreter(
+            "run",
+            "1 is odd\n2 is even\n3 is odd\n4 is even\ntotal 10 after 5 calls\n",
+            10,
+        );
+    }
+    #[test]
+    fn types() {
+        test_interpreter("types", "", 7);
+    }
+    #[test]
+    fn inference() {
+        test_interpreter("inference", "", 3);
+    }
+    #[test]
+    fn scopes() {
+        test_interpreter("scopes", "", 8);
+    }
+    #[test]
+    fn returns() {
+        test_interpreter("returns", "", 5);
+    }
+    #[test]
+    fn lexer_errors() {
+        let interpreter = &mut Interpreter::capturing();
+        assert_eq!(
+            interpreter.run("./src/examples/lexer_errors.lang"),
+            Err("Not running a program with lexer errors".to_string())
+        );
+        assert_eq!(interpreter.output.as_deref(), Some(""));
+    }
+}
+
+mod vm {
+    use crate::{
+        lexer::Lexer,
+        test::utils::test_vm,
+        vm::{compiler, Vm},
+    };
+    #[test]
+    fn division() {
+        test_vm("division", "", 1);
+    }
+    #[test]
+    fn generator() {
+        test_vm("generator", "", 0);
+    }
+    #[test]
+    fn delegate() {
+        test_vm("delegate", "", 324);
+    }
+    #[test]
+    fn pipe() {
+        test_vm("pipe", "", 1225);
+    }
+    #[test]
+    fn loop_state() {
+        test_vm("loop_state", "", 33);
+    }
+    #[test]
+    fn loops() {
+        test_vm("loops", "", 131);
+    }
+    #[test]
+    fn ranges() {
+        test_vm("ranges", "", 133);
+    }
+    #[test]
+    fn enumerate() {
+        test_vm("enumerate", "", 39);
+    }
+    #[test]
+    fn structs() {
+        test_vm("structs", "Point{x:3}\n", 119);
+    }
+    #[test]
+    fn enums() {
+        test_vm("enums", "", 42);
+    }
+    #[test]
+    fn match_() {
+        test_vm("match", "", 42);
+    }
+    #[test]
+    fn methods() {
+        test_vm("methods", "", 66);
+    }
+    #[test]
+    fn traits() {
+        test_vm("traits", "", 60);
+    }
+    #[test]
+    fn generics() {
+        test_vm("generics", "", 9);
+    }
+    #[test]
+    fn if_() {
+        test_vm("if", "", 0);
+    }
+    #[test]
+    fn function() {
+        test_vm("main", "", 0);
+    }
+    #[test]
+    fn run() {
+        test_vm(
+            "run",
+            "1 is odd\n2 is even\n3 is odd\n4 is even\ntotal 10 after 5 calls\n",
+            10,
+        );
+    }
+    #[test]
+    fn types() {
+        test_vm("types", "", 7);
+    }
+    #[test]
+    fn inference() {
+        test_vm("inference", "", 3);
+    }
+    #[test]
+    fn scopes() {
+        test_vm("scopes", "", 8);
+    }
+    #[test]
+    fn returns() {
+        test_vm("returns", "", 5);
+    }
+    #[test]
+    fn lexer_errors() {
+        assert_eq!(
+            compiler::compile_file("./src/examples/lexer_errors.lang"),
+            Err("Not compiling a program with lexer errors".to_string())
+        );
+    }
+    #[test]
+    fn depth() {
+        let run = |n: i32| {
+            let mut lexer = Lexer::quiet();
+            lexer.parse(format!(
+                "fn r(n: int): int {{\nif n == 0 {{\nreturn 0\n}}\nreturn r(n - 1) + 1\n}}\nfn main(): int {{\nreturn r({n})\n}}"
+            ));
+            Vm::capturing(compiler::compile(&lexer.ast).unwrap()).run()
+        };
+        assert_eq!(run(998), Ok(998));
+        assert_eq!(
+            run(999),
+            Err("More than 1000 calls running at once\n    in `r`\n    in `main`".to_string())
+        );
+    }
+}
+
+mod repl {
+    use crate::{interpreter::with_stack, repl::Repl};
+    #[test]
+    fn session() {
+        let repl = &mut Repl::capturing();
+        assert_eq!(repl.eval("let x = 2"), Ok(String::new()));
+        assert_eq!(
+            repl.eval("fn sq(n: int): int {\n    return n * n\n}"),
+            Ok(String::new())
+        );
+        assert_eq!(repl.eval("sq(x) + 1"), Ok("5".to_string()));
+        assert_eq!(repl.eval("print(\"x is\", x)"), Ok(String::new()));
+        assert_eq!(repl.interpreter.output.as_deref(), Some("x is 2\n"));
+        assert_eq!(repl.eval(":type sq(x) > 3"), Ok("bool".to_string()));
+        // the names of the type checker
+        assert_eq!(repl.eval(":type x * 1.5"), Ok("f64".to_string()));
+        assert_eq!(
+            repl.eval("x(1)"),
+            Err("`x` is a `int`, not a function".to_string())
+        );
+        assert!(repl.eval("return x").is_err());
+        // variables survive errors
+        assert_eq!(repl.eval("x"), Ok("2".to_string()));
+    }
+    #[test]
+    fn pipe() {
+        let repl = &mut Repl::capturing();
+        assert_eq!(
+            repl.eval("fn double(x: int): int {\n    return x * 2\n}"),
+            Ok(String::new())
+        );
+        assert_eq!(repl.eval("let s = \"x |> y\""), Ok(String::new()));
+        assert_eq!(repl.eval("s"), Ok("x |> y".to_string()));
+        assert_eq!(repl.eval("(4 |> double) + 1"), Ok("9".to_string()));
+        assert_eq!(repl.eval("print(4 |> double)"), Ok(String::new()));
+        assert_eq!(repl.interpreter.output.as_deref(), Some("8\n"));
+    }
+    #[test]
+    fn strings() {
+        assert!(Repl::complete("let s = \"a { b\""));
+        assert!(!Repl::complete("if s == \"}\" {"));
+        let repl = &mut Repl::capturing();
+        assert_eq!(repl.eval("print(\"}\")"), Ok(String::new()));
+        assert_eq!(repl.interpreter.output.as_deref(), Some("}\n"));
+    }
+    #[test]
+    fn lexer_errors() {
+        let repl = &mut Repl::capturing();
+        let rejected = Err("Not running input with lexer errors".to_string());
+        assert_eq!(repl.eval("let = 5"), rejected);
+        assert_eq!(repl.eval("fn f(): int {\nlet = 2\nreturn 1\n}"), rejected);
+    }
+    #[test]
+    fn cxx() {
+        let repl = &mut Repl::capturing();
+        let double = "fn double(n: int): int {\nreturn n * 2\n}";
+        assert_eq!(repl.eval(double), Ok(String::new()));
+        assert_eq!(repl.eval("let x = 2"), Ok(String::new()));
+        assert_eq!(repl.eval("if x > 1 {\nlet y = 1\n}"), Ok(String::new()));
+        assert_eq!(repl.eval("let x = 3"), Ok(String::new()));
+        assert_eq!(repl.eval("double(x)"), Ok("6".to_string()));
+        // the function and `x` are declared before the input using them
+        let cxx = repl.eval(":cxx").unwrap();
+        let lines: Vec<&str> = cxx.lines().collect();
+        assert_eq!(lines[0], "int double_ (int n)");
+        assert_eq!(lines[5..].join("\n"), "{\nauto x = 3;\ndouble_ (x );\n}");
+    }
+    #[test]
+    fn depth() {
+        with_stack(|| {
+            let repl = &mut Repl::capturing();
+            let r = "fn r(n: int): int {\n    if n == 0 {\n        return 0\n    }\n    return r(n - 1) + 1\n}";
+            assert_eq!(repl.eval(r), Ok(String::new()));
+            assert_eq!(repl.eval("r(999)"), Ok("999".to_string()));
+            assert_eq!(
+                repl.eval("r(1000)"),
+                Err("More than 1000 calls running at once\n    in `r`".to_string())
+            );
+        });
+    }
+    #[test]
+    fn structs() {
+        let repl = &mut Repl::capturing();
+        assert_eq!(repl.eval("struct P { x: int, y: int }"), Ok(String::new()));
+        assert_eq!(repl.eval("let p = P { y: 2, x: 1 }"), Ok(String::new()));
+        assert_eq!(repl.eval("p.x + p.y"), Ok("3".to_string()));
+        assert_eq!(repl.eval("p"), Ok("P { x: 1, y: 2 }".to_string()));
+        assert!(repl.eval("let q = P { x: 1 }").is_err());
+    }
+    #[test]
+    fn enums() {
+        let repl = &mut Repl::capturing();
+        assert_eq!(
+            repl.eval("enum Shape { Circle(float), Empty }"),
+            Ok(String::new())
+        );
+        assert_eq!(
+            repl.eval("Shape::Circle(2)"),
+            Ok("Shape::Circle(2)".to_string())
+        );
+        assert_eq!(
+            repl.eval("Shape::Empty == Shape::Empty"),
+            Ok("true".to_string())
+        );
+        assert!(repl.eval("Shape::Circle(1, 2)").is_err());
+        assert!(repl.eval("Shape::Square").is_err());
+    }
+    #[test]
+    fn match_() {
+        let repl = &mut Repl::capturing();
+        assert_eq!(
+            repl.eval("let s = match 3 {\n    1 => \"one\",\n    n => \"many\",\n}"),
+            Ok(String::new())
+        );
+        assert_eq!(repl.eval("s"), Ok("many".to_string()));
+        assert!(repl.eval("let t = match 3 { 1 => 0 }").is_err());
+    }
+    #[test]
+    fn methods() {
+        let repl = &mut Repl::capturing();
+        assert_eq!(repl.eval("struct P { x: int }"), Ok(String::new()));
+        assert_eq!(
+            repl.eval("impl P {\n    fn double(self): int {\n        return self.x * 2\n    }\n}"),
+            Ok(String::new())
+        );
+        assert_eq!(repl.eval("let p = P { x: 4 }"), Ok(String::new()));
+        assert_eq!(repl.eval("p.double() + 1"), Ok("9".to_string()));
+        assert!(repl.eval("p.triple()").is_err());
+        assert!(repl.eval("impl Q {\n}").is_err());
+    }
+    #[test]
+    fn traits() {
+        let repl = &mut Repl::capturing();
+        assert_eq!(
+            repl.eval("trait Named {\n    fn name(self): string\n}"),
+            Ok(String::new())
+        );
+        assert_eq!(repl.eval("struct Cat { age: int }"), Ok(String::new()));
+        assert_eq!(
+            repl.eval("impl Named for Cat {\n    fn name(self): string {\n        return \"cat\"\n    }\n}"),
+            Ok(String::new())
+        );
+        assert_eq!(
+            repl.eval("fn greet(x: dyn Named): string {\n    return x.name()\n}"),
+            Ok(String::new())
+        );
+        assert_eq!(repl.eval("let c = Cat { age: 2 }"), Ok(String::new()));
+        assert_eq!(repl.eval("greet(c)"), Ok("cat".to_string()));
+        assert!(repl.eval("greet(2)").is_err());
+    }
+    #[test]
+    fn generics() {
+        let repl = &mut Repl::capturing();
+        assert_eq!(
+            repl.eval("fn larger<T>(a: T, b: T): T {\n    if a > b {\n        return a\n    }\n    return b\n}"),
+            Ok(String::new())
+        );
+        assert_eq!(repl.eval("larger(2, 5)"), Ok("5".to_string()));
+        assert_eq!(repl.eval("larger(\"a\", \"b\")"), Ok("b".to_string()));
+        assert!(repl.eval("larger(1, 2.5)").is_err());
+    }
+    #[test]
+    fn complete() {
+        assert!(Repl::complete("let x = 1"));
+        assert!(!Repl::complete("fn main(): int {"));
+        assert!(!Repl::complete("if x {\n    print(\"{\")\n"));
+        assert!(Repl::complete("if x {\n    print(\"{\")\n}"));
+    }
+    #[test]
+    fn types() {
+        let repl = &mut Repl::capturing();
+        assert_eq!(
+            repl.eval("fn byte(x: u8): u8 {\n    return x\n}"),
+            Ok(String::new())
+        );
+        assert_eq!(repl.eval("byte(300)"), Ok("44".to_string()));
+        assert_eq!(
+            repl.eval("fn small(x: i8): i8 {\n    return x\n}"),
+            Ok(String::new())
+        );
+        assert_eq!(repl.eval("small(200)"), Ok("-56".to_string()));
+    }
+    #[test]
+    fn inference() {
+        let repl = &mut Repl::capturing();
+        assert_eq!(
+            repl.eval("fn area(w: i64, h: i64): i64 {\n    return w * h\n}"),
+            Ok(String::new())
+        );
+        assert_eq!(
+            repl.eval("fn label(): str {\n    return area(2, 3)\n}"),
+            Err("`label` returns `str` but got `i64` at line 2 in `label`".to_string())
+        );
+        assert!(repl.eval("label()").is_err());
+    }
+    #[test]
+    fn resolve() {
+        let repl = &mut Repl::capturing();
+        assert_eq!(
+            repl.eval("fn total(xs: [int]): int {\n    let sum = 0\n    for x in xs {\n        sum = sum + x\n    }\n    return summ\n}"),
+            Err("Unknown variable `summ` at line 6 in `total`, did you mean `sum`?".to_string())
+        );
+        assert!(repl.eval("total([1, 2])").is_err());
+    }
+    #[test]
+    fn flow() {
+        let repl = &mut Repl::capturing();
+        assert_eq!(
+            repl.eval("fn sign(n: int): int {\n    if n > 0 {\n        return 1\n    }\n}"),
+            Err(
+                "`sign` returns `int` but can reach its end without a `return` at line 5 in `sign`"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            repl.eval("fn sign(n: int): int {\n    return 1\n    return 0\n}"),
+            Err("Unreachable code after `return` at line 3 in `sign`".to_string())
+        );
+        assert!(repl.eval("sign(1)").is_err());
+    }
 }
 
 mod functional {
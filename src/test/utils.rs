@@ -1,5 +1,11 @@
 use {
-    crate::{log, printx, transpiler::*, PrintT},
+    crate::{
+        interpreter::Interpreter,
+        log, printx,
+        transpiler::*,
+        vm::{bytecode::Program, compiler, Vm},
+        PrintT,
+    },
     std::{fs::read_to_string, path::Path},
 };
 
@@ -19,13 +25,72 @@ where
     }
 }
 
-pub fn test_cxx(test: &str) {
-    let cxx = &mut cxx::new();
-    run(cxx, &format!("./src/examples/{test}.lang"));
-    let mut code1 = String::from(cxx.buffer.trim());
-    code1.push_str("\n");
+/// Transpiles `./src/examples/{test}.lang` and compares the output line by
+/// line (ignoring indentation) with `./src/examples/out/{dir}/{test}.{ext}`,
+/// which has to exist.
+pub fn test_transpiler(this: &mut Transpiler, test: &str, dir: &str, ext: &str) {
+    run(this, format!("./src/examples/{test}.lang"));
+    let mut code1 = String::new();
+    this.buffer.trim().lines().for_each(|line| {
+        code1.push_str(&format!("{}\n", line.trim()));
+    });
     log!(Info, f("\n{code1}"));
-    if let Some(code2) = load_file(&format!("./src/examples/out/cxx/{test}.cxx")) {
-        assert_eq!(code1, code2);
-    }
+    let path = format!("./src/examples/out/{dir}/{test}.{ext}");
+    let code2 = load_file(&path).unwrap_or_else(|| panic!("Missing the expected output {path}"));
+    assert_eq!(code1, code2);
+}
+
+pub fn test_cxx(test: &str) {
+    test_transpiler(&mut cxx::new(), test, "cxx", "cxx");
+}
+
+/// Like [`test_transpiler`], but the output has to be exactly the expected
+/// one, indentation included, as it is what `gofmt` writes.
+pub fn test_go(test: &str) {
+    let this = &mut go::new();
+    run(this, format!("./src/examples/{test}.lang"));
+    let path = format!("./src/examples/out/go/{test}.go");
+    let expected =
+        read_to_string(&path).unwrap_or_else(|_| panic!("Missing the expected output {path}"));
+    assert_eq!(this.buffer, expected);
+}
+
+pub fn test_java(test: &str) {
+    test_transpiler(&mut java::new(), test, "java", "java");
+}
+
+pub fn test_csharp(test: &str) {
+    test_transpiler(&mut csharp::new(), test, "csharp", "cs");
+}
+
+pub fn test_lua(test: &str) {
+    test_transpiler(&mut lua::new(), test, "lua", "lua");
+}
+
+pub fn test_wat(test: &str) {
+    test_transpiler(&mut wat::new(), test, "wat", "wat");
+}
+
+pub fn test_llvm(test: &str) {
+    test_transpiler(&mut llvm::new(), test, "llvm", "ll");
+}
+
+/// Runs an example with the interpreter and compares what it printed and
+/// its exit code.
+pub fn test_interpreter(test: &str, output: &str, code: i32) {
+    let interpreter = &mut Interpreter::capturing();
+    let result = interpreter.run(format!("./src/examples/{test}.lang"));
+    assert_eq!(result, Ok(code));
+    assert_eq!(interpreter.output.as_deref(), Some(output));
+}
+
+/// Compiles an example to bytecode, checks that it survives encoding and
+/// runs it on the VM like [`test_interpreter`].
+pub fn test_vm(test: &str, output: &str, code: i32) {
+    let program = compiler::compile_file(format!("./src/examples/{test}.lang")).unwrap();
+    let decoded = Program::decode(&program.encode());
+    assert_eq!(decoded.as_ref(), Ok(&program));
+    let vm = &mut Vm::capturing(program);
+    assert_eq!(vm.run(), Ok(code));
+    assert_eq!(vm.output.as_deref(), Some(output));
 }
@@ -0,0 +1,642 @@
+use super::*;
+
+pub type CSharp = Transpiler;
+pub fn new() -> CSharp {
+    Transpiler {
+        buffer: String::new(),
+        imports: vec![],
+        target_lang: TranspilerLang::CSharp,
+        globals: HashMap::new(),
+    }
+}
+
+/// Name of the static class all top-level items are wrapped in.
+pub const CLASS_NAME: &str = "Program";
+
+/// The lazy `map`, `filter` and `take` of `|>` pipelines, generators are
+/// `IEnumerable`s already.
+const ADAPTERS: &str = "
+    static IEnumerable<U> map<T, U>(IEnumerable<T> xs, Func<T, U> f)
+    {
+        foreach (var x in xs)
+        {
+            yield return f(x);
+        }
+    }
+
+    static IEnumerable<T> filter<T>(IEnumerable<T> xs, Func<T, bool> p)
+    {
+        foreach (var x in xs)
+        {
+            if (p(x))
+            {
+                yield return x;
+            }
+        }
+    }
+
+    static IEnumerable<T> take<T>(IEnumerable<T> xs, long n)
+    {
+        if (n <= 0)
+        {
+            yield break;
+        }
+        foreach (var x in xs)
+        {
+            yield return x;
+            if (--n <= 0)
+            {
+                yield break;
+            }
+        }
+    }
+";
+
+/// `print` of the interpreter, the values separated by spaces and `bool`s
+/// written like in lang.
+const PRINT: &str = "
+    static void print(params object[] values)
+    {
+        var words = Array.ConvertAll(values, v => v is bool b ? (b ? \"true\" : \"false\") : v);
+        Console.WriteLine(string.Join(\" \", words));
+    }
+";
+
+pub fn transpile(this: &mut Transpiler, ast: Vec<Token>) {
+    let mut types: Vec<String> = vec![];
+    let mut fields: Vec<String> = vec![];
+    if prints(&ast) {
+        using(this, "System");
+        this.buffer.push_str(PRINT);
+    }
+    if !adapters(&ast).is_empty() {
+        using(this, "System");
+        using(this, "System.Collections.Generic");
+        this.buffer.push_str(ADAPTERS);
+    }
+    let mut ast_iter = ast.iter().peekable();
+    while ast_iter.peek().is_some() {
+        let nt = ast_iter.next().unwrap();
+        match nt {
+            Token::Const(x) => {
+                let (name, exp) = (x.name.clone(), x.exp.clone());
+                let value = expression(this.clone().target_lang, exp.clone());
+                // `const` needs a type known at compile time, anything else
+                // can still be initialized once
+                match literal_type(&exp) {
+                    Some(type_) => fields.push(format!(
+                        "    const {} {name} = {value};\n",
+                        type_name(this, &type_)
+                    )),
+                    None => fields.push(format!(
+                        "    static readonly {} {name} = {value};\n",
+                        field_type(this, &name, &exp)
+                    )),
+                }
+            }
+            Token::Global(x) => {
+                let (name, exp) = (x.name.clone(), x.exp.clone());
+                fields.push(format!(
+                    "    static {} {name} = {};\n",
+                    field_type(this, &name, &exp),
+                    expression(this.clone().target_lang, exp.clone())
+                ));
+            }
+            Token::Struct(x) => {
+                let mut struct_ = format!("struct {}", x.name);
+                let traits = implemented(&ast, &x.name);
+                if !traits.is_empty() {
+                    struct_.push_str(&format!(" : {}", traits.join(", ")));
+                }
+                struct_.push_str("\n{\n");
+                for field in &x.fields {
+                    struct_.push_str(&format!(
+                        "    public {} {};\n",
+                        type_name(this, &field.type_),
+                        field.name
+                    ));
+                }
+                for function in functions(&ast, &x.name) {
+                    let buffer = std::mem::take(&mut this.buffer);
+                    method(this, &x.name, function);
+                    struct_.push_str(&std::mem::replace(&mut this.buffer, buffer));
+                }
+                struct_.push_str("}\n\n");
+                types.push(struct_);
+            }
+            Token::Enum(x) => {
+                types.push(enum_(this, x));
+            }
+            // the structs implementing it implement the interface
+            Token::Trait(x) => {
+                let mut interface = format!("interface {}\n{{\n", x.name);
+                for function in &x.functions {
+                    let return_type = type_name(this, &function.return_type);
+                    let args = params(this, &function.arguments[1..]);
+                    interface.push_str(&format!("    {return_type} {}({args});\n", function.name));
+                }
+                interface.push_str("}\n\n");
+                types.push(interface);
+            }
+            // the functions are in the struct
+            Token::Impl(_) => {}
+            Token::Function(x) => {
+                function(this, x, FunctionType::Function);
+            }
+            Token::LoopFunction(x) => {
+                function(this, x, FunctionType::Loop);
+            }
+            Token::GeneratorFunction(x) => {
+                using(this, "System.Collections.Generic");
+                function(this, x, FunctionType::Generator);
+            }
+            Token::Import(i) if dotnet(i) => {
+                using(this, i);
+            }
+            Token::Import(i) => {
+                log!(CSHARP, f("Skipping import {i}"));
+            }
+            Token::CImport(ci) => {
+                log!(CSHARP, f("Skipping C include {ci}"));
+            }
+            Token::Comment(_) => {}
+            _ => {
+                log!(Error, f("unexpected Token {nt:?}"));
+            }
+        }
+    }
+
+    // usings have to come before the class, so the file is assembled once
+    // all methods are known
+    let mut file = String::new();
+    for namespace in &this.imports {
+        file.push_str(&format!("using {namespace};\n"));
+    }
+    if !file.is_empty() {
+        file.push('\n');
+    }
+    file.push_str(&types.concat());
+    file.push_str(&format!("static class {CLASS_NAME}\n{{\n"));
+    file.push_str(&fields.concat());
+    if fields.is_empty() {
+        file.push_str(this.buffer.trim_start_matches('\n'));
+    } else {
+        file.push_str(&this.buffer);
+    }
+    file.push_str("}\n");
+    this.buffer = file;
+}
+
+/// Whether `namespace` is one of .NET, only those can be `using`s.
+fn dotnet(namespace: &str) -> bool {
+    let root = namespace.split('.').next();
+    root == Some("System") || root == Some("Microsoft")
+}
+
+fn using(this: &mut Transpiler, namespace: &str) {
+    if !this.imports.contains(&namespace.to_string()) {
+        this.imports.push(namespace.to_string());
+    }
+}
+
+/// Fields need an explicit type, the one inferred for the global or const
+/// `name`, or else the one of the literal it is initialized with.
+fn field_type(this: &mut Transpiler, name: &str, exp: &Expression) -> String {
+    let type_ = this.globals.get(name).cloned();
+    match type_.or_else(|| literal_type(exp)) {
+        Some(type_) => type_name(this, &type_),
+        None => {
+            log!(
+                CSHARP,
+                f(
+                    "Unable to infer the type of `{}`, using object",
+                    expression(this.clone().target_lang, exp.clone())
+                )
+            );
+            "object".to_string()
+        }
+    }
+}
+
+/// Starts the loop of a `loop fn`, with `every` each iteration waits first.
+fn tick(this: &mut Transpiler, x: &Function) {
+    this.buffer.push_str("        while (true)\n        {\n");
+    if let Some(ms) = x.every {
+        using(this, "System.Threading");
+        this.buffer
+            .push_str(&format!("            Thread.Sleep({ms});\n"));
+    }
+}
+
+fn params(this: &mut Transpiler, arguments: &[Arg]) -> String {
+    let args: Vec<String> = (arguments.iter())
+        .map(|arg| format!("{} {}", type_name(this, &arg.type_), arg.name))
+        .collect();
+    args.join(", ")
+}
+
+pub fn function(this: &mut Transpiler, x: &Function, type_: FunctionType) {
+    let mut return_type = type_name(this, &x.return_type);
+    // C# looks for `Main` as the entry point, it may return the exit code
+    let name = if x.name == "main" {
+        "Main".to_string()
+    } else {
+        x.name.clone()
+    };
+
+    let args = params(this, &x.arguments);
+
+    if type_ == FunctionType::Generator {
+        return_type = format!("IEnumerable<{return_type}>");
+    }
+
+    this.buffer.push_str(&format!(
+        "\n    static {return_type} {name}({args})\n    {{\n"
+    ));
+    body(this, x, type_, &return_type);
+}
+
+/// A function of the `impl` of `owner`, in the struct. Methods aren't
+/// static, `self` is `this`.
+fn method(this: &mut Transpiler, owner: &str, x: &Function) {
+    let return_type = type_name(this, &x.return_type);
+    let name = &x.name;
+    if x.method() {
+        let args = params(this, &x.arguments[1..]);
+        this.buffer.push_str(&format!(
+            "\n    public {return_type} {name}({args})\n    {{\n"
+        ));
+        if reads(x, "self") {
+            this.buffer.push_str("        var self = this;\n");
+        }
+    } else {
+        let args = params(this, &x.arguments);
+        this.buffer.push_str(&format!(
+            "\n    public static {return_type} {name}({args})\n    {{\n"
+        ));
+    }
+    let x = Function {
+        name: format!("{owner}.{name}"),
+        ..x.clone()
+    };
+    body(this, &x, FunctionType::Function, &return_type);
+}
+
+/// The statements of a function up to its closing brace.
+fn body(this: &mut Transpiler, x: &Function, type_: FunctionType, return_type: &str) {
+    let name = &x.name;
+
+    let mut depth = 2;
+    let mut state = None;
+    if type_ == FunctionType::Loop {
+        state = state_block(x);
+        if state.is_none() {
+            tick(this, x);
+            depth += 1;
+        }
+    }
+    // C# has no labelled jumps, they `goto` labels around the loop
+    let (breaks, continues) = jump_labels(x);
+    let mut labels: Vec<(i32, String)> = vec![];
+    let mut label = None;
+    // the ids of the loops in a block of their own, which holds the counter
+    // of `for i, x in ...`
+    let mut wrapped: Vec<i32> = vec![];
+
+    for line in x.lines.clone() {
+        let mut token_iter = line.tokens.iter().peekable();
+        let mut statement = String::new();
+        while token_iter.peek().is_some() {
+            let token = token_iter.next().unwrap();
+            let indent = indent(depth);
+            match token {
+                Token::If(_if) => {
+                    let condition = expression(this.clone().target_lang, _if.condition.clone());
+                    this.buffer
+                        .push_str(&format!("{indent}if ({condition})\n{indent}{{\n"));
+                    depth += 1;
+                }
+                Token::ElseIf(_if) => {
+                    let condition = expression(this.clone().target_lang, _if.condition.clone());
+                    this.buffer
+                        .push_str(&format!("{indent}else if ({condition})\n{indent}{{\n"));
+                    depth += 1;
+                }
+                Token::Else(_) => {
+                    this.buffer.push_str(&format!("{indent}else\n{indent}{{\n"));
+                    depth += 1;
+                }
+                Token::For(_for) => {
+                    let var_name = _for.var_name.clone();
+                    let mut indent = indent;
+                    if let Some(index) = &_for.index {
+                        this.buffer.push_str(&format!("{indent}{{\n"));
+                        depth += 1;
+                        indent = self::indent(depth);
+                        this.buffer
+                            .push_str(&format!("{indent}var {index}_next = 0;\n"));
+                        wrapped.push(_for.id);
+                    }
+                    match &_for.range {
+                        // a counted loop instead of a collection
+                        Some(range) => {
+                            let lang = this.clone().target_lang;
+                            let Counted {
+                                first,
+                                cmp,
+                                bound,
+                                step,
+                                down,
+                            } = counted(range);
+                            let (first, bound) = (
+                                expression(lang.clone(), first),
+                                expression(lang.clone(), bound),
+                            );
+                            // going down, it steps before the body
+                            let step = step.map(|step| expression(lang, step));
+                            let (update, down) = match (step, down) {
+                                (None, false) => (format!(" {var_name}++"), String::new()),
+                                (None, true) => (String::new(), format!("{var_name}--;")),
+                                (Some(step), false) => {
+                                    (format!(" {var_name} += {step}"), String::new())
+                                }
+                                (Some(step), true) => {
+                                    (String::new(), format!("{var_name} -= {step};"))
+                                }
+                            };
+                            this.buffer.push_str(&format!(
+                                "{indent}for (var {var_name} = {first}; {var_name} {cmp} {bound};{update})\n{indent}{{\n"
+                            ));
+                            if !down.is_empty() {
+                                this.buffer
+                                    .push_str(&format!("{}{down}\n", self::indent(depth + 1)));
+                            }
+                        }
+                        None => {
+                            let iter_exp =
+                                expression(this.clone().target_lang, _for.iter_exp.clone());
+                            this.buffer.push_str(&format!(
+                                "{indent}foreach (var {var_name} in {iter_exp})\n{indent}{{\n"
+                            ));
+                        }
+                    }
+                    depth += 1;
+                    if let Some(index) = &_for.index {
+                        this.buffer.push_str(&format!(
+                            "{}var {index} = {index}_next++;\n",
+                            self::indent(depth)
+                        ));
+                    }
+                    if let Some(label) = label.take() {
+                        labels.push((_for.id, label));
+                    }
+                }
+                Token::While(_while) => {
+                    let condition = expression(this.clone().target_lang, _while.condition.clone());
+                    this.buffer
+                        .push_str(&format!("{indent}while ({condition})\n{indent}{{\n"));
+                    depth += 1;
+                    if let Some(label) = label.take() {
+                        labels.push((_while.id, label));
+                    }
+                }
+                Token::Loop(_loop) => {
+                    this.buffer
+                        .push_str(&format!("{indent}while (true)\n{indent}{{\n"));
+                    depth += 1;
+                    if let Some(label) = label.take() {
+                        labels.push((_loop.id, label));
+                    }
+                }
+                Token::Label(name) => {
+                    label = Some(name.clone());
+                }
+                // the statements of the block come before the loop
+                Token::State(br) => {
+                    if state != Some(br.id) {
+                        log!(Error, "`state` blocks only go at the start of a `loop fn`");
+                    }
+                }
+                Token::End(br) if state == Some(br.id) => {
+                    state = None;
+                    tick(this, x);
+                    depth += 1;
+                }
+                Token::Break(None) => {
+                    this.buffer.push_str(&format!("{indent}break;\n"));
+                }
+                Token::Break(Some(name)) => {
+                    this.buffer
+                        .push_str(&format!("{indent}goto {name}_break;\n"));
+                }
+                Token::Continue(None) => {
+                    this.buffer.push_str(&format!("{indent}continue;\n"));
+                }
+                Token::Continue(Some(name)) => {
+                    this.buffer
+                        .push_str(&format!("{indent}goto {name}_continue;\n"));
+                }
+                Token::Assign(_assign) => {
+                    let name = _assign.var.clone();
+                    let exp = expression(this.clone().target_lang, _assign.exp.clone());
+                    this.buffer.push_str(&format!("{indent}{name} = {exp};\n"));
+                }
+                Token::Return(_return) if type_ == FunctionType::Generator => {
+                    if !_return.is_empty() {
+                        log!(
+                            Error,
+                            f("`return` in the generator `{name}` can't have a value")
+                        );
+                    }
+                    this.buffer.push_str(&format!("{indent}yield break;\n"));
+                }
+                Token::Return(_return) if _return.is_empty() => {
+                    this.buffer.push_str(&format!("{indent}return;\n"));
+                }
+                Token::Return(_return) => {
+                    let exp = expression(this.clone().target_lang, _return.clone());
+                    this.buffer.push_str(&format!("{indent}return {exp};\n"));
+                }
+                Token::Yield(_yield) => {
+                    let exp = expression(this.clone().target_lang, _yield.clone());
+                    this.buffer
+                        .push_str(&format!("{indent}yield return {exp};\n"));
+                }
+                Token::YieldFrom(_yield) => {
+                    let exp = expression(this.clone().target_lang, _yield.clone());
+                    this.buffer.push_str(&format!(
+                        "{indent}foreach (var _v in {exp})\n{indent}{{\n{indent}    yield return _v;\n{indent}}}\n"
+                    ));
+                }
+                Token::Var(_var) => {
+                    let (name, exp) = (&_var.name, _var.exp.clone());
+                    this.buffer.push_str(&format!(
+                        "{indent}var {name} = {};\n",
+                        expression(this.clone().target_lang, exp)
+                    ));
+                }
+                Token::End(br) => {
+                    match labels.last() {
+                        Some((id, _)) if *id == br.id => {
+                            let (_, name) = labels.pop().unwrap();
+                            if continues.contains(&name) {
+                                this.buffer
+                                    .push_str(&format!("{indent}{name}_continue: ;\n"));
+                            }
+                            depth -= 1;
+                            let indent = self::indent(depth);
+                            this.buffer.push_str(&format!("{indent}}}\n"));
+                            if breaks.contains(&name) {
+                                this.buffer.push_str(&format!("{indent}{name}_break: ;\n"));
+                            }
+                        }
+                        _ => {
+                            depth -= 1;
+                            this.buffer
+                                .push_str(&format!("{}}}\n", self::indent(depth)));
+                        }
+                    }
+                    if wrapped.last() == Some(&br.id) {
+                        wrapped.pop();
+                        depth -= 1;
+                        this.buffer
+                            .push_str(&format!("{}}}\n", self::indent(depth)));
+                    }
+                }
+                Token::Empty => {
+                    this.buffer.push('\n');
+                }
+                Token::Generic(s) => {
+                    if !statement.is_empty() && !statement.ends_with('(') {
+                        statement.push(' ');
+                    }
+                    statement.push_str(s);
+                }
+                Token::OpenRoBr(_) => {
+                    statement.push('(');
+                }
+                Token::CloseRoBr(_) => {
+                    statement.push(')');
+                }
+                Token::OpenSqBr(_) => {
+                    statement.push('[');
+                }
+                Token::CloseSqBr(_) => {
+                    statement.push(']');
+                }
+                Token::Comma => {
+                    statement.push(',');
+                }
+                _ => {
+                    log!(Error, f("Unexpected Token {token:?}"));
+                }
+            }
+        }
+        if !statement.is_empty() {
+            this.buffer
+                .push_str(&format!("{}{statement};\n", indent(depth)));
+        }
+    }
+
+    match type_ {
+        FunctionType::Loop => this.buffer.push_str("        }\n"),
+        FunctionType::Function => {
+            // like in C++, falling off the end of `main` returns 0
+            let returns = matches!(
+                x.lines.last().and_then(|l| l.tokens.last()),
+                Some(Token::Return(_))
+            );
+            if name == "main" && return_type != "void" && !returns {
+                this.buffer.push_str("        return 0;\n");
+            }
+        }
+        FunctionType::Generator => {}
+    }
+    this.buffer.push_str("    }\n");
+}
+
+/// A C-like enum becomes a C# enum. Otherwise the enum is an abstract record
+/// with a nested record per variant: `Shape::Circle(1.5)` is
+/// `new Shape.Circle(1.5)`.
+fn enum_(this: &mut Transpiler, x: &Enum) -> String {
+    let name = &x.name;
+    let mut enum_ = match x.c_like() {
+        true => format!("enum {name}\n{{\n"),
+        false => format!("abstract record {name}\n{{\n"),
+    };
+    for v in &x.variants {
+        if x.c_like() {
+            enum_.push_str(&format!("    {},\n", v.name));
+            continue;
+        }
+        let params: Vec<String> = (v.fields.iter().enumerate())
+            .map(|(i, type_)| format!("{} _{i}", type_name(this, type_)))
+            .collect();
+        enum_.push_str(&format!(
+            "    public sealed record {}({}) : {name};\n",
+            v.name,
+            params.join(", ")
+        ));
+    }
+    enum_.push_str("}\n\n");
+    enum_
+}
+
+/// A `match` is a switch expression, C# 9 has patterns for all of it.
+pub fn match_(m: Match) -> String {
+    let value = expression(TranspilerLang::CSharp, bracketed(&m.value));
+    let arms: Vec<String> = (m.arms.into_iter())
+        .map(|(pattern, exp)| {
+            let pattern = match pattern {
+                Pattern::Binding(n) => format!("var {n}"),
+                Pattern::Range(start, end) => format!(">= {start} and <= {end}"),
+                Pattern::Variant(name, variant, values) if !values.is_empty() => {
+                    let values: Vec<String> = (values.into_iter())
+                        .map(|v| v.map_or("_".to_string(), |v| format!("var {v}")))
+                        .collect();
+                    format!("{name}.{variant}({})", values.join(", "))
+                }
+                Pattern::Variant(name, variant, _) => format!("{name}.{variant}"),
+                pattern => pattern.to_string(),
+            };
+            format!("{pattern} => {}", expression(TranspilerLang::CSharp, exp))
+        })
+        .collect();
+    format!("{value} switch {{ {} }}", arms.join(", "))
+}
+
+fn indent(depth: usize) -> String {
+    "    ".repeat(depth)
+}
+
+/// Maps a lang type onto the matching C# type. `impl Name` and `dyn Name`
+/// are the interface of the trait.
+pub fn type_name(this: &mut Transpiler, type_: &Type) -> String {
+    let list = |this: &mut Transpiler, types: &[Type]| {
+        let types: Vec<String> = types.iter().map(|type_| type_name(this, type_)).collect();
+        types.join(", ")
+    };
+    match type_ {
+        Type::Primitive(p) => primitive(*p, &TranspilerLang::CSharp).to_string(),
+        Type::Array(item) => {
+            using(this, "System.Collections.Generic");
+            format!("List<{}>", type_name(this, item))
+        }
+        Type::Optional(item) => format!("{}?", type_name(this, item)),
+        Type::Function(args, result) => {
+            using(this, "System");
+            match (args.is_empty(), result.is_void()) {
+                (true, true) => "Action".to_string(),
+                (false, true) => format!("Action<{}>", list(this, args)),
+                (true, false) => format!("Func<{}>", type_name(this, result)),
+                (false, false) => {
+                    let args = list(this, args);
+                    format!("Func<{args}, {}>", type_name(this, result))
+                }
+            }
+        }
+        Type::Impl(trait_) | Type::Dyn(trait_) => trait_.clone(),
+        Type::Named(name, arguments) if arguments.is_empty() => name.clone(),
+        Type::Named(name, arguments) => format!("{name}<{}>", list(this, arguments)),
+    }
+}
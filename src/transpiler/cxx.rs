@@ -1,10 +1,821 @@
 use super::*;
 
 pub type Cxx = Transpiler;
+
+/// Lazy generators for `gen fn`, emitted once before the first one. The
+/// body only runs up to the next `co_yield` when the next value is needed,
+/// leaving a `for` early destroys the coroutine. Needs `-std=c++20`, on
+/// purpose: there is no lowering for older standards, as filling a vector
+/// can't run generators that never end.
+const GENERATOR: &str = "#include <coroutine>
+#include <exception>
+#include <utility>
+
+template <typename T>
+class generator
+{
+public:
+    struct promise_type
+    {
+        T value{};
+        std::exception_ptr exception;
+        generator get_return_object()
+        {
+            return generator(std::coroutine_handle<promise_type>::from_promise(*this));
+        }
+        std::suspend_always initial_suspend() noexcept { return {}; }
+        std::suspend_always final_suspend() noexcept { return {}; }
+        std::suspend_always yield_value(T v)
+        {
+            value = std::move(v);
+            return {};
+        }
+        void return_void() {}
+        void unhandled_exception() { exception = std::current_exception(); }
+    };
+    struct sentinel {};
+    struct iterator
+    {
+        generator *gen;
+        iterator &operator++()
+        {
+            gen->advance();
+            return *this;
+        }
+        T &operator*() const { return gen->handle.promise().value; }
+        bool operator!=(sentinel) const { return !gen->handle.done(); }
+    };
+    explicit generator(std::coroutine_handle<promise_type> handle) : handle(handle) {}
+    generator(generator &&other) noexcept : handle(std::exchange(other.handle, {})) {}
+    generator(const generator &) = delete;
+    ~generator()
+    {
+        if (handle)
+        {
+            handle.destroy();
+        }
+    }
+    iterator begin()
+    {
+        advance();
+        return iterator{this};
+    }
+    sentinel end() { return {}; }
+
+private:
+    std::coroutine_handle<promise_type> handle;
+    void advance()
+    {
+        handle.resume();
+        if (handle.promise().exception)
+        {
+            std::rethrow_exception(handle.promise().exception);
+        }
+    }
+};
+";
 pub fn new() -> Cxx {
     Transpiler {
         buffer: String::new(),
         imports: vec![],
         target_lang: TranspilerLang::Cxx,
+        globals: HashMap::new(),
+    }
+}
+
+/// The lazy `map`, `filter` and `take` of `|>` pipelines. Ranges are taken
+/// by value, so a temporary generator lives as long as the adapter using it.
+const ADAPTERS: &str = "#include <type_traits>
+
+template <typename R, typename F>
+auto map(R xs, F f) -> generator<std::decay_t<decltype(f(*xs.begin()))>>
+{
+    for (auto &&x : xs)
+    {
+        co_yield f(x);
+    }
+}
+
+template <typename R, typename F>
+auto filter(R xs, F p) -> generator<std::decay_t<decltype(*xs.begin())>>
+{
+    for (auto &&x : xs)
+    {
+        if (p(x))
+        {
+            co_yield x;
+        }
+    }
+}
+
+template <typename R>
+auto take(R xs, long n) -> generator<std::decay_t<decltype(*xs.begin())>>
+{
+    if (n <= 0)
+    {
+        co_return;
+    }
+    for (auto &&x : xs)
+    {
+        co_yield x;
+        if (--n <= 0)
+        {
+            co_return;
+        }
+    }
+}
+";
+
+/// `print` of the interpreter, the values separated by spaces.
+const PRINT: &str = "template <typename... Ts>
+void print(const Ts &...values)
+{
+    const char *separator = \"\";
+    ((std::cout << std::boolalpha << separator << values, separator = \" \"), ...);
+    std::cout << std::endl;
+}
+";
+
+/// Builds the visitor of `std::visit` from one lambda per variant.
+const OVERLOADED: &str = "template <class... Ts>
+struct overloaded : Ts...
+{
+    using Ts::operator()...;
+};
+";
+
+pub fn transpile(this: &mut Transpiler, ast: Vec<Token>) {
+    headers(this, &ast);
+    if visits(&ast) {
+        include(this, "<variant>");
+        this.buffer.push_str(OVERLOADED);
+        this.buffer.push('\n');
+    }
+    if prints(&ast) {
+        include(this, "<iostream>");
+        this.buffer.push_str(PRINT);
+        this.buffer.push('\n');
+    }
+    if !adapters(&ast).is_empty() {
+        this.imports.push("generator".to_string());
+        this.buffer.push_str(GENERATOR);
+        this.buffer.push('\n');
+        this.buffer.push_str(ADAPTERS);
+        this.buffer.push('\n');
+    }
+    let mut ast_iter = ast.iter().peekable();
+    while ast_iter.peek().is_some() {
+        let nt = ast_iter.next().unwrap();
+        match nt {
+            Token::Const(x) => {
+                let (name, exp) = (x.name.clone(), x.exp.clone());
+                this.buffer.push_str(&format!(
+                    "const auto {name} = {};\n",
+                    expression(this.clone().target_lang, exp)
+                ));
+            }
+            Token::Global(x) => {
+                let (name, exp) = (x.name.clone(), x.exp.clone());
+                this.buffer.push_str(&format!(
+                    "auto {name} = {};\n",
+                    expression(this.clone().target_lang, exp)
+                ));
+            }
+            Token::Function(x) => {
+                function(this, x, FunctionType::Function);
+            }
+            Token::LoopFunction(x) => {
+                if x.every.is_some() {
+                    include(this, "<chrono>");
+                    include(this, "<thread>");
+                }
+                function(this, x, FunctionType::Loop);
+            }
+            Token::GeneratorFunction(x) => {
+                if !this.imports.contains(&"generator".to_string()) {
+                    this.imports.push("generator".to_string());
+                    this.buffer.push_str(GENERATOR);
+                    this.buffer.push('\n');
+                }
+                function(this, x, FunctionType::Generator);
+            }
+            Token::Struct(x) => {
+                this.buffer.push_str(&template(&x.generics));
+                this.buffer.push_str(&format!("struct {}\n{{\n", x.name));
+                for field in &x.fields {
+                    this.buffer
+                        .push_str(&format!("{} {};\n", type_name(&field.type_), field.name));
+                }
+                // defined where the `impl` is
+                for function in functions(&ast, &x.name) {
+                    let args = params(&function.arguments[function.method() as usize..]);
+                    let (name, return_type) = (&function.name, type_name(&function.return_type));
+                    this.buffer.push_str(&match function.method() {
+                        true => format!("{return_type} {name}({args}) const;\n"),
+                        false => format!("static {return_type} {name}({args});\n"),
+                    });
+                }
+                this.buffer.push_str("};\n");
+            }
+            Token::Impl(x) => {
+                for function in &x.functions {
+                    method(this, &x.name, function);
+                }
+            }
+            Token::Enum(x) => enum_(this, x),
+            Token::Trait(x) => trait_(this, x, &ast),
+            Token::CImport(ci) => include(this, ci),
+            Token::Import(i) => {
+                log!(CXX, f("Skipping import {i}"));
+            }
+            Token::Comment(_) => {}
+            _ => {
+                log!(Error, f("unexpected Token"));
+                dbg!(&nt);
+            }
+        }
+    }
+}
+
+fn include(this: &mut Transpiler, header: &str) {
+    if !this.imports.iter().any(|i| i == header) {
+        this.buffer.push_str(&format!("#include {header}\n"));
+        this.imports.push(header.to_string());
+    }
+}
+
+/// A C-like enum becomes an `enum class`. Otherwise each variant is a struct
+/// in the namespace `Name_` and the enum a `std::variant` of them, with a
+/// static function constructing each variant: `Shape::Circle(1.5)`.
+fn enum_(this: &mut Transpiler, x: &Enum) {
+    let name = &x.name;
+    if x.c_like() {
+        let variants: Vec<&str> = x.variants.iter().map(|v| v.name.as_str()).collect();
+        this.buffer.push_str(&format!(
+            "enum class {name}\n{{\n{}\n}};\n",
+            variants.join(",\n")
+        ));
+        return;
+    }
+    include(this, "<variant>");
+    this.buffer.push_str(&format!("namespace {name}_\n{{\n"));
+    for v in &x.variants {
+        this.buffer.push_str(&format!("struct {}\n{{\n", v.name));
+        for (i, type_) in v.fields.iter().enumerate() {
+            this.buffer
+                .push_str(&format!("{} _{i};\n", type_name(type_)));
+        }
+        this.buffer.push_str("};\n");
+    }
+    this.buffer.push_str("}\n");
+    let alternatives: Vec<String> = x
+        .variants
+        .iter()
+        .map(|v| format!("{name}_::{}", v.name))
+        .collect();
+    this.buffer.push_str(&format!(
+        "struct {name} : std::variant<{}>\n{{\nusing variant::variant;\n",
+        alternatives.join(", ")
+    ));
+    for v in &x.variants {
+        let params: Vec<String> = (v.fields.iter().enumerate())
+            .map(|(i, type_)| format!("{} _{i}", type_name(type_)))
+            .collect();
+        let values: Vec<String> = (0..v.fields.len()).map(|i| format!("_{i}")).collect();
+        this.buffer.push_str(&format!(
+            "static {name} {}({})\n{{\nreturn {name}_::{}{{{}}};\n}}\n",
+            v.name,
+            params.join(", "),
+            v.name,
+            values.join(", ")
+        ));
+    }
+    this.buffer.push_str("};\n");
+}
+
+/// A trait is a concept, `impl Name` is a parameter constrained by it. When
+/// `dyn Name` is used, the class `DynName` holds a value of any type
+/// satisfying the concept behind an abstract base class and calls its
+/// methods through virtual functions.
+fn trait_(this: &mut Transpiler, x: &Trait, ast: &[Token]) {
+    include(this, "<concepts>");
+    include(this, "<utility>");
+    let name = &x.name;
+    this.buffer.push_str(&format!(
+        "template <typename T>\nconcept {name} = requires(const T &self)\n{{\n"
+    ));
+    for function in &x.functions {
+        let args: Vec<String> = (function.arguments[1..].iter())
+            .map(|arg| format!("std::declval<{}>()", type_name(&arg.type_)))
+            .collect();
+        let call = format!("self.{}({})", function.name, args.join(", "));
+        this.buffer.push_str(&match &function.return_type {
+            type_ if type_.is_void() => format!("{call};\n"),
+            type_ => format!(
+                "{{ {call} }} -> std::convertible_to<{}>;\n",
+                type_name(type_)
+            ),
+        });
+    }
+    this.buffer.push_str("};\n");
+    if !dyn_used(ast, name) {
+        return;
+    }
+    include(this, "<memory>");
+    // the declaration and the call of each method
+    let methods: Vec<(String, String)> = (x.functions.iter())
+        .map(|function| {
+            let (args, names): (Vec<String>, Vec<&str>) = (function.arguments[1..].iter())
+                .map(|arg| {
+                    (
+                        format!("{} {}", type_name(&arg.type_), arg.name),
+                        arg.name.as_str(),
+                    )
+                })
+                .unzip();
+            (
+                format!(
+                    "{} {}({}) const",
+                    type_name(&function.return_type),
+                    function.name,
+                    args.join(", ")
+                ),
+                format!("{}({})", function.name, names.join(", ")),
+            )
+        })
+        .collect();
+    this.buffer.push_str(&format!(
+        "class Dyn{name}\n{{\nstruct Base\n{{\nvirtual ~Base() = default;\n"
+    ));
+    for (declaration, _) in &methods {
+        this.buffer
+            .push_str(&format!("virtual {declaration} = 0;\n"));
+    }
+    this.buffer.push_str(
+        "};\ntemplate <typename T>\nstruct Model : Base\n{\nT self;\nModel(T value) : self(value) {}\n",
+    );
+    for (declaration, call) in &methods {
+        this.buffer.push_str(&format!(
+            "{declaration} override\n{{\nreturn self.{call};\n}}\n"
+        ));
+    }
+    this.buffer.push_str(&format!(
+        "}};\nstd::shared_ptr<const Base> self;\n\npublic:\ntemplate <{name} T>\nDyn{name}(T value) : self(std::make_shared<Model<T>>(value)) {{}}\n"
+    ));
+    for (declaration, call) in &methods {
+        this.buffer
+            .push_str(&format!("{declaration}\n{{\nreturn self->{call};\n}}\n"));
+    }
+    this.buffer.push_str("};\n");
+}
+
+/// `impl Name` is a parameter constrained by the concept of the trait,
+/// `dyn Name` the class calling the methods of any value implementing it.
+/// The headers are included by [`headers`].
+pub fn type_name(type_: &Type) -> String {
+    let list = |types: &[Type]| {
+        let types: Vec<String> = types.iter().map(type_name).collect();
+        types.join(", ")
+    };
+    match type_ {
+        Type::Primitive(p) => primitive(*p, &TranspilerLang::Cxx).to_string(),
+        Type::Array(item) => format!("std::vector<{}>", type_name(item)),
+        Type::Optional(item) => format!("std::optional<{}>", type_name(item)),
+        Type::Function(args, result) => {
+            format!("std::function<{}({})>", type_name(result), list(args))
+        }
+        Type::Impl(trait_) => format!("{trait_} auto"),
+        Type::Dyn(trait_) => format!("Dyn{trait_}"),
+        Type::Named(name, arguments) if arguments.is_empty() => name.clone(),
+        Type::Named(name, arguments) => format!("{name}<{}>", list(arguments)),
+    }
+}
+
+/// Includes the headers of the standard types the program uses.
+fn headers(this: &mut Transpiler, ast: &[Token]) {
+    let mut headers = vec![];
+    for type_ in declared_types(ast) {
+        type_.visit(&mut |type_| {
+            let header = match type_ {
+                Type::Primitive(Primitive::Str) => "<string>",
+                Type::Primitive(p) if p.integer() && *p != Primitive::Int => "<cstdint>",
+                Type::Array(_) => "<vector>",
+                Type::Optional(_) => "<optional>",
+                Type::Function(..) => "<functional>",
+                _ => return,
+            };
+            if !headers.contains(&header) {
+                headers.push(header);
+            }
+        });
+    }
+    for header in headers {
+        include(this, header);
+    }
+}
+
+/// `template <typename T, Trait U>` in front of a generic function or struct,
+/// the concept of the trait constrains a type parameter with a bound.
+fn template(generics: &[Generic]) -> String {
+    if generics.is_empty() {
+        return String::new();
+    }
+    let params: Vec<String> = (generics.iter())
+        .map(|g| format!("{} {}", g.bound.as_deref().unwrap_or("typename"), g.name))
+        .collect();
+    format!("template <{}>\n", params.join(", "))
+}
+
+/// Starts an iteration of a `loop fn`, with `every` it waits first.
+fn tick(this: &mut Transpiler, x: &Function) {
+    this.buffer.push_str("do {\n");
+    if let Some(ms) = x.every {
+        this.buffer.push_str(&format!(
+            "std::this_thread::sleep_for(std::chrono::milliseconds({ms}));\n"
+        ));
+    }
+}
+
+/// Whether the program matches variants holding values, which needs
+/// [`OVERLOADED`].
+fn visits(ast: &[Token]) -> bool {
+    let holds = |exp: &Expression| {
+        (0..exp.len()).any(|i| super::match_(exp, i).is_some_and(|(m, _)| m.holds))
+    };
+    ast.iter().any(|nt| match nt {
+        Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) => x
+            .lines
+            .iter()
+            .flat_map(|line| line.tokens.iter())
+            .any(|t| match t {
+                Token::Var(x) => holds(&x.exp),
+                Token::Assign(x) => holds(&x.exp),
+                Token::Return(exp) | Token::Yield(exp) => holds(exp),
+                _ => false,
+            }),
+        Token::Const(x) | Token::Global(x) => holds(&x.exp),
+        _ => false,
+    })
+}
+
+/// A `match` is a lambda called right away. Integers and C-like enums are
+/// matched by a `switch`, variants holding values by `std::visit` and the
+/// rest by `if`s. The arms cover every value, so the last one is taken
+/// without testing it.
+pub fn match_(m: Match) -> String {
+    let value = expression(TranspilerLang::Cxx, m.value);
+    let last = m.arms.len() - 1;
+    if m.holds {
+        let name = m.arms.iter().find_map(|(pattern, _)| match pattern {
+            Pattern::Variant(name, ..) => Some(name.clone()),
+            _ => None,
+        });
+        let arms: Vec<String> = (m.arms.into_iter())
+            .map(|(pattern, tokens)| {
+                let exp = expression(TranspilerLang::Cxx, tokens.clone());
+                match pattern {
+                    Pattern::Variant(name, variant, values) => {
+                        let bindings: Vec<String> = (values.iter().enumerate())
+                            .filter_map(|(i, v)| Some((i, v.as_ref()?)))
+                            .filter(|(_, v)| uses(&tokens, v))
+                            .map(|(i, v)| format!("auto {v} = _v._{i};\n"))
+                            .collect();
+                        let param = if bindings.is_empty() { "" } else { "_v" };
+                        format!(
+                            "[&](const {name}_::{variant} &{param})\n{{\n{}return {exp};\n}}",
+                            bindings.concat()
+                        )
+                    }
+                    Pattern::Binding(n) if uses(&tokens, &n) => format!(
+                        "[&](const auto &_v)\n{{\n{} {n} = _v;\nreturn {exp};\n}}",
+                        name.as_deref().unwrap_or("auto")
+                    ),
+                    _ => format!("[&](const auto &)\n{{\nreturn {exp};\n}}"),
+                }
+            })
+            .collect();
+        return format!("std::visit(overloaded{{\n{}}}, {value})", arms.join(",\n"));
+    }
+    let switch = m.arms.iter().all(|(pattern, _)| {
+        !matches!(
+            pattern,
+            Pattern::Range(..) | Pattern::Bool(_) | Pattern::Str(_)
+        )
+    });
+    let mut body = String::new();
+    if switch {
+        body.push_str("switch (_m)\n{\n");
+    }
+    for (i, (pattern, tokens)) in m.arms.into_iter().enumerate() {
+        let exp = expression(TranspilerLang::Cxx, tokens.clone());
+        let arm = match &pattern {
+            Pattern::Binding(n) if uses(&tokens, n) => {
+                format!("auto {n} = _m;\nreturn {exp};\n")
+            }
+            _ => format!("return {exp};\n"),
+        };
+        if switch {
+            match (i == last, &pattern) {
+                (true, Pattern::Binding(_)) => body.push_str(&format!("default:\n{{\n{arm}}}\n")),
+                (true, _) => body.push_str(&format!("default:\n{arm}")),
+                (false, _) => body.push_str(&format!("case {pattern}:\n{arm}")),
+            }
+            continue;
+        }
+        let condition = match &pattern {
+            _ if i == last => {
+                body.push_str(&arm);
+                continue;
+            }
+            Pattern::Range(start, end) => format!("_m >= {start} && _m <= {end}"),
+            Pattern::Bool(true) => "_m".to_string(),
+            Pattern::Bool(false) => "!_m".to_string(),
+            Pattern::Str(s) => format!("_m == std::string(\"{s}\")"),
+            _ => format!("_m == {pattern}"),
+        };
+        body.push_str(&format!("if ({condition})\n{{\n{arm}}}\n"));
+    }
+    if switch {
+        body.push_str("}\n");
+    }
+    format!("[&](const auto &_m)\n{{\n{body}}}({value})")
+}
+
+/// Remembers the label of a loop that was just opened. The body gets a block
+/// of its own when it's continued, so the `goto` doesn't jump over variables
+/// still in scope at the label.
+fn open_loop(
+    this: &mut Transpiler,
+    labels: &mut Vec<(i32, String)>,
+    label: Option<String>,
+    id: i32,
+    continues: &[String],
+) {
+    if let Some(label) = label {
+        if continues.contains(&label) {
+            this.buffer.push_str("{\n");
+        }
+        labels.push((id, label));
+    }
+}
+
+fn params(arguments: &[Arg]) -> String {
+    let args: Vec<String> = (arguments.iter())
+        .map(|arg| format!("{} {}", type_name(&arg.type_), arg.name))
+        .collect();
+    args.join(", ")
+}
+
+pub fn function(this: &mut Transpiler, x: &Function, type_: FunctionType) {
+    let (name, mut return_type) = (x.name.clone(), type_name(&x.return_type));
+
+    let args = params(&x.arguments);
+
+    if type_ == FunctionType::Generator {
+        return_type = format!("generator<{return_type}>");
+    }
+
+    this.buffer.push_str(&template(&x.generics));
+    this.buffer
+        .push_str(&format!("{return_type} {name} ({args})\n{{\n"));
+    body(this, x, type_);
+}
+
+/// A function of the `impl` of `owner`, declared in the struct. Methods
+/// can't change the struct, `self` is a reference to it.
+fn method(this: &mut Transpiler, owner: &str, x: &Function) {
+    let (name, return_type) = (&x.name, type_name(&x.return_type));
+    let args = params(&x.arguments[x.method() as usize..]);
+    let qualifier = if x.method() { " const" } else { "" };
+    this.buffer.push_str(&format!(
+        "{return_type} {owner}::{name} ({args}){qualifier}\n{{\n"
+    ));
+    if x.method() && reads(x, "self") {
+        this.buffer.push_str("const auto &self = *this;\n");
+    }
+    let x = Function {
+        name: format!("{owner}::{name}"),
+        ..x.clone()
+    };
+    body(this, &x, FunctionType::Function);
+}
+
+/// The statements of a function up to its closing brace.
+fn body(this: &mut Transpiler, x: &Function, type_: FunctionType) {
+    let name = &x.name;
+    let mut state = None;
+    if type_ == FunctionType::Loop {
+        state = state_block(x);
+        if state.is_none() {
+            tick(this, x);
+        }
+    }
+    // C++ has no labelled jumps, they `goto` labels around the loop
+    let (breaks, continues) = jump_labels(x);
+    let mut labels: Vec<(i32, String)> = vec![];
+    let mut label = None;
+    // the ids of the loops in a block of their own, which holds the counter
+    // of `for i, x in ...`
+    let mut wrapped: Vec<i32> = vec![];
+    for line in x.lines.clone() {
+        let mut token_iter = line.tokens.iter().peekable();
+        let mut semic = false;
+        while token_iter.peek().is_some() {
+            let token = token_iter.next().unwrap();
+            match token {
+                Token::If(_if) => {
+                    let condition = expression(this.clone().target_lang, _if.condition.clone());
+                    this.buffer.push_str(&format!("if ({condition})\n{{\n"));
+                }
+                Token::ElseIf(_if) => {
+                    let condition = expression(this.clone().target_lang, _if.condition.clone());
+                    this.buffer
+                        .push_str(&format!("else if ({condition})\n{{\n"));
+                }
+                Token::For(_for) => {
+                    let var_name = _for.var_name.clone();
+                    if let Some(index) = &_for.index {
+                        this.buffer
+                            .push_str(&format!("{{\nauto {index}_next = 0;\n"));
+                        wrapped.push(_for.id);
+                    }
+                    match &_for.range {
+                        // a counted loop instead of a container
+                        Some(range) => {
+                            let lang = this.clone().target_lang;
+                            let Counted {
+                                first,
+                                cmp,
+                                bound,
+                                step,
+                                down,
+                            } = counted(range);
+                            let (first, bound) = (
+                                expression(lang.clone(), first),
+                                expression(lang.clone(), bound),
+                            );
+                            // going down, it steps before the body
+                            let step = step.map(|step| expression(lang, step));
+                            let (update, down) = match (step, down) {
+                                (None, false) => (format!(" ++{var_name}"), String::new()),
+                                (None, true) => (String::new(), format!("--{var_name};\n")),
+                                (Some(step), false) => {
+                                    (format!(" {var_name} += {step}"), String::new())
+                                }
+                                (Some(step), true) => {
+                                    (String::new(), format!("{var_name} -= {step};\n"))
+                                }
+                            };
+                            this.buffer.push_str(&format!(
+                                "for (auto {var_name} = {first}; {var_name} {cmp} {bound};{update})\n{{\n{down}"
+                            ));
+                        }
+                        None => {
+                            let iter_exp =
+                                expression(this.clone().target_lang, _for.iter_exp.clone());
+                            this.buffer
+                                .push_str(&format!("for(auto {var_name}:{iter_exp})\n{{\n"));
+                        }
+                    }
+                    if let Some(index) = &_for.index {
+                        this.buffer
+                            .push_str(&format!("auto {index} = {index}_next++;\n"));
+                    }
+                    open_loop(this, &mut labels, label.take(), _for.id, &continues);
+                }
+                Token::While(_while) => {
+                    let condition = expression(this.clone().target_lang, _while.condition.clone());
+                    this.buffer.push_str(&format!("while ({condition})\n{{\n"));
+                    open_loop(this, &mut labels, label.take(), _while.id, &continues);
+                }
+                Token::Loop(_loop) => {
+                    this.buffer.push_str("while (true)\n{\n");
+                    open_loop(this, &mut labels, label.take(), _loop.id, &continues);
+                }
+                Token::Label(name) => {
+                    label = Some(name.clone());
+                }
+                // the statements of the block come before the loop
+                Token::State(br) => {
+                    if state != Some(br.id) {
+                        log!(Error, "`state` blocks only go at the start of a `loop fn`");
+                    }
+                }
+                Token::End(br) if state == Some(br.id) => {
+                    state = None;
+                    tick(this, x);
+                }
+                Token::Break(None) => {
+                    this.buffer.push_str("break;\n");
+                }
+                Token::Break(Some(name)) => {
+                    this.buffer.push_str(&format!("goto {name}_break;\n"));
+                }
+                Token::Continue(None) => {
+                    this.buffer.push_str("continue;\n");
+                }
+                Token::Continue(Some(name)) => {
+                    this.buffer.push_str(&format!("goto {name}_continue;\n"));
+                }
+                Token::Assign(_assign) => {
+                    let name = _assign.var.clone();
+                    let exp = expression(this.clone().target_lang, _assign.exp.clone());
+                    this.buffer.push_str(&format!("{name} = {exp};\n"));
+                }
+                Token::Return(_return) if type_ == FunctionType::Generator => {
+                    if !_return.is_empty() {
+                        log!(
+                            Error,
+                            f("`return` in the generator `{name}` can't have a value")
+                        );
+                    }
+                    this.buffer.push_str("co_return;\n");
+                }
+                Token::Return(_return) if _return.is_empty() => {
+                    this.buffer.push_str("return;\n");
+                }
+                Token::Return(_return) => {
+                    let exp = expression(this.clone().target_lang, _return.clone());
+                    this.buffer.push_str(&format!("return {exp};\n"));
+                }
+                Token::Yield(_yield) => {
+                    let exp = expression(this.clone().target_lang, _yield.clone());
+                    this.buffer.push_str(&format!("co_yield {exp};\n"));
+                }
+                Token::YieldFrom(_yield) => {
+                    let exp = expression(this.clone().target_lang, _yield.clone());
+                    this.buffer
+                        .push_str(&format!("for (auto &&_v : {exp})\n{{\nco_yield _v;\n}}\n"));
+                }
+                Token::Var(_var) => {
+                    let (name, exp) = (&_var.name, _var.exp.clone());
+                    this.buffer.push_str(&format!(
+                        "auto {name} = {};\n",
+                        expression(this.clone().target_lang, exp)
+                    ));
+                }
+                Token::End(br) => {
+                    match labels.last() {
+                        Some((id, _)) if *id == br.id => {
+                            let (_, name) = labels.pop().unwrap();
+                            if continues.contains(&name) {
+                                this.buffer.push_str(&format!("}}\n{name}_continue:;\n"));
+                            }
+                            this.buffer.push_str("}\n");
+                            if breaks.contains(&name) {
+                                this.buffer.push_str(&format!("{name}_break:;\n"));
+                            }
+                        }
+                        _ => this.buffer.push_str("}\n"),
+                    }
+                    if wrapped.last() == Some(&br.id) {
+                        wrapped.pop();
+                        this.buffer.push_str("}\n");
+                    }
+                }
+                Token::Else(_) => {
+                    this.buffer.push_str("else{\n");
+                }
+                Token::Empty => {
+                    this.buffer.push_str("\n");
+                }
+                Token::Generic(s) => {
+                    this.buffer.push_str(&format!("{s} "));
+                    semic = true;
+                }
+                Token::OpenRoBr(_) => {
+                    this.buffer.push_str(&format!("("));
+                    semic = true;
+                }
+                Token::CloseRoBr(_) => {
+                    this.buffer.push_str(&format!(")"));
+                    semic = true;
+                }
+                Token::Comma => {
+                    this.buffer.push_str(", ");
+                    semic = true;
+                }
+                Token::OpenSqBr(_) => {
+                    this.buffer.push_str(&format!("["));
+                }
+                Token::CloseSqBr(_) => {
+                    this.buffer.push_str(&format!("]"));
+                }
+                _ => {
+                    log!(Error, "Unexpected Token");
+                    dbg!(&token);
+                }
+            }
+        }
+        if semic {
+            this.buffer.push_str(";\n");
+        }
+    }
+
+    match type_ {
+        FunctionType::Loop => this.buffer.push_str("} while (1);\n}\n"),
+        FunctionType::Function => this.buffer.push_str("}\n"),
+        // makes it a coroutine even without any `yield`
+        FunctionType::Generator => this.buffer.push_str("co_return;\n}\n"),
     }
 }
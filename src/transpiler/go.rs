@@ -0,0 +1,883 @@
+use super::*;
+
+pub type Go = Transpiler;
+pub fn new() -> Go {
+    Transpiler {
+        buffer: String::new(),
+        imports: vec![],
+        target_lang: TranspilerLang::Go,
+        globals: HashMap::new(),
+    }
+}
+
+/// Name of the slice a generator function collects its yielded values in.
+const GEN_BUFFER: &str = "_gen";
+
+pub fn transpile(this: &mut Transpiler, ast: Vec<Token>) {
+    // a generator fills a slice before returning it, so an endless one
+    // never returns and `take` can't stop it
+    let used = adapters(&ast);
+    if !used.is_empty() {
+        let used = used.join("`, `");
+        log!(
+            Error,
+            f("`{used}` of `|>` pipelines need lazy generators, which Go doesn't have")
+        );
+    }
+    let mut consts: Vec<(String, String)> = vec![];
+    let mut globals: Vec<(String, String)> = vec![];
+    let mut ast_iter = ast.iter().peekable();
+    while ast_iter.peek().is_some() {
+        let nt = ast_iter.next().unwrap();
+        match nt {
+            // `const` needs a value known at compile time, anything else is
+            // initialized once like a global
+            Token::Const(x) => match literal_type(&x.exp) {
+                Some(_) => consts.push((x.name.clone(), expression(&x.exp, 1))),
+                None => globals.push((x.name.clone(), expression(&x.exp, 1))),
+            },
+            Token::Global(x) => {
+                globals.push((x.name.clone(), expression(&x.exp, 1)));
+            }
+            Token::Function(x) => {
+                function(this, x, FunctionType::Function);
+            }
+            Token::LoopFunction(x) => {
+                function(this, x, FunctionType::Loop);
+            }
+            Token::GeneratorFunction(x) => {
+                function(this, x, FunctionType::Generator);
+            }
+            Token::Struct(x) => {
+                // the types line up like gofmt does it
+                let width = x.fields.iter().map(|f| f.name.len()).max().unwrap_or(0);
+                this.buffer
+                    .push_str(&format!("\ntype {} struct {{\n", x.name));
+                for field in &x.fields {
+                    this.buffer.push_str(&format!(
+                        "\t{:width$} {}\n",
+                        field.name,
+                        type_name(&field.type_)
+                    ));
+                }
+                this.buffer.push_str("}\n");
+            }
+            Token::Enum(x) => enum_(this, x),
+            // the structs implement it by having its methods
+            Token::Trait(x) => {
+                this.buffer
+                    .push_str(&format!("\ntype {} interface {{\n", x.name));
+                for function in &x.functions {
+                    let mut return_type = type_name(&function.return_type);
+                    if !return_type.is_empty() {
+                        return_type.insert(0, ' ');
+                    }
+                    let args = params(&function.arguments[1..]);
+                    this.buffer
+                        .push_str(&format!("\t{}({args}){return_type}\n", function.name));
+                }
+                this.buffer.push_str("}\n");
+            }
+            Token::Impl(x) => {
+                for function in &x.functions {
+                    method(this, &x.name, function);
+                }
+            }
+            Token::CImport(ci) => {
+                log!(GO, f("Skipping C include {ci}"));
+            }
+            Token::Import(i) => {
+                log!(GO, f("Skipping import {i}"));
+            }
+            Token::Comment(_) => {}
+            _ => {
+                log!(Error, f("unexpected Token {nt:?}"));
+            }
+        }
+    }
+
+    // Go wants the package clause, imports and declarations before the
+    // functions, so the file is assembled once all functions are known.
+    let mut file = String::from("package main\n");
+    // sorted like gofmt does
+    this.imports.sort();
+    match this.imports.len() {
+        0 => {}
+        1 => file.push_str(&format!("\nimport \"{}\"\n", this.imports[0])),
+        _ => {
+            file.push_str("\nimport (\n");
+            for import in &this.imports {
+                file.push_str(&format!("\t\"{import}\"\n"));
+            }
+            file.push_str(")\n");
+        }
+    }
+    file.push_str(&declaration_block("const", &consts));
+    file.push_str(&declaration_block("var", &globals));
+    file.push_str(&this.buffer);
+    this.buffer = file;
+}
+
+/// Builds a `const (...)`/`var (...)` block with the `=` aligned like gofmt does.
+fn declaration_block(keyword: &str, declarations: &[(String, String)]) -> String {
+    if declarations.is_empty() {
+        return String::new();
+    }
+    let width = declarations
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let mut block = format!("\n{keyword} (\n");
+    for (name, exp) in declarations {
+        block.push_str(&format!("\t{name:width$} = {exp}\n"));
+    }
+    block.push_str(")\n");
+    block
+}
+
+/// Starts the loop of a `loop fn`, with `every` each iteration waits first.
+fn tick(this: &mut Transpiler, x: &Function) {
+    this.buffer.push_str("\tfor {\n");
+    if let Some(ms) = x.every {
+        if !this.imports.iter().any(|i| i == "time") {
+            this.imports.push("time".to_string());
+        }
+        this.buffer
+            .push_str(&format!("\t\ttime.Sleep({ms} * time.Millisecond)\n"));
+    }
+}
+
+fn params(arguments: &[Arg]) -> String {
+    let args: Vec<String> = (arguments.iter())
+        .map(|arg| format!("{} {}", arg.name, type_name(&arg.type_)))
+        .collect();
+    args.join(", ")
+}
+
+pub fn function(this: &mut Transpiler, x: &Function, type_: FunctionType) {
+    let name = x.name.clone();
+    // `func main` can't return anything in Go, its return value becomes the exit code
+    let is_main = name == "main";
+
+    let args = params(&x.arguments);
+
+    let mut return_type = type_name(&x.return_type);
+    if type_ == FunctionType::Generator {
+        return_type = format!("[]{return_type}");
+    }
+    if is_main {
+        return_type.clear();
+    }
+    if !return_type.is_empty() {
+        return_type.push(' ');
+    }
+
+    this.buffer
+        .push_str(&format!("\nfunc {name}({args}) {return_type}{{\n"));
+    body(this, x, type_);
+}
+
+/// A function of the `impl` of `owner`. Methods get `self` as their
+/// receiver, the other functions are named like `PointOrigin`.
+fn method(this: &mut Transpiler, owner: &str, x: &Function) {
+    let mut return_type = type_name(&x.return_type);
+    if !return_type.is_empty() {
+        return_type.push(' ');
+    }
+    let name = match x.method() {
+        true => {
+            let args = params(&x.arguments[1..]);
+            format!("(self {owner}) {}({args})", x.name)
+        }
+        false => {
+            let args = params(&x.arguments);
+            format!(
+                "{}({args})",
+                associated(owner, &x.name, &TranspilerLang::Go)
+            )
+        }
+    };
+    this.buffer
+        .push_str(&format!("\nfunc {name} {return_type}{{\n"));
+    let x = Function {
+        name: format!("{owner}.{}", x.name),
+        ..x.clone()
+    };
+    body(this, &x, FunctionType::Function);
+}
+
+/// The statements of a function up to its closing brace.
+fn body(this: &mut Transpiler, x: &Function, type_: FunctionType) {
+    let name = &x.name;
+    let is_main = name == "main";
+    let mut depth = 1;
+    let mut state = None;
+    match type_ {
+        FunctionType::Loop => {
+            state = state_block(x);
+            if state.is_none() {
+                tick(this, x);
+                depth += 1;
+            }
+        }
+        FunctionType::Generator => this.buffer.push_str(&format!(
+            "\tvar {GEN_BUFFER} []{}\n",
+            type_name(&x.return_type)
+        )),
+        _ => {}
+    }
+
+    let (breaks, continues) = jump_labels(x);
+    // set when a `}` is followed by an `else`, which has to stay on its line
+    let mut continued = false;
+    for (ln, line) in x.lines.iter().enumerate() {
+        let mut token_iter = line.tokens.iter().peekable();
+        let mut statement = String::new();
+        while token_iter.peek().is_some() {
+            let token = token_iter.next().unwrap();
+            let indent = if continued {
+                continued = false;
+                String::new()
+            } else {
+                indent(depth)
+            };
+            match token {
+                Token::If(_if) => {
+                    let condition = expression(&_if.condition, 1);
+                    this.buffer
+                        .push_str(&format!("{indent}if {condition} {{\n"));
+                    depth += 1;
+                }
+                Token::ElseIf(_if) => {
+                    let condition = expression(&_if.condition, 1);
+                    this.buffer
+                        .push_str(&format!("{indent}else if {condition} {{\n"));
+                    depth += 1;
+                }
+                Token::Else(_) => {
+                    this.buffer.push_str(&format!("{indent}else {{\n"));
+                    depth += 1;
+                }
+                Token::For(_for) => {
+                    let var_name = _for.var_name.clone();
+                    match (&_for.range, &_for.index) {
+                        // a counted loop instead of a slice
+                        (Some(range), index) => {
+                            let Counted {
+                                first,
+                                cmp,
+                                bound,
+                                step,
+                                down,
+                            } = counted(range);
+                            let (first, bound) = (expression(&first, 1), expression(&bound, 1));
+                            // going down, it steps before the body
+                            let down = down.then(|| match &step {
+                                Some(step) => format!("{var_name} -= {}", expression(step, 1)),
+                                None => format!("{var_name}--"),
+                            });
+                            let header = match (index, step) {
+                                // `i++` alone doesn't use the index
+                                (index, _) if down.is_some() => {
+                                    match index.as_deref().filter(|i| loop_reads(x, ln, i)) {
+                                        Some(index) => format!(
+                                            "{var_name}, {index} := {first}, 0; {var_name} {cmp} {bound}; {index}++"
+                                        ),
+                                        None => format!("{var_name} := {first}; {var_name} {cmp} {bound};"),
+                                    }
+                                }
+                                (None, None) => format!(
+                                    "{var_name} := {first}; {var_name} {cmp} {bound}; {var_name}++"
+                                ),
+                                (None, Some(step)) => format!(
+                                    "{var_name} := {first}; {var_name} {cmp} {bound}; {var_name} += {}",
+                                    expression(&step, 1)
+                                ),
+                                // both change in one assignment, which gofmt
+                                // formats one level deeper
+                                (Some(index), step) => {
+                                    let step = match step {
+                                        Some(step) => expression(&step, 2),
+                                        None => "1".to_string(),
+                                    };
+                                    format!(
+                                        "{var_name}, {index} := {first}, 0; {var_name} {cmp} {bound}; {var_name}, {index} = {var_name}+{step}, {index}+1"
+                                    )
+                                }
+                            };
+                            this.buffer.push_str(&format!("{indent}for {header} {{\n"));
+                            if let Some(down) = down {
+                                this.buffer
+                                    .push_str(&format!("{}{down}\n", self::indent(depth + 1)));
+                            }
+                        }
+                        (None, index) => {
+                            let iter_exp = expression(&_for.iter_exp, 1);
+                            // the variables that aren't used are left out
+                            let used = |name: &str| loop_reads(x, ln, name);
+                            let index = index.as_deref().filter(|index| used(index));
+                            let vars = match (index, used(&var_name)) {
+                                (index, true) => {
+                                    format!("{}, {var_name} := ", index.unwrap_or("_"))
+                                }
+                                (Some(index), false) => format!("{index} := "),
+                                (None, false) => String::new(),
+                            };
+                            this.buffer
+                                .push_str(&format!("{indent}for {vars}range {iter_exp} {{\n"));
+                        }
+                    }
+                    depth += 1;
+                }
+                Token::While(_while) => {
+                    let condition = expression(&_while.condition, 1);
+                    this.buffer
+                        .push_str(&format!("{indent}for {condition} {{\n"));
+                    depth += 1;
+                }
+                Token::Loop(_) => {
+                    this.buffer.push_str(&format!("{indent}for {{\n"));
+                    depth += 1;
+                }
+                // Go rejects labels nobody jumps to, gofmt outdents them
+                Token::Label(name) if breaks.contains(name) || continues.contains(name) => {
+                    this.buffer
+                        .push_str(&format!("{}{name}:\n", self::indent(depth - 1)));
+                }
+                Token::Label(_) => {}
+                // the statements of the block come before the loop
+                Token::State(br) => {
+                    if state != Some(br.id) {
+                        log!(Error, "`state` blocks only go at the start of a `loop fn`");
+                    }
+                }
+                Token::End(br) if state == Some(br.id) => {
+                    state = None;
+                    tick(this, x);
+                    depth += 1;
+                }
+                Token::Break(None) => {
+                    this.buffer.push_str(&format!("{indent}break\n"));
+                }
+                Token::Break(Some(name)) => {
+                    this.buffer.push_str(&format!("{indent}break {name}\n"));
+                }
+                Token::Continue(None) => {
+                    this.buffer.push_str(&format!("{indent}continue\n"));
+                }
+                Token::Continue(Some(name)) => {
+                    this.buffer.push_str(&format!("{indent}continue {name}\n"));
+                }
+                Token::Assign(_assign) => {
+                    let name = _assign.var.clone();
+                    let exp = nested(expression(&_assign.exp, 1), &indent);
+                    this.buffer.push_str(&format!("{indent}{name} = {exp}\n"));
+                }
+                Token::Return(_return) if type_ == FunctionType::Generator => {
+                    if !_return.is_empty() {
+                        log!(
+                            Error,
+                            f("`return` in the generator `{name}` can't have a value")
+                        );
+                    }
+                    this.buffer
+                        .push_str(&format!("{indent}return {GEN_BUFFER}\n"));
+                }
+                Token::Return(_return) if _return.is_empty() => {
+                    this.buffer.push_str(&format!("{indent}return\n"));
+                }
+                Token::Return(_return) => {
+                    let exp = nested(expression(_return, 1), &indent);
+                    if is_main {
+                        if !this.imports.contains(&"os".to_string()) {
+                            this.imports.push("os".to_string());
+                        }
+                        this.buffer.push_str(&format!("{indent}os.Exit({exp})\n"));
+                    } else {
+                        this.buffer.push_str(&format!("{indent}return {exp}\n"));
+                    }
+                }
+                Token::Yield(_yield) => {
+                    // the value is the second argument of `append`, which gofmt
+                    // formats one level deeper
+                    let exp = nested(expression(_yield, 2), &indent);
+                    this.buffer.push_str(&format!(
+                        "{indent}{GEN_BUFFER} = append({GEN_BUFFER}, {exp})\n"
+                    ));
+                }
+                Token::YieldFrom(_yield) => {
+                    let exp = nested(expression(_yield, 2), &indent);
+                    this.buffer.push_str(&format!(
+                        "{indent}{GEN_BUFFER} = append({GEN_BUFFER}, {exp}...)\n"
+                    ));
+                }
+                Token::Var(_var) => {
+                    let (name, exp) = (&_var.name, expression(&_var.exp, 1));
+                    let exp = nested(exp, &indent);
+                    this.buffer.push_str(&format!("{indent}{name} := {exp}\n"));
+                    // Go doesn't build with a local that is never used
+                    if !reads(x, name) {
+                        this.buffer.push_str(&format!("{indent}_ = {name}\n"));
+                    }
+                }
+                Token::End(_) => {
+                    depth -= 1;
+                    match next_token(x, ln, token_iter.peek().copied()) {
+                        Some(Token::Else(_)) | Some(Token::ElseIf(_)) => {
+                            // Go only accepts `else` on the same line as the `}`
+                            this.buffer.push_str(&format!("{}}} ", self::indent(depth)));
+                            continued = true;
+                        }
+                        _ => this
+                            .buffer
+                            .push_str(&format!("{}}}\n", self::indent(depth))),
+                    }
+                }
+                Token::Empty => {
+                    this.buffer.push('\n');
+                }
+                Token::Generic(s) => {
+                    if !statement.is_empty() && !statement.ends_with('(') {
+                        statement.push(' ');
+                    }
+                    statement.push_str(s);
+                }
+                Token::OpenRoBr(_) => {
+                    statement.push('(');
+                }
+                Token::CloseRoBr(_) => {
+                    statement.push(')');
+                }
+                Token::OpenSqBr(_) => {
+                    statement.push('[');
+                }
+                Token::CloseSqBr(_) => {
+                    statement.push(']');
+                }
+                Token::Comma => {
+                    statement.push(',');
+                }
+                _ => {
+                    log!(Error, f("Unexpected Token {token:?}"));
+                }
+            }
+        }
+        if !statement.is_empty() {
+            this.buffer
+                .push_str(&format!("{}{statement}\n", indent(depth)));
+        }
+    }
+
+    match type_ {
+        FunctionType::Loop => this.buffer.push_str("\t}\n}\n"),
+        FunctionType::Function => this.buffer.push_str("}\n"),
+        FunctionType::Generator => this
+            .buffer
+            .push_str(&format!("\treturn {GEN_BUFFER}\n}}\n")),
+    }
+}
+
+/// A C-like enum becomes constants of a named `int` type, `Color::Red` is
+/// `ColorRed`. Otherwise the enum is an interface, which a struct per variant
+/// implements: `Shape::Circle(1.5)` is `ShapeCircle{1.5}`.
+fn enum_(this: &mut Transpiler, x: &Enum) {
+    let name = &x.name;
+    if x.c_like() {
+        this.buffer
+            .push_str(&format!("\ntype {name} int\n\nconst (\n"));
+        for (i, v) in x.variants.iter().enumerate() {
+            match i {
+                0 => this
+                    .buffer
+                    .push_str(&format!("\t{name}{} {name} = iota\n", v.name)),
+                _ => this.buffer.push_str(&format!("\t{name}{}\n", v.name)),
+            }
+        }
+        this.buffer.push_str(")\n");
+        return;
+    }
+    this.buffer
+        .push_str(&format!("\ntype {name} interface {{\n\tis{name}()\n}}\n"));
+    for v in &x.variants {
+        let variant = format!("{name}{}", v.name);
+        if v.fields.is_empty() {
+            this.buffer
+                .push_str(&format!("\ntype {variant} struct{{}}\n"));
+        } else {
+            this.buffer
+                .push_str(&format!("\ntype {variant} struct {{\n"));
+            for (i, type_) in v.fields.iter().enumerate() {
+                this.buffer
+                    .push_str(&format!("\t_{i} {}\n", type_name(type_)));
+            }
+            this.buffer.push_str("}\n");
+        }
+        this.buffer
+            .push_str(&format!("\nfunc ({variant}) is{name}() {{}}\n"));
+    }
+}
+
+/// A `match` is a function literal called right away, with a type switch
+/// over the variants holding values or a `switch` of conditions otherwise.
+/// The arms cover every value, so the last one is the `default`. Go needs
+/// the result type, which is guessed from the literal arms.
+fn switch(m: Match) -> String {
+    let value = nested(expression(&m.value, 1), "\t");
+    let type_ = match m.arms.iter().find_map(|(_, exp)| literal_type(exp)) {
+        Some(type_) => type_name(&type_),
+        None => {
+            log!(
+                GO,
+                "The type of a `match` without literal arms is unknown, using `any`"
+            );
+            "any".to_string()
+        }
+    };
+    let last = m.arms.len() - 1;
+    let mut used = false;
+    let mut cases = String::new();
+    for (i, (pattern, exp)) in m.arms.iter().enumerate() {
+        let mut bindings = String::new();
+        let case = match pattern {
+            // Go rejects variables that aren't used
+            Pattern::Binding(n) if uses(exp, n) => {
+                bindings.push_str(&format!("\t\t{n} := _m\n"));
+                None
+            }
+            Pattern::Variant(name, variant, values) if m.holds => {
+                for (at, v) in values.iter().enumerate() {
+                    if let Some(v) = v.as_ref().filter(|v| uses(exp, v)) {
+                        bindings.push_str(&format!("\t\t{v} := _m._{at}\n"));
+                    }
+                }
+                Some(format!("{name}{variant}"))
+            }
+            Pattern::Wildcard | Pattern::Binding(_) => None,
+            Pattern::Int(n) => Some(format!("_m == {n}")),
+            Pattern::Range(start, end) => Some(format!("_m >= {start} && _m <= {end}")),
+            Pattern::Bool(true) => Some("_m".to_string()),
+            Pattern::Bool(false) => Some("!_m".to_string()),
+            Pattern::Str(s) => Some(format!("_m == \"{s}\"")),
+            Pattern::Variant(name, variant, _) => Some(format!("_m == {name}{variant}")),
+        };
+        used |= !bindings.is_empty() || (case.is_some() && i < last && !m.holds);
+        let case = match case {
+            Some(case) if i < last => format!("case {case}:"),
+            _ => "default:".to_string(),
+        };
+        cases.push_str(&format!(
+            "\t{case}\n{bindings}\t\treturn {}\n",
+            nested(expression(exp, 1), "\t\t")
+        ));
+    }
+    let switch = match (m.holds, used) {
+        (true, true) => format!("switch _m := {value}.(type) {{"),
+        (true, false) => format!("switch {value}.(type) {{"),
+        (false, true) => format!("switch _m := {value}; {{"),
+        (false, false) => "switch {".to_string(),
+    };
+    format!("func() {type_} {{\n\t{switch}\n{cases}\t}}\n}}()")
+}
+
+/// An expression on a line starting with `indent`, the lines of the function
+/// literals of `match`es in it are indented as deep.
+fn nested(exp: String, indent: &str) -> String {
+    exp.replace('\n', &format!("\n{indent}"))
+}
+
+fn indent(depth: usize) -> String {
+    "\t".repeat(depth)
+}
+
+/// Maps a lang type onto the matching Go type, `void` maps to no type at all.
+/// `impl Name` and `dyn Name` are the interface of the trait.
+pub fn type_name(type_: &Type) -> String {
+    let list = |types: &[Type]| {
+        let types: Vec<String> = types.iter().map(type_name).collect();
+        types.join(", ")
+    };
+    match type_ {
+        Type::Primitive(p) => primitive(*p, &TranspilerLang::Go).to_string(),
+        Type::Array(item) => format!("[]{}", type_name(item)),
+        // a pointer, `nil` when there is no value
+        Type::Optional(item) => format!("*{}", type_name(item)),
+        Type::Function(args, result) if result.is_void() => format!("func({})", list(args)),
+        Type::Function(args, result) => format!("func({}) {}", list(args), type_name(result)),
+        Type::Impl(trait_) | Type::Dyn(trait_) => trait_.clone(),
+        Type::Named(name, arguments) if arguments.is_empty() => name.clone(),
+        Type::Named(name, arguments) => format!("{name}[{}]", list(arguments)),
+    }
+}
+
+/// Go operator precedence as used by gofmt to decide where blanks go.
+fn precedence(o: &Operator) -> usize {
+    match o {
+        Operator::Mul
+        | Operator::Div
+        | Operator::BitShiftLeft
+        | Operator::BitShiftRight
+        | Operator::Mod
+        | Operator::BitAnd => 5,
+        Operator::Plus | Operator::Minus | Operator::BitOr => 4,
+        Operator::Equals
+        | Operator::NotEquals
+        | Operator::Less
+        | Operator::Greater
+        | Operator::LessEquals
+        | Operator::GreaterEquals => 3,
+        Operator::And => 2,
+        Operator::Or | Operator::Pipe => 1,
+    }
+}
+
+fn operator(o: &Operator) -> &'static str {
+    match o {
+        Operator::Plus => "+",
+        Operator::Minus => "-",
+        Operator::Mul => "*",
+        Operator::Div => "/",
+        Operator::BitShiftLeft => "<<",
+        Operator::BitShiftRight => ">>",
+        Operator::Mod => "%",
+        Operator::Equals => "==",
+        Operator::NotEquals => "!=",
+        Operator::Less => "<",
+        Operator::Greater => ">",
+        Operator::LessEquals => "<=",
+        Operator::GreaterEquals => ">=",
+        Operator::And => "&&",
+        Operator::Or => "||",
+        Operator::BitAnd => "&",
+        Operator::BitOr => "|",
+        // in strings, or when rewriting the pipes failed
+        Operator::Pipe => "|>",
+    }
+}
+
+/// Renders an expression the way gofmt lays it out.
+///
+/// `depth` follows gofmt: 1 for a whole expression, one more for the right
+/// operand of a binary operator, for a left operand binding differently than
+/// its operator, in an index and in the arguments of a call with several
+/// arguments, and one less in brackets. A binary expression gets blanks
+/// around its operators binding weaker than its cutoff, see [`cutoff`].
+pub fn expression(exp: &[Token], depth: usize) -> String {
+    if let Some(exp) = pipes(exp) {
+        return expression(&exp, depth);
+    }
+    // operands and the binary operators between them, on this bracket level
+    let mut operands: Vec<&[Token]> = vec![];
+    let mut operators: Vec<&Operator> = vec![];
+    let mut start = 0;
+    let mut i = 0;
+    while i < exp.len() {
+        match &exp[i] {
+            Token::Operator(o) if i > start => {
+                operands.push(&exp[start..i]);
+                operators.push(o);
+                start = i + 1;
+            }
+            Token::ExpVal(_) if match_(exp, i).is_some() => i = match_(exp, i).unwrap().1,
+            Token::ExpVal(_) if literal(exp, i).is_some() => i = literal(exp, i).unwrap().1,
+            Token::ExpVal(s) if s == "(" || s == "[" => {
+                i = closing_bracket(exp, i).unwrap_or(exp.len());
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    operands.push(&exp[start..]);
+    let first = Binary::Operand(operands.remove(0));
+    let rest: Vec<_> = operators.into_iter().zip(operands).collect();
+    let mut rest = rest.into_iter().peekable();
+    binary(&Binary::new(first, &mut rest, 0), depth)
+}
+
+/// A binary expression of Go, built from the operands and operators of one
+/// bracket level.
+enum Binary<'a> {
+    Operand(&'a [Token]),
+    Operator(Box<Binary<'a>>, &'a Operator, Box<Binary<'a>>),
+}
+
+/// The operators following the first operand, each with the operand after it.
+type Rest<'a> = std::iter::Peekable<std::vec::IntoIter<(&'a Operator, &'a [Token])>>;
+
+impl<'a> Binary<'a> {
+    /// Adds the operators binding tighter than `min` that follow `left`.
+    fn new(mut left: Binary<'a>, rest: &mut Rest<'a>, min: usize) -> Binary<'a> {
+        while let Some((o, operand)) = rest.next_if(|(o, _)| precedence(o) > min) {
+            let right = Binary::new(Binary::Operand(operand), rest, precedence(o));
+            left = Binary::Operator(Box::new(left), o, Box::new(right));
+        }
+        left
+    }
+    fn precedence(&self) -> Option<usize> {
+        match self {
+            Binary::Operand(_) => None,
+            Binary::Operator(_, o, _) => Some(precedence(o)),
+        }
+    }
+}
+
+/// Prints `x` like gofmt's `binaryExpr`.
+fn binary(x: &Binary, depth: usize) -> String {
+    let (left, o, right) = match x {
+        Binary::Operand(exp) => return operand(exp, depth),
+        Binary::Operator(left, o, right) => (left, o, right),
+    };
+    let prec = precedence(o);
+    let blank = prec < cutoff(x, depth);
+    // gofmt's `diffPrec`
+    let diff = usize::from(left.precedence() != Some(prec));
+    let left = binary(left, depth + diff);
+    let right = binary(right, depth + 1);
+    match blank {
+        true => format!("{left} {} {right}", operator(o)),
+        false => format!("{left}{}{right}", operator(o)),
+    }
+}
+
+/// The precedence operators of `x` need to bind weaker than to get blanks,
+/// gofmt's `cutoff`: at depth 1 all of them unless `*`-level and `+`-level
+/// operators are mixed, then only the `+`-level ones. Deeper down only
+/// those binding weaker than `+`.
+fn cutoff(x: &Binary, depth: usize) -> usize {
+    let (has4, has5, problem) = walk(x);
+    if problem > 0 {
+        return problem + 1;
+    }
+    match (has4 && has5, depth == 1) {
+        (true, true) => 5,
+        (false, true) => 6,
+        (_, false) => 4,
+    }
+}
+
+/// Whether `x` has `+`-level and `*`-level operators, and the precedence
+/// of an operator that would run into a unary one after it, like `- -`.
+fn walk(x: &Binary) -> (bool, bool, usize) {
+    let Binary::Operator(left, o, right) = x else {
+        return (false, false, 0);
+    };
+    let prec = precedence(o);
+    let (mut has4, mut has5) = (prec == 4, prec == 5);
+    let mut problem = 0;
+    for side in [left, right] {
+        let (h4, h5, p) = walk(side);
+        has4 |= h4;
+        has5 |= h5;
+        problem = problem.max(p);
+    }
+    if let Binary::Operand([Token::Operator(unary), ..]) = **right {
+        match format!("{}{}", operator(o), operator(unary)).as_str() {
+            "/*" | "&&" | "&^" => problem = 5,
+            "++" | "--" => problem = problem.max(4),
+            _ => {}
+        }
+    }
+    (has4, has5, problem)
+}
+
+/// Renders an operand of a binary expression, with its unary operators.
+fn operand(exp: &[Token], depth: usize) -> String {
+    let mut current = String::new();
+    let mut exp_iter = exp.iter().enumerate().peekable();
+    while exp_iter.peek().is_some() {
+        let (i, token) = exp_iter.next().unwrap();
+        match token {
+            Token::Operator(o) => current.push_str(operator(o)),
+            Token::ExpVal(_) if match_(exp, i).is_some() => {
+                let (m, end) = match_(exp, i).unwrap();
+                current.push_str(&switch(m));
+                while exp_iter.peek().is_some_and(|(j, _)| *j <= end) {
+                    exp_iter.next();
+                }
+            }
+            Token::ExpVal(_) if literal(exp, i).is_some() => {
+                let (Literal { name, fields }, end) = literal(exp, i).unwrap();
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(field, value)| format!("{field}: {}", expression(value, 1)))
+                    .collect();
+                current.push_str(&format!("{name}{{{}}}", fields.join(", ")));
+                while exp_iter.peek().is_some_and(|(j, _)| *j <= end) {
+                    exp_iter.next();
+                }
+            }
+            // the values of a variant are those of its struct
+            Token::ExpVal(s) if s.contains("::") => {
+                current.push_str(&s.replace("::", ""));
+            }
+            Token::ExpVal(s) if s == "(" || s == "[" => {
+                let after =
+                    i > 0 && matches!(&exp[i - 1], Token::ExpVal(v) if v != "(" && v != "[");
+                let variant = i > 0 && matches!(&exp[i - 1], Token::ExpVal(v) if v.contains("::"));
+                let (s, close) = match (s.as_str(), variant) {
+                    ("(", true) => ("{", "}"),
+                    ("(", false) => ("(", ")"),
+                    _ => ("[", "]"),
+                };
+                let end = closing_bracket(exp, i).unwrap_or(exp.len());
+                // split at the commas of this bracket, not of nested ones
+                let mut level = 0;
+                let args: Vec<&[Token]> = exp[i + 1..end]
+                    .split(|t| match t {
+                        Token::ExpVal(c) if c == "(" || c == "[" || c == "{" => {
+                            level += 1;
+                            false
+                        }
+                        Token::ExpVal(c) if c == ")" || c == "]" || c == "}" => {
+                            level -= 1;
+                            false
+                        }
+                        Token::ExpVal(c) => c == "," && level == 0,
+                        _ => false,
+                    })
+                    .filter(|arg| !arg.is_empty())
+                    .collect();
+                let depth = match (s, after) {
+                    // the elements of a composite literal
+                    ("{", _) => 1,
+                    ("[", true) => depth + 1,
+                    ("(", false) => depth.saturating_sub(1).max(1),
+                    _ if args.len() > 1 => depth + 1,
+                    _ => depth,
+                };
+                let args: Vec<String> = args.iter().map(|arg| expression(arg, depth)).collect();
+                current.push_str(&format!("{s}{}{close}", args.join(", ")));
+                while exp_iter.peek().is_some_and(|(j, _)| *j <= end) {
+                    exp_iter.next();
+                }
+            }
+            Token::ExpVal(s) => {
+                current.push_str(s);
+            }
+            _ => {
+                log!(Error, "Unexpected token in Expression");
+            }
+        }
+    }
+    current
+}
+
+/// Index of the bracket closing the one opened at `open`.
+fn closing_bracket(exp: &[Token], open: usize) -> Option<usize> {
+    let mut level = 0;
+    for (i, token) in exp.iter().enumerate().skip(open) {
+        if let Token::ExpVal(s) = token {
+            match s.as_str() {
+                "(" | "[" => level += 1,
+                ")" | "]" => {
+                    level -= 1;
+                    if level == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,708 @@
+use super::*;
+
+pub type Java = Transpiler;
+pub fn new() -> Java {
+    Transpiler {
+        buffer: String::new(),
+        imports: vec![],
+        target_lang: TranspilerLang::Java,
+        globals: HashMap::new(),
+    }
+}
+
+/// Name of the class all top-level items are wrapped in, the output has to be
+/// saved as `Main.java`.
+pub const CLASS_NAME: &str = "Main";
+/// Name of the list a generator method collects its yielded values in.
+const GEN_BUFFER: &str = "_gen";
+
+/// `print` of the interpreter, the values separated by spaces.
+const PRINT: &str = "
+    static void print(Object... values) {
+        var line = new StringJoiner(\" \");
+        for (var value : values) {
+            line.add(String.valueOf(value));
+        }
+        System.out.println(line);
+    }
+";
+
+pub fn transpile(this: &mut Transpiler, ast: Vec<Token>) {
+    // a generator fills a list before returning it, so an endless one
+    // never returns and `take` can't stop it
+    let used = adapters(&ast);
+    if !used.is_empty() {
+        let used = used.join("`, `");
+        log!(
+            Error,
+            f("`{used}` of `|>` pipelines need lazy generators, which Java doesn't have")
+        );
+    }
+    if prints(&ast) {
+        import(this, "java.util.StringJoiner");
+        this.buffer.push_str(PRINT);
+    }
+    let mut classes: Vec<String> = vec![];
+    let mut fields: Vec<String> = vec![];
+    let mut ast_iter = ast.iter().peekable();
+    while ast_iter.peek().is_some() {
+        let nt = ast_iter.next().unwrap();
+        match nt {
+            Token::Const(x) => {
+                let (name, exp) = (x.name.clone(), x.exp.clone());
+                fields.push(format!(
+                    "    static final {} {name} = {};\n",
+                    field_type(this, &name, &exp),
+                    expression(this.clone().target_lang, exp.clone())
+                ));
+            }
+            Token::Global(x) => {
+                let (name, exp) = (x.name.clone(), x.exp.clone());
+                fields.push(format!(
+                    "    static {} {name} = {};\n",
+                    field_type(this, &name, &exp),
+                    expression(this.clone().target_lang, exp.clone())
+                ));
+            }
+            Token::Struct(x) => {
+                classes.push(class(this, x, &ast));
+            }
+            // the functions are in the class of the struct
+            Token::Impl(_) => {}
+            Token::Enum(x) => {
+                classes.push(enum_(this, x));
+            }
+            Token::Trait(x) => {
+                classes.push(interface(this, x));
+            }
+            Token::Function(x) => {
+                function(this, x, FunctionType::Function);
+            }
+            Token::LoopFunction(x) => {
+                function(this, x, FunctionType::Loop);
+            }
+            Token::GeneratorFunction(x) => {
+                import(this, "java.util.List");
+                import(this, "java.util.ArrayList");
+                function(this, x, FunctionType::Generator);
+            }
+            Token::Import(i) => {
+                import(this, i);
+            }
+            Token::CImport(ci) => {
+                log!(JAVA, f("Skipping C include {ci}"));
+            }
+            Token::Comment(_) => {}
+            _ => {
+                log!(Error, f("unexpected Token {nt:?}"));
+            }
+        }
+    }
+
+    // imports have to come before the class, so the file is assembled once
+    // all methods are known
+    let mut file = String::new();
+    for import in &this.imports {
+        file.push_str(&format!("import {import};\n"));
+    }
+    if !file.is_empty() {
+        file.push('\n');
+    }
+    file.push_str(&format!("public class {CLASS_NAME} {{\n"));
+    file.push_str(&classes.join("\n"));
+    if !classes.is_empty() && !fields.is_empty() {
+        file.push('\n');
+    }
+    file.push_str(&fields.concat());
+    if fields.is_empty() && classes.is_empty() {
+        file.push_str(this.buffer.trim_start_matches('\n'));
+    } else {
+        file.push_str(&this.buffer);
+    }
+    file.push_str("}\n");
+    this.buffer = file;
+}
+
+/// A trait becomes an interface, which the classes of the structs
+/// implementing it implement.
+fn interface(this: &mut Transpiler, x: &Trait) -> String {
+    let mut interface = format!("    interface {} {{\n", x.name);
+    for function in &x.functions {
+        let return_type = type_name(this, &function.return_type);
+        let args = params(this, &function.arguments[1..]);
+        interface.push_str(&format!(
+            "        {return_type} {}({args});\n",
+            function.name
+        ));
+    }
+    interface.push_str("    }\n");
+    interface
+}
+
+/// A struct becomes a nested class with a constructor taking the fields in
+/// order, which struct literals call. The functions of its `impl`s are
+/// methods of the class.
+fn class(this: &mut Transpiler, x: &Struct, ast: &[Token]) -> String {
+    let fields: Vec<(String, &str)> = x
+        .fields
+        .iter()
+        .map(|field| (type_name(this, &field.type_), field.name.as_str()))
+        .collect();
+    let mut class = format!("    static class {}", x.name);
+    let traits = implemented(ast, &x.name);
+    if !traits.is_empty() {
+        class.push_str(&format!(" implements {}", traits.join(", ")));
+    }
+    class.push_str(" {\n");
+    for (type_, name) in &fields {
+        class.push_str(&format!("        {type_} {name};\n"));
+    }
+    let params: Vec<String> = fields
+        .iter()
+        .map(|(type_, name)| format!("{type_} {name}"))
+        .collect();
+    class.push_str(&format!("\n        {}({}) {{\n", x.name, params.join(", ")));
+    for (_, name) in &fields {
+        class.push_str(&format!("            this.{name} = {name};\n"));
+    }
+    class.push_str("        }\n");
+    let impls = ast.iter().filter_map(|nt| match nt {
+        Token::Impl(impl_) if impl_.name == x.name => Some(impl_),
+        _ => None,
+    });
+    for impl_ in impls {
+        for function in &impl_.functions {
+            let buffer = std::mem::take(&mut this.buffer);
+            method(this, &x.name, function, impl_.trait_.is_some());
+            let method = std::mem::replace(&mut this.buffer, buffer);
+            for line in method.lines() {
+                match line.is_empty() {
+                    true => class.push('\n'),
+                    false => class.push_str(&format!("    {line}\n")),
+                }
+            }
+        }
+    }
+    class.push_str("    }\n");
+    class
+}
+
+fn import(this: &mut Transpiler, path: &str) {
+    if !this.imports.contains(&path.to_string()) {
+        this.imports.push(path.to_string());
+    }
+}
+
+/// Fields need an explicit type, the one inferred for the global or const
+/// `name`, or else the one of the literal it is initialized with.
+fn field_type(this: &mut Transpiler, name: &str, exp: &Expression) -> String {
+    let type_ = this.globals.get(name).cloned();
+    match type_.or_else(|| literal_type(exp)) {
+        Some(type_) => type_name(this, &type_),
+        None => {
+            log!(
+                JAVA,
+                f(
+                    "Unable to infer the type of `{}`, using Object",
+                    expression(this.clone().target_lang, exp.clone())
+                )
+            );
+            "Object".to_string()
+        }
+    }
+}
+
+/// Starts the loop of a `loop fn`, with `every` each iteration waits first.
+fn tick(this: &mut Transpiler, x: &Function) {
+    this.buffer.push_str("        while (true) {\n");
+    if let Some(ms) = x.every {
+        this.buffer.push_str(&format!(
+            "            try {{
+                Thread.sleep({ms});
+            }} catch (InterruptedException e) {{
+                throw new RuntimeException(e);
+            }}\n"
+        ));
+    }
+}
+
+fn params(this: &mut Transpiler, arguments: &[Arg]) -> String {
+    let args: Vec<String> = (arguments.iter())
+        .map(|arg| format!("{} {}", type_name(this, &arg.type_), arg.name))
+        .collect();
+    args.join(", ")
+}
+
+pub fn function(this: &mut Transpiler, x: &Function, type_: FunctionType) {
+    let name = x.name.clone();
+    // Java's entry point has a fixed signature, the return value becomes the exit code
+    let is_main = name == "main";
+
+    let args = params(this, &x.arguments);
+
+    let mut return_type = type_name(this, &x.return_type);
+    if type_ == FunctionType::Generator {
+        return_type = format!("List<{}>", boxed(&return_type));
+    }
+
+    if is_main {
+        this.buffer
+            .push_str("\n    public static void main(String[] args) {\n");
+    } else {
+        this.buffer
+            .push_str(&format!("\n    static {return_type} {name}({args}) {{\n"));
+    }
+    body(this, x, type_, &return_type);
+}
+
+/// A function of the `impl` of `owner`, written like a function of
+/// [`CLASS_NAME`] and indented into the class by [`class`]. Methods aren't
+/// static, `self` is `this`. The ones of an interface are `public`.
+fn method(this: &mut Transpiler, owner: &str, x: &Function, public: bool) {
+    let return_type = type_name(this, &x.return_type);
+    let name = &x.name;
+    if x.method() {
+        let args = params(this, &x.arguments[1..]);
+        let public = if public { "public " } else { "" };
+        this.buffer
+            .push_str(&format!("\n    {public}{return_type} {name}({args}) {{\n"));
+        if reads(x, "self") {
+            this.buffer
+                .push_str(&format!("        {owner} self = this;\n"));
+        }
+    } else {
+        let args = params(this, &x.arguments);
+        this.buffer
+            .push_str(&format!("\n    static {return_type} {name}({args}) {{\n"));
+    }
+    let x = Function {
+        name: format!("{owner}.{name}"),
+        ..x.clone()
+    };
+    body(this, &x, FunctionType::Function, &return_type);
+}
+
+/// The statements of a function up to its closing brace.
+fn body(this: &mut Transpiler, x: &Function, type_: FunctionType, return_type: &str) {
+    let name = &x.name;
+    let is_main = name == "main";
+    let mut depth = 2;
+    let mut state = None;
+    match type_ {
+        FunctionType::Loop => {
+            state = state_block(x);
+            if state.is_none() {
+                tick(this, x);
+                depth += 1;
+            }
+        }
+        FunctionType::Generator => this.buffer.push_str(&format!(
+            "        {return_type} {GEN_BUFFER} = new ArrayList<>();\n"
+        )),
+        _ => {}
+    }
+
+    // set when a `}` is followed by an `else`, which is kept on its line
+    let mut continued = false;
+    // the label of the loop that starts next, it goes right before the loop
+    let mut label = None;
+    // the ids of the loops in a block of their own, which holds the counter
+    // of `for i, x in ...`
+    let mut wrapped: Vec<i32> = vec![];
+    for (ln, line) in x.lines.iter().enumerate() {
+        let mut token_iter = line.tokens.iter().peekable();
+        let mut statement = String::new();
+        while token_iter.peek().is_some() {
+            let token = token_iter.next().unwrap();
+            let indent = if continued {
+                continued = false;
+                String::new()
+            } else {
+                indent(depth)
+            };
+            match token {
+                Token::If(_if) => {
+                    let condition = expression(this.clone().target_lang, _if.condition.clone());
+                    this.buffer
+                        .push_str(&format!("{indent}if ({condition}) {{\n"));
+                    depth += 1;
+                }
+                Token::ElseIf(_if) => {
+                    let condition = expression(this.clone().target_lang, _if.condition.clone());
+                    this.buffer
+                        .push_str(&format!("{indent}else if ({condition}) {{\n"));
+                    depth += 1;
+                }
+                Token::Else(_) => {
+                    this.buffer.push_str(&format!("{indent}else {{\n"));
+                    depth += 1;
+                }
+                Token::For(_for) => {
+                    let var_name = _for.var_name.clone();
+                    let mut indent = indent;
+                    if let Some(index) = &_for.index {
+                        this.buffer.push_str(&format!("{indent}{{\n"));
+                        depth += 1;
+                        indent = self::indent(depth);
+                        this.buffer
+                            .push_str(&format!("{indent}var {index}_next = 0;\n"));
+                        wrapped.push(_for.id);
+                    }
+                    if let Some(name) = label.take() {
+                        this.buffer.push_str(&format!("{indent}{name}:\n"));
+                    }
+                    match &_for.range {
+                        // a counted loop instead of a collection
+                        Some(range) => {
+                            let lang = this.clone().target_lang;
+                            let Counted {
+                                first,
+                                cmp,
+                                bound,
+                                step,
+                                down,
+                            } = counted(range);
+                            let (first, bound) = (
+                                expression(lang.clone(), first),
+                                expression(lang.clone(), bound),
+                            );
+                            // going down, it steps before the body
+                            let step = step.map(|step| expression(lang, step));
+                            let (update, down) = match (step, down) {
+                                (None, false) => (format!(" {var_name}++"), String::new()),
+                                (None, true) => (String::new(), format!("{var_name}--;")),
+                                (Some(step), false) => {
+                                    (format!(" {var_name} += {step}"), String::new())
+                                }
+                                (Some(step), true) => {
+                                    (String::new(), format!("{var_name} -= {step};"))
+                                }
+                            };
+                            this.buffer.push_str(&format!(
+                                "{indent}for (var {var_name} = {first}; {var_name} {cmp} {bound};{update}) {{\n"
+                            ));
+                            if !down.is_empty() {
+                                this.buffer
+                                    .push_str(&format!("{}{down}\n", self::indent(depth + 1)));
+                            }
+                        }
+                        None => {
+                            let iter_exp =
+                                expression(this.clone().target_lang, _for.iter_exp.clone());
+                            this.buffer.push_str(&format!(
+                                "{indent}for (var {var_name} : {iter_exp}) {{\n"
+                            ));
+                        }
+                    }
+                    depth += 1;
+                    if let Some(index) = &_for.index {
+                        this.buffer.push_str(&format!(
+                            "{}var {index} = {index}_next++;\n",
+                            self::indent(depth)
+                        ));
+                    }
+                }
+                Token::While(_while) => {
+                    if let Some(name) = label.take() {
+                        this.buffer.push_str(&format!("{indent}{name}:\n"));
+                    }
+                    let condition = expression(this.clone().target_lang, _while.condition.clone());
+                    this.buffer
+                        .push_str(&format!("{indent}while ({condition}) {{\n"));
+                    depth += 1;
+                }
+                Token::Loop(_) => {
+                    if let Some(name) = label.take() {
+                        this.buffer.push_str(&format!("{indent}{name}:\n"));
+                    }
+                    this.buffer.push_str(&format!("{indent}while (true) {{\n"));
+                    depth += 1;
+                }
+                Token::Label(name) => {
+                    label = Some(name.clone());
+                }
+                // the statements of the block come before the loop
+                Token::State(br) => {
+                    if state != Some(br.id) {
+                        log!(Error, "`state` blocks only go at the start of a `loop fn`");
+                    }
+                }
+                Token::End(br) if state == Some(br.id) => {
+                    state = None;
+                    tick(this, x);
+                    depth += 1;
+                }
+                Token::Break(None) => {
+                    this.buffer.push_str(&format!("{indent}break;\n"));
+                }
+                Token::Break(Some(name)) => {
+                    this.buffer.push_str(&format!("{indent}break {name};\n"));
+                }
+                Token::Continue(None) => {
+                    this.buffer.push_str(&format!("{indent}continue;\n"));
+                }
+                Token::Continue(Some(name)) => {
+                    this.buffer.push_str(&format!("{indent}continue {name};\n"));
+                }
+                Token::Assign(_assign) => {
+                    let name = _assign.var.clone();
+                    let exp = expression(this.clone().target_lang, _assign.exp.clone());
+                    this.buffer.push_str(&format!("{indent}{name} = {exp};\n"));
+                }
+                Token::Return(_return) if type_ == FunctionType::Generator => {
+                    if !_return.is_empty() {
+                        log!(
+                            Error,
+                            f("`return` in the generator `{name}` can't have a value")
+                        );
+                    }
+                    this.buffer
+                        .push_str(&format!("{indent}return {GEN_BUFFER};\n"));
+                }
+                Token::Return(_return) if _return.is_empty() => {
+                    this.buffer.push_str(&format!("{indent}return;\n"));
+                }
+                Token::Return(_return) => {
+                    let exp = expression(this.clone().target_lang, _return.clone());
+                    if is_main {
+                        this.buffer
+                            .push_str(&format!("{indent}System.exit({exp});\n"));
+                    } else {
+                        this.buffer.push_str(&format!("{indent}return {exp};\n"));
+                    }
+                }
+                Token::Yield(_yield) => {
+                    let exp = expression(this.clone().target_lang, _yield.clone());
+                    this.buffer
+                        .push_str(&format!("{indent}{GEN_BUFFER}.add({exp});\n"));
+                }
+                Token::YieldFrom(_yield) => {
+                    // works for arrays and the lists of other generators
+                    let exp = expression(this.clone().target_lang, _yield.clone());
+                    this.buffer.push_str(&format!(
+                        "{indent}for (var _v : {exp}) {{\n{indent}    {GEN_BUFFER}.add(_v);\n{indent}}}\n"
+                    ));
+                }
+                Token::Var(_var) => {
+                    let (name, exp) = (&_var.name, _var.exp.clone());
+                    this.buffer.push_str(&format!(
+                        "{indent}var {name} = {};\n",
+                        expression(this.clone().target_lang, exp)
+                    ));
+                }
+                Token::End(br) => {
+                    depth -= 1;
+                    match next_token(x, ln, token_iter.peek().copied()) {
+                        Some(Token::Else(_)) | Some(Token::ElseIf(_)) => {
+                            this.buffer.push_str(&format!("{}}} ", self::indent(depth)));
+                            continued = true;
+                        }
+                        _ => this
+                            .buffer
+                            .push_str(&format!("{}}}\n", self::indent(depth))),
+                    }
+                    if wrapped.last() == Some(&br.id) {
+                        wrapped.pop();
+                        depth -= 1;
+                        this.buffer
+                            .push_str(&format!("{}}}\n", self::indent(depth)));
+                    }
+                }
+                Token::Empty => {
+                    this.buffer.push('\n');
+                }
+                Token::Generic(s) => {
+                    if !statement.is_empty() && !statement.ends_with('(') {
+                        statement.push(' ');
+                    }
+                    statement.push_str(s);
+                }
+                Token::OpenRoBr(_) => {
+                    statement.push('(');
+                }
+                Token::CloseRoBr(_) => {
+                    statement.push(')');
+                }
+                Token::OpenSqBr(_) => {
+                    statement.push('[');
+                }
+                Token::CloseSqBr(_) => {
+                    statement.push(']');
+                }
+                Token::Comma => {
+                    statement.push(',');
+                }
+                _ => {
+                    log!(Error, f("Unexpected Token {token:?}"));
+                }
+            }
+        }
+        if !statement.is_empty() {
+            this.buffer
+                .push_str(&format!("{}{statement};\n", indent(depth)));
+        }
+    }
+
+    match type_ {
+        FunctionType::Loop => this.buffer.push_str("        }\n    }\n"),
+        FunctionType::Function => this.buffer.push_str("    }\n"),
+        FunctionType::Generator => this
+            .buffer
+            .push_str(&format!("        return {GEN_BUFFER};\n    }}\n")),
+    }
+}
+
+/// A C-like enum becomes a Java enum. Otherwise the enum is a sealed
+/// interface with a record per variant: `Shape::Circle(1.5)` is
+/// `new Shape.Circle(1.5)`.
+fn enum_(this: &mut Transpiler, x: &Enum) -> String {
+    let name = &x.name;
+    if x.c_like() {
+        let variants: Vec<&str> = x.variants.iter().map(|v| v.name.as_str()).collect();
+        return format!(
+            "    enum {name} {{\n        {}\n    }}\n",
+            variants.join(", ")
+        );
+    }
+    let mut enum_ = format!("    sealed interface {name} {{\n");
+    for v in &x.variants {
+        let params: Vec<String> = (v.fields.iter().enumerate())
+            .map(|(i, type_)| format!("{} _{i}", type_name(this, type_)))
+            .collect();
+        enum_.push_str(&format!(
+            "        record {}({}) implements {name} {{}}\n",
+            v.name,
+            params.join(", ")
+        ));
+    }
+    enum_.push_str("    }\n");
+    enum_
+}
+
+/// A `match` is a chain of `?:`, the arms cover every value so the last one
+/// is taken without testing it. The values of variants are read through the
+/// record, their names are replaced in the arm.
+pub fn match_(m: Match) -> String {
+    let value = expression(TranspilerLang::Java, bracketed(&m.value));
+    let last = m.arms.len() - 1;
+    let mut chain = String::new();
+    for (i, (pattern, exp)) in m.arms.into_iter().enumerate() {
+        let mut names: Vec<(String, String)> = vec![];
+        let condition = match &pattern {
+            Pattern::Wildcard => None,
+            Pattern::Binding(n) => {
+                names.push((n.clone(), value.clone()));
+                None
+            }
+            Pattern::Int(n) => Some(format!("{value}=={n}")),
+            Pattern::Range(start, end) => Some(format!("{value}>={start}&&{value}<={end}")),
+            Pattern::Bool(true) => Some(value.clone()),
+            Pattern::Bool(false) => Some(format!("!{value}")),
+            Pattern::Str(s) => Some(format!("{value}.equals(\"{s}\")")),
+            Pattern::Variant(name, variant, values) if m.holds => {
+                let record = match i == last {
+                    true => format!("(({name}.{variant}) {value})"),
+                    false => "_m".to_string(),
+                };
+                for (at, v) in values.iter().enumerate() {
+                    if let Some(v) = v {
+                        names.push((v.clone(), format!("{record}._{at}()")));
+                    }
+                }
+                match names.is_empty() {
+                    true => Some(format!("{value} instanceof {name}.{variant}")),
+                    false => Some(format!("{value} instanceof {name}.{variant} _m")),
+                }
+            }
+            Pattern::Variant(name, variant, _) => Some(format!("{value}=={name}.{variant}")),
+        };
+        let exp = exp
+            .into_iter()
+            .map(|t| match &t {
+                Token::ExpVal(s) => {
+                    let renamed = names.iter().find_map(|(n, to)| {
+                        let rest = s.strip_prefix(n.as_str())?;
+                        (rest.is_empty() || rest.starts_with('.')).then(|| format!("{to}{rest}"))
+                    });
+                    renamed.map(Token::ExpVal).unwrap_or(t)
+                }
+                _ => t,
+            })
+            .collect();
+        let exp = expression(TranspilerLang::Java, exp);
+        match condition {
+            Some(condition) if i < last => chain.push_str(&format!("{condition} ? {exp} : ")),
+            _ => chain.push_str(&exp),
+        }
+    }
+    format!("({chain})")
+}
+
+fn indent(depth: usize) -> String {
+    "    ".repeat(depth)
+}
+
+/// Maps a lang type onto the matching Java type. `impl Name` and `dyn Name`
+/// are the interface of the trait.
+pub fn type_name(this: &mut Transpiler, type_: &Type) -> String {
+    let boxed_list = |this: &mut Transpiler, types: &[Type]| {
+        let types: Vec<String> = (types.iter())
+            .map(|type_| boxed(&type_name(this, type_)))
+            .collect();
+        types.join(", ")
+    };
+    match type_ {
+        Type::Primitive(p) => primitive(*p, &TranspilerLang::Java).to_string(),
+        Type::Array(item) => {
+            import(this, "java.util.List");
+            format!("List<{}>", boxed(&type_name(this, item)))
+        }
+        // a reference, `null` when there is no value
+        Type::Optional(item) => boxed(&type_name(this, item)),
+        Type::Function(args, result) => {
+            let interface = match (args.len(), result.is_void()) {
+                (0, true) => "Runnable",
+                (0, false) => "Supplier",
+                (1, true) => "Consumer",
+                (1, false) => "Function",
+                (2, true) => "BiConsumer",
+                (2, false) => "BiFunction",
+                _ => {
+                    log!(
+                        JAVA,
+                        f("Java has no function type taking more than 2 arguments like `{type_}`, using Object")
+                    );
+                    return "Object".to_string();
+                }
+            };
+            if interface == "Runnable" {
+                return interface.to_string();
+            }
+            import(this, &format!("java.util.function.{interface}"));
+            let mut types = args.clone();
+            if !result.is_void() {
+                types.push(*result.clone());
+            }
+            format!("{interface}<{}>", boxed_list(this, &types))
+        }
+        Type::Impl(trait_) | Type::Dyn(trait_) => trait_.clone(),
+        Type::Named(name, arguments) if arguments.is_empty() => name.clone(),
+        Type::Named(name, arguments) => format!("{name}<{}>", boxed_list(this, arguments)),
+    }
+}
+
+/// Generic type arguments can't be primitives in Java.
+fn boxed(type_: &str) -> String {
+    match type_ {
+        "int" => "Integer",
+        "char" => "Character",
+        "boolean" => "Boolean",
+        "byte" => "Byte",
+        "short" => "Short",
+        "long" => "Long",
+        "float" => "Float",
+        "double" => "Double",
+        _ => type_,
+    }
+    .to_string()
+}
@@ -0,0 +1,1166 @@
+use {
+    super::*,
+    crate::lexer::tree::{self, Expr, Iterable, Stmt},
+    std::collections::HashMap,
+};
+
+pub type Llvm = Transpiler;
+pub fn new() -> Llvm {
+    Transpiler {
+        buffer: String::new(),
+        imports: vec![],
+        target_lang: TranspilerLang::Llvm,
+        globals: HashMap::new(),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IrType {
+    I1,
+    I8,
+    I16,
+    I32,
+    I64,
+    Float,
+    Double,
+    /// opaque pointer, LLVM 14 only reads these with `-opaque-pointers`
+    Ptr,
+    Array(usize, Box<IrType>),
+}
+
+impl IrType {
+    fn name(&self) -> String {
+        match self {
+            IrType::I1 => "i1".to_string(),
+            IrType::I8 => "i8".to_string(),
+            IrType::I16 => "i16".to_string(),
+            IrType::I32 => "i32".to_string(),
+            IrType::I64 => "i64".to_string(),
+            IrType::Float => "float".to_string(),
+            IrType::Double => "double".to_string(),
+            IrType::Ptr => "ptr".to_string(),
+            IrType::Array(len, type_) => format!("[{len} x {}]", type_.name()),
+        }
+    }
+    fn is_int(&self) -> bool {
+        matches!(
+            self,
+            IrType::I1 | IrType::I8 | IrType::I16 | IrType::I32 | IrType::I64
+        )
+    }
+    fn is_float(&self) -> bool {
+        matches!(self, IrType::Float | IrType::Double)
+    }
+    fn bits(&self) -> u32 {
+        match self {
+            IrType::I1 => 1,
+            IrType::I8 => 8,
+            IrType::I16 => 16,
+            IrType::I32 | IrType::Float => 32,
+            _ => 64,
+        }
+    }
+}
+
+/// Maps a lang type onto an LLVM type, `void` maps to no value.
+pub fn ir_type(type_: &Type) -> Result<Option<IrType>, String> {
+    let Some(p) = type_.primitive() else {
+        return Err(format!(
+            "type `{type_}` is not supported by the LLVM backend"
+        ));
+    };
+    Ok(match p {
+        Primitive::Void => None,
+        Primitive::Bool => Some(IrType::I1),
+        Primitive::Char | Primitive::I8 | Primitive::U8 => Some(IrType::I8),
+        Primitive::I16 | Primitive::U16 => Some(IrType::I16),
+        Primitive::Int | Primitive::I32 | Primitive::U32 => Some(IrType::I32),
+        Primitive::I64 | Primitive::U64 => Some(IrType::I64),
+        Primitive::F32 => Some(IrType::Float),
+        Primitive::F64 => Some(IrType::Double),
+        Primitive::Str => Some(IrType::Ptr),
+    })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Signature {
+    params: Vec<IrType>,
+    result: Option<IrType>,
+}
+
+impl Signature {
+    fn result_name(&self) -> String {
+        match &self.result {
+            Some(type_) => type_.name(),
+            None => "void".to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Module {
+    functions: HashMap<String, Signature>,
+    /// type and mutability
+    globals: HashMap<String, (IrType, bool)>,
+    /// functions that are called but not defined in lang (e.g. `print`), in
+    /// the order they were first called
+    declares: Vec<(String, Signature)>,
+    /// string literals, stored as `@.str.N` constants
+    strings: Vec<String>,
+}
+
+impl Module {
+    /// The global holding a string literal.
+    fn string(&mut self, s: &str) -> String {
+        let index = match self.strings.iter().position(|known| known == s) {
+            Some(index) => index,
+            None => {
+                self.strings.push(s.to_string());
+                self.strings.len() - 1
+            }
+        };
+        format!("@.str.{index}")
+    }
+}
+
+pub fn transpile(this: &mut Transpiler, ast: Vec<Token>) {
+    let mut module = Module::default();
+    let mut globals = String::new();
+    let mut functions = vec![];
+
+    // signatures first, so functions can call the ones defined after them
+    for nt in ast.iter() {
+        if let Token::Function(x) | Token::LoopFunction(x) = nt {
+            match signature(x) {
+                Ok(sig) => {
+                    module.functions.insert(x.name.clone(), sig);
+                }
+                Err(e) => {
+                    log!(LLVM, f("Skipping function `{}`: {e}", x.name));
+                }
+            }
+        }
+    }
+
+    let mut ast_iter = ast.iter().peekable();
+    while ast_iter.peek().is_some() {
+        let nt = ast_iter.next().unwrap();
+        match nt {
+            Token::Const(x) | Token::Global(x) => {
+                let mutable = matches!(nt, Token::Global(_));
+                match global(&mut module, &x.name, &x.exp, mutable) {
+                    Ok((ir, type_)) => {
+                        module.globals.insert(x.name.clone(), (type_, mutable));
+                        globals.push_str(&ir);
+                    }
+                    Err(e) => {
+                        log!(LLVM, f("Skipping `{}`: {e}", x.name));
+                    }
+                }
+            }
+            Token::Function(x) | Token::LoopFunction(x) => {
+                if !module.functions.contains_key(&x.name) {
+                    continue;
+                }
+                let type_ = match nt {
+                    Token::LoopFunction(_) => FunctionType::Loop,
+                    _ => FunctionType::Function,
+                };
+                match function(&mut module, x, type_) {
+                    Ok(ir) => functions.push(ir),
+                    Err(e) => {
+                        log!(LLVM, f("Skipping function `{}`: {e}", x.name));
+                        module.functions.remove(&x.name);
+                    }
+                }
+            }
+            Token::GeneratorFunction(x) => {
+                log!(
+                    LLVM,
+                    f(
+                        "Skipping `gen fn {}` at line {}: generator functions are not supported by the LLVM backend",
+                        x.name,
+                        x.start_ln
+                    )
+                );
+            }
+            Token::Struct(x) => {
+                log!(
+                    LLVM,
+                    f(
+                        "Skipping `struct {}` at line {}: structs are not supported by the LLVM backend",
+                        x.name,
+                        x.line
+                    )
+                );
+            }
+            Token::Enum(x) => {
+                log!(
+                    LLVM,
+                    f(
+                        "Skipping `enum {}` at line {}: enums are not supported by the LLVM backend",
+                        x.name,
+                        x.line
+                    )
+                );
+            }
+            Token::Impl(x) => {
+                log!(
+                    LLVM,
+                    f(
+                        "Skipping `impl {}` at line {}: methods are not supported by the LLVM backend",
+                        x.name,
+                        x.line
+                    )
+                );
+            }
+            Token::Trait(x) => {
+                log!(
+                    LLVM,
+                    f(
+                        "Skipping `trait {}` at line {}: methods are not supported by the LLVM backend",
+                        x.name,
+                        x.line
+                    )
+                );
+            }
+            Token::Import(i) => {
+                log!(LLVM, f("Skipping import {i}"));
+            }
+            Token::CImport(ci) => {
+                log!(LLVM, f("Skipping C include {ci}"));
+            }
+            Token::Comment(_) => {}
+            _ => {
+                log!(Error, f("unexpected Token {nt:?}"));
+            }
+        }
+    }
+
+    let mut sections = vec![];
+    for (index, s) in module.strings.iter().enumerate() {
+        globals.push_str(&format!(
+            "@.str.{index} = private unnamed_addr constant [{} x i8] c\"{}\\00\"\n",
+            s.len() + 1,
+            escape(s)
+        ));
+    }
+    if !globals.is_empty() {
+        sections.push(globals);
+    }
+    let mut declares = String::new();
+    for (name, sig) in &module.declares {
+        let params: Vec<String> = sig.params.iter().map(|p| p.name()).collect();
+        declares.push_str(&format!(
+            "declare {} @{name}({})\n",
+            sig.result_name(),
+            params.join(", ")
+        ));
+        if !this.imports.contains(name) {
+            this.imports.push(name.clone());
+        }
+    }
+    if !declares.is_empty() {
+        sections.push(declares);
+    }
+    sections.extend(functions);
+    this.buffer.push_str(&sections.join("\n"));
+}
+
+/// Escapes a string for a `c"..."` constant.
+fn escape(s: &str) -> String {
+    let mut escaped = String::new();
+    for b in s.bytes() {
+        if b == b'"' || b == b'\\' || !(0x20..0x7f).contains(&b) {
+            escaped.push_str(&format!("\\{b:02X}"));
+        } else {
+            escaped.push(b as char);
+        }
+    }
+    escaped
+}
+
+fn signature(x: &Function) -> Result<Signature, String> {
+    let mut params = vec![];
+    for arg in &x.arguments {
+        match ir_type(&arg.type_)? {
+            Some(type_) => params.push(type_),
+            None => return Err(format!("argument `{}` can't be `void`", arg.name)),
+        }
+    }
+    Ok(Signature {
+        params,
+        result: ir_type(&x.return_type)?,
+    })
+}
+
+/// Globals have to be initialized with a constant.
+fn global(
+    module: &mut Module,
+    name: &str,
+    exp: &Expression,
+    mutable: bool,
+) -> Result<(String, IrType), String> {
+    let (value, type_) = match tree::expression(exp)? {
+        Expr::Int(i) => (i.to_string(), int_type(i)),
+        Expr::Char(c) => ((c as i64).to_string(), int_type(c as i64)),
+        Expr::Float(f) => (float(f, &IrType::Double), IrType::Double),
+        Expr::Bool(b) => (b.to_string(), IrType::I1),
+        Expr::Str(s) => (module.string(&s), IrType::Ptr),
+        _ => return Err("globals have to be initialized with a literal".to_string()),
+    };
+    let kind = if mutable { "global" } else { "constant" };
+    Ok((
+        format!("@{name} = {kind} {} {value}\n", type_.name()),
+        type_,
+    ))
+}
+
+fn int_type(i: i64) -> IrType {
+    if i32::try_from(i).is_ok() {
+        IrType::I32
+    } else {
+        IrType::I64
+    }
+}
+
+/// LLVM only accepts decimal floats that are exact in binary, the hex form
+/// (always the bits of a double, even for `float`) works for every value.
+fn float(f: f64, type_: &IrType) -> String {
+    let f = match type_ {
+        IrType::Float => f as f32 as f64,
+        _ => f,
+    };
+    format!("0x{:016X}", f.to_bits())
+}
+
+struct Body<'a> {
+    module: &'a mut Module,
+    /// variables in scope with the stack slot they live in
+    locals: Vec<(String, String, IrType)>,
+    result: Option<IrType>,
+    /// stack slots, all allocated in the entry block so `mem2reg` can turn
+    /// them into registers
+    allocas: Vec<String>,
+    code: String,
+    /// counter for unnamed values
+    values: usize,
+    /// counter for unique block labels
+    labels: usize,
+    /// label of the block instructions are added to
+    block: String,
+    /// set after a `br` or `ret`, until the next block starts
+    terminated: bool,
+    /// the loops the code is in, innermost last
+    loops: Vec<Loop>,
+    /// the label of the loop that starts next
+    pending_label: Option<String>,
+}
+
+/// The blocks `continue` and `break` branch to.
+struct Loop {
+    label: Option<String>,
+    next: String,
+    end: String,
+}
+
+fn function(module: &mut Module, x: &Function, type_: FunctionType) -> Result<String, String> {
+    let sig = module.functions[&x.name].clone();
+    if x.every.is_some() {
+        return Err("`every` needs a `sleep`, which the LLVM backend doesn't have".to_string());
+    }
+    let stmts = match type_ {
+        FunctionType::Loop => tree::loop_function(x)?,
+        _ => tree::function(x)?,
+    };
+    let mut body = Body {
+        module,
+        locals: vec![],
+        result: sig.result.clone(),
+        allocas: vec![],
+        code: String::new(),
+        values: 0,
+        labels: 0,
+        block: "entry".to_string(),
+        terminated: false,
+        loops: vec![],
+        pending_label: None,
+    };
+
+    // arguments are copied into stack slots so they can be assigned to
+    for (arg, type_) in x.arguments.iter().zip(sig.params.iter()) {
+        let slot = body.alloca(&arg.name, type_);
+        body.emit(&format!(
+            "store {t} %{}, ptr {slot}",
+            arg.name,
+            t = type_.name()
+        ));
+        body.locals.push((arg.name.clone(), slot, type_.clone()));
+    }
+
+    match stmts.split_last() {
+        // the `state` block, followed by the loop
+        Some((Stmt::Loop(tick), state)) if type_ == FunctionType::Loop => {
+            // not a block, the variables stay in scope for the loop
+            for stmt in state {
+                body.stmt(stmt)?;
+            }
+            body.start("tick");
+            body.enter("tick".to_string(), "tick.end".to_string());
+            body.block(tick)?;
+            body.loops.pop();
+            body.terminate("br label %tick");
+            if tree::breaks(tick) {
+                body.start("tick.end");
+            }
+        }
+        _ => body.block(&stmts)?,
+    }
+    if !body.terminated {
+        match &sig.result {
+            // like in C++, falling off the end of `main` returns 0
+            Some(result) if x.name == "main" => {
+                let zero = body.convert("0".to_string(), &IrType::I32, result)?;
+                body.terminate(&format!("ret {} {zero}", result.name()));
+            }
+            Some(_) => body.terminate("unreachable"),
+            None => body.terminate("ret void"),
+        }
+    }
+
+    let params: Vec<String> = x
+        .arguments
+        .iter()
+        .zip(sig.params.iter())
+        .map(|(arg, type_)| format!("{} %{}", type_.name(), arg.name))
+        .collect();
+    let mut ir = format!(
+        "define {} @{}({}) {{\nentry:\n",
+        sig.result_name(),
+        x.name,
+        params.join(", ")
+    );
+    for alloca in &body.allocas {
+        ir.push_str(&format!("  {alloca}\n"));
+    }
+    ir.push_str(&body.code);
+    ir.push_str("}\n");
+    Ok(ir)
+}
+
+impl Body<'_> {
+    fn local(&self, name: &str) -> Option<(String, IrType)> {
+        self.locals
+            .iter()
+            .rev()
+            .find(|(local, ..)| local == name)
+            .map(|(_, slot, type_)| (slot.clone(), type_.clone()))
+    }
+
+    /// Allocates a stack slot named after `name`.
+    fn alloca(&mut self, name: &str, type_: &IrType) -> String {
+        let mut slot = format!("%{name}.addr");
+        let mut n = 1;
+        while self
+            .allocas
+            .iter()
+            .any(|a| a.starts_with(&format!("{slot} ")))
+        {
+            slot = format!("%{name}.addr{n}");
+            n += 1;
+        }
+        self.allocas
+            .push(format!("{slot} = alloca {}", type_.name()));
+        slot
+    }
+
+    fn label(&mut self) -> usize {
+        self.labels += 1;
+        self.labels - 1
+    }
+
+    /// Adds an instruction to the current block. Code following a `ret` or
+    /// `br` can't be reached but still needs a block to live in.
+    fn emit(&mut self, instr: &str) {
+        if self.terminated {
+            let label = format!("dead{}", self.label());
+            self.start(&label);
+        }
+        self.code.push_str(&format!("  {instr}\n"));
+    }
+
+    /// Adds an instruction producing a value and returns the value.
+    fn value(&mut self, instr: &str) -> String {
+        let value = format!("%{}", self.values);
+        self.values += 1;
+        self.emit(&format!("{value} = {instr}"));
+        value
+    }
+
+    fn terminate(&mut self, instr: &str) {
+        self.emit(instr);
+        self.terminated = true;
+    }
+
+    /// Starts a new block, the current one falls through into it.
+    fn start(&mut self, label: &str) {
+        if !self.terminated {
+            self.code.push_str(&format!("  br label %{label}\n"));
+        }
+        self.code.push_str(&format!("{label}:\n"));
+        self.block = label.to_string();
+        self.terminated = false;
+    }
+
+    /// Runs a block of statements, variables declared in it go out of scope
+    /// at its end.
+    fn block(&mut self, stmts: &[Stmt]) -> Result<(), String> {
+        let scope = self.locals.len();
+        for stmt in stmts {
+            self.stmt(stmt)?;
+        }
+        self.locals.truncate(scope);
+        Ok(())
+    }
+
+    fn store(&mut self, name: &str, value: String, type_: &IrType) -> Result<(), String> {
+        if let Some((slot, local)) = self.local(name) {
+            if let IrType::Array(..) = local {
+                return Err(format!("Can't assign to the array `{name}`"));
+            }
+            let value = self.convert(value, type_, &local)?;
+            self.emit(&format!("store {} {value}, ptr {slot}", local.name()));
+        } else if let Some((global, mutable)) = self.module.globals.get(name).cloned() {
+            if !mutable {
+                return Err(format!("Can't assign to const `{name}`"));
+            }
+            let value = self.convert(value, type_, &global)?;
+            self.emit(&format!("store {} {value}, ptr @{name}", global.name()));
+        } else {
+            return Err(format!("Unknown variable `{name}`"));
+        }
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Result<(), String> {
+        match stmt {
+            Stmt::Let(name, exp) => {
+                let (value, type_) = self.expr(exp)?;
+                match type_ {
+                    // the array already lives in a stack slot of its own
+                    IrType::Array(..) => self.locals.push((name.clone(), value, type_)),
+                    _ => {
+                        let slot = self.alloca(name, &type_);
+                        self.emit(&format!("store {} {value}, ptr {slot}", type_.name()));
+                        self.locals.push((name.clone(), slot, type_));
+                    }
+                }
+            }
+            Stmt::Assign(name, exp) => {
+                let (value, type_) = self.expr(exp)?;
+                self.store(name, value, &type_)?;
+            }
+            Stmt::If(branches, otherwise) => {
+                self.if_(branches, otherwise.as_deref())?;
+            }
+            Stmt::Loop(stmts) => {
+                let label = self.label();
+                self.start(&format!("loop{label}"));
+                self.enter(format!("loop{label}"), format!("loop.end{label}"));
+                self.block(stmts)?;
+                self.loops.pop();
+                self.terminate(&format!("br label %loop{label}"));
+                self.start(&format!("loop.end{label}"));
+            }
+            Stmt::While(condition, stmts) => {
+                let label = self.label();
+                self.start(&format!("while.cond{label}"));
+                let condition = self.condition(condition)?;
+                self.terminate(&format!(
+                    "br i1 {condition}, label %while.body{label}, label %while.end{label}"
+                ));
+                self.start(&format!("while.body{label}"));
+                self.enter(format!("while.cond{label}"), format!("while.end{label}"));
+                self.block(stmts)?;
+                self.loops.pop();
+                self.terminate(&format!("br label %while.cond{label}"));
+                self.start(&format!("while.end{label}"));
+            }
+            Stmt::For(for_) => match &for_.iter {
+                Iterable::Expr(exp) => self.for_(for_, exp)?,
+                Iterable::Range(range) => self.range(for_, range)?,
+            },
+            Stmt::Labeled(label, stmt) => {
+                self.pending_label = Some(label.clone());
+                self.stmt(stmt)?;
+            }
+            Stmt::Break(label) => {
+                let end = self.target(label)?.end.clone();
+                self.terminate(&format!("br label %{end}"));
+            }
+            Stmt::Continue(label) => {
+                let next = self.target(label)?.next.clone();
+                self.terminate(&format!("br label %{next}"));
+            }
+            Stmt::Return(None) => match &self.result {
+                Some(result) => {
+                    return Err(format!("`return` needs a `{}` value", result.name()));
+                }
+                None => self.terminate("ret void"),
+            },
+            Stmt::Return(Some(exp)) => {
+                let (value, type_) = self.expr(exp)?;
+                match self.result.clone() {
+                    Some(result) => {
+                        let value = self.convert(value, &type_, &result)?;
+                        self.terminate(&format!("ret {} {value}", result.name()));
+                    }
+                    None => return Err("Can't return a value from a `void` function".to_string()),
+                }
+            }
+            Stmt::Yield(_) | Stmt::YieldFrom(_) => {
+                return Err("`yield` is only allowed in generator functions".to_string());
+            }
+            Stmt::Expr(Expr::Call(name, args)) if !self.module.functions.contains_key(name) => {
+                self.external(name, args)?;
+            }
+            Stmt::Expr(exp) => {
+                self.expr_or_void(exp)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn if_(
+        &mut self,
+        branches: &[(Expr, Vec<Stmt>)],
+        otherwise: Option<&[Stmt]>,
+    ) -> Result<(), String> {
+        let label = self.label();
+        let (condition, stmts) = &branches[0];
+        let condition = self.condition(condition)?;
+        let has_else = branches.len() > 1 || otherwise.is_some();
+        let next = if has_else {
+            format!("if.else{label}")
+        } else {
+            format!("if.end{label}")
+        };
+        self.terminate(&format!(
+            "br i1 {condition}, label %if.then{label}, label %{next}"
+        ));
+        self.start(&format!("if.then{label}"));
+        self.block(stmts)?;
+        if has_else {
+            if !self.terminated {
+                self.terminate(&format!("br label %if.end{label}"));
+            }
+            self.start(&next);
+            if branches.len() > 1 {
+                // `else if` becomes an `if` in the `else` block
+                self.if_(&branches[1..], otherwise)?;
+            } else if let Some(stmts) = otherwise {
+                self.block(stmts)?;
+            }
+        }
+        self.start(&format!("if.end{label}"));
+        Ok(())
+    }
+
+    /// `for` loops walk over arrays with an index.
+    fn for_(&mut self, x: &tree::For, exp: &Expr) -> Result<(), String> {
+        let var_name = &x.var_name;
+        let (array, type_) = self.expr(exp)?;
+        let (len, element) = match type_ {
+            IrType::Array(len, element) => (len, *element),
+            _ => {
+                return Err(
+                    "`for` loops can only iterate over arrays in the LLVM backend".to_string(),
+                )
+            }
+        };
+        let label = self.label();
+        let index = self.alloca(&format!("{var_name}.index"), &IrType::I64);
+        self.emit(&format!("store i64 0, ptr {index}"));
+        self.start(&format!("for.cond{label}"));
+        let i = self.value(&format!("load i64, ptr {index}"));
+        let more = self.value(&format!("icmp slt i64 {i}, {len}"));
+        self.terminate(&format!(
+            "br i1 {more}, label %for.body{label}, label %for.end{label}"
+        ));
+
+        self.start(&format!("for.body{label}"));
+        let scope = self.locals.len();
+        let item = self.element(&array, len, &element, &i);
+        let slot = self.alloca(var_name, &element);
+        self.emit(&format!("store {} {item}, ptr {slot}", element.name()));
+        self.locals.push((var_name.to_string(), slot, element));
+        if let Some(index) = &x.index {
+            let count = self.value(&format!("trunc i64 {i} to i32"));
+            let slot = self.alloca(index, &IrType::I32);
+            self.emit(&format!("store i32 {count}, ptr {slot}"));
+            self.locals.push((index.clone(), slot, IrType::I32));
+        }
+        self.enter(format!("for.inc{label}"), format!("for.end{label}"));
+        self.block(&x.body)?;
+        self.loops.pop();
+        self.locals.truncate(scope);
+
+        self.start(&format!("for.inc{label}"));
+        let i = self.value(&format!("load i64, ptr {index}"));
+        let next = self.value(&format!("add i64 {i}, 1"));
+        self.emit(&format!("store i64 {next}, ptr {index}"));
+        self.terminate(&format!("br label %for.cond{label}"));
+        self.start(&format!("for.end{label}"));
+        Ok(())
+    }
+
+    /// A counted loop over a range of integers.
+    fn range(&mut self, x: &tree::For, range: &tree::Range) -> Result<(), String> {
+        if let Some(Expr::Int(step)) = &range.step {
+            if *step <= 0 {
+                return Err(format!("The step of a range must be positive, not {step}"));
+            }
+        }
+        let (first, cmp, bound) = range.counted();
+        // the bound and the step are evaluated once, before the loop
+        let (first, first_t) = self.expr(&first)?;
+        let (bound, bound_t) = self.expr(&bound)?;
+        let (step, step_t) = self.expr(&range.step())?;
+        let type_ = promote(&common(&common(&first_t, &bound_t)?, &step_t)?);
+        if !type_.is_int() {
+            return Err("Ranges need integers".to_string());
+        }
+        let first = self.convert(first, &first_t, &type_)?;
+        let bound = self.convert(bound, &bound_t, &type_)?;
+        let step = self.convert(step, &step_t, &type_)?;
+        let t = type_.name();
+
+        let label = self.label();
+        let counter = self.alloca(&format!("{}.counter", x.var_name), &type_);
+        self.emit(&format!("store {t} {first}, ptr {counter}"));
+        let count = match &x.index {
+            Some(index) => {
+                let count = self.alloca(&format!("{index}.count"), &IrType::I32);
+                self.emit(&format!("store i32 0, ptr {count}"));
+                Some(count)
+            }
+            None => None,
+        };
+        self.start(&format!("for.cond{label}"));
+        let i = self.value(&format!("load {t}, ptr {counter}"));
+        let cmp = match cmp {
+            Operator::Less => "slt",
+            Operator::LessEquals => "sle",
+            _ => "sge",
+        };
+        let more = self.value(&format!("icmp {cmp} {t} {i}, {bound}"));
+        self.terminate(&format!(
+            "br i1 {more}, label %for.body{label}, label %for.end{label}"
+        ));
+
+        self.start(&format!("for.body{label}"));
+        let scope = self.locals.len();
+        let slot = self.alloca(&x.var_name, &type_);
+        self.emit(&format!("store {t} {i}, ptr {slot}"));
+        self.locals.push((x.var_name.clone(), slot, type_.clone()));
+        if let (Some(index), Some(count)) = (&x.index, count) {
+            let n = self.value(&format!("load i32, ptr {count}"));
+            let slot = self.alloca(index, &IrType::I32);
+            self.emit(&format!("store i32 {n}, ptr {slot}"));
+            self.locals.push((index.clone(), slot, IrType::I32));
+            let next = self.value(&format!("add i32 {n}, 1"));
+            self.emit(&format!("store i32 {next}, ptr {count}"));
+        }
+        self.enter(format!("for.inc{label}"), format!("for.end{label}"));
+        self.block(&x.body)?;
+        self.loops.pop();
+        self.locals.truncate(scope);
+
+        self.start(&format!("for.inc{label}"));
+        let i = self.value(&format!("load {t}, ptr {counter}"));
+        let op = if range.rev { "sub" } else { "add" };
+        let next = self.value(&format!("{op} {t} {i}, {step}"));
+        self.emit(&format!("store {t} {next}, ptr {counter}"));
+        self.terminate(&format!("br label %for.cond{label}"));
+        self.start(&format!("for.end{label}"));
+        Ok(())
+    }
+
+    /// Starts the body of a loop, `continue` branches to `next` and `break`
+    /// to `end`.
+    fn enter(&mut self, next: String, end: String) {
+        self.loops.push(Loop {
+            label: self.pending_label.take(),
+            next,
+            end,
+        });
+    }
+
+    /// The loop a `break` or `continue` leaves, the innermost one without a
+    /// label.
+    fn target(&self, label: &Option<String>) -> Result<&Loop, String> {
+        match self
+            .loops
+            .iter()
+            .rev()
+            .find(|loop_| label.is_none() || loop_.label == *label)
+        {
+            Some(loop_) => Ok(loop_),
+            None => Err("There is no loop to leave".to_string()),
+        }
+    }
+
+    /// Loads the element at `index` (an `i64`) of an array.
+    fn element(&mut self, array: &str, len: usize, element: &IrType, index: &str) -> String {
+        let t = element.name();
+        let ptr = self.value(&format!(
+            "getelementptr inbounds [{len} x {t}], ptr {array}, i64 0, i64 {index}"
+        ));
+        self.value(&format!("load {t}, ptr {ptr}"))
+    }
+
+    /// Calls a function that isn't defined in lang, it is declared with the
+    /// types of the arguments of its first call. The runtime the output is
+    /// linked with defines it.
+    fn external(&mut self, name: &str, args: &[Expr]) -> Result<(), String> {
+        let mut params = vec![];
+        let mut values = vec![];
+        for arg in args {
+            let (value, type_) = self.expr(arg)?;
+            values.push(format!("{} {value}", type_.name()));
+            params.push(type_);
+        }
+        let sig = Signature {
+            params,
+            result: None,
+        };
+        match self.module.declares.iter().find(|(known, _)| known == name) {
+            Some((_, known)) if *known != sig => {
+                return Err(format!(
+                    "External function `{name}` is called with different argument types"
+                ))
+            }
+            Some(_) => {}
+            None => self.module.declares.push((name.to_string(), sig)),
+        }
+        self.emit(&format!("call void @{name}({})", values.join(", ")));
+        Ok(())
+    }
+
+    fn signature(&self, name: &str) -> Option<Signature> {
+        self.module.functions.get(name).cloned().or_else(|| {
+            self.module
+                .declares
+                .iter()
+                .find(|(known, _)| known == name)
+                .map(|(_, sig)| sig.clone())
+        })
+    }
+
+    /// An `i1` that is false for 0 and true for anything else.
+    fn condition(&mut self, exp: &Expr) -> Result<String, String> {
+        let (value, type_) = self.expr(exp)?;
+        self.convert(value, &type_, &IrType::I1)
+    }
+
+    fn expr(&mut self, exp: &Expr) -> Result<(String, IrType), String> {
+        match self.expr_or_void(exp)? {
+            (value, Some(type_)) => Ok((value, type_)),
+            (_, None) => Err("A function returning `void` can't be used as a value".to_string()),
+        }
+    }
+
+    fn expr_or_void(&mut self, exp: &Expr) -> Result<(String, Option<IrType>), String> {
+        let (value, type_) = match exp {
+            Expr::Int(i) => (i.to_string(), int_type(*i)),
+            Expr::Char(c) => ((*c as i64).to_string(), int_type(*c as i64)),
+            Expr::Float(f) => (float(*f, &IrType::Double), IrType::Double),
+            Expr::Bool(b) => (b.to_string(), IrType::I1),
+            Expr::Str(s) => (self.module.string(s), IrType::Ptr),
+            Expr::Var(name) => {
+                if let Some((slot, type_)) = self.local(name) {
+                    match type_ {
+                        IrType::Array(..) => (slot, type_),
+                        _ => (
+                            self.value(&format!("load {}, ptr {slot}", type_.name())),
+                            type_,
+                        ),
+                    }
+                } else if let Some((type_, _)) = self.module.globals.get(name).cloned() {
+                    (
+                        self.value(&format!("load {}, ptr @{name}", type_.name())),
+                        type_,
+                    )
+                } else {
+                    return Err(format!("Unknown variable `{name}`"));
+                }
+            }
+            Expr::Array(items) => self.array(items)?,
+            Expr::Index(array, index) => {
+                let (array, type_) = self.expr(array)?;
+                let (len, element) = match type_ {
+                    IrType::Array(len, element) => (len, *element),
+                    _ => return Err("Only arrays can be indexed".to_string()),
+                };
+                let (index, index_type) = self.expr(index)?;
+                let index = self.convert(index, &index_type, &IrType::I64)?;
+                (self.element(&array, len, &element, &index), element)
+            }
+            Expr::Call(name, args) => {
+                let sig = match self.signature(name) {
+                    Some(sig) => sig,
+                    None => return Err(format!("Unknown function `{name}`")),
+                };
+                if sig.params.len() != args.len() {
+                    return Err(format!(
+                        "`{name}` takes {} arguments but got {}",
+                        sig.params.len(),
+                        args.len()
+                    ));
+                }
+                let mut values = vec![];
+                for (arg, param) in args.iter().zip(sig.params.iter()) {
+                    let (value, type_) = self.expr(arg)?;
+                    let value = self.convert(value, &type_, param)?;
+                    values.push(format!("{} {value}", param.name()));
+                }
+                let call = format!("call {} @{name}({})", sig.result_name(), values.join(", "));
+                return Ok(match sig.result {
+                    Some(type_) => (self.value(&call), Some(type_)),
+                    None => {
+                        self.emit(&call);
+                        (String::new(), None)
+                    }
+                });
+            }
+            Expr::Neg(exp) => {
+                let (value, type_) = self.expr(exp)?;
+                if type_.is_float() {
+                    (self.value(&format!("fneg {} {value}", type_.name())), type_)
+                } else if type_.is_int() {
+                    let promoted = promote(&type_);
+                    let value = self.convert(value, &type_, &promoted)?;
+                    (
+                        self.value(&format!("sub {} 0, {value}", promoted.name())),
+                        promoted,
+                    )
+                } else {
+                    return Err("Only numbers can be negated".to_string());
+                }
+            }
+            Expr::Binary(lhs, o, rhs) => self.binary(lhs, o, rhs)?,
+            Expr::Struct(..) | Expr::Field(..) => {
+                return Err("structs are not supported by the LLVM backend".to_string())
+            }
+            Expr::Variant(..) => {
+                return Err("enums are not supported by the LLVM backend".to_string())
+            }
+            Expr::Match(..) => {
+                return Err("`match` is not supported by the LLVM backend".to_string())
+            }
+            Expr::Method(..) => {
+                return Err("methods are not supported by the LLVM backend".to_string())
+            }
+        };
+        Ok((value, Some(type_)))
+    }
+
+    /// Array literals are built in a stack slot, the value of an array is a
+    /// pointer to it.
+    fn array(&mut self, items: &[Expr]) -> Result<(String, IrType), String> {
+        let mut values = vec![];
+        let mut element: Option<IrType> = None;
+        for item in items {
+            let (value, type_) = self.expr(item)?;
+            element = Some(match element {
+                Some(known) => common(&known, &type_)?,
+                None => type_.clone(),
+            });
+            values.push((value, type_));
+        }
+        let element = match element {
+            Some(element) => element,
+            None => return Err("Can't infer the type of an empty array".to_string()),
+        };
+        let type_ = IrType::Array(items.len(), Box::new(element.clone()));
+        let slot = self.alloca("array", &type_);
+        for (i, (value, from)) in values.into_iter().enumerate() {
+            let value = self.convert(value, &from, &element)?;
+            let ptr = self.value(&format!(
+                "getelementptr inbounds {}, ptr {slot}, i64 0, i64 {i}",
+                type_.name()
+            ));
+            self.emit(&format!("store {} {value}, ptr {ptr}", element.name()));
+        }
+        Ok((slot, type_))
+    }
+
+    fn binary(&mut self, lhs: &Expr, o: &Operator, rhs: &Expr) -> Result<(String, IrType), String> {
+        if let Operator::And | Operator::Or = o {
+            return self.short_circuit(lhs, o, rhs);
+        }
+        if let Operator::Pipe = o {
+            return Err("`|>` is not supported by the LLVM backend".to_string());
+        }
+        let (l, lt) = self.expr(lhs)?;
+        let (r, rt) = self.expr(rhs)?;
+        let type_ = promote(&common(&lt, &rt)?);
+        if !type_.is_int() && !type_.is_float() {
+            return Err(format!("{o:?} needs number operands"));
+        }
+        let l = self.convert(l, &lt, &type_)?;
+        let r = self.convert(r, &rt, &type_)?;
+        let t = type_.name();
+        let (instr, result) = if type_.is_float() {
+            match o {
+                Operator::Plus => ("fadd", type_),
+                Operator::Minus => ("fsub", type_),
+                Operator::Mul => ("fmul", type_),
+                Operator::Div => ("fdiv", type_),
+                Operator::Mod => ("frem", type_),
+                Operator::Equals => ("fcmp oeq", IrType::I1),
+                Operator::NotEquals => ("fcmp une", IrType::I1),
+                Operator::Less => ("fcmp olt", IrType::I1),
+                Operator::Greater => ("fcmp ogt", IrType::I1),
+                Operator::LessEquals => ("fcmp ole", IrType::I1),
+                Operator::GreaterEquals => ("fcmp oge", IrType::I1),
+                _ => return Err(format!("{o:?} needs integer operands")),
+            }
+        } else {
+            match o {
+                Operator::Plus => ("add", type_),
+                Operator::Minus => ("sub", type_),
+                Operator::Mul => ("mul", type_),
+                Operator::Div => ("sdiv", type_),
+                Operator::Mod => ("srem", type_),
+                Operator::BitAnd => ("and", type_),
+                Operator::BitOr => ("or", type_),
+                Operator::BitShiftLeft => ("shl", type_),
+                Operator::BitShiftRight => ("ashr", type_),
+                Operator::Equals => ("icmp eq", IrType::I1),
+                Operator::NotEquals => ("icmp ne", IrType::I1),
+                Operator::Less => ("icmp slt", IrType::I1),
+                Operator::Greater => ("icmp sgt", IrType::I1),
+                Operator::LessEquals => ("icmp sle", IrType::I1),
+                Operator::GreaterEquals => ("icmp sge", IrType::I1),
+                _ => unreachable!(),
+            }
+        };
+        Ok((self.value(&format!("{instr} {t} {l}, {r}")), result))
+    }
+
+    /// `&&` and `||` only evaluate their right side when needed, like in C.
+    fn short_circuit(
+        &mut self,
+        lhs: &Expr,
+        o: &Operator,
+        rhs: &Expr,
+    ) -> Result<(String, IrType), String> {
+        let label = self.label();
+        let (prefix, skipped) = match o {
+            Operator::And => ("and", "false"),
+            _ => ("or", "true"),
+        };
+        let l = self.condition(lhs)?;
+        let from = self.block.clone();
+        let (then, end) = (
+            format!("{prefix}.rhs{label}"),
+            format!("{prefix}.end{label}"),
+        );
+        match o {
+            Operator::And => self.terminate(&format!("br i1 {l}, label %{then}, label %{end}")),
+            _ => self.terminate(&format!("br i1 {l}, label %{end}, label %{then}")),
+        }
+        self.start(&then);
+        let r = self.condition(rhs)?;
+        let from_rhs = self.block.clone();
+        self.start(&end);
+        Ok((
+            self.value(&format!(
+                "phi i1 [ {skipped}, %{from} ], [ {r}, %{from_rhs} ]"
+            )),
+            IrType::I1,
+        ))
+    }
+
+    /// Converts a value between types the way C converts between numbers.
+    fn convert(&mut self, value: String, from: &IrType, to: &IrType) -> Result<String, String> {
+        if from == to {
+            return Ok(value);
+        }
+        if !(from.is_int() || from.is_float()) || !(to.is_int() || to.is_float()) {
+            return Err(format!(
+                "Can't convert `{}` to `{}`",
+                from.name(),
+                to.name()
+            ));
+        }
+        // literals are converted right away
+        let literal = match value.as_str() {
+            "true" => Some(1),
+            "false" => Some(0),
+            _ => value.parse::<i64>().ok(),
+        };
+        if let Some(literal) = literal {
+            return Ok(match to {
+                IrType::I1 => (literal != 0).to_string(),
+                _ if to.is_float() => float(literal as f64, to),
+                _ => literal.to_string(),
+            });
+        }
+        let (f, t) = (from.name(), to.name());
+        let instr = if from.is_int() && to.is_int() {
+            if *to == IrType::I1 {
+                return Ok(self.value(&format!("icmp ne {f} {value}, 0")));
+            } else if *from == IrType::I1 {
+                "zext"
+            } else if from.bits() < to.bits() {
+                "sext"
+            } else {
+                "trunc"
+            }
+        } else if from.is_int() {
+            if *from == IrType::I1 {
+                "uitofp"
+            } else {
+                "sitofp"
+            }
+        } else if to.is_int() {
+            if *to == IrType::I1 {
+                let zero = float(0.0, from);
+                return Ok(self.value(&format!("fcmp une {f} {value}, {zero}")));
+            }
+            "fptosi"
+        } else if from.bits() < to.bits() {
+            "fpext"
+        } else {
+            "fptrunc"
+        };
+        Ok(self.value(&format!("{instr} {f} {value} to {t}")))
+    }
+}
+
+/// The type two operands are converted to before an operation.
+fn common(a: &IrType, b: &IrType) -> Result<IrType, String> {
+    if !(a.is_int() || a.is_float()) || !(b.is_int() || b.is_float()) {
+        if a == b {
+            return Ok(a.clone());
+        }
+        return Err(format!("Can't combine `{}` and `{}`", a.name(), b.name()));
+    }
+    Ok(match (a, b) {
+        (IrType::Double, _) | (_, IrType::Double) => IrType::Double,
+        (IrType::Float, _) | (_, IrType::Float) => IrType::Float,
+        _ if a.bits() >= b.bits() => a.clone(),
+        _ => b.clone(),
+    })
+}
+
+/// Integers smaller than `int` are calculated with as `int`, like in C.
+fn promote(type_: &IrType) -> IrType {
+    if type_.is_int() && type_.bits() < 32 {
+        IrType::I32
+    } else {
+        type_.clone()
+    }
+}
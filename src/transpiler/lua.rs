@@ -0,0 +1,891 @@
+use super::*;
+
+pub type Lua = Transpiler;
+pub fn new() -> Lua {
+    Transpiler {
+        buffer: String::new(),
+        imports: vec![],
+        target_lang: TranspilerLang::Lua,
+        globals: HashMap::new(),
+    }
+}
+
+/// Whether `type_` is an integer, dividing two of them has to use [`DIV`]
+/// in Lua.
+fn int_type(type_: &Type) -> bool {
+    (type_.primitive()).is_some_and(|p| p.integer() || p == Primitive::Char)
+}
+
+/// Integer division truncating like the other targets, `//` rounds down.
+const DIV: &str = "local function _div(a, b)
+    local q = a // b
+    if q < 0 and q * b ~= a then
+        q = q + 1
+    end
+    return q
+end
+";
+
+/// Lua can't sleep without a C module, `every` waits busily instead.
+const SLEEP: &str = "
+local function sleep(ms)
+    local stop = os.clock() + ms / 1000
+    while os.clock() < stop do end
+end
+";
+
+/// A loop the code is in.
+struct OpenLoop {
+    /// the id of the `End` closing it, `None` for the loop of a `loop fn`
+    id: Option<i32>,
+    label: Option<String>,
+    /// whether a `continue` jumps to its end
+    continued: bool,
+}
+
+impl OpenLoop {
+    fn new(id: Option<i32>, label: Option<String>) -> Self {
+        OpenLoop {
+            id,
+            label,
+            continued: false,
+        }
+    }
+}
+
+/// Lua has no `continue`, it jumps to a label at the end of the innermost
+/// loop body. Labels of nested loops can't share a name.
+fn continue_label(loops: &[OpenLoop]) -> String {
+    match loops.last().and_then(|l| l.label.as_ref()) {
+        Some(label) => format!("{label}_continue"),
+        None => match loops.len() {
+            0 | 1 => "continue".to_string(),
+            n => format!("continue{n}"),
+        },
+    }
+}
+
+/// Starts an iteration of a `loop fn`, with `every` it waits first.
+fn tick(this: &mut Transpiler, x: &Function) {
+    this.buffer.push_str("    while true do\n");
+    if let Some(ms) = x.every {
+        this.buffer.push_str(&format!("        sleep({ms})\n"));
+    }
+}
+
+/// The variants of a C-like enum are their names. Otherwise each variant is
+/// a function returning a table with its `tag` and the values it holds.
+fn enum_(this: &mut Transpiler, x: &Enum) {
+    let name = &x.name;
+    if x.c_like() {
+        let variants: Vec<String> = (x.variants.iter())
+            .map(|v| format!("{0} = \"{0}\"", v.name))
+            .collect();
+        this.buffer.push_str(&format!(
+            "\nlocal {name} <const> = {{ {} }}\n",
+            variants.join(", ")
+        ));
+        return;
+    }
+    this.buffer
+        .push_str(&format!("\nlocal {name} <const> = {{\n"));
+    for v in &x.variants {
+        let params: Vec<String> = (0..v.fields.len()).map(|i| format!("_{i}")).collect();
+        let mut table = vec![format!("tag = \"{}\"", v.name)];
+        table.extend(params.iter().cloned());
+        this.buffer.push_str(&format!(
+            "    {} = function({}) return {{ {} }} end,\n",
+            v.name,
+            params.join(", "),
+            table.join(", ")
+        ));
+    }
+    this.buffer.push_str("}\n");
+}
+
+/// The lazy `map`, `filter` and `take` of `|>` pipelines, they take
+/// generators as well as arrays and return generators.
+const ADAPTERS: &str = "local function _values(xs)
+    if type(xs) ~= \"table\" then
+        return xs
+    end
+    local i = 0
+    return function()
+        i = i + 1
+        return xs[i]
+    end
+end
+
+local function map(xs, f)
+    return coroutine.wrap(function()
+        for x in _values(xs) do
+            coroutine.yield(f(x))
+        end
+    end)
+end
+
+local function filter(xs, p)
+    return coroutine.wrap(function()
+        for x in _values(xs) do
+            if p(x) then
+                coroutine.yield(x)
+            end
+        end
+    end)
+end
+
+local function take(xs, n)
+    return coroutine.wrap(function()
+        if n <= 0 then
+            return
+        end
+        for x in _values(xs) do
+            coroutine.yield(x)
+            n = n - 1
+            if n <= 0 then
+                return
+            end
+        end
+    end)
+end
+";
+
+pub fn transpile(this: &mut Transpiler, ast: Vec<Token>) {
+    // `for x in gen()` can use a generator directly, everything else is
+    // iterated with `ipairs`
+    let mut generators: Vec<String> = ast
+        .iter()
+        .filter_map(|nt| match nt {
+            Token::GeneratorFunction(x) => Some(x.name.clone()),
+            _ => None,
+        })
+        .collect();
+    let adapters = adapters(&ast);
+    if !adapters.is_empty() {
+        this.buffer.push_str(ADAPTERS);
+        generators.extend(adapters.iter().map(|name| name.to_string()));
+    }
+    // calls of functions returning integers count as integer operands
+    let int_functions: Vec<String> = ast
+        .iter()
+        .filter_map(|nt| match nt {
+            Token::Function(x) if int_type(&x.return_type) => Some(x.name.clone()),
+            _ => None,
+        })
+        .collect();
+    let mut main = None;
+    let mut ast_iter = ast.iter().peekable();
+    while ast_iter.peek().is_some() {
+        let nt = ast_iter.next().unwrap();
+        match nt {
+            Token::Const(x) => {
+                let (name, exp) = (x.name.clone(), x.exp.clone());
+                this.buffer.push_str(&format!(
+                    "local {name} <const> = {}\n",
+                    expression(&exp, &[])
+                ));
+            }
+            Token::Global(x) => {
+                let (name, exp) = (x.name.clone(), x.exp.clone());
+                this.buffer
+                    .push_str(&format!("local {name} = {}\n", expression(&exp, &[])));
+            }
+            Token::Function(x) => {
+                function(this, x, FunctionType::Function, &generators, &int_functions);
+            }
+            Token::LoopFunction(x) => {
+                if x.every.is_some() && !this.imports.iter().any(|i| i == "sleep") {
+                    this.imports.push("sleep".to_string());
+                    this.buffer.push_str(SLEEP);
+                }
+                function(this, x, FunctionType::Loop, &generators, &int_functions);
+            }
+            Token::GeneratorFunction(x) => {
+                function(
+                    this,
+                    x,
+                    FunctionType::Generator,
+                    &generators,
+                    &int_functions,
+                );
+            }
+            Token::Import(i) => {
+                log!(LUA, f("Skipping import {i}"));
+            }
+            // tables don't need a declaration, the ones of structs with
+            // methods get them from a metatable
+            Token::Struct(x) => {
+                if functions(&ast, &x.name).next().is_some() {
+                    let name = &x.name;
+                    this.buffer
+                        .push_str(&format!("\nlocal {name} = {{}}\n{name}.__index = {name}\n"));
+                }
+            }
+            Token::Impl(x) => {
+                for function in &x.functions {
+                    method(this, &x.name, function, &int_functions);
+                }
+            }
+            Token::Enum(x) => enum_(this, x),
+            // any table with the methods will do
+            Token::Trait(_) => {}
+            Token::CImport(ci) => {
+                log!(LUA, f("Skipping C include {ci}"));
+            }
+            Token::Comment(_) => {}
+            _ => {
+                log!(Error, f("unexpected Token {nt:?}"));
+            }
+        }
+        if let Token::Function(x) | Token::LoopFunction(x) = nt {
+            if x.name == "main" {
+                main = Some(x.return_type.clone());
+            }
+        }
+    }
+    if this.buffer.contains("_div(") {
+        if !this.buffer.starts_with('\n') {
+            this.buffer.insert(0, '\n');
+        }
+        this.buffer.insert_str(0, DIV);
+    }
+    if this.buffer.starts_with('\n') {
+        this.buffer.remove(0);
+    }
+    // Lua has no entry point, the chunk itself runs `main`
+    match main {
+        Some(Type::VOID) => this.buffer.push_str("\nmain()\n"),
+        Some(_) => this.buffer.push_str("\nos.exit(main())\n"),
+        None => {}
+    }
+}
+
+pub fn function(
+    this: &mut Transpiler,
+    x: &Function,
+    type_: FunctionType,
+    generators: &[String],
+    int_functions: &[String],
+) {
+    let name = &x.name;
+    let args: Vec<&str> = x.arguments.iter().map(|arg| arg.name.as_str()).collect();
+    this.buffer
+        .push_str(&format!("\nlocal function {name}({})\n", args.join(", ")));
+    body(this, x, type_, generators, int_functions);
+}
+
+/// A function of the `impl` of `owner`, stored in its metatable. Methods are
+/// defined with `:`, which makes `self` their first argument.
+fn method(this: &mut Transpiler, owner: &str, x: &Function, int_functions: &[String]) {
+    let (name, separator, arguments) = match x.method() {
+        true => (&x.name, ':', &x.arguments[1..]),
+        false => (&x.name, '.', &x.arguments[..]),
+    };
+    let args: Vec<&str> = arguments.iter().map(|arg| arg.name.as_str()).collect();
+    this.buffer.push_str(&format!(
+        "\nfunction {owner}{separator}{name}({})\n",
+        args.join(", ")
+    ));
+    let x = Function {
+        name: format!("{owner}{separator}{name}"),
+        ..x.clone()
+    };
+    body(this, &x, FunctionType::Function, &[], int_functions);
+}
+
+/// The statements of a function up to its closing `end`.
+fn body(
+    this: &mut Transpiler,
+    x: &Function,
+    type_: FunctionType,
+    generators: &[String],
+    int_functions: &[String],
+) {
+    let name = &x.name;
+    // variables and functions known to hold/return integers
+    let mut ints: Vec<String> = int_functions.to_vec();
+    for arg in &x.arguments {
+        if int_type(&arg.type_) {
+            ints.push(arg.name.clone());
+        }
+    }
+
+    let mut depth = 1;
+    // the loops the code is in, innermost last
+    let mut loops: Vec<OpenLoop> = vec![];
+    // the label of the loop that starts next
+    let mut label = None;
+    // labelled `break`s jump past the end of the loop
+    let (breaks, _) = jump_labels(x);
+    let mut state = None;
+    match type_ {
+        FunctionType::Loop => {
+            loops.push(OpenLoop::new(None, None));
+            state = state_block(x);
+            if state.is_none() {
+                tick(this, x);
+                depth += 1;
+            }
+        }
+        FunctionType::Generator => {
+            // `coroutine.wrap` turns the body into an iterator function, so
+            // `for x in gen()` works natively
+            this.buffer
+                .push_str("    return coroutine.wrap(function()\n");
+            depth += 1;
+        }
+        _ => {}
+    }
+
+    for (ln, line) in x.lines.iter().enumerate() {
+        let mut token_iter = line.tokens.iter().peekable();
+        let mut statement = String::new();
+        while token_iter.peek().is_some() {
+            let token = token_iter.next().unwrap();
+            let indent = indent(depth);
+            match token {
+                Token::If(_if) => {
+                    let condition = expression(&_if.condition, &ints);
+                    this.buffer
+                        .push_str(&format!("{indent}if {condition} then\n"));
+                    depth += 1;
+                }
+                Token::ElseIf(_if) => {
+                    let condition = expression(&_if.condition, &ints);
+                    this.buffer
+                        .push_str(&format!("{indent}elseif {condition} then\n"));
+                    depth += 1;
+                }
+                Token::Else(_) => {
+                    this.buffer.push_str(&format!("{indent}else\n"));
+                    depth += 1;
+                }
+                Token::For(_for) => {
+                    let var_name = _for.var_name.clone();
+                    if let Some(index) = &_for.index {
+                        this.buffer
+                            .push_str(&format!("{indent}local {index}_next = 0\n"));
+                    }
+                    if let Some(range) = &_for.range {
+                        // Lua's numeric `for` includes its last value, and goes
+                        // down from the first value itself
+                        let Counted {
+                            first,
+                            cmp,
+                            bound,
+                            step,
+                            down,
+                        } = counted(range);
+                        let (first, last) = match (down, range.inclusive) {
+                            (true, true) => (range.end.clone(), range.start.clone()),
+                            (true, false) => (minus_one(&range.end), range.start.clone()),
+                            (false, _) if cmp == "<" => (first, minus_one(&bound)),
+                            (false, _) => (first, bound),
+                        };
+                        let (first, last) = (expression(&first, &ints), expression(&last, &ints));
+                        let step = match (step, down) {
+                            (None, false) => String::new(),
+                            (None, true) => ", -1".to_string(),
+                            (Some(step), false) => format!(", {}", expression(&step, &ints)),
+                            (Some(step), true) => {
+                                format!(", -{}", expression(&bracketed(&step), &ints))
+                            }
+                        };
+                        this.buffer.push_str(&format!(
+                            "{indent}for {var_name} = {first}, {last}{step} do\n"
+                        ));
+                        ints.push(var_name);
+                    } else {
+                        let iter_exp = expression(&_for.iter_exp, &ints);
+                        let is_generator = matches!(
+                            _for.iter_exp.first(),
+                            Some(Token::ExpVal(f)) if generators.contains(f)
+                        );
+                        if is_generator {
+                            this.buffer
+                                .push_str(&format!("{indent}for {var_name} in {iter_exp} do\n"));
+                        } else {
+                            this.buffer.push_str(&format!(
+                                "{indent}for _, {var_name} in ipairs({iter_exp}) do\n"
+                            ));
+                        }
+                    }
+                    loops.push(OpenLoop::new(Some(_for.id), label.take()));
+                    depth += 1;
+                    if let Some(index) = &_for.index {
+                        let indent = self::indent(depth);
+                        this.buffer.push_str(&format!(
+                            "{indent}local {index} = {index}_next\n{indent}{index}_next = {index}_next + 1\n"
+                        ));
+                        ints.push(index.clone());
+                    }
+                }
+                Token::While(_while) => {
+                    let condition = expression(&_while.condition, &ints);
+                    this.buffer
+                        .push_str(&format!("{indent}while {condition} do\n"));
+                    loops.push(OpenLoop::new(Some(_while.id), label.take()));
+                    depth += 1;
+                }
+                Token::Loop(_loop) => {
+                    this.buffer.push_str(&format!("{indent}while true do\n"));
+                    loops.push(OpenLoop::new(Some(_loop.id), label.take()));
+                    depth += 1;
+                }
+                Token::Label(name) => {
+                    label = Some(name.clone());
+                }
+                // the statements of the block come before the loop
+                Token::State(br) => {
+                    if state != Some(br.id) {
+                        log!(Error, "`state` blocks only go at the start of a `loop fn`");
+                    }
+                }
+                Token::End(br) if state == Some(br.id) => {
+                    state = None;
+                    tick(this, x);
+                    depth += 1;
+                }
+                Token::Break(None) => {
+                    this.buffer.push_str(&format!("{indent}break\n"));
+                }
+                Token::Break(Some(name)) => {
+                    this.buffer
+                        .push_str(&format!("{indent}goto {name}_break\n"));
+                }
+                Token::Continue(None) => {
+                    let label = continue_label(&loops);
+                    match loops.last_mut() {
+                        Some(open) => open.continued = true,
+                        None => {
+                            log!(Error, "`continue` outside of a loop");
+                        }
+                    }
+                    this.buffer.push_str(&format!("{indent}goto {label}\n"));
+                }
+                Token::Continue(Some(name)) => {
+                    match loops
+                        .iter_mut()
+                        .find(|open| open.label.as_ref() == Some(name))
+                    {
+                        Some(open) => open.continued = true,
+                        None => {
+                            log!(
+                                Error,
+                                f("`continue '{name}` isn't in a loop named `'{name}`")
+                            );
+                        }
+                    }
+                    this.buffer
+                        .push_str(&format!("{indent}goto {name}_continue\n"));
+                }
+                Token::Assign(_assign) => {
+                    let name = _assign.var.clone();
+                    let exp = expression(&_assign.exp, &ints);
+                    this.buffer.push_str(&format!("{indent}{name} = {exp}\n"));
+                }
+                Token::Return(_return) if type_ == FunctionType::Generator => {
+                    if !_return.is_empty() {
+                        log!(
+                            Error,
+                            f("`return` in the generator `{name}` can't have a value")
+                        );
+                    }
+                    // ends the coroutine, which ends the `for` over it
+                    this.buffer.push_str(&format!("{indent}return\n"));
+                }
+                Token::Return(_return) if _return.is_empty() => {
+                    this.buffer.push_str(&format!("{indent}return\n"));
+                }
+                Token::Return(_return) => {
+                    let exp = expression(_return, &ints);
+                    this.buffer.push_str(&format!("{indent}return {exp}\n"));
+                }
+                Token::Yield(_yield) => {
+                    let exp = expression(_yield, &ints);
+                    this.buffer
+                        .push_str(&format!("{indent}coroutine.yield({exp})\n"));
+                }
+                Token::YieldFrom(_yield) => {
+                    let exp = expression(_yield, &ints);
+                    let is_generator = matches!(
+                        _yield.first(),
+                        Some(Token::ExpVal(f)) if generators.contains(f)
+                    );
+                    let iter = if is_generator {
+                        format!("_v in {exp}")
+                    } else {
+                        format!("_, _v in ipairs({exp})")
+                    };
+                    this.buffer.push_str(&format!(
+                        "{indent}for {iter} do\n{indent}    coroutine.yield(_v)\n{indent}end\n"
+                    ));
+                }
+                Token::Var(_var) => {
+                    let (name, exp) = (&_var.name, _var.exp.clone());
+                    if is_int(&exp, &ints) {
+                        ints.push(name.clone());
+                    }
+                    this.buffer.push_str(&format!(
+                        "{indent}local {name} = {}\n",
+                        expression(&exp, &ints)
+                    ));
+                }
+                Token::End(br) => {
+                    let mut broken = None;
+                    if matches!(loops.last(), Some(open) if open.id == Some(br.id)) {
+                        if loops.last().is_some_and(|open| open.continued) {
+                            let label = continue_label(&loops);
+                            this.buffer.push_str(&format!("{indent}::{label}::\n"));
+                        }
+                        broken = loops
+                            .pop()
+                            .and_then(|open| open.label)
+                            .filter(|name| breaks.contains(name));
+                    }
+                    depth -= 1;
+                    // `else`/`elseif` close the previous branch themselves
+                    match next_token(x, ln, token_iter.peek().copied()) {
+                        Some(Token::Else(_)) | Some(Token::ElseIf(_)) => {}
+                        _ => this
+                            .buffer
+                            .push_str(&format!("{}end\n", self::indent(depth))),
+                    }
+                    if let Some(name) = broken {
+                        this.buffer
+                            .push_str(&format!("{}::{name}_break::\n", self::indent(depth)));
+                    }
+                }
+                Token::Empty => {
+                    this.buffer.push('\n');
+                }
+                Token::Generic(s) => {
+                    if !statement.is_empty() && !statement.ends_with('(') {
+                        statement.push(' ');
+                    }
+                    statement.push_str(s);
+                }
+                Token::OpenRoBr(_) => {
+                    statement.push('(');
+                }
+                Token::CloseRoBr(_) => {
+                    statement.push(')');
+                }
+                Token::OpenSqBr(_) => {
+                    statement.push('[');
+                }
+                Token::CloseSqBr(_) => {
+                    statement.push(']');
+                }
+                Token::Comma => {
+                    statement.push(',');
+                }
+                _ => {
+                    log!(Error, f("Unexpected Token {token:?}"));
+                }
+            }
+        }
+        if !statement.is_empty() {
+            this.buffer
+                .push_str(&format!("{}{statement}\n", indent(depth)));
+        }
+    }
+
+    if loops.last().is_some_and(|open| open.continued) {
+        this.buffer.push_str("        ::continue::\n");
+    }
+    match type_ {
+        FunctionType::Loop => this.buffer.push_str("    end\nend\n"),
+        FunctionType::Function => this.buffer.push_str("end\n"),
+        FunctionType::Generator => this.buffer.push_str("    end)\nend\n"),
+    }
+}
+
+/// A `match` is a function called right away, with an `if` per arm. The
+/// arms cover every value, so the last one is the `else`.
+fn ifs(m: Match, ints: &[String]) -> String {
+    let value = expression(&m.value, ints);
+    let last = m.arms.len() - 1;
+    let mut body = String::new();
+    for (i, (pattern, exp)) in m.arms.into_iter().enumerate() {
+        let mut bindings = String::new();
+        let condition = match &pattern {
+            Pattern::Wildcard => None,
+            Pattern::Binding(n) => {
+                bindings.push_str(&format!("local {n} = _m\n"));
+                None
+            }
+            Pattern::Int(n) => Some(format!("_m == {n}")),
+            Pattern::Range(start, end) => Some(format!("_m >= {start} and _m <= {end}")),
+            Pattern::Bool(true) => Some("_m".to_string()),
+            Pattern::Bool(false) => Some("not _m".to_string()),
+            Pattern::Str(s) => Some(format!("_m == \"{s}\"")),
+            Pattern::Variant(_, variant, values) if m.holds => {
+                for (at, v) in values.iter().enumerate() {
+                    if let Some(v) = v {
+                        bindings.push_str(&format!("local {v} = _m[{}]\n", at + 1));
+                    }
+                }
+                Some(format!("_m.tag == \"{variant}\""))
+            }
+            Pattern::Variant(name, variant, _) => Some(format!("_m == {name}.{variant}")),
+        };
+        let arm = format!("{bindings}return {}\n", expression(&exp, ints));
+        let depth = if last == 0 { 1 } else { 2 };
+        let arm: String = arm
+            .lines()
+            .map(|l| format!("{}{l}\n", indent(depth)))
+            .collect();
+        match (i, condition) {
+            (0, Some(condition)) if i < last => {
+                body.push_str(&format!("    if {condition} then\n{arm}"))
+            }
+            (_, Some(condition)) if i < last => {
+                body.push_str(&format!("    elseif {condition} then\n{arm}"))
+            }
+            _ if last == 0 => body.push_str(&arm),
+            _ => body.push_str(&format!("    else\n{arm}    end\n")),
+        }
+    }
+    format!("(function(_m)\n{body}end)({value})")
+}
+
+fn indent(depth: usize) -> String {
+    "    ".repeat(depth)
+}
+
+/// Whether every value in the expression is an integer literal or a variable
+/// known to hold an integer, the calls of [`DIV`] and `math.fmod` are
+/// integers when their arguments are.
+fn is_int(exp: &[Token], ints: &[String]) -> bool {
+    !exp.is_empty()
+        && exp.iter().all(|x| match x {
+            Token::Operator(o) => matches!(
+                o,
+                Operator::Plus
+                    | Operator::Minus
+                    | Operator::Mul
+                    | Operator::Div
+                    | Operator::Mod
+                    | Operator::BitShiftLeft
+                    | Operator::BitShiftRight
+                    | Operator::BitAnd
+                    | Operator::BitOr
+            ),
+            Token::ExpVal(s) => {
+                ["(", ")", ",", "_div", "math.fmod"].contains(&s.as_str())
+                    || s.parse::<i64>().is_ok()
+                    || ints.contains(s)
+            }
+            _ => false,
+        })
+}
+
+/// Whether `exp[i]` is a unary operator, one at the start or after another
+/// operator, a `(` or a `,`.
+fn unary(exp: &[Token], i: usize) -> bool {
+    matches!(exp[i], Token::Operator(_))
+        && (i == 0
+            || match &exp[i - 1] {
+                Token::Operator(_) => true,
+                Token::ExpVal(s) => s == "(" || s == ",",
+                _ => false,
+            })
+}
+
+/// Rewrites the first `%`, or `/` of two integers, in `exp` into a call of
+/// `math.fmod` or [`DIV`], which truncate like the other targets where `%`
+/// and `//` round down. The left operand is everything bound as tightly,
+/// `a * b / c` divides `a * b`.
+fn truncated(exp: &[Token], ints: &[String]) -> Option<Expression> {
+    for i in 1..exp.len().saturating_sub(1) {
+        let name = match exp[i] {
+            Token::Operator(Operator::Div) => "_div",
+            Token::Operator(Operator::Mod) => "math.fmod",
+            _ => continue,
+        };
+        if unary(exp, i) {
+            continue;
+        }
+        let mut start = i - operand(exp, i - 1, true).len();
+        loop {
+            if start > 0 && unary(exp, start - 1) {
+                start -= 1;
+            } else if start > 1
+                && matches!(
+                    exp[start - 1],
+                    Token::Operator(Operator::Mul | Operator::Div | Operator::Mod)
+                )
+            {
+                start -= 1 + operand(exp, start - 2, true).len();
+            } else {
+                break;
+            }
+        }
+        let mut end = i + 1;
+        while end + 1 < exp.len() && unary(exp, end) {
+            end += 1;
+        }
+        end += operand(exp, end, false).len();
+        let (left, right) = (&exp[start..i], &exp[i + 1..end]);
+        if name == "_div" && !(is_int(left, ints) && is_int(right, ints)) {
+            continue;
+        }
+        // the brackets around a whole argument aren't needed anymore
+        let bare = |x: &[Token]| -> Vec<Token> {
+            match x {
+                [Token::ExpVal(open), inner @ .., Token::ExpVal(_)]
+                    if open == "(" && operand(x, 0, false).len() == x.len() =>
+                {
+                    inner.to_vec()
+                }
+                _ => x.to_vec(),
+            }
+        };
+        let (left, right) = (bare(left), bare(right));
+        let value = |s: &str| Token::ExpVal(s.to_string());
+        let mut call = exp[..start].to_vec();
+        call.extend([value(name), value("(")]);
+        call.extend(left);
+        call.push(value(","));
+        call.extend(right);
+        call.push(value(")"));
+        call.extend_from_slice(&exp[end..]);
+        return Some(call);
+    }
+    None
+}
+
+/// The tokens making up the operand that ends at `index` (`backwards`) or
+/// starts at it, including bracketed groups and the name of a called function.
+fn operand(exp: &[Token], index: usize, backwards: bool) -> &[Token] {
+    let is = |i: usize, s: &str| matches!(&exp[i], Token::ExpVal(v) if v == s);
+    let matching = |from: usize| {
+        let mut level = 0;
+        let mut i = from;
+        loop {
+            if is(i, "(") {
+                level += 1;
+            } else if is(i, ")") {
+                level -= 1;
+            }
+            if level == 0 {
+                return i;
+            }
+            match backwards {
+                true if i > 0 => i -= 1,
+                false if i + 1 < exp.len() => i += 1,
+                _ => return i,
+            }
+        }
+    };
+    if backwards {
+        let mut start = index;
+        if is(start, ")") {
+            start = matching(start);
+            if start > 0 && matches!(&exp[start - 1], Token::ExpVal(v) if v != "(") {
+                start -= 1;
+            }
+        }
+        &exp[start..=index]
+    } else {
+        let mut end = index;
+        if index + 1 < exp.len() && is(index + 1, "(") && !is(index, "(") {
+            end += 1;
+        }
+        if is(end, "(") {
+            end = matching(end);
+        }
+        &exp[index..=end]
+    }
+}
+
+fn operator(o: &Operator) -> &'static str {
+    match o {
+        Operator::Plus => "+",
+        Operator::Minus => "-",
+        Operator::Mul => "*",
+        Operator::Div => "/",
+        Operator::BitShiftLeft => "<<",
+        Operator::BitShiftRight => ">>",
+        Operator::Mod => "%",
+        Operator::Equals => "==",
+        Operator::NotEquals => "~=",
+        Operator::Less => "<",
+        Operator::Greater => ">",
+        Operator::LessEquals => "<=",
+        Operator::GreaterEquals => ">=",
+        Operator::And => "and",
+        Operator::Or => "or",
+        Operator::BitAnd => "&",
+        Operator::BitOr => "|",
+        // in strings, or when rewriting the pipes failed
+        Operator::Pipe => "|>",
+    }
+}
+
+/// Renders an expression for Lua. `/` always yields a float in Lua, so a
+/// division of two integer operands (see [`is_int`]) becomes a call of
+/// [`DIV`], see [`truncated`].
+pub fn expression(exp: &[Token], ints: &[String]) -> String {
+    if let Some(exp) = pipes(exp) {
+        return expression(&exp, ints);
+    }
+    if let Some(exp) = truncated(exp, ints) {
+        return expression(&exp, ints);
+    }
+    let mut string = String::new();
+    let mut exp_iter = exp.iter().enumerate().peekable();
+    // an operator directly after another one (or at the start) is unary
+    let mut unary = true;
+    while exp_iter.peek().is_some() {
+        let (i, x) = exp_iter.next().unwrap();
+        if let Some((m, end)) = match_(exp, i) {
+            string.push_str(&ifs(m, ints));
+            while exp_iter.peek().is_some_and(|(j, _)| *j <= end) {
+                exp_iter.next();
+            }
+            unary = false;
+            continue;
+        }
+        // structs are tables
+        if let Some((Literal { fields, .. }, end)) = literal(exp, i) {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(field, value)| format!("{field} = {}", expression(value, ints)))
+                .collect();
+            string.push_str(&format!("{{ {} }}", fields.join(", ")));
+            while exp_iter.peek().is_some_and(|(j, _)| *j <= end) {
+                exp_iter.next();
+            }
+            unary = false;
+            continue;
+        }
+        match x {
+            Token::Operator(o) if unary => {
+                string.push_str(operator(o));
+            }
+            Token::Operator(o) => {
+                string.push_str(&format!(" {} ", operator(o)));
+                unary = true;
+            }
+            Token::ExpVal(s) if s == "," => {
+                string.push_str(", ");
+                unary = true;
+            }
+            // `Shape::Circle(1.5)` calls the function of the variant
+            Token::ExpVal(s) if s.contains("::") => {
+                string.push_str(&s.replace("::", "."));
+                unary = false;
+            }
+            Token::ExpVal(s) => {
+                string.push_str(s);
+                unary = s == "(";
+            }
+            _ => {
+                log!(Error, "Unexpected token in Expression");
+            }
+        }
+    }
+    string
+}
@@ -1,20 +1,39 @@
 #![allow(dead_code)]
 use {
     crate::{
+        interpreter,
         lexer::{
-            token::{expression::Operator, Expression, Function},
-            FunctionType, Lexer, Token,
+            check, flow, resolve,
+            token::{
+                expression::Operator, Arg, Enum, Expression, Function, Generic, Impl, Range,
+                Struct, Trait,
+            },
+            tree::{self, Pattern},
+            types::{Primitive, Type},
+            FunctionType, Lexer, Line, Token,
         },
         log, printx, PrintT,
     },
-    std::{fs::read_to_string, path::Path},
+    std::{collections::HashMap, fs::read_to_string, path::Path},
 };
 
+pub mod csharp;
 pub mod cxx;
+pub mod go;
+pub mod java;
+pub mod llvm;
+pub mod lua;
+pub mod wat;
 
 #[derive(Clone, Debug)]
 pub enum TranspilerLang {
     Cxx,
+    Go,
+    Java,
+    CSharp,
+    Lua,
+    Wat,
+    Llvm,
 }
 
 #[derive(Clone, Debug)]
@@ -22,6 +41,9 @@ pub struct Transpiler {
     pub buffer: String,
     pub imports: Vec<String>,
     pub target_lang: TranspilerLang,
+    /// the types of the globals and consts, for the targets that have to
+    /// write them out, see [`check::globals`]
+    pub globals: HashMap<String, Type>,
 }
 
 pub fn run<P>(this: &mut Transpiler, file: P)
@@ -39,169 +61,1481 @@ where
 }
 
 pub fn transpile(this: &mut Transpiler, ast: Vec<Token>) {
-    let mut ast_iter = ast.iter().peekable();
-    while ast_iter.peek().is_some() {
-        let nt = ast_iter.next().unwrap();
+    let ast = types(ast, &this.target_lang);
+    let mut signatures = tree::Signatures::default();
+    // logged by `types`
+    if signatures.extend(&ast).is_ok() {
+        this.globals = check::globals(&signatures, &ast);
+    }
+    match this.target_lang {
+        TranspilerLang::Cxx => cxx::transpile(this, ast),
+        TranspilerLang::Go => go::transpile(this, ast),
+        TranspilerLang::Java => java::transpile(this, ast),
+        TranspilerLang::CSharp => csharp::transpile(this, ast),
+        TranspilerLang::Lua => lua::transpile(this, ast),
+        TranspilerLang::Wat => wat::transpile(this, ast),
+        TranspilerLang::Llvm => llvm::transpile(this, ast),
+    }
+}
+
+/// How the primitives are written in C++, Go, Java and C#. Java has no
+/// unsigned integers, they become a signed one holding all their values,
+/// except for `u64`.
+pub const PRIMITIVES: [(Primitive, [&str; 4]); 15] = [
+    (Primitive::I8, ["std::int8_t", "int8", "byte", "sbyte"]),
+    (Primitive::I16, ["std::int16_t", "int16", "short", "short"]),
+    (Primitive::I32, ["std::int32_t", "int32", "int", "int"]),
+    (Primitive::I64, ["std::int64_t", "int64", "long", "long"]),
+    (Primitive::U8, ["std::uint8_t", "uint8", "short", "byte"]),
+    (Primitive::U16, ["std::uint16_t", "uint16", "int", "ushort"]),
+    (Primitive::U32, ["std::uint32_t", "uint32", "long", "uint"]),
+    (Primitive::U64, ["std::uint64_t", "uint64", "long", "ulong"]),
+    (Primitive::F32, ["float", "float32", "float", "float"]),
+    (Primitive::F64, ["double", "float64", "double", "double"]),
+    (Primitive::Int, ["int", "int", "int", "int"]),
+    (Primitive::Bool, ["bool", "bool", "boolean", "bool"]),
+    (Primitive::Char, ["char", "rune", "char", "char"]),
+    (
+        Primitive::Str,
+        ["std::string", "string", "String", "string"],
+    ),
+    (Primitive::Void, ["void", "", "void", "void"]),
+];
+
+/// The name of `p` in `lang`, which is C++, Go, Java or C#.
+pub fn primitive(p: Primitive, lang: &TranspilerLang) -> &'static str {
+    let column = match lang {
+        TranspilerLang::Cxx => 0,
+        TranspilerLang::Go => 1,
+        TranspilerLang::Java => 2,
+        TranspilerLang::CSharp => 3,
+        _ => unreachable!("{lang:?} has no table of primitives"),
+    };
+    let (_, names) = (PRIMITIVES.iter()).find(|(of, _)| *of == p).unwrap();
+    names[column]
+}
+
+/// Moves the structs, enums and traits to the front of the program, each one
+/// after the types it uses, puts the fields of struct literals in the order
+/// they are declared in and checks the enum variants, the calls of methods,
+/// the traits and the types. C++ and Java need the order, the other targets
+/// keep the output alike. Generics are copied per types for the targets
+/// without templates, see [`Copies`].
+fn types(ast: Vec<Token>, lang: &TranspilerLang) -> Vec<Token> {
+    checks(&ast, lang);
+    let mut ast = match lang {
+        // templates, and no types at all in Lua
+        TranspilerLang::Cxx | TranspilerLang::Lua => ast,
+        _ => Copies::monomorphise(ast),
+    };
+    escape_reserved(&mut ast, lang);
+    let (types, mut rest): (Vec<Token>, Vec<Token>) =
+        ast.into_iter().partition(|nt| declared(nt).is_some());
+    let mut sorted: Vec<Token> = vec![];
+    for x in &types {
+        depend(x, &types, &mut sorted, &mut vec![]);
+    }
+    let structs: Vec<Struct> = sorted
+        .iter()
+        .filter_map(|nt| match nt {
+            Token::Struct(x) => Some(x.clone()),
+            _ => None,
+        })
+        .collect();
+    let enums: Vec<Enum> = sorted
+        .iter()
+        .filter_map(|nt| match nt {
+            Token::Enum(x) => Some(x.clone()),
+            _ => None,
+        })
+        .collect();
+    let impls: Vec<Impl> = rest
+        .iter()
+        .filter_map(|nt| match nt {
+            Token::Impl(x) => Some(x.clone()),
+            _ => None,
+        })
+        .collect();
+    for x in &impls {
+        if !structs.iter().any(|s| s.name == x.name) {
+            log!(Error, f("`impl {}` needs a struct named `{0}`", x.name));
+        }
+    }
+    for nt in rest.iter_mut() {
+        let exps = match nt {
+            Token::Const(x) | Token::Global(x) => vec![&mut x.exp],
+            Token::Impl(x) => x
+                .functions
+                .iter_mut()
+                .flat_map(|x| x.lines.iter_mut())
+                .flat_map(|line| line.tokens.iter_mut())
+                .flat_map(expressions)
+                .collect(),
+            Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) => x
+                .lines
+                .iter_mut()
+                .flat_map(|line| line.tokens.iter_mut())
+                .flat_map(expressions)
+                .collect(),
+            _ => vec![],
+        };
+        for exp in exps {
+            ordered(exp, &structs);
+            variants(exp, &enums);
+            exhaustive(exp, &enums);
+            methods(exp, &structs, &impls, lang);
+        }
+        // calls standing on their own line
+        let lines = match nt {
+            Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) => {
+                x.lines.iter_mut().collect()
+            }
+            Token::Impl(x) => x
+                .functions
+                .iter_mut()
+                .flat_map(|x| x.lines.iter_mut())
+                .collect(),
+            _ => vec![],
+        };
+        for line in lines {
+            piped_call(line);
+            for i in 0..line.tokens.len() {
+                let called = matches!(line.tokens.get(i + 1), Some(Token::OpenRoBr(_)));
+                if let Token::Generic(s) = &line.tokens[i] {
+                    if let Some(s) = call(s, called, &structs, &impls, lang) {
+                        line.tokens[i] = Token::Generic(s);
+                    }
+                }
+            }
+        }
+    }
+    sorted.append(&mut rest);
+    sorted
+}
+
+/// The names functions and methods can't have in `lang`: its keywords and,
+/// in Lua, the globals the output calls.
+fn reserved(lang: &TranspilerLang) -> &'static str {
+    match lang {
+        TranspilerLang::Cxx => {
+            "and asm auto bool case catch char class concept default delete do double \
+             explicit export extern float friend goto inline int long mutable \
+             namespace new not operator or private protected public register requires \
+             short signed sizeof static switch template this throw try typedef \
+             typename union unsigned using virtual void volatile xor"
+        }
+        TranspilerLang::Go => {
+            "case chan default defer fallthrough func go goto interface map package \
+             range select switch type var append os"
+        }
+        TranspilerLang::Java => {
+            "abstract boolean byte case catch char class default do double extends \
+             final finally float goto implements instanceof int interface long native \
+             new package private protected public short static super switch \
+             synchronized this throw throws transient try void volatile"
+        }
+        TranspilerLang::CSharp => {
+            "abstract as base bool byte case catch char checked class decimal default \
+             delegate do double event explicit extern finally fixed float foreach goto \
+             implicit in int interface internal is lock long namespace new null object \
+             operator out override params private protected public readonly ref sealed \
+             short sizeof static string switch this throw try typeof unchecked unsafe \
+             using virtual void volatile"
+        }
+        TranspilerLang::Lua => {
+            "and do elseif end function goto local nil not or repeat then until \
+             coroutine ipairs math os pairs setmetatable"
+        }
+        // `$name` and `@name` can't clash with anything
+        TranspilerLang::Wat | TranspilerLang::Llvm => "",
+    }
+}
+
+/// Renames the functions and methods named like something [`reserved`] in
+/// the target, `new` becomes `new_`, along with the calls of them.
+fn escape_reserved(ast: &mut [Token], lang: &TranspilerLang) {
+    let reserved = reserved(lang);
+    let clashes = |x: &Function| reserved.split_whitespace().any(|r| r == x.name);
+    let mut functions: Vec<String> = vec![];
+    let mut methods: Vec<String> = vec![];
+    for nt in ast.iter() {
         match nt {
-            Token::Const(x) => {
-                let (name, exp) = (x.name.clone(), x.exp.clone());
-                this.buffer.push_str(&format!(
-                    "const auto {name} = {};\n",
-                    expression(this.clone().target_lang, exp)
-                ));
+            Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x)
+                if clashes(x) =>
+            {
+                functions.push(x.name.clone());
             }
-            Token::Global(x) => {
-                let (name, exp) = (x.name.clone(), x.exp.clone());
-                this.buffer.push_str(&format!(
-                    "auto {name} = {};\n",
-                    expression(this.clone().target_lang, exp)
-                ));
+            Token::Impl(Impl { functions: fs, .. }) | Token::Trait(Trait { functions: fs, .. }) => {
+                methods.extend(fs.iter().filter(|x| clashes(x)).map(|x| x.name.clone()));
             }
-            Token::Function(x) => {
-                function(this, x, FunctionType::Function);
+            _ => {}
+        }
+    }
+    if functions.is_empty() && methods.is_empty() {
+        return;
+    }
+    // `f`, `Point::f` and `p.f`
+    let renamed = |s: &mut String| {
+        let method = match s.rfind(['.', ':']) {
+            Some(at) => methods.contains(&s[at + 1..].to_string()),
+            None => false,
+        };
+        if method || functions.contains(s) {
+            s.push('_');
+        }
+    };
+    for nt in ast.iter_mut() {
+        let declared: Vec<&mut Function> = match nt {
+            Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) => {
+                if functions.contains(&x.name) {
+                    x.name.push('_');
+                }
+                vec![x]
             }
-            Token::LoopFunction(x) => {
-                function(this, x, FunctionType::Loop);
+            Token::Impl(Impl { functions: fs, .. }) | Token::Trait(Trait { functions: fs, .. }) => {
+                for x in fs.iter_mut().filter(|x| methods.contains(&x.name)) {
+                    x.name.push('_');
+                }
+                fs.iter_mut().collect()
             }
-            Token::GeneratorFunction(x) => {
-                if !this.imports.contains(&"vector".to_string()) {
-                    this.imports.push("vector".to_string());
-                    this.buffer.push_str("#include <vector>\n\n");
+            Token::Const(x) | Token::Global(x) => {
+                for t in x.exp.iter_mut() {
+                    if let Token::ExpVal(s) = t {
+                        renamed(s);
+                    }
                 }
-                function(this, x, FunctionType::Generator);
+                vec![]
             }
-            Token::CImport(ci) => {
-                if !this.imports.contains(ci) {
-                    this.buffer.push_str(&format!("#include {ci}\n"));
-                    this.imports.push(ci.to_owned());
+            _ => vec![],
+        };
+        for line in declared.into_iter().flat_map(|x| x.lines.iter_mut()) {
+            for t in line.tokens.iter_mut() {
+                if let Token::Generic(s) = t {
+                    renamed(s);
+                }
+                for exp in expressions(t) {
+                    for t in exp.iter_mut() {
+                        if let Token::ExpVal(s) = t {
+                            renamed(s);
+                        }
+                    }
                 }
             }
-            Token::Comment(_) => {}
-            _ => {
-                log!(Error, f("unexpected Token"));
-                dbg!(&nt);
+        }
+    }
+}
+
+/// Checks the names used, see [`resolve`], the `impl`s of traits and the
+/// values passed as `impl Name` or `dyn Name`, see [`tree::Signatures`], the
+/// types in the function bodies, see [`check`], and their control flow, see
+/// [`flow`]. Bodies the statement tree can't be built for are left to the
+/// target. LLVM declares the functions not defined, see [`resolve::linked`].
+fn checks(ast: &[Token], lang: &TranspilerLang) {
+    let resolved = match lang {
+        TranspilerLang::Llvm => resolve::linked(ast, &interpreter::predefined()),
+        _ => resolve::program(ast, &interpreter::predefined()),
+    };
+    for e in resolved.errors {
+        log!(Error, f("{e}"));
+    }
+    for warning in resolved.warnings {
+        log!(Warning, f("{warning}"));
+    }
+    let mut signatures = tree::Signatures::default();
+    if let Err(e) = signatures.extend(ast) {
+        log!(Error, f("{e}"));
+        return;
+    }
+    for nt in ast {
+        let functions = match nt {
+            Token::Function(x) | Token::GeneratorFunction(x) => vec![(x.clone(), false)],
+            Token::LoopFunction(x) => vec![(x.clone(), true)],
+            Token::Impl(x) => (x.functions.iter())
+                .map(|function| {
+                    let name = format!("{}::{}", x.name, function.name);
+                    (
+                        Function {
+                            name,
+                            ..function.clone()
+                        },
+                        false,
+                    )
+                })
+                .collect(),
+            _ => continue,
+        };
+        for (x, loop_) in functions {
+            let body = match loop_ {
+                true => tree::loop_function(&x),
+                false => tree::function(&x),
+            };
+            let Ok(body) = body else {
+                continue;
+            };
+            if let Err(e) = signatures.bounds(&x.generics, &x.arguments, &body) {
+                log!(Error, f("{e} in `{}`", x.name));
             }
         }
     }
+    let mut errors = check::program(&signatures, ast);
+    errors.extend(flow::program(ast));
+    for e in errors {
+        log!(Error, f("{e}"));
+    }
 }
 
-pub fn function(this: &mut Transpiler, x: &Function, type_: FunctionType) {
-    let (name, arguments, mut return_type) =
-        (x.name.clone(), x.arguments.clone(), x.return_type.clone());
+/// The copies of the generic functions and structs for the targets without
+/// templates, one per combination of types they are used with: `larger(1,
+/// 2)` calls `larger_int`, `Pair { first: 1, second: 2 }` is a `Pair_int`.
+struct Copies {
+    signatures: tree::Signatures,
+    functions: HashMap<String, Token>,
+    structs: HashMap<String, Struct>,
+    /// the copies in the order they are needed, by the name of the generic
+    made: Vec<(String, Token)>,
+    /// the generic and the types of the copies that are made or waiting
+    known: Vec<(String, Vec<Type>)>,
+    waiting: Vec<(String, Vec<Type>)>,
+}
 
-    let mut args_v = vec![];
+impl Copies {
+    /// The program with each generic function and struct replaced by its
+    /// copies. Calls whose types can't be told are logged.
+    fn monomorphise(ast: Vec<Token>) -> Vec<Token> {
+        let mut signatures = tree::Signatures::default();
+        // logged by `checks`
+        if signatures.extend(&ast).is_err() {
+            return ast;
+        }
+        let mut this = Copies {
+            signatures,
+            functions: HashMap::new(),
+            structs: HashMap::new(),
+            made: vec![],
+            known: vec![],
+            waiting: vec![],
+        };
+        for nt in &ast {
+            match nt {
+                Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x)
+                    if !x.generics.is_empty() =>
+                {
+                    this.functions.insert(x.name.clone(), nt.clone());
+                }
+                Token::Struct(x) if !x.generics.is_empty() => {
+                    this.structs.insert(x.name.clone(), x.clone());
+                }
+                _ => {}
+            }
+        }
+        if this.functions.is_empty() && this.structs.is_empty() {
+            return ast;
+        }
+        let ast: Vec<Token> = (ast.into_iter())
+            .map(|mut nt| {
+                match &mut nt {
+                    Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x)
+                        if x.generics.is_empty() =>
+                    {
+                        this.function(x)
+                    }
+                    Token::Impl(x) => x.functions.iter_mut().for_each(|x| this.function(x)),
+                    Token::Struct(x) => this.fields(x),
+                    Token::Const(x) | Token::Global(x) => {
+                        this.rename(&mut x.exp, &HashMap::new(), &x.name)
+                    }
+                    _ => {}
+                }
+                nt
+            })
+            .collect();
+        while !this.waiting.is_empty() {
+            let (name, types) = this.waiting.remove(0);
+            if this.made.len() >= 1000 {
+                log!(
+                    Error,
+                    f("`{name}` needs more than 1000 copies, are its types growing?")
+                );
+                break;
+            }
+            this.copy(&name, &types);
+        }
+        // the copies go where the generics were
+        let mut program = vec![];
+        for nt in ast {
+            let (name, generics) = match &nt {
+                Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) => {
+                    (&x.name, &x.generics)
+                }
+                Token::Struct(x) => (&x.name, &x.generics),
+                _ => {
+                    program.push(nt);
+                    continue;
+                }
+            };
+            if generics.is_empty() {
+                program.push(nt);
+                continue;
+            }
+            let kind = std::mem::discriminant(&nt);
+            program.extend(
+                (this.made.iter())
+                    .filter(|(of, copy)| of == name && std::mem::discriminant(copy) == kind)
+                    .map(|(_, copy)| copy.clone()),
+            );
+        }
+        program
+    }
 
-    for arg in arguments {
-        args_v.push(format!("{} {}", arg.type_, arg.name));
+    /// Makes the copy of the generic `name` for `types`, the types its type
+    /// parameters stand for in order.
+    fn copy(&mut self, name: &str, types: &[Type]) {
+        let copy = mangle(name, types);
+        if let Some(x) = self.structs.get(name) {
+            let substituted = (x.generics.iter().map(|g| g.name.clone()))
+                .zip(types.iter().cloned())
+                .collect();
+            let mut x = Struct {
+                name: copy,
+                fields: (x.fields.iter())
+                    .map(|field| Arg {
+                        name: field.name.clone(),
+                        type_: field.type_.substitute(&substituted),
+                    })
+                    .collect(),
+                generics: vec![],
+                ..x.clone()
+            };
+            self.fields(&mut x);
+            self.made.push((name.to_string(), Token::Struct(x)));
+            return;
+        }
+        let Some(mut nt) = self.functions.get(name).cloned() else {
+            return;
+        };
+        if let Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) = &mut nt {
+            let substituted = (x.generics.iter().map(|g| g.name.clone()))
+                .zip(types.iter().cloned())
+                .collect();
+            for arg in x.arguments.iter_mut() {
+                arg.type_ = arg.type_.substitute(&substituted);
+            }
+            x.return_type = x.return_type.substitute(&substituted);
+            x.name = copy;
+            x.generics = vec![];
+            self.function(x);
+        }
+        self.made.push((name.to_string(), nt));
     }
 
-    let args = args_v.join(", ");
+    /// The name of the copy of the generic `name` for `types`, which is made
+    /// unless it is known already.
+    fn instance(&mut self, name: &str, types: Vec<Type>) -> String {
+        let copy = mangle(name, &types);
+        let key = (name.to_string(), types);
+        if !self.known.contains(&key) {
+            self.known.push(key.clone());
+            self.waiting.push(key);
+        }
+        copy
+    }
 
-    if type_ == FunctionType::Generator {
-        return_type = format!("std::vector<{return_type}>");
+    /// `type_` with the generic structs in it replaced by their copies, like
+    /// `[Pair_int]` for `[Pair<int>]`.
+    fn type_(&mut self, type_: &Type) -> Type {
+        match type_ {
+            Type::Named(name, arguments) if self.structs.contains_key(name) => {
+                Type::named(&self.instance(name, arguments.clone()))
+            }
+            Type::Named(name, arguments) => {
+                let arguments = arguments.iter().map(|arg| self.type_(arg)).collect();
+                Type::Named(name.clone(), arguments)
+            }
+            Type::Array(item) => Type::Array(Box::new(self.type_(item))),
+            Type::Optional(item) => Type::Optional(Box::new(self.type_(item))),
+            Type::Function(args, result) => {
+                let args = args.iter().map(|arg| self.type_(arg)).collect();
+                Type::Function(args, Box::new(self.type_(result)))
+            }
+            Type::Primitive(_) | Type::Impl(_) | Type::Dyn(_) => type_.clone(),
+        }
     }
 
-    this.buffer
-        .push_str(&format!("{return_type} {name} ({args})\n{{\n"));
+    fn fields(&mut self, x: &mut Struct) {
+        if x.generics.is_empty() {
+            for field in x.fields.iter_mut() {
+                field.type_ = self.type_(&field.type_);
+            }
+        }
+    }
+
+    /// Points the calls and literals of generics in the body of `x` to their
+    /// copies, and the types of its arguments as well.
+    fn function(&mut self, x: &mut Function) {
+        // the `state` block of a `loop fn` only builds as one
+        let body = tree::function(x).or_else(|_| tree::loop_function(x));
+        let vars = self
+            .signatures
+            .vars(&x.arguments, &body.unwrap_or_default());
+        for line in x.lines.iter_mut() {
+            // calls standing on their own line
+            if let Some(Token::Generic(_)) = line.tokens.first() {
+                let Ok(stmts) = tree::block(std::slice::from_ref(line), x.start_ln) else {
+                    continue;
+                };
+                let Some(tree::Stmt::Expr(exp)) = stmts.first() else {
+                    continue;
+                };
+                let mut copies = self.copies(exp, &vars, &x.name).into_iter();
+                for i in 0..line.tokens.len() {
+                    let called = matches!(line.tokens.get(i + 1), Some(Token::OpenRoBr(_)));
+                    if let Token::Generic(name) = &line.tokens[i] {
+                        if called && self.functions.contains_key(name) {
+                            if let Some(Some(copy)) = copies.next() {
+                                line.tokens[i] = Token::Generic(copy);
+                            }
+                        }
+                    }
+                }
+                continue;
+            }
+            for token in line.tokens.iter_mut() {
+                let exps = match token {
+                    Token::For(x) => {
+                        let mut exps = vec![&mut x.iter_exp];
+                        if let Some(range) = &mut x.range {
+                            exps.extend([&mut range.start, &mut range.end]);
+                            exps.extend(range.step.as_mut());
+                        }
+                        exps
+                    }
+                    token => expressions(token),
+                };
+                for exp in exps {
+                    self.rename(exp, &vars, &x.name);
+                }
+            }
+        }
+        for arg in x.arguments.iter_mut() {
+            arg.type_ = self.type_(&arg.type_);
+        }
+        x.return_type = self.type_(&x.return_type);
+    }
 
+    /// Points the calls and literals of generics in `exp` to their copies.
+    /// `in_` names the function `exp` is in.
+    fn rename(&mut self, exp: &mut Expression, vars: &HashMap<String, Type>, in_: &str) {
+        if let Some(piped) = pipes(exp) {
+            *exp = piped;
+        }
+        let Ok(tree) = tree::expression(exp) else {
+            return;
+        };
+        let mut copies = self.copies(&tree, vars, in_).into_iter();
+        for i in 0..exp.len() {
+            let Token::ExpVal(name) = &exp[i] else {
+                continue;
+            };
+            let generic = match exp.get(i + 1) {
+                Some(Token::ExpVal(open)) if open == "(" => self.functions.contains_key(name),
+                Some(Token::ExpVal(open)) if open == "{" => self.structs.contains_key(name),
+                _ => false,
+            };
+            if generic {
+                if let Some(Some(copy)) = copies.next() {
+                    exp[i] = Token::ExpVal(copy);
+                }
+            }
+        }
+    }
+
+    /// The copies used by the calls and literals of generics in `exp`, in
+    /// the order they are written. `None` for the ones whose types can't be
+    /// told, which are logged.
+    fn copies(
+        &mut self,
+        exp: &tree::Expr,
+        vars: &HashMap<String, Type>,
+        in_: &str,
+    ) -> Vec<Option<String>> {
+        let mut found = vec![];
+        let _ = tree::walk_expr(exp, &mut |exp| {
+            let (name, generics) = match exp {
+                tree::Expr::Call(name, _) => match self.functions.get(name) {
+                    Some(
+                        Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x),
+                    ) => (name, &x.generics),
+                    _ => return Ok(()),
+                },
+                tree::Expr::Struct(name, _) => match self.structs.get(name) {
+                    Some(x) => (name, &x.generics),
+                    None => return Ok(()),
+                },
+                _ => return Ok(()),
+            };
+            // conflicting types are logged by `traits`
+            let Ok(types) = self.signatures.type_arguments(exp, vars) else {
+                found.push(None);
+                return Ok(());
+            };
+            match generics.iter().find(|g| !types.contains_key(&g.name)) {
+                Some(g) => {
+                    log!(Error, f("Can't tell the type `{}` of `{name}` in `{in_}`, it is inferred from the types of the values passed to it", g.name));
+                    found.push(None);
+                }
+                None => {
+                    let types = generics.iter().map(|g| types[&g.name].clone()).collect();
+                    found.push(Some((name.clone(), types)));
+                }
+            }
+            Ok(())
+        });
+        (found.into_iter())
+            .map(|found| found.map(|(name, types)| self.instance(&name, types)))
+            .collect()
+    }
+}
+
+/// `larger_int` for `larger<int>`, `Pair_vec_int` for `Pair<[int]>`.
+fn mangle(name: &str, types: &[Type]) -> String {
+    let mut mangled = name.to_string();
+    for type_ in types {
+        mangled.push('_');
+        mangled.push_str(&mangled_type(type_));
+    }
+    mangled
+}
+
+/// A type written with the characters of a name, `opt_int` for `int?`.
+fn mangled_type(type_: &Type) -> String {
     match type_ {
-        FunctionType::Loop => this.buffer.push_str("do {\n"),
-        FunctionType::Generator => this.buffer.push_str("std::vector<int> dfjfjfdjfndjfnjd;\n"),
-        _ => {}
+        Type::Primitive(p) => p.name().to_string(),
+        Type::Array(item) => format!("vec_{}", mangled_type(item)),
+        Type::Optional(item) => format!("opt_{}", mangled_type(item)),
+        Type::Function(args, result) => {
+            let args: Vec<String> = args.iter().map(mangled_type).collect();
+            format!("fn_{}_{}", args.join("_"), mangled_type(result))
+        }
+        Type::Impl(trait_) => format!("impl_{trait_}"),
+        Type::Dyn(trait_) => format!("dyn_{trait_}"),
+        Type::Named(name, arguments) => mangle(name, arguments),
     }
-    dbg!(&x.lines);
-
-    for line in x.lines.clone() {
-        let mut token_iter = line.tokens.iter().peekable();
-        let mut semic = false;
-        while token_iter.peek().is_some() {
-            let token = token_iter.next().unwrap();
-            match token {
-                Token::If(_if) => {
-                    let condition = expression(this.clone().target_lang, _if.condition.clone());
-                    this.buffer.push_str(&format!("if ({condition})\n{{\n"));
-                }
-                Token::ElseIf(_if) => {
-                    let condition = expression(this.clone().target_lang, _if.condition.clone());
-                    this.buffer
-                        .push_str(&format!("else if ({condition})\n{{\n"));
-                }
-                Token::For(_for) => {
-                    let var_name = _for.var_name.clone();
-                    let iter_exp = expression(this.clone().target_lang, _for.iter_exp.clone());
-                    this.buffer
-                        .push_str(&format!("for(auto {var_name}:{iter_exp})\n{{\n"));
-                }
-                Token::Assign(_assign) => {
-                    let name = _assign.var.clone();
-                    let exp = expression(this.clone().target_lang, _assign.exp.clone());
-                    this.buffer.push_str(&format!("{name} = {exp};\n"));
-                }
-                Token::Return(_return) => {
-                    let exp = expression(this.clone().target_lang, _return.clone());
-                    this.buffer.push_str(&format!("return {exp};\n"));
-                }
-                Token::Yield(_yield) => {
-                    let exp = expression(this.clone().target_lang, _yield.clone());
-                    this.buffer
-                        .push_str(&format!("dfjfjfdjfndjfnjd.push_back({exp});\n"));
-                }
-                Token::Var(_var) => {
-                    let (name, exp) = (&_var.name, _var.exp.clone());
-                    this.buffer.push_str(&format!(
-                        "auto {name} = {};\n",
-                        expression(this.clone().target_lang, exp)
-                    ));
-                }
-                Token::End(_) => {
-                    this.buffer.push_str("}\n");
-                }
-                Token::Else(_) => {
-                    this.buffer.push_str("else{\n");
-                }
-                Token::Empty => {
-                    this.buffer.push_str("\n");
-                }
-                Token::Generic(s) => {
-                    this.buffer.push_str(&format!("{s} "));
-                    semic = true;
-                }
-                Token::OpenRoBr(_) => {
-                    this.buffer.push_str(&format!("("));
-                    semic = true;
-                }
-                Token::CloseRoBr(_) => {
-                    this.buffer.push_str(&format!(")"));
-                    semic = true;
-                }
-                Token::OpenSqBr(_) => {
-                    this.buffer.push_str(&format!("["));
-                }
-                Token::CloseSqBr(_) => {
-                    this.buffer.push_str(&format!("]"));
+}
+
+/// The name of a struct, enum or trait and the types of the values it holds,
+/// for traits the ones its functions take and return.
+fn declared(nt: &Token) -> Option<(&str, Vec<&Type>)> {
+    match nt {
+        Token::Struct(x) => Some((&x.name, x.fields.iter().map(|field| &field.type_).collect())),
+        Token::Enum(x) => Some((
+            &x.name,
+            x.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        )),
+        // `self` and the trait itself are known in its functions
+        Token::Trait(x) => Some((
+            &x.name,
+            (x.functions.iter())
+                .flat_map(|f| (f.arguments.iter().skip(1)).map(|arg| &arg.type_))
+                .chain(x.functions.iter().map(|f| &f.return_type))
+                .filter(|type_| type_.bound() != Some(&x.name))
+                .collect(),
+        )),
+        _ => None,
+    }
+}
+
+/// Adds `x` to `sorted` after the types it depends on. `path` holds the
+/// types that are waiting for `x`, to find types containing themselves.
+fn depend<'a>(x: &'a Token, types: &'a [Token], sorted: &mut Vec<Token>, path: &mut Vec<&'a str>) {
+    let Some((name, fields)) = declared(x) else {
+        return;
+    };
+    let name_of = |nt: &Token| declared(nt).map(|(name, _)| name.to_string());
+    if sorted.iter().any(|nt| name_of(nt).as_deref() == Some(name)) {
+        return;
+    }
+    if path.contains(&name) {
+        log!(
+            Error,
+            f(
+                "The type `{name}` contains itself through `{}`",
+                path.join("`, `")
+            )
+        );
+        return;
+    }
+    path.push(name);
+    for type_ in fields {
+        for word in type_.names() {
+            if let Some(dependency) = types.iter().find(|nt| name_of(nt).as_deref() == Some(word)) {
+                depend(dependency, types, sorted, path);
+            }
+        }
+    }
+    path.pop();
+    if !sorted.iter().any(|nt| name_of(nt).as_deref() == Some(name)) {
+        sorted.push(x.clone());
+    }
+}
+
+/// The expressions held by a token of a function body.
+fn expressions(token: &mut Token) -> Vec<&mut Expression> {
+    match token {
+        Token::If(x) | Token::ElseIf(x) => vec![&mut x.condition],
+        Token::While(x) => vec![&mut x.condition],
+        Token::For(x) => vec![&mut x.iter_exp],
+        Token::Var(x) => vec![&mut x.exp],
+        Token::Assign(x) => vec![&mut x.exp],
+        Token::Return(exp) | Token::Yield(exp) | Token::YieldFrom(exp) => vec![exp],
+        _ => vec![],
+    }
+}
+
+/// A struct literal `Name { field: value, ... }` in an expression.
+pub struct Literal {
+    pub name: String,
+    pub fields: Vec<(String, Expression)>,
+}
+
+/// The struct literal starting at `exp[i]` and the index of its `}`.
+pub fn literal(exp: &[Token], i: usize) -> Option<(Literal, usize)> {
+    let name = match (exp.get(i), exp.get(i + 1)) {
+        (Some(Token::ExpVal(name)), Some(Token::ExpVal(open)))
+            if open == "{" && name.starts_with(|c: char| c.is_alphabetic() || c == '_') =>
+        {
+            name
+        }
+        _ => return None,
+    };
+    let mut fields = vec![];
+    let mut field: Option<(String, Expression)> = None;
+    let mut depth = 0;
+    for (j, t) in exp.iter().enumerate().skip(i + 2) {
+        match t {
+            Token::ExpVal(s) if depth == 0 && (s == "," || s == "}") => {
+                fields.extend(field.take());
+                if s == "}" {
+                    let name = name.clone();
+                    return Some((Literal { name, fields }, j));
                 }
-                _ => {
-                    log!(Error, "Unexpected Token");
-                    dbg!(&token);
+            }
+            Token::ExpVal(s) if depth == 0 && field.is_none() => {
+                let name = s.strip_suffix(':').filter(|name| !name.is_empty())?;
+                field = Some((name.to_string(), vec![]));
+            }
+            t => {
+                if let Token::ExpVal(s) = t {
+                    match s.as_str() {
+                        "(" | "[" | "{" => depth += 1,
+                        ")" | "]" | "}" => depth -= 1,
+                        _ => {}
+                    }
+                }
+                field.as_mut()?.1.push(t.clone());
+            }
+        }
+    }
+    None
+}
+
+/// Puts the fields of the struct literals in `exp` in the order the struct
+/// declares them, errors are logged and leave the literal as it is.
+fn ordered(exp: &mut Expression, structs: &[Struct]) {
+    let value = |s: &str| Token::ExpVal(s.to_string());
+    let mut i = 0;
+    while i < exp.len() {
+        // `value {` of a `match` isn't a literal
+        if let Some(open) = arms(exp, i) {
+            i = open + 1;
+            continue;
+        }
+        let Some((mut literal, end)) = literal(exp, i) else {
+            i += 1;
+            continue;
+        };
+        for (_, value) in literal.fields.iter_mut() {
+            ordered(value, structs);
+        }
+        let name = &literal.name;
+        let Some(x) = structs.iter().find(|x| x.name == *name) else {
+            log!(Error, f("Unknown struct `{name}`"));
+            i = end + 1;
+            continue;
+        };
+        let mut tokens = vec![value(name), value("{")];
+        for field in &x.fields {
+            match literal.fields.iter().position(|(f, _)| *f == field.name) {
+                Some(at) => {
+                    let (f, mut value_) = literal.fields.remove(at);
+                    if tokens.len() > 2 {
+                        tokens.push(value(","));
+                    }
+                    tokens.push(value(&format!("{f}:")));
+                    tokens.append(&mut value_);
+                }
+                None => {
+                    log!(
+                        Error,
+                        f("Missing field `{}` in the `{name}` literal", field.name)
+                    );
                 }
             }
         }
-        if semic {
-            this.buffer.push_str(";\n");
+        for (f, _) in &literal.fields {
+            log!(Error, f("The struct `{name}` has no field `{f}`"));
         }
+        tokens.push(value("}"));
+        let len = tokens.len();
+        exp.splice(i..=end, tokens);
+        i += len;
     }
+}
 
-    match type_ {
-        FunctionType::Loop => this.buffer.push_str("} while (1);\n}\n"),
-        FunctionType::Function => this.buffer.push_str("}\n"),
-        FunctionType::Generator => this.buffer.push_str("return dfjfjfdjfndjfnjd;\n}\n"),
+/// Checks the enum variants in `exp` against their declaration. The variants
+/// of enums holding values become calls, `Shape::Empty` is written like
+/// `Shape::Circle(1.5)` as `Shape::Empty()`. Names of other enums are left to
+/// the target, e.g. `std::` in C++.
+fn variants(exp: &mut Expression, enums: &[Enum]) {
+    let mut i = 0;
+    while i < exp.len() {
+        let Token::ExpVal(s) = &exp[i] else {
+            i += 1;
+            continue;
+        };
+        let Some((name, variant)) = s.split_once("::").filter(|_| !s.starts_with('"')) else {
+            i += 1;
+            continue;
+        };
+        let Some(x) = enums.iter().find(|x| x.name == name) else {
+            i += 1;
+            continue;
+        };
+        let Some(declared) = x.variants.iter().find(|v| v.name == variant) else {
+            log!(Error, f("The enum `{name}` has no variant `{variant}`"));
+            i += 1;
+            continue;
+        };
+        let values = match exp.get(i + 1) {
+            Some(Token::ExpVal(open)) if open == "(" => Some(arguments(exp, i + 1)),
+            _ => None,
+        };
+        let count = values.unwrap_or(0);
+        if count != declared.fields.len() {
+            log!(
+                Error,
+                f(
+                    "`{name}::{variant}` holds {} values but got {count}",
+                    declared.fields.len()
+                )
+            );
+        }
+        if values.is_none() && !x.c_like() {
+            let value = |s: &str| Token::ExpVal(s.to_string());
+            exp.splice(i + 1..i + 1, [value("("), value(")")]);
+        }
+        i += 1;
     }
 }
 
-pub fn expression(_this: TranspilerLang, exp: Expression) -> String {
-    let mut string = String::new();
+/// Checks the calls of the functions of `impl`s in `exp` and writes them the
+/// way the target calls them, see [`call`]. In Lua the literals of structs
+/// with methods get the metatable holding them.
+fn methods(exp: &mut Expression, structs: &[Struct], impls: &[Impl], lang: &TranspilerLang) {
+    let value = |s: &str| Token::ExpVal(s.to_string());
+    // the `{` of the arms of a `match`, the value before it isn't a literal
+    let mut arms_at = None;
+    let mut i = 0;
+    while i < exp.len() {
+        let Token::ExpVal(s) = &exp[i] else {
+            i += 1;
+            continue;
+        };
+        if s.starts_with('"') {
+            i += 1;
+            continue;
+        }
+        if let Some(open) = arms(exp, i) {
+            arms_at = Some(open);
+        }
+        let called = matches!(exp.get(i + 1), Some(Token::ExpVal(open)) if open == "(");
+        if let Some(s) = call(s, called, structs, impls, lang) {
+            exp[i] = value(&s);
+        }
+        let wrapped = match literal(exp, i) {
+            Some((literal, end)) if matches!(lang, TranspilerLang::Lua) => (arms_at != Some(i + 1)
+                && impls.iter().any(|x| x.name == literal.name))
+            .then_some((literal.name, end)),
+            _ => None,
+        };
+        if let Some((name, end)) = wrapped {
+            exp.splice(end + 1..end + 1, [value(","), value(&name), value(")")]);
+            exp.splice(i..i, [value("setmetatable"), value("(")]);
+            i += 2;
+        }
+        i += 1;
+    }
+}
+
+/// The value `s` the way the target writes it, when it is a function of an
+/// `impl`, see [`associated`]. In Lua `p.len()` becomes `p:len()`. `None`
+/// leaves `s` as it is, errors are logged.
+fn call(
+    s: &str,
+    called: bool,
+    structs: &[Struct],
+    impls: &[Impl],
+    lang: &TranspilerLang,
+) -> Option<String> {
+    if let Some((name, function)) = s.split_once("::") {
+        if !structs.iter().any(|x| x.name == name) {
+            return None;
+        }
+        let found = (impls.iter())
+            .filter(|x| x.name == name)
+            .flat_map(|x| x.functions.iter())
+            .find(|x| x.name == function);
+        match found {
+            Some(x) if x.method() => {
+                log!(
+                    Error,
+                    f("`{s}` takes `self`, call it like `value.{function}()`")
+                );
+            }
+            Some(_) => return Some(associated(name, function, lang)),
+            None => {
+                log!(Error, f("The struct `{name}` has no function `{function}`"));
+            }
+        }
+        return None;
+    }
+    let (receiver, method) = s.rsplit_once('.')?;
+    let mut methods = impls.iter().flat_map(|x| x.functions.iter());
+    match lang {
+        TranspilerLang::Lua if called && methods.any(|x| x.method() && x.name == method) => {
+            Some(format!("{receiver}:{method}"))
+        }
+        _ => None,
+    }
+}
+
+/// How the target calls the function `function` of the `impl` of `name`:
+/// `Point::origin()` is `Point.origin()` in Java, C# and Lua and
+/// `PointOrigin()` in Go.
+pub fn associated(name: &str, function: &str, lang: &TranspilerLang) -> String {
+    match lang {
+        TranspilerLang::Go => {
+            let mut chars = function.chars();
+            let first = chars.next().map(|c| c.to_uppercase().to_string());
+            format!("{name}{}{}", first.unwrap_or_default(), chars.as_str())
+        }
+        TranspilerLang::Java | TranspilerLang::CSharp | TranspilerLang::Lua => {
+            format!("{name}.{function}")
+        }
+        _ => format!("{name}::{function}"),
+    }
+}
+
+/// The traits the struct `name` implements.
+pub fn implemented<'a>(ast: &'a [Token], name: &'a str) -> Vec<&'a str> {
+    (ast.iter())
+        .filter_map(|nt| match nt {
+            Token::Impl(x) if x.name == name => x.trait_.as_deref(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The types of the arguments, return values, fields and variants of the
+/// program.
+pub fn declared_types(ast: &[Token]) -> Vec<&Type> {
+    fn in_function(x: &Function) -> Vec<&Type> {
+        (x.arguments.iter().map(|arg| &arg.type_))
+            .chain([&x.return_type])
+            .collect()
+    }
+    ast.iter()
+        .flat_map(|nt| match nt {
+            Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) => {
+                in_function(x)
+            }
+            Token::Impl(Impl { functions, .. }) | Token::Trait(Trait { functions, .. }) => {
+                functions.iter().flat_map(in_function).collect()
+            }
+            Token::Struct(x) => x.fields.iter().map(|field| &field.type_).collect(),
+            Token::Enum(x) => x.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+            _ => vec![],
+        })
+        .collect()
+}
+
+/// Whether a type of the program is `dyn name`, which needs the values
+/// implementing the trait to be callable through it.
+pub fn dyn_used(ast: &[Token], name: &str) -> bool {
+    declared_types(ast).into_iter().any(|type_| {
+        let mut used = false;
+        type_.visit(&mut |type_| used |= matches!(type_, Type::Dyn(trait_) if trait_ == name));
+        used
+    })
+}
+
+/// The functions of the `impl`s of the struct `name`.
+pub fn functions<'a>(ast: &'a [Token], name: &'a str) -> impl Iterator<Item = &'a Function> {
+    ast.iter()
+        .filter_map(move |nt| match nt {
+            Token::Impl(x) if x.name == name => Some(x.functions.iter()),
+            _ => None,
+        })
+        .flatten()
+}
+
+/// A `match` in an expression, the values of the arms are kept as tokens.
+pub struct Match {
+    pub value: Expression,
+    pub arms: Vec<(Pattern, Expression)>,
+    /// whether the variants matched hold values, [`types`] writes them with
+    /// brackets then
+    pub holds: bool,
+}
+
+/// The index of the `{` opening the arms of the `match` at `exp[i]`.
+fn arms(exp: &[Token], i: usize) -> Option<usize> {
+    if !matches!(exp.get(i), Some(Token::ExpVal(s)) if s == "match") {
+        return None;
+    }
+    let mut depth = 0;
+    for (j, t) in exp.iter().enumerate().skip(i + 1) {
+        let Token::ExpVal(s) = t else {
+            continue;
+        };
+        match s.as_str() {
+            "{" if depth == 0 => return Some(j),
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The `match` starting at `exp[i]` and the index of its `}`.
+pub fn match_(exp: &[Token], i: usize) -> Option<(Match, usize)> {
+    split(exp, i).ok()
+}
+
+fn split(exp: &[Token], i: usize) -> Result<(Match, usize), String> {
+    let open = arms(exp, i).ok_or("Expected the arms of the `match` in `{ }`")?;
+    // lines ending in a bracket leave an empty value behind
+    let empty = |t: &&Token| matches!(t, Token::ExpVal(s) if s.is_empty());
+    let value = exp[i + 1..open]
+        .iter()
+        .filter(|t| !empty(t))
+        .cloned()
+        .collect();
+    let mut arms = vec![];
+    let mut holds = false;
+    let mut pattern: Option<Pattern> = None;
+    let mut tokens: Expression = vec![];
+    let mut depth = 0;
+    for (j, t) in exp.iter().enumerate().skip(open + 1) {
+        if empty(&t) {
+            continue;
+        }
+        if let Token::ExpVal(s) = t {
+            match s.as_str() {
+                "=>" if depth == 0 && pattern.is_none() => {
+                    holds |= tokens
+                        .windows(2)
+                        .any(|w| matches!(&w[1], Token::ExpVal(s) if s == "("));
+                    pattern = Some(tree::pattern(&tokens)?);
+                    tokens.clear();
+                    continue;
+                }
+                "," | "}" if depth == 0 => {
+                    match pattern.take() {
+                        Some(pattern) => arms.push((pattern, std::mem::take(&mut tokens))),
+                        None if tokens.is_empty() => {}
+                        None => return Err("Expected `=>` after the pattern".to_string()),
+                    }
+                    if s == "}" {
+                        let m = Match { value, arms, holds };
+                        return Ok((m, j));
+                    }
+                    continue;
+                }
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth -= 1,
+                _ => {}
+            }
+        }
+        tokens.push(t.clone());
+    }
+    Err("Expected `}` at the end of the `match`".to_string())
+}
+
+/// Whether `exp` reads the variable `name`, or one of its fields.
+pub fn uses(exp: &Expression, name: &str) -> bool {
+    exp.iter().any(|t| match t {
+        Token::ExpVal(s) => s
+            .strip_prefix(name)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.')),
+        _ => false,
+    })
+}
+
+/// Whether the body of `x` reads the variable `name`, see [`uses`].
+pub fn reads(x: &Function, name: &str) -> bool {
+    read(x.lines.iter().flat_map(|line| line.tokens.iter()), name)
+}
+
+/// Whether the body of the `for` loop on the line `ln` of `x` reads the
+/// variable `name`.
+pub fn loop_reads(x: &Function, ln: usize, name: &str) -> bool {
+    let tokens = x.lines[ln..].iter().flat_map(|line| line.tokens.iter());
+    let mut tokens = tokens.skip_while(|t| !matches!(t, Token::For(_)));
+    let Some(Token::For(for_)) = tokens.next() else {
+        return false;
+    };
+    // loops next to each other share their id, the first `}` closes this one
+    let body = tokens.take_while(|t| !matches!(t, Token::End(br) if br.id == for_.id));
+    read(body, name)
+}
+
+fn read<'a>(mut tokens: impl Iterator<Item = &'a Token>, name: &str) -> bool {
+    tokens.any(|t| match t.clone() {
+        Token::Generic(s) => uses(&vec![Token::ExpVal(s)], name),
+        mut t => expressions(&mut t).into_iter().any(|exp| uses(exp, name)),
+    })
+}
+
+/// The adapters of `|>` pipelines the program calls without defining them,
+/// out of `map`, `filter` and `take`.
+pub fn adapters(ast: &[Token]) -> Vec<&'static str> {
+    undefined(ast, &["map", "filter", "take"])
+}
+
+/// Whether the program calls the builtin `print`, which targets without one
+/// define.
+pub fn prints(ast: &[Token]) -> bool {
+    !undefined(ast, &["print"]).is_empty()
+}
+
+/// The functions out of `names` the program calls without defining them.
+fn undefined(ast: &[Token], names: &[&'static str]) -> Vec<&'static str> {
+    let mut functions: Vec<&Function> = vec![];
+    let mut exps: Vec<&Expression> = vec![];
+    for nt in ast {
+        match nt {
+            Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) => {
+                functions.push(x);
+            }
+            Token::Impl(x) => functions.extend(&x.functions),
+            Token::Const(x) | Token::Global(x) => exps.push(&x.exp),
+            _ => {}
+        }
+    }
+    let defined = |name: &str| functions.iter().any(|x| x.name == name);
+    let called = |name: &str| {
+        functions.iter().any(|x| reads(x, name)) || exps.iter().any(|exp| uses(exp, name))
+    };
+    (names.iter().copied())
+        .filter(|name| called(name) && !defined(name))
+        .collect()
+}
+
+/// Checks the patterns of the `match`es in `exp`, errors are logged.
+fn exhaustive(exp: &Expression, enums: &[Enum]) {
+    for i in 0..exp.len() {
+        if arms(exp, i).is_none() {
+            continue;
+        }
+        let checked = split(exp, i).and_then(|(m, _)| {
+            let patterns = m.arms.iter().map(|(pattern, _)| pattern);
+            tree::exhaustive(patterns, |name| {
+                let x = enums.iter().find(|x| x.name == name)?;
+                Some(
+                    x.variants
+                        .iter()
+                        .map(|v| (v.name.clone(), v.fields.len()))
+                        .collect(),
+                )
+            })
+        });
+        if let Err(e) = checked {
+            log!(Error, f("{e}"));
+        }
+    }
+}
+
+/// The number of comma separated values in the brackets opened at
+/// `exp[open]`.
+fn arguments(exp: &[Token], open: usize) -> usize {
+    let mut depth = 0;
+    let mut commas = 0;
+    for (i, t) in exp.iter().enumerate().skip(open) {
+        let Token::ExpVal(s) = t else {
+            continue;
+        };
+        match s.as_str() {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    // `()` holds nothing
+                    return if i == open + 1 { 0 } else { commas + 1 };
+                }
+            }
+            "," if depth == 1 => commas += 1,
+            _ => {}
+        }
+    }
+    0
+}
+
+/// Guesses the lang type of an expression made up of literals, for targets
+/// that need a type where lang lets it be inferred (e.g. class fields).
+pub fn literal_type(exp: &Expression) -> Option<Type> {
+    let mut type_ = None;
     for x in exp {
         match x {
+            Token::Operator(
+                Operator::Equals
+                | Operator::NotEquals
+                | Operator::Less
+                | Operator::Greater
+                | Operator::LessEquals
+                | Operator::GreaterEquals
+                | Operator::And
+                | Operator::Or,
+            ) => {
+                return Some(Type::BOOL);
+            }
+            Token::Operator(_) => {}
+            Token::ExpVal(s) if s.starts_with('"') => type_ = Some(Type::Primitive(Primitive::Str)),
+            Token::ExpVal(s) if s == "true" || s == "false" => type_ = Some(Type::BOOL),
+            Token::ExpVal(s) if s.parse::<i64>().is_ok() => {
+                if type_.is_none() {
+                    type_ = Some(Type::INT);
+                }
+            }
+            Token::ExpVal(s) if s.parse::<f64>().is_ok() => {
+                type_ = Some(Type::Primitive(Primitive::F64))
+            }
+            _ => return None,
+        }
+    }
+    type_
+}
+
+/// The token following the current one in a function body. `peeked` is the
+/// next token on line `ln`; a closing brace often ends its line, so at the
+/// end of a line this looks at the start of the next one.
+pub fn next_token<'a>(x: &'a Function, ln: usize, peeked: Option<&'a Token>) -> Option<&'a Token> {
+    peeked.or_else(|| x.lines.get(ln + 1).and_then(|l| l.tokens.first()))
+}
+
+/// The id of the `state` block a `loop fn` starts with. Its statements run
+/// once, the loop starts at the `}` closing it, the `End` with the same id.
+pub fn state_block(x: &Function) -> Option<i32> {
+    match x.lines.iter().flat_map(|line| line.tokens.iter()).next() {
+        Some(Token::State(br)) => Some(br.id),
+        _ => None,
+    }
+}
+
+/// The labels jumped to by the labelled `break`s and `continue`s of a
+/// function. Go rejects labels nobody jumps to, and the targets without
+/// labelled jumps only place the `goto` labels that are used.
+pub fn jump_labels(x: &Function) -> (Vec<String>, Vec<String>) {
+    let mut breaks = vec![];
+    let mut continues = vec![];
+    for token in x.lines.iter().flat_map(|line| line.tokens.iter()) {
+        match token {
+            Token::Break(Some(label)) => breaks.push(label.clone()),
+            Token::Continue(Some(label)) => continues.push(label.clone()),
+            _ => {}
+        }
+    }
+    (breaks, continues)
+}
+
+/// A counted loop over a range: it starts at `first` and runs while the
+/// counter compares with `bound` like `cmp`, going up by `step`. `None` steps
+/// by 1. A reversed range starts one step above its first value and goes
+/// `down` at the start of each round, so unsigned counters never wrap around
+/// below zero.
+pub struct Counted {
+    pub first: Expression,
+    pub cmp: &'static str,
+    pub bound: Expression,
+    pub step: Option<Expression>,
+    pub down: bool,
+}
+
+pub fn counted(range: &Range) -> Counted {
+    if !range.rev {
+        return Counted {
+            first: range.start.clone(),
+            cmp: if range.inclusive { "<=" } else { "<" },
+            bound: range.end.clone(),
+            step: range.step.clone(),
+            down: false,
+        };
+    }
+    let one = vec![Token::ExpVal("1".to_string())];
+    let step = range.step.as_ref().unwrap_or(&one);
+    Counted {
+        first: match range.inclusive {
+            true => plus(&range.end, step),
+            false => plus(&range.end, &minus_one(step)),
+        },
+        cmp: if range.step.is_some() { ">=" } else { ">" },
+        bound: match &range.step {
+            Some(step) => plus(&range.start, step),
+            None => range.start.clone(),
+        },
+        step: range.step.clone(),
+        down: true,
+    }
+}
+
+/// `exp + n`, calculated right away for numbers.
+fn plus(exp: &Expression, n: &Expression) -> Expression {
+    let number = |exp: &Expression| match exp.as_slice() {
+        [Token::ExpVal(n)] => n.parse::<i64>().ok(),
+        _ => None,
+    };
+    match (number(exp), number(n)) {
+        (Some(a), Some(b)) => vec![Token::ExpVal((a + b).to_string())],
+        (_, Some(0)) => exp.clone(),
+        _ => {
+            let mut exp = bracketed(exp);
+            exp.push(Token::Operator(Operator::Plus));
+            exp.extend(bracketed(n));
+            exp
+        }
+    }
+}
+
+/// `exp - 1`, calculated right away for numbers.
+pub fn minus_one(exp: &Expression) -> Expression {
+    let value = |s: &str| Token::ExpVal(s.to_string());
+    if let [Token::ExpVal(n)] = exp.as_slice() {
+        if let Ok(n) = n.parse::<i64>() {
+            return vec![value(&(n - 1).to_string())];
+        }
+    }
+    let mut exp = bracketed(exp);
+    exp.push(Token::Operator(Operator::Minus));
+    exp.push(value("1"));
+    exp
+}
+
+/// The expression in brackets, unless it is a single value.
+pub fn bracketed(exp: &Expression) -> Expression {
+    if exp.len() == 1 {
+        return exp.clone();
+    }
+    let mut bracketed = vec![Token::ExpVal("(".to_string())];
+    bracketed.extend_from_slice(exp);
+    bracketed.push(Token::ExpVal(")".to_string()));
+    bracketed
+}
+
+/// Rewrites the pipes of the call standing on its own `line`, its words are
+/// made into an expression and back.
+fn piped_call(line: &mut Line) {
+    if !(line.tokens.iter()).any(|t| matches!(t, Token::Generic(s) if s == "|>")) {
+        return;
+    }
+    let Some(br) = line.tokens.iter().find_map(|t| match t {
+        Token::OpenRoBr(br) => Some(br.clone()),
+        _ => None,
+    }) else {
+        return;
+    };
+    let tokens = tree::tokens(std::slice::from_ref(line), 0);
+    let [(_, Token::Expression(exp))] = tokens.as_slice() else {
+        return;
+    };
+    let Some(exp) = pipes(exp) else {
+        return;
+    };
+    line.tokens = exp
+        .into_iter()
+        .map(|t| match t {
+            Token::ExpVal(s) => match s.as_str() {
+                "(" => Token::OpenRoBr(br.clone()),
+                ")" => Token::CloseRoBr(br.clone()),
+                "[" => Token::OpenSqBr(br.clone()),
+                "]" => Token::CloseSqBr(br.clone()),
+                "," => Token::Comma,
+                _ => Token::Generic(s),
+            },
+            Token::Operator(o) => Token::Generic(check::symbol(&o).to_string()),
+            t => t,
+        })
+        .collect();
+}
+
+/// The expression with `|>` rewritten into calls, `None` when it has no
+/// pipes. See [`tree::pipes`].
+pub fn pipes(exp: &[Token]) -> Option<Expression> {
+    let piped = exp
+        .iter()
+        .any(|t| matches!(t, Token::Operator(Operator::Pipe)));
+    if !piped {
+        return None;
+    }
+    match tree::pipes(exp) {
+        Ok(exp) => Some(exp),
+        Err(e) => {
+            log!(Error, f("{e}"));
+            None
+        }
+    }
+}
+
+pub fn expression(lang: TranspilerLang, exp: Expression) -> String {
+    if let Some(exp) = pipes(&exp) {
+        return expression(lang, exp);
+    }
+    match lang {
+        TranspilerLang::Go => return go::expression(&exp, 1),
+        TranspilerLang::Lua => return lua::expression(&exp, &[]),
+        _ => {}
+    }
+    let mut string = String::new();
+    let mut exp_iter = exp.iter().enumerate().peekable();
+    while let Some((i, x)) = exp_iter.next() {
+        if let Some((m, end)) = match_(&exp, i) {
+            string.push_str(&match lang {
+                TranspilerLang::Cxx => cxx::match_(m),
+                TranspilerLang::Java => java::match_(m),
+                _ => csharp::match_(m),
+            });
+            while exp_iter.peek().is_some_and(|(j, _)| *j <= end) {
+                exp_iter.next();
+            }
+            continue;
+        }
+        if let Some((literal, end)) = literal(&exp, i) {
+            string.push_str(&struct_literal(&lang, literal));
+            while exp_iter.peek().is_some_and(|(j, _)| *j <= end) {
+                exp_iter.next();
+            }
+            continue;
+        }
+        match x {
+            // `Enum.Variant`, the variants holding values are classes
+            Token::ExpVal(s)
+                if s.contains("::")
+                    && matches!(lang, TranspilerLang::Java | TranspilerLang::CSharp) =>
+            {
+                if matches!(exp.get(i + 1), Some(Token::ExpVal(open)) if open == "(") {
+                    string.push_str("new ");
+                }
+                string.push_str(&s.replace("::", "."));
+            }
             Token::Operator(o) => match o {
                 Operator::Plus => {
                     string.push_str(&format!("+"));
@@ -221,9 +1555,27 @@ pub fn expression(_this: TranspilerLang, exp: Expression) -> String {
                 Operator::BitShiftRight => {
                     string.push_str(&format!(">>"));
                 }
+                Operator::Mod => {
+                    string.push('%');
+                }
                 Operator::Equals => {
                     string.push_str(&format!("=="));
                 }
+                Operator::NotEquals => {
+                    string.push_str("!=");
+                }
+                Operator::Less => {
+                    string.push('<');
+                }
+                Operator::Greater => {
+                    string.push('>');
+                }
+                Operator::LessEquals => {
+                    string.push_str("<=");
+                }
+                Operator::GreaterEquals => {
+                    string.push_str(">=");
+                }
                 Operator::And => {
                     string.push_str(&format!("&&"));
                 }
@@ -236,12 +1588,13 @@ pub fn expression(_this: TranspilerLang, exp: Expression) -> String {
                 Operator::BitOr => {
                     string.push_str(&format!("|"));
                 }
+                // in strings, or when rewriting the pipes failed
                 Operator::Pipe => {
-                    log!(CXX, "Operator::Pipe : not yet implemented");
+                    string.push_str("|>");
                 }
             },
             Token::ExpVal(s) => {
-                string.push_str(&s);
+                string.push_str(s);
             }
             _ => {
                 log!(Error, "Unexpected token in Expression");
@@ -250,3 +1603,28 @@ pub fn expression(_this: TranspilerLang, exp: Expression) -> String {
     }
     string
 }
+
+/// A struct literal for C++, Java and C#. The fields are in the order they
+/// are declared in, see [`types`].
+fn struct_literal(lang: &TranspilerLang, literal: Literal) -> String {
+    let Literal { name, fields } = literal;
+    let fields = fields
+        .into_iter()
+        .map(|(field, value)| (field, expression(lang.clone(), value)));
+    match lang {
+        // designated initializers, C++20
+        TranspilerLang::Cxx => {
+            let fields: Vec<String> = fields.map(|(f, v)| format!(".{f} = {v}")).collect();
+            format!("{name}{{{}}}", fields.join(", "))
+        }
+        // the constructor takes the fields in order
+        TranspilerLang::Java => {
+            let values: Vec<String> = fields.map(|(_, v)| v).collect();
+            format!("new {name}({})", values.join(", "))
+        }
+        _ => {
+            let fields: Vec<String> = fields.map(|(f, v)| format!("{f} = {v}")).collect();
+            format!("new {name} {{ {} }}", fields.join(", "))
+        }
+    }
+}
@@ -0,0 +1,853 @@
+use {
+    super::*,
+    crate::lexer::tree::{self, Expr, Iterable, Stmt},
+    std::collections::HashMap,
+};
+
+pub type Wat = Transpiler;
+pub fn new() -> Wat {
+    Transpiler {
+        buffer: String::new(),
+        imports: vec![],
+        target_lang: TranspilerLang::Wat,
+        globals: HashMap::new(),
+    }
+}
+
+/// Module host functions are imported from, when a function is called that
+/// isn't defined in the lang code (e.g. `print`).
+pub const HOST_MODULE: &str = "env";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValType {
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+impl ValType {
+    fn name(self) -> &'static str {
+        match self {
+            ValType::I32 => "i32",
+            ValType::I64 => "i64",
+            ValType::F32 => "f32",
+            ValType::F64 => "f64",
+        }
+    }
+    fn is_int(self) -> bool {
+        matches!(self, ValType::I32 | ValType::I64)
+    }
+}
+
+/// Maps a lang type onto a wasm value type, `void` maps to no value.
+pub fn val_type(type_: &Type) -> Result<Option<ValType>, String> {
+    let unsupported = || format!("type `{type_}` is not supported by the WAT backend");
+    match type_.primitive().ok_or_else(unsupported)? {
+        Primitive::Void => Ok(None),
+        Primitive::I64 | Primitive::U64 => Ok(Some(ValType::I64)),
+        Primitive::F32 => Ok(Some(ValType::F32)),
+        Primitive::F64 => Ok(Some(ValType::F64)),
+        Primitive::Str => Err(unsupported()),
+        _ => Ok(Some(ValType::I32)),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Signature {
+    params: Vec<ValType>,
+    result: Option<ValType>,
+}
+
+impl Signature {
+    fn wat(&self) -> String {
+        let mut s = String::new();
+        for param in &self.params {
+            s.push_str(&format!(" (param {})", param.name()));
+        }
+        if let Some(result) = self.result {
+            s.push_str(&format!(" (result {})", result.name()));
+        }
+        s
+    }
+}
+
+#[derive(Default)]
+struct Module {
+    functions: HashMap<String, Signature>,
+    /// type and mutability
+    globals: HashMap<String, (ValType, bool)>,
+    /// host functions in the order they were first called
+    host: Vec<(String, Signature)>,
+}
+
+pub fn transpile(this: &mut Transpiler, ast: Vec<Token>) {
+    let mut module = Module::default();
+    let mut globals = String::new();
+    let mut functions = String::new();
+
+    // signatures first, so functions can call the ones defined after them
+    for nt in ast.iter() {
+        if let Token::Function(x) | Token::LoopFunction(x) = nt {
+            match signature(x) {
+                Ok(sig) => {
+                    module.functions.insert(x.name.clone(), sig);
+                }
+                Err(e) => {
+                    log!(WAT, f("Skipping function `{}`: {e}", x.name));
+                }
+            }
+        }
+    }
+
+    let mut ast_iter = ast.iter().peekable();
+    while ast_iter.peek().is_some() {
+        let nt = ast_iter.next().unwrap();
+        match nt {
+            Token::Const(x) | Token::Global(x) => {
+                let mutable = matches!(nt, Token::Global(_));
+                match global(&x.name, &x.exp, mutable) {
+                    Ok((wat, type_)) => {
+                        module.globals.insert(x.name.clone(), (type_, mutable));
+                        globals.push_str(&wat);
+                    }
+                    Err(e) => {
+                        log!(WAT, f("Skipping `{}`: {e}", x.name));
+                    }
+                }
+            }
+            Token::Function(x) | Token::LoopFunction(x) => {
+                if !module.functions.contains_key(&x.name) {
+                    continue;
+                }
+                let type_ = match nt {
+                    Token::LoopFunction(_) => FunctionType::Loop,
+                    _ => FunctionType::Function,
+                };
+                match function(&mut module, x, type_) {
+                    Ok(wat) => functions.push_str(&wat),
+                    Err(e) => {
+                        log!(WAT, f("Skipping function `{}`: {e}", x.name));
+                        module.functions.remove(&x.name);
+                    }
+                }
+            }
+            Token::GeneratorFunction(x) => {
+                log!(
+                    WAT,
+                    f(
+                        "Skipping `gen fn {}` at line {}: generator functions are not supported by the WAT backend",
+                        x.name,
+                        x.start_ln
+                    )
+                );
+            }
+            Token::Struct(x) => {
+                log!(
+                    WAT,
+                    f(
+                        "Skipping `struct {}` at line {}: structs are not supported by the WAT backend",
+                        x.name,
+                        x.line
+                    )
+                );
+            }
+            Token::Enum(x) => {
+                log!(
+                    WAT,
+                    f(
+                        "Skipping `enum {}` at line {}: enums are not supported by the WAT backend",
+                        x.name,
+                        x.line
+                    )
+                );
+            }
+            Token::Impl(x) => {
+                log!(
+                    WAT,
+                    f(
+                        "Skipping `impl {}` at line {}: methods are not supported by the WAT backend",
+                        x.name,
+                        x.line
+                    )
+                );
+            }
+            Token::Trait(x) => {
+                log!(
+                    WAT,
+                    f(
+                        "Skipping `trait {}` at line {}: methods are not supported by the WAT backend",
+                        x.name,
+                        x.line
+                    )
+                );
+            }
+            Token::Import(i) => {
+                log!(WAT, f("Skipping import {i}"));
+            }
+            Token::CImport(ci) => {
+                log!(WAT, f("Skipping C include {ci}"));
+            }
+            Token::Comment(_) => {}
+            _ => {
+                log!(Error, f("unexpected Token {nt:?}"));
+            }
+        }
+    }
+
+    // imports have to come before any definition
+    this.buffer.push_str("(module\n");
+    for (name, sig) in &module.host {
+        this.buffer.push_str(&format!(
+            "  (import \"{HOST_MODULE}\" \"{name}\" (func ${name}{}))\n",
+            sig.wat()
+        ));
+        if !this.imports.contains(name) {
+            this.imports.push(name.clone());
+        }
+    }
+    this.buffer.push_str(&globals);
+    this.buffer.push_str(&functions);
+    this.buffer.push_str(")\n");
+}
+
+fn signature(x: &Function) -> Result<Signature, String> {
+    let mut params = vec![];
+    for arg in &x.arguments {
+        match val_type(&arg.type_)? {
+            Some(type_) => params.push(type_),
+            None => return Err(format!("argument `{}` can't be `void`", arg.name)),
+        }
+    }
+    Ok(Signature {
+        params,
+        result: val_type(&x.return_type)?,
+    })
+}
+
+/// Globals have to be initialized with a constant.
+fn global(name: &str, exp: &Expression, mutable: bool) -> Result<(String, ValType), String> {
+    let (value, type_) = match tree::expression(exp)? {
+        Expr::Int(i) => (i.to_string(), int_type(i)),
+        Expr::Char(c) => ((c as i64).to_string(), int_type(c as i64)),
+        Expr::Float(f) => (format!("{f:?}"), ValType::F64),
+        Expr::Bool(b) => ((b as i32).to_string(), ValType::I32),
+        _ => return Err("globals have to be initialized with a number literal".to_string()),
+    };
+    let global_type = if mutable {
+        format!("(mut {})", type_.name())
+    } else {
+        type_.name().to_string()
+    };
+    Ok((
+        format!(
+            "  (global ${name} {global_type} ({}.const {value}))\n",
+            type_.name()
+        ),
+        type_,
+    ))
+}
+
+fn int_type(i: i64) -> ValType {
+    if i32::try_from(i).is_ok() {
+        ValType::I32
+    } else {
+        ValType::I64
+    }
+}
+
+struct Body<'a> {
+    module: &'a mut Module,
+    /// parameters followed by the locals, in declaration order
+    locals: Vec<(String, ValType)>,
+    result: Option<ValType>,
+    /// counter for unique block labels
+    labels: usize,
+    /// the loops the code is in, innermost last
+    loops: Vec<Loop>,
+    /// the label of the loop that starts next
+    pending_label: Option<String>,
+}
+
+/// The labels `continue` and `break` branch to.
+struct Loop {
+    label: Option<String>,
+    next: String,
+    end: String,
+}
+
+fn function(module: &mut Module, x: &Function, type_: FunctionType) -> Result<String, String> {
+    let sig = module.functions[&x.name].clone();
+    let stmts = match type_ {
+        FunctionType::Loop => tree::loop_function(x)?,
+        _ => tree::function(x)?,
+    };
+    let mut body = Body {
+        module,
+        locals: x
+            .arguments
+            .iter()
+            .map(|arg| arg.name.clone())
+            .zip(sig.params.iter().copied())
+            .collect(),
+        result: sig.result,
+        labels: 0,
+        loops: vec![],
+        pending_label: None,
+    };
+
+    let mut code = String::new();
+    let mut ends = type_ == FunctionType::Function;
+    match stmts.split_last() {
+        // the `state` block, followed by the loop
+        Some((Stmt::Loop(tick), state)) if type_ == FunctionType::Loop => {
+            body.block(state, 2, &mut code)?;
+            let mut depth = 2;
+            ends = tree::breaks(tick);
+            if ends {
+                code.push_str("    (block $tick.end\n");
+                depth += 1;
+            }
+            let indent = indent(depth);
+            code.push_str(&format!("{indent}(loop $tick\n"));
+            body.enter("$tick".to_string(), "$tick.end".to_string());
+            body.block(tick, depth + 1, &mut code)?;
+            body.loops.pop();
+            code.push_str(&format!("{indent}  (br $tick)\n{indent})\n"));
+            if ends {
+                code.push_str("    )\n");
+            }
+        }
+        _ => body.block(&stmts, 2, &mut code)?,
+    }
+    if let Some(result) = sig.result {
+        let returns = matches!(stmts.last(), Some(Stmt::Return(_)));
+        if x.name == "main" && ends && !returns {
+            // like in C++, falling off the end of `main` returns 0
+            code.push_str(&format!("    ({}.const 0)\n", result.name()));
+        } else if !returns {
+            code.push_str("    (unreachable)\n");
+        }
+    }
+
+    let name = &x.name;
+    let mut wat = format!("  (func ${name} (export \"{name}\")");
+    for (param, type_) in body.locals.iter().take(sig.params.len()) {
+        wat.push_str(&format!(" (param ${param} {})", type_.name()));
+    }
+    if let Some(result) = sig.result {
+        wat.push_str(&format!(" (result {})", result.name()));
+    }
+    wat.push('\n');
+    for (local, type_) in body.locals.iter().skip(sig.params.len()) {
+        wat.push_str(&format!("    (local ${local} {})\n", type_.name()));
+    }
+    wat.push_str(&code);
+    wat.push_str("  )\n");
+    Ok(wat)
+}
+
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
+
+impl Body<'_> {
+    fn local(&self, name: &str) -> Option<ValType> {
+        self.locals
+            .iter()
+            .find(|(local, _)| local == name)
+            .map(|(_, type_)| *type_)
+    }
+
+    fn block(&mut self, stmts: &[Stmt], depth: usize, code: &mut String) -> Result<(), String> {
+        for stmt in stmts {
+            self.stmt(stmt, depth, code)?;
+        }
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt, depth: usize, code: &mut String) -> Result<(), String> {
+        let indent = indent(depth);
+        match stmt {
+            Stmt::Let(name, exp) => {
+                let (value, type_) = self.expr(exp)?;
+                let value = match self.local(name) {
+                    Some(local) => convert(value, type_, local),
+                    None => {
+                        self.locals.push((name.clone(), type_));
+                        value
+                    }
+                };
+                code.push_str(&format!("{indent}(local.set ${name} {value})\n"));
+            }
+            Stmt::Assign(name, exp) => {
+                let (value, type_) = self.expr(exp)?;
+                if let Some(local) = self.local(name) {
+                    let value = convert(value, type_, local);
+                    code.push_str(&format!("{indent}(local.set ${name} {value})\n"));
+                } else if let Some((global, mutable)) = self.module.globals.get(name).copied() {
+                    if !mutable {
+                        return Err(format!("Can't assign to const `{name}`"));
+                    }
+                    let value = convert(value, type_, global);
+                    code.push_str(&format!("{indent}(global.set ${name} {value})\n"));
+                } else {
+                    return Err(format!("Unknown variable `{name}`"));
+                }
+            }
+            Stmt::If(branches, otherwise) => {
+                self.if_(branches, otherwise.as_deref(), depth, code)?;
+            }
+            Stmt::Loop(stmts) | Stmt::While(_, stmts) => {
+                let label = self.labels;
+                self.labels += 1;
+                code.push_str(&format!("{indent}(block $break{label}\n"));
+                code.push_str(&format!("{indent}  (loop $continue{label}\n"));
+                if let Stmt::While(condition, _) = stmt {
+                    let condition = self.condition(condition)?;
+                    code.push_str(&format!(
+                        "{indent}    (br_if $break{label} (i32.eqz {condition}))\n"
+                    ));
+                }
+                self.enter(format!("$continue{label}"), format!("$break{label}"));
+                self.block(stmts, depth + 2, code)?;
+                self.loops.pop();
+                code.push_str(&format!("{indent}    (br $continue{label})\n"));
+                code.push_str(&format!("{indent}  )\n{indent})\n"));
+            }
+            Stmt::Labeled(label, stmt) => {
+                self.pending_label = Some(label.clone());
+                self.stmt(stmt, depth, code)?;
+            }
+            Stmt::Break(label) => {
+                let end = &self.target(label)?.end;
+                code.push_str(&format!("{indent}(br {end})\n"));
+            }
+            Stmt::Continue(label) => {
+                let next = &self.target(label)?.next;
+                code.push_str(&format!("{indent}(br {next})\n"));
+            }
+            Stmt::For(for_) => match &for_.iter {
+                Iterable::Range(range) => self.range(for_, range, depth, code)?,
+                Iterable::Expr(_) => {
+                    return Err(
+                        "`for` loops over arrays and generators need iterators, which the WAT backend doesn't support"
+                            .to_string(),
+                    );
+                }
+            },
+            Stmt::Return(None) => match self.result {
+                Some(result) => {
+                    return Err(format!("`return` needs a `{}` value", result.name()));
+                }
+                None => code.push_str(&format!("{indent}(return)\n")),
+            },
+            Stmt::Return(Some(exp)) => {
+                let (value, type_) = self.expr(exp)?;
+                match self.result {
+                    Some(result) => {
+                        let value = convert(value, type_, result);
+                        code.push_str(&format!("{indent}(return {value})\n"));
+                    }
+                    None => return Err("Can't return a value from a `void` function".to_string()),
+                }
+            }
+            Stmt::Yield(_) | Stmt::YieldFrom(_) => {
+                return Err("`yield` is only allowed in generator functions".to_string());
+            }
+            Stmt::Expr(exp) => {
+                if let Expr::Call(name, args) = exp {
+                    if !self.module.functions.contains_key(name) {
+                        self.host(name, args)?;
+                    }
+                }
+                let (value, type_) = self.expr_or_void(exp)?;
+                match type_ {
+                    Some(_) => code.push_str(&format!("{indent}(drop {value})\n")),
+                    None => code.push_str(&format!("{indent}{value}\n")),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts the body of a loop, `continue` branches to `next` and `break`
+    /// to `end`.
+    /// Sets a local, declaring it the first time.
+    fn set(&mut self, name: &str, value: String, type_: ValType) -> String {
+        let value = match self.local(name) {
+            Some(local) => convert(value, type_, local),
+            None => {
+                self.locals.push((name.to_string(), type_));
+                value
+            }
+        };
+        format!("(local.set ${name} {value})")
+    }
+
+    /// A counted loop over a range of integers. The body is a block of its
+    /// own, `continue` leaves it to count on.
+    fn range(
+        &mut self,
+        x: &tree::For,
+        range: &tree::Range,
+        depth: usize,
+        code: &mut String,
+    ) -> Result<(), String> {
+        let indent = indent(depth);
+        if let Some(Expr::Int(step)) = &range.step {
+            if *step <= 0 {
+                return Err(format!("The step of a range must be positive, not {step}"));
+            }
+        }
+        let (first, cmp, bound) = range.counted();
+        let (first, first_t) = self.expr(&first)?;
+        let (bound, bound_t) = self.expr(&bound)?;
+        let (step, step_t) = self.expr(&range.step())?;
+        if !(first_t.is_int() && bound_t.is_int() && step_t.is_int()) {
+            return Err("Ranges need integers".to_string());
+        }
+        let type_ = match [first_t, bound_t, step_t].contains(&ValType::I64) {
+            true => ValType::I64,
+            false => ValType::I32,
+        };
+        let t = type_.name();
+        let var = &x.var_name;
+        // the bound and the step are evaluated once, before the loop
+        let (counter, end, by) = (
+            format!("{var}.counter"),
+            format!("{var}.bound"),
+            format!("{var}.step"),
+        );
+        for (name, value, from) in [
+            (&counter, first, first_t),
+            (&end, bound, bound_t),
+            (&by, step, step_t),
+        ] {
+            let value = convert(value, from, type_);
+            let set = self.set(name, value, type_);
+            code.push_str(&format!("{indent}{set}\n"));
+        }
+        if let Some(index) = &x.index {
+            let set = self.set(
+                &format!("{index}.count"),
+                "(i32.const 0)".to_string(),
+                ValType::I32,
+            );
+            code.push_str(&format!("{indent}{set}\n"));
+        }
+
+        let label = self.labels;
+        self.labels += 1;
+        let cmp = match cmp {
+            Operator::Less => "lt_s",
+            Operator::LessEquals => "le_s",
+            _ => "ge_s",
+        };
+        code.push_str(&format!("{indent}(block $break{label}\n"));
+        code.push_str(&format!("{indent}  (loop $for{label}\n"));
+        code.push_str(&format!(
+            "{indent}    (br_if $break{label} (i32.eqz ({t}.{cmp} (local.get ${counter}) (local.get ${end}))))\n"
+        ));
+        let set = self.set(var, format!("(local.get ${counter})"), type_);
+        code.push_str(&format!("{indent}    {set}\n"));
+        if let Some(index) = &x.index {
+            let set = self.set(index, format!("(local.get ${index}.count)"), ValType::I32);
+            code.push_str(&format!("{indent}    {set}\n"));
+            code.push_str(&format!(
+                "{indent}    (local.set ${index}.count (i32.add (local.get ${index}.count) (i32.const 1)))\n"
+            ));
+        }
+        code.push_str(&format!("{indent}    (block $continue{label}\n"));
+        self.enter(format!("$continue{label}"), format!("$break{label}"));
+        self.block(&x.body, depth + 3, code)?;
+        self.loops.pop();
+        code.push_str(&format!("{indent}    )\n"));
+        let op = if range.rev { "sub" } else { "add" };
+        code.push_str(&format!(
+            "{indent}    (local.set ${counter} ({t}.{op} (local.get ${counter}) (local.get ${by})))\n"
+        ));
+        code.push_str(&format!("{indent}    (br $for{label})\n"));
+        code.push_str(&format!("{indent}  )\n{indent})\n"));
+        Ok(())
+    }
+
+    fn enter(&mut self, next: String, end: String) {
+        self.loops.push(Loop {
+            label: self.pending_label.take(),
+            next,
+            end,
+        });
+    }
+
+    /// The loop a `break` or `continue` leaves, the innermost one without a
+    /// label.
+    fn target(&self, label: &Option<String>) -> Result<&Loop, String> {
+        match self
+            .loops
+            .iter()
+            .rev()
+            .find(|loop_| label.is_none() || loop_.label == *label)
+        {
+            Some(loop_) => Ok(loop_),
+            None => Err("There is no loop to leave".to_string()),
+        }
+    }
+
+    fn if_(
+        &mut self,
+        branches: &[(Expr, Vec<Stmt>)],
+        otherwise: Option<&[Stmt]>,
+        depth: usize,
+        code: &mut String,
+    ) -> Result<(), String> {
+        let indent = indent(depth);
+        let (condition, stmts) = &branches[0];
+        let condition = self.condition(condition)?;
+        code.push_str(&format!("{indent}(if {condition}\n{indent}  (then\n"));
+        self.block(stmts, depth + 2, code)?;
+        code.push_str(&format!("{indent}  )\n"));
+        if branches.len() > 1 {
+            // `else if` becomes an `if` nested in the `else` branch
+            code.push_str(&format!("{indent}  (else\n"));
+            self.if_(&branches[1..], otherwise, depth + 2, code)?;
+            code.push_str(&format!("{indent}  )\n"));
+        } else if let Some(stmts) = otherwise {
+            code.push_str(&format!("{indent}  (else\n"));
+            self.block(stmts, depth + 2, code)?;
+            code.push_str(&format!("{indent}  )\n"));
+        }
+        code.push_str(&format!("{indent})\n"));
+        Ok(())
+    }
+
+    /// Registers a call to a function that isn't defined in lang as an
+    /// import from the host.
+    fn host(&mut self, name: &str, args: &[Expr]) -> Result<(), String> {
+        let mut params = vec![];
+        for arg in args {
+            params.push(self.expr(arg)?.1);
+        }
+        let sig = Signature {
+            params,
+            result: None,
+        };
+        match self.module.host.iter().find(|(host, _)| host == name) {
+            Some((_, known)) if *known != sig => Err(format!(
+                "Host function `{name}` is called with different argument types"
+            )),
+            Some(_) => Ok(()),
+            None => {
+                self.module.host.push((name.to_string(), sig));
+                Ok(())
+            }
+        }
+    }
+
+    fn signature(&self, name: &str) -> Option<Signature> {
+        self.module.functions.get(name).cloned().or_else(|| {
+            self.module
+                .host
+                .iter()
+                .find(|(host, _)| host == name)
+                .map(|(_, sig)| sig.clone())
+        })
+    }
+
+    /// An `i32` that is 0 for false and anything else for true.
+    fn condition(&mut self, exp: &Expr) -> Result<String, String> {
+        let (value, type_) = self.expr(exp)?;
+        Ok(match type_ {
+            ValType::I32 => value,
+            _ => format!("({t}.ne {value} ({t}.const 0))", t = type_.name()),
+        })
+    }
+
+    fn expr(&mut self, exp: &Expr) -> Result<(String, ValType), String> {
+        match self.expr_or_void(exp)? {
+            (value, Some(type_)) => Ok((value, type_)),
+            (_, None) => Err("A function returning `void` can't be used as a value".to_string()),
+        }
+    }
+
+    fn expr_or_void(&mut self, exp: &Expr) -> Result<(String, Option<ValType>), String> {
+        let (value, type_) = match exp {
+            Expr::Int(i) => {
+                let type_ = int_type(*i);
+                (format!("({}.const {i})", type_.name()), type_)
+            }
+            Expr::Char(c) => (format!("(i32.const {})", *c as i64), ValType::I32),
+            Expr::Float(f) => (format!("(f64.const {f:?})"), ValType::F64),
+            Expr::Bool(b) => (format!("(i32.const {})", *b as i32), ValType::I32),
+            Expr::Var(name) => {
+                if let Some(type_) = self.local(name) {
+                    (format!("(local.get ${name})"), type_)
+                } else if let Some((type_, _)) = self.module.globals.get(name) {
+                    (format!("(global.get ${name})"), *type_)
+                } else {
+                    return Err(format!("Unknown variable `{name}`"));
+                }
+            }
+            Expr::Call(name, args) => {
+                let sig = match self.signature(name) {
+                    Some(sig) => sig,
+                    None => return Err(format!("Unknown function `{name}`")),
+                };
+                if sig.params.len() != args.len() {
+                    return Err(format!(
+                        "`{name}` takes {} arguments but got {}",
+                        sig.params.len(),
+                        args.len()
+                    ));
+                }
+                let mut call = format!("(call ${name}");
+                for (arg, param) in args.iter().zip(sig.params.iter()) {
+                    let (value, type_) = self.expr(arg)?;
+                    call.push(' ');
+                    call.push_str(&convert(value, type_, *param));
+                }
+                call.push(')');
+                return Ok((call, sig.result));
+            }
+            Expr::Neg(exp) => {
+                let (value, type_) = self.expr(exp)?;
+                let t = type_.name();
+                if type_.is_int() {
+                    (format!("({t}.sub ({t}.const 0) {value})"), type_)
+                } else {
+                    (format!("({t}.neg {value})"), type_)
+                }
+            }
+            Expr::Binary(lhs, o, rhs) => self.binary(lhs, o, rhs)?,
+            Expr::Str(_) => return Err("strings are not supported by the WAT backend".to_string()),
+            Expr::Array(_) | Expr::Index(..) => {
+                return Err("arrays are not supported by the WAT backend".to_string())
+            }
+            Expr::Struct(..) | Expr::Field(..) => {
+                return Err("structs are not supported by the WAT backend".to_string())
+            }
+            Expr::Variant(..) => {
+                return Err("enums are not supported by the WAT backend".to_string())
+            }
+            Expr::Match(..) => {
+                return Err("`match` is not supported by the WAT backend".to_string())
+            }
+            Expr::Method(..) => {
+                return Err("methods are not supported by the WAT backend".to_string())
+            }
+        };
+        Ok((value, Some(type_)))
+    }
+
+    fn binary(
+        &mut self,
+        lhs: &Expr,
+        o: &Operator,
+        rhs: &Expr,
+    ) -> Result<(String, ValType), String> {
+        let (l, lt) = self.expr(lhs)?;
+        let (r, rt) = self.expr(rhs)?;
+        if let Operator::And | Operator::Or = o {
+            let (l, r) = (truthy(lhs, l, lt), truthy(rhs, r, rt));
+            // short-circuiting like in C
+            return Ok(match o {
+                Operator::And => (
+                    format!("(if (result i32) {l} (then {r}) (else (i32.const 0)))"),
+                    ValType::I32,
+                ),
+                _ => (
+                    format!("(if (result i32) {l} (then (i32.const 1)) (else {r}))"),
+                    ValType::I32,
+                ),
+            });
+        }
+        let type_ = match (lt, rt) {
+            (ValType::F64, _) | (_, ValType::F64) => ValType::F64,
+            (ValType::F32, _) | (_, ValType::F32) => ValType::F32,
+            (ValType::I64, _) | (_, ValType::I64) => ValType::I64,
+            _ => ValType::I32,
+        };
+        let (l, r) = (convert(l, lt, type_), convert(r, rt, type_));
+        let int = type_.is_int();
+        let (instr, result) = match o {
+            Operator::Plus => ("add", type_),
+            Operator::Minus => ("sub", type_),
+            Operator::Mul => ("mul", type_),
+            Operator::Div if int => ("div_s", type_),
+            Operator::Div => ("div", type_),
+            Operator::Mod if int => ("rem_s", type_),
+            Operator::BitAnd if int => ("and", type_),
+            Operator::BitOr if int => ("or", type_),
+            Operator::BitShiftLeft if int => ("shl", type_),
+            Operator::BitShiftRight if int => ("shr_s", type_),
+            Operator::Equals => ("eq", ValType::I32),
+            Operator::NotEquals => ("ne", ValType::I32),
+            Operator::Less if int => ("lt_s", ValType::I32),
+            Operator::Less => ("lt", ValType::I32),
+            Operator::Greater if int => ("gt_s", ValType::I32),
+            Operator::Greater => ("gt", ValType::I32),
+            Operator::LessEquals if int => ("le_s", ValType::I32),
+            Operator::LessEquals => ("le", ValType::I32),
+            Operator::GreaterEquals if int => ("ge_s", ValType::I32),
+            Operator::GreaterEquals => ("ge", ValType::I32),
+            Operator::Pipe => {
+                return Err("`|>` is not supported by the WAT backend".to_string());
+            }
+            _ => return Err(format!("{o:?} needs integer operands")),
+        };
+        Ok((format!("({}.{instr} {l} {r})", type_.name()), result))
+    }
+}
+
+/// Turns any value into an `i32` that is 1 for true and 0 for false.
+fn truthy(exp: &Expr, value: String, type_: ValType) -> String {
+    let boolean = match exp {
+        Expr::Bool(_) => true,
+        Expr::Binary(_, o, _) => matches!(
+            o,
+            Operator::Equals
+                | Operator::NotEquals
+                | Operator::Less
+                | Operator::Greater
+                | Operator::LessEquals
+                | Operator::GreaterEquals
+                | Operator::And
+                | Operator::Or
+        ),
+        _ => false,
+    };
+    if boolean {
+        return value;
+    }
+    let t = type_.name();
+    format!("({t}.ne {value} ({t}.const 0))")
+}
+
+/// Converts a value between wasm types the way C converts between numbers.
+fn convert(value: String, from: ValType, to: ValType) -> String {
+    // literals are converted right away
+    if let Some(literal) = value
+        .strip_prefix(&format!("({}.const ", from.name()))
+        .and_then(|v| v.strip_suffix(')'))
+    {
+        if from.is_int() && !to.is_int() {
+            return format!("({}.const {literal})", to.name());
+        }
+    }
+    let instr = match (from, to) {
+        (ValType::I32, ValType::I64) => "i64.extend_i32_s",
+        (ValType::I64, ValType::I32) => "i32.wrap_i64",
+        (ValType::I32, ValType::F32) => "f32.convert_i32_s",
+        (ValType::I64, ValType::F32) => "f32.convert_i64_s",
+        (ValType::I32, ValType::F64) => "f64.convert_i32_s",
+        (ValType::I64, ValType::F64) => "f64.convert_i64_s",
+        (ValType::F32, ValType::F64) => "f64.promote_f32",
+        (ValType::F64, ValType::F32) => "f32.demote_f64",
+        (ValType::F32, ValType::I32) => "i32.trunc_f32_s",
+        (ValType::F64, ValType::I32) => "i32.trunc_f64_s",
+        (ValType::F32, ValType::I64) => "i64.trunc_f32_s",
+        (ValType::F64, ValType::I64) => "i64.trunc_f64_s",
+        _ => return value,
+    };
+    format!("({instr} {value})")
+}
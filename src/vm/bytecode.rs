@@ -0,0 +1,597 @@
+//! The bytecode format and its binary encoding.
+//!
+//! A file starts with [`MAGIC`] and a version byte. All numbers are little
+//! endian, strings and lists are prefixed with their length as a `u32`.
+use crate::lexer::{
+    token::expression::Operator,
+    types::{Primitive, Type},
+};
+
+pub const MAGIC: &[u8; 6] = b"LANGBC";
+pub const VERSION: u8 = 7;
+
+/// Operators in the order they are encoded in.
+const OPERATORS: [Operator; 18] = [
+    Operator::BitShiftRight,
+    Operator::BitShiftLeft,
+    Operator::And,
+    Operator::Or,
+    Operator::BitAnd,
+    Operator::BitOr,
+    Operator::Plus,
+    Operator::Minus,
+    Operator::Mul,
+    Operator::Div,
+    Operator::Mod,
+    Operator::Equals,
+    Operator::NotEquals,
+    Operator::Less,
+    Operator::Greater,
+    Operator::LessEquals,
+    Operator::GreaterEquals,
+    Operator::Pipe,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Op {
+    /// pushes a constant of the program
+    Const(u32),
+    Pop,
+    GetLocal(u16),
+    /// pops the value to store
+    SetLocal(u16),
+    GetGlobal(u16),
+    SetGlobal(u16),
+    Binary(Operator),
+    Neg,
+    /// replaces the value on top with whether it is true
+    Truthy,
+    Jump(u32),
+    /// pops the condition
+    JumpIfFalse(u32),
+    /// pops that many values into an array
+    Array(u16),
+    /// pops the index and the value indexed
+    Index,
+    /// pops the fields of the struct with that index, in declaration order
+    Struct(u16),
+    /// replaces the struct on top with its field named by a string constant
+    Field(u32),
+    /// enum and variant index, pops the values the variant holds
+    Variant(u16, u16),
+    /// replaces the enum on top with whether it is the variant with that
+    /// enum and variant index
+    IsVariant(u16, u16),
+    /// replaces the enum on top with the value it holds at that index
+    Held(u8),
+    /// function index and number of arguments
+    Call(u16, u8),
+    /// pops the function to call, which was pushed after the arguments
+    CallValue(u8),
+    /// calls the method named by a string constant of the struct below the
+    /// arguments, with the struct as `self`
+    CallMethod(u32, u8),
+    /// index into [`super::BUILTINS`] and number of arguments
+    Builtin(u8, u8),
+    Return,
+    /// pops an array or generator and stores an iterator over it in a local
+    Iter(u16),
+    /// pushes the next value of the iterator in a local, or jumps when there
+    /// are no values left
+    Next(u16, u32),
+    Yield,
+}
+
+/// How a value is converted when it is passed to or returned from a
+/// function, numbers convert into each other like in C.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TypeTag {
+    Int,
+    Float,
+    Bool,
+    Void,
+    /// values of any other type are passed as they are
+    Any,
+    /// the integers smaller than 64 bits, which wrap around to their size
+    I8,
+    I16,
+    I32,
+    U8,
+    U16,
+    U32,
+}
+
+impl TypeTag {
+    pub fn of(type_: &Type) -> TypeTag {
+        match type_.primitive() {
+            Some(Primitive::I8) => TypeTag::I8,
+            Some(Primitive::I16) => TypeTag::I16,
+            Some(Primitive::I32) => TypeTag::I32,
+            Some(Primitive::U8) => TypeTag::U8,
+            Some(Primitive::U16) => TypeTag::U16,
+            Some(Primitive::U32) => TypeTag::U32,
+            Some(Primitive::Bool) => TypeTag::Bool,
+            Some(Primitive::Void) => TypeTag::Void,
+            Some(p) if p.float() => TypeTag::Float,
+            Some(p) if p.integer() || p == Primitive::Char => TypeTag::Int,
+            _ => TypeTag::Any,
+        }
+    }
+
+    /// The primitive values are converted to, `None` for [`TypeTag::Any`].
+    pub fn primitive(self) -> Option<Primitive> {
+        Some(match self {
+            TypeTag::Int => Primitive::I64,
+            TypeTag::Float => Primitive::F64,
+            TypeTag::Bool => Primitive::Bool,
+            TypeTag::Void => Primitive::Void,
+            TypeTag::Any => return None,
+            TypeTag::I8 => Primitive::I8,
+            TypeTag::I16 => Primitive::I16,
+            TypeTag::I32 => Primitive::I32,
+            TypeTag::U8 => Primitive::U8,
+            TypeTag::U16 => Primitive::U16,
+            TypeTag::U32 => Primitive::U32,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FunctionKind {
+    Function,
+    Loop,
+    Generator,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Constant {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    Void,
+    /// a function used as a value, by index
+    Function(u16),
+    /// index into [`super::BUILTINS`]
+    Builtin(u8),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionCode {
+    pub name: String,
+    pub kind: FunctionKind,
+    pub params: Vec<TypeTag>,
+    pub result: TypeTag,
+    /// number of local slots, the arguments are the first ones
+    pub locals: u16,
+    pub code: Vec<Op>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructCode {
+    pub name: String,
+    /// in declaration order
+    pub fields: Vec<(String, TypeTag)>,
+    /// the functions of its `impl` taking `self`, by name
+    pub methods: Vec<(String, u16)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumCode {
+    pub name: String,
+    /// the variants with the types of the values they hold
+    pub variants: Vec<(String, Vec<TypeTag>)>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Program {
+    pub constants: Vec<Constant>,
+    pub structs: Vec<StructCode>,
+    pub enums: Vec<EnumCode>,
+    /// name and mutability
+    pub globals: Vec<(String, bool)>,
+    /// initializes the globals, runs before `main`
+    pub init: Vec<Op>,
+    pub functions: Vec<FunctionCode>,
+}
+
+impl Program {
+    pub fn function(&self, name: &str) -> Option<u16> {
+        self.functions
+            .iter()
+            .position(|f| f.name == name)
+            .map(|i| i as u16)
+    }
+
+    pub fn struct_(&self, name: &str) -> Option<u16> {
+        self.structs
+            .iter()
+            .position(|x| x.name == name)
+            .map(|i| i as u16)
+    }
+
+    pub fn enum_(&self, name: &str) -> Option<u16> {
+        self.enums
+            .iter()
+            .position(|x| x.name == name)
+            .map(|i| i as u16)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        w.bytes.extend_from_slice(MAGIC);
+        w.u8(VERSION);
+        w.u32(self.constants.len() as u32);
+        for constant in &self.constants {
+            match constant {
+                Constant::Int(i) => {
+                    w.u8(0);
+                    w.bytes.extend_from_slice(&i.to_le_bytes());
+                }
+                Constant::Float(f) => {
+                    w.u8(1);
+                    w.bytes.extend_from_slice(&f.to_le_bytes());
+                }
+                Constant::Bool(b) => {
+                    w.u8(2);
+                    w.u8(*b as u8);
+                }
+                Constant::Str(s) => {
+                    w.u8(3);
+                    w.str(s);
+                }
+                Constant::Void => w.u8(4),
+                Constant::Function(index) => {
+                    w.u8(5);
+                    w.u16(*index);
+                }
+                Constant::Builtin(index) => {
+                    w.u8(6);
+                    w.u8(*index);
+                }
+            }
+        }
+        w.u32(self.structs.len() as u32);
+        for x in &self.structs {
+            w.str(&x.name);
+            w.u32(x.fields.len() as u32);
+            for (field, type_) in &x.fields {
+                w.str(field);
+                w.u8(*type_ as u8);
+            }
+            w.u32(x.methods.len() as u32);
+            for (method, function) in &x.methods {
+                w.str(method);
+                w.u16(*function);
+            }
+        }
+        w.u32(self.enums.len() as u32);
+        for x in &self.enums {
+            w.str(&x.name);
+            w.u32(x.variants.len() as u32);
+            for (variant, types) in &x.variants {
+                w.str(variant);
+                w.u32(types.len() as u32);
+                for type_ in types {
+                    w.u8(*type_ as u8);
+                }
+            }
+        }
+        w.u32(self.globals.len() as u32);
+        for (name, mutable) in &self.globals {
+            w.str(name);
+            w.u8(*mutable as u8);
+        }
+        w.code(&self.init);
+        w.u32(self.functions.len() as u32);
+        for function in &self.functions {
+            w.str(&function.name);
+            w.u8(function.kind as u8);
+            w.u32(function.params.len() as u32);
+            for param in &function.params {
+                w.u8(*param as u8);
+            }
+            w.u8(function.result as u8);
+            w.u16(function.locals);
+            w.code(&function.code);
+        }
+        w.bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Program, String> {
+        if !bytes.starts_with(MAGIC) {
+            return Err("Not a lang bytecode file".to_string());
+        }
+        let mut r = Reader {
+            bytes,
+            pos: MAGIC.len(),
+        };
+        let version = r.u8()?;
+        if version != VERSION {
+            return Err(format!(
+                "Bytecode version {version} is not supported, expected {VERSION}"
+            ));
+        }
+        let mut program = Program::default();
+        for _ in 0..r.u32()? {
+            program.constants.push(match r.u8()? {
+                0 => Constant::Int(i64::from_le_bytes(r.array()?)),
+                1 => Constant::Float(f64::from_le_bytes(r.array()?)),
+                2 => Constant::Bool(r.u8()? != 0),
+                3 => Constant::Str(r.str()?),
+                4 => Constant::Void,
+                5 => Constant::Function(r.u16()?),
+                6 => Constant::Builtin(r.u8()?),
+                tag => return Err(format!("Unknown constant tag {tag}")),
+            });
+        }
+        for _ in 0..r.u32()? {
+            let name = r.str()?;
+            let mut fields = vec![];
+            for _ in 0..r.u32()? {
+                fields.push((r.str()?, r.type_tag()?));
+            }
+            let mut methods = vec![];
+            for _ in 0..r.u32()? {
+                methods.push((r.str()?, r.u16()?));
+            }
+            program.structs.push(StructCode {
+                name,
+                fields,
+                methods,
+            });
+        }
+        for _ in 0..r.u32()? {
+            let name = r.str()?;
+            let mut variants = vec![];
+            for _ in 0..r.u32()? {
+                let variant = r.str()?;
+                let mut types = vec![];
+                for _ in 0..r.u32()? {
+                    types.push(r.type_tag()?);
+                }
+                variants.push((variant, types));
+            }
+            program.enums.push(EnumCode { name, variants });
+        }
+        for _ in 0..r.u32()? {
+            program.globals.push((r.str()?, r.u8()? != 0));
+        }
+        program.init = r.code()?;
+        for _ in 0..r.u32()? {
+            let name = r.str()?;
+            let kind = match r.u8()? {
+                0 => FunctionKind::Function,
+                1 => FunctionKind::Loop,
+                2 => FunctionKind::Generator,
+                kind => return Err(format!("Unknown function kind {kind}")),
+            };
+            let mut params = vec![];
+            for _ in 0..r.u32()? {
+                params.push(r.type_tag()?);
+            }
+            program.functions.push(FunctionCode {
+                name,
+                kind,
+                params,
+                result: r.type_tag()?,
+                locals: r.u16()?,
+                code: r.code()?,
+            });
+        }
+        if r.pos != bytes.len() {
+            return Err("Unexpected data after the end of the bytecode".to_string());
+        }
+        Ok(program)
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn u8(&mut self, v: u8) {
+        self.bytes.push(v);
+    }
+    fn u16(&mut self, v: u16) {
+        self.bytes.extend_from_slice(&v.to_le_bytes());
+    }
+    fn u32(&mut self, v: u32) {
+        self.bytes.extend_from_slice(&v.to_le_bytes());
+    }
+    fn str(&mut self, s: &str) {
+        self.u32(s.len() as u32);
+        self.bytes.extend_from_slice(s.as_bytes());
+    }
+    fn code(&mut self, code: &[Op]) {
+        self.u32(code.len() as u32);
+        for op in code {
+            match *op {
+                Op::Const(i) => {
+                    self.u8(0);
+                    self.u32(i);
+                }
+                Op::Pop => self.u8(1),
+                Op::GetLocal(i) => {
+                    self.u8(2);
+                    self.u16(i);
+                }
+                Op::SetLocal(i) => {
+                    self.u8(3);
+                    self.u16(i);
+                }
+                Op::GetGlobal(i) => {
+                    self.u8(4);
+                    self.u16(i);
+                }
+                Op::SetGlobal(i) => {
+                    self.u8(5);
+                    self.u16(i);
+                }
+                Op::Binary(o) => {
+                    self.u8(6);
+                    self.u8(OPERATORS.iter().position(|x| *x == o).unwrap() as u8);
+                }
+                Op::Neg => self.u8(7),
+                Op::Truthy => self.u8(8),
+                Op::Jump(to) => {
+                    self.u8(9);
+                    self.u32(to);
+                }
+                Op::JumpIfFalse(to) => {
+                    self.u8(10);
+                    self.u32(to);
+                }
+                Op::Array(len) => {
+                    self.u8(11);
+                    self.u16(len);
+                }
+                Op::Index => self.u8(12),
+                Op::Call(function, argc) => {
+                    self.u8(13);
+                    self.u16(function);
+                    self.u8(argc);
+                }
+                Op::Builtin(builtin, argc) => {
+                    self.u8(14);
+                    self.u8(builtin);
+                    self.u8(argc);
+                }
+                Op::Return => self.u8(15),
+                Op::Iter(local) => {
+                    self.u8(16);
+                    self.u16(local);
+                }
+                Op::Next(local, to) => {
+                    self.u8(17);
+                    self.u16(local);
+                    self.u32(to);
+                }
+                Op::Yield => self.u8(18),
+                Op::CallValue(argc) => {
+                    self.u8(19);
+                    self.u8(argc);
+                }
+                Op::Struct(index) => {
+                    self.u8(20);
+                    self.u16(index);
+                }
+                Op::Field(name) => {
+                    self.u8(21);
+                    self.u32(name);
+                }
+                Op::Variant(index, variant) => {
+                    self.u8(22);
+                    self.u16(index);
+                    self.u16(variant);
+                }
+                Op::IsVariant(index, variant) => {
+                    self.u8(23);
+                    self.u16(index);
+                    self.u16(variant);
+                }
+                Op::Held(at) => {
+                    self.u8(24);
+                    self.u8(at);
+                }
+                Op::CallMethod(name, argc) => {
+                    self.u8(25);
+                    self.u32(name);
+                    self.u8(argc);
+                }
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        match self.bytes.get(self.pos..self.pos + N) {
+            Some(bytes) => {
+                self.pos += N;
+                Ok(bytes.try_into().unwrap())
+            }
+            None => Err("Unexpected end of the bytecode".to_string()),
+        }
+    }
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.array::<1>()?[0])
+    }
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+    fn str(&mut self) -> Result<String, String> {
+        let len = self.u32()? as usize;
+        match self.bytes.get(self.pos..self.pos + len) {
+            Some(bytes) => {
+                self.pos += len;
+                String::from_utf8(bytes.to_vec()).map_err(|_| "Invalid string".to_string())
+            }
+            None => Err("Unexpected end of the bytecode".to_string()),
+        }
+    }
+    fn type_tag(&mut self) -> Result<TypeTag, String> {
+        Ok(match self.u8()? {
+            0 => TypeTag::Int,
+            1 => TypeTag::Float,
+            2 => TypeTag::Bool,
+            3 => TypeTag::Void,
+            4 => TypeTag::Any,
+            5 => TypeTag::I8,
+            6 => TypeTag::I16,
+            7 => TypeTag::I32,
+            8 => TypeTag::U8,
+            9 => TypeTag::U16,
+            10 => TypeTag::U32,
+            tag => return Err(format!("Unknown type tag {tag}")),
+        })
+    }
+    fn code(&mut self) -> Result<Vec<Op>, String> {
+        let mut code = vec![];
+        for _ in 0..self.u32()? {
+            code.push(match self.u8()? {
+                0 => Op::Const(self.u32()?),
+                1 => Op::Pop,
+                2 => Op::GetLocal(self.u16()?),
+                3 => Op::SetLocal(self.u16()?),
+                4 => Op::GetGlobal(self.u16()?),
+                5 => Op::SetGlobal(self.u16()?),
+                6 => match OPERATORS.get(self.u8()? as usize) {
+                    Some(o) => Op::Binary(*o),
+                    None => return Err("Unknown operator".to_string()),
+                },
+                7 => Op::Neg,
+                8 => Op::Truthy,
+                9 => Op::Jump(self.u32()?),
+                10 => Op::JumpIfFalse(self.u32()?),
+                11 => Op::Array(self.u16()?),
+                12 => Op::Index,
+                13 => Op::Call(self.u16()?, self.u8()?),
+                14 => Op::Builtin(self.u8()?, self.u8()?),
+                15 => Op::Return,
+                16 => Op::Iter(self.u16()?),
+                17 => Op::Next(self.u16()?, self.u32()?),
+                18 => Op::Yield,
+                19 => Op::CallValue(self.u8()?),
+                20 => Op::Struct(self.u16()?),
+                21 => Op::Field(self.u32()?),
+                22 => Op::Variant(self.u16()?, self.u16()?),
+                23 => Op::IsVariant(self.u16()?, self.u16()?),
+                24 => Op::Held(self.u8()?),
+                25 => Op::CallMethod(self.u32()?, self.u8()?),
+                op => return Err(format!("Unknown opcode {op}")),
+            });
+        }
+        Ok(code)
+    }
+}
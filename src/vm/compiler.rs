@@ -0,0 +1,870 @@
+//! Compiles the statement tree of a program into bytecode.
+use {
+    super::{bytecode::*, predefined, BUILTINS, PRELUDE},
+    crate::{
+        lexer::{
+            check, flow, resolve,
+            token::{expression::Operator, Function},
+            tree::{self, Expr, Iterable, Pattern, Stmt},
+            Lexer, Token,
+        },
+        log, printx, PrintT,
+    },
+    std::{fs::read_to_string, path::Path},
+};
+
+pub fn compile_file<P>(file: P) -> Result<Program, String>
+where
+    P: AsRef<Path>,
+{
+    match read_to_string(file) {
+        Ok(code) => {
+            let mut lexer = Lexer::quiet();
+            lexer.parse(code);
+            if lexer.errors > 0 {
+                return Err("Not compiling a program with lexer errors".to_string());
+            }
+            compile(&lexer.ast)
+        }
+        Err(e) => Err(format!("Unable to read file: {e}")),
+    }
+}
+
+pub fn compile(ast: &[Token]) -> Result<Program, String> {
+    let mut program = Program::default();
+
+    // the adapters of the prelude, unless the program defines its own
+    let mut lexer = Lexer::quiet();
+    lexer.parse(PRELUDE.to_string());
+    let defined = |name: &str| {
+        ast.iter().any(|nt| {
+            matches!(nt, Token::Function(x) | Token::LoopFunction(x) | Token::GeneratorFunction(x) if x.name == name)
+        })
+    };
+    let prelude: Vec<&Token> = lexer
+        .ast
+        .iter()
+        .filter(|nt| !matches!(nt, Token::GeneratorFunction(x) if defined(&x.name)))
+        .collect();
+    let mut signatures = tree::Signatures::default();
+    let resolved = resolve::program(ast, &predefined());
+    for warning in &resolved.warnings {
+        log!(Warning, f("{warning}"));
+    }
+    if !resolved.errors.is_empty() {
+        return Err(resolved.errors.join("\n"));
+    }
+    signatures.extend(ast)?;
+    let mut errors = check::program(&signatures, ast);
+    errors.extend(flow::program(ast));
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    for nt in ast {
+        match nt {
+            Token::Struct(x) => {
+                if program.struct_(&x.name).is_some() {
+                    return Err(format!("The struct `{}` is defined twice", x.name));
+                }
+                program.structs.push(StructCode {
+                    name: x.name.clone(),
+                    fields: x
+                        .fields
+                        .iter()
+                        .map(|field| (field.name.clone(), TypeTag::of(&field.type_)))
+                        .collect(),
+                    methods: vec![],
+                });
+            }
+            Token::Enum(x) => {
+                if program.enum_(&x.name).is_some() {
+                    return Err(format!("The enum `{}` is defined twice", x.name));
+                }
+                program.enums.push(EnumCode {
+                    name: x.name.clone(),
+                    variants: x
+                        .variants
+                        .iter()
+                        .map(|v| {
+                            let types = v.fields.iter().map(TypeTag::of).collect();
+                            (v.name.clone(), types)
+                        })
+                        .collect(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // functions are known before any code is compiled, so they can be
+    // called before their definition
+    let mut bodies = vec![];
+    for nt in ast.iter().chain(prelude) {
+        let functions = match nt {
+            Token::Function(x) => vec![(x.clone(), FunctionKind::Function)],
+            Token::LoopFunction(x) => vec![(x.clone(), FunctionKind::Loop)],
+            Token::GeneratorFunction(x) => vec![(x.clone(), FunctionKind::Generator)],
+            // called like `Name::function()`, the methods through the struct
+            Token::Impl(x) => {
+                let Some(index) = program.struct_(&x.name) else {
+                    return Err(format!("`impl {}` needs a struct named `{0}`", x.name));
+                };
+                for (at, function) in x.functions.iter().enumerate() {
+                    if function.method() {
+                        let method = (function.name.clone(), (program.functions.len() + at) as u16);
+                        program.structs[index as usize].methods.push(method);
+                    }
+                }
+                (x.functions.iter())
+                    .map(|function| {
+                        let name = format!("{}::{}", x.name, function.name);
+                        (
+                            Function {
+                                name,
+                                ..function.clone()
+                            },
+                            FunctionKind::Function,
+                        )
+                    })
+                    .collect()
+            }
+            _ => continue,
+        };
+        for (x, kind) in functions {
+            if program.function(&x.name).is_some() {
+                return Err(format!("`{}` is defined twice", x.name));
+            }
+            program.functions.push(FunctionCode {
+                name: x.name.clone(),
+                kind,
+                params: x
+                    .arguments
+                    .iter()
+                    .map(|arg| TypeTag::of(&arg.type_))
+                    .collect(),
+                result: TypeTag::of(&x.return_type),
+                locals: 0,
+                code: vec![],
+            });
+            bodies.push(x);
+        }
+    }
+
+    for nt in ast {
+        match nt {
+            Token::Const(x) | Token::Global(x) => {
+                let exp = tree::expression(&x.exp).map_err(|e| format!("{e} in `{}`", x.name))?;
+                let mut compiler = FunctionCompiler::new(&mut program, FunctionKind::Function);
+                compiler
+                    .expr(&exp)
+                    .map_err(|e| format!("{e} in `{}`", x.name))?;
+                let code = compiler.code;
+                program.init.extend(code);
+                program
+                    .init
+                    .push(Op::SetGlobal(program.globals.len() as u16));
+                program
+                    .globals
+                    .push((x.name.clone(), matches!(nt, Token::Global(_))));
+            }
+            Token::Import(i) => {
+                log!(VM, f("Skipping import {i}"));
+            }
+            Token::CImport(ci) => {
+                log!(VM, f("Skipping C include {ci}"));
+            }
+            _ => {}
+        }
+    }
+
+    for (index, x) in bodies.into_iter().enumerate() {
+        let kind = program.functions[index].kind;
+        let (code, locals) = function(&mut program, &signatures, &x, kind)
+            .map_err(|e| format!("{e} in `{}`", x.name))?;
+        program.functions[index].code = code;
+        program.functions[index].locals = locals;
+    }
+    Ok(program)
+}
+
+fn function(
+    program: &mut Program,
+    signatures: &tree::Signatures,
+    x: &Function,
+    kind: FunctionKind,
+) -> Result<(Vec<Op>, u16), String> {
+    let stmts = match kind {
+        FunctionKind::Loop => tree::loop_function(x)?,
+        _ => tree::function(x)?,
+    };
+    signatures.bounds(&x.generics, &x.arguments, &stmts)?;
+    let mut compiler = FunctionCompiler::new(program, kind);
+    for arg in &x.arguments {
+        compiler.declare(&arg.name)?;
+    }
+    compiler.block(&stmts)?;
+    // ending without `return`, the VM checks whether that is allowed
+    let void = compiler.constant(Constant::Void);
+    compiler.emit(Op::Const(void));
+    compiler.emit(Op::Return);
+    Ok((compiler.code, compiler.locals))
+}
+
+struct FunctionCompiler<'a> {
+    program: &'a mut Program,
+    kind: FunctionKind,
+    code: Vec<Op>,
+    /// local variables and their slots, one list per block
+    scopes: Vec<Vec<(String, u16)>>,
+    /// number of slots used
+    locals: u16,
+    /// the loops the code is in, the innermost is last
+    loops: Vec<Loop>,
+    /// the label of the loop compiled next
+    label: Option<String>,
+}
+
+struct Loop {
+    label: Option<String>,
+    /// where `continue` jumps to, `None` until the end of the body is known
+    start: Option<u32>,
+    /// the jumps of `continue` waiting for `start`
+    continues: Vec<usize>,
+    /// the jumps of `break`, pointed at the end of the loop
+    breaks: Vec<usize>,
+}
+
+impl FunctionCompiler<'_> {
+    fn new(program: &mut Program, kind: FunctionKind) -> FunctionCompiler<'_> {
+        FunctionCompiler {
+            program,
+            kind,
+            code: vec![],
+            scopes: vec![vec![]],
+            locals: 0,
+            loops: vec![],
+            label: None,
+        }
+    }
+
+    fn emit(&mut self, op: Op) -> usize {
+        self.code.push(op);
+        self.code.len() - 1
+    }
+
+    fn here(&self) -> u32 {
+        self.code.len() as u32
+    }
+
+    /// Points the jump at `at` to the current position.
+    fn patch(&mut self, at: usize) {
+        let here = self.here();
+        match &mut self.code[at] {
+            Op::Jump(to) | Op::JumpIfFalse(to) | Op::Next(_, to) => *to = here,
+            _ => unreachable!(),
+        }
+    }
+
+    fn constant(&mut self, constant: Constant) -> u32 {
+        match self.program.constants.iter().position(|c| *c == constant) {
+            Some(index) => index as u32,
+            None => {
+                self.program.constants.push(constant);
+                self.program.constants.len() as u32 - 1
+            }
+        }
+    }
+
+    fn declare(&mut self, name: &str) -> Result<u16, String> {
+        let slot = self.locals;
+        self.locals = match self.locals.checked_add(1) {
+            Some(locals) => locals,
+            None => return Err("Too many local variables".to_string()),
+        };
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .push((name.to_string(), slot));
+        Ok(slot)
+    }
+
+    fn local(&self, name: &str) -> Option<u16> {
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|(local, _)| local == name)
+            .map(|(_, slot)| *slot)
+    }
+
+    fn global(&self, name: &str) -> Option<(u16, bool)> {
+        self.program
+            .globals
+            .iter()
+            .position(|(global, _)| global == name)
+            .map(|i| (i as u16, self.program.globals[i].1))
+    }
+
+    fn block(&mut self, stmts: &[Stmt]) -> Result<(), String> {
+        self.scopes.push(vec![]);
+        for stmt in stmts {
+            self.stmt(stmt)?;
+        }
+        self.scopes.pop();
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Result<(), String> {
+        match stmt {
+            Stmt::Let(name, exp) => {
+                self.expr(exp)?;
+                let slot = self.declare(name)?;
+                self.emit(Op::SetLocal(slot));
+            }
+            Stmt::Assign(name, exp) => {
+                self.expr(exp)?;
+                if let Some(slot) = self.local(name) {
+                    self.emit(Op::SetLocal(slot));
+                } else if let Some((index, mutable)) = self.global(name) {
+                    if !mutable {
+                        return Err(format!("Can't assign to const `{name}`"));
+                    }
+                    self.emit(Op::SetGlobal(index));
+                } else {
+                    return Err(format!("Unknown variable `{name}`"));
+                }
+            }
+            Stmt::If(branches, otherwise) => {
+                let mut ends = vec![];
+                for (condition, stmts) in branches {
+                    self.expr(condition)?;
+                    let next = self.emit(Op::JumpIfFalse(0));
+                    self.block(stmts)?;
+                    ends.push(self.emit(Op::Jump(0)));
+                    self.patch(next);
+                }
+                if let Some(stmts) = otherwise {
+                    self.block(stmts)?;
+                }
+                for end in ends {
+                    self.patch(end);
+                }
+            }
+            Stmt::Loop(stmts) => {
+                let start = self.here();
+                self.loop_(Some(start), stmts)?;
+                self.emit(Op::Jump(start));
+                self.end_loop();
+            }
+            Stmt::While(condition, stmts) => {
+                let start = self.here();
+                self.expr(condition)?;
+                let end = self.emit(Op::JumpIfFalse(0));
+                self.loop_(Some(start), stmts)?;
+                self.emit(Op::Jump(start));
+                self.patch(end);
+                self.end_loop();
+            }
+            Stmt::For(for_) => {
+                self.for_(for_)?;
+            }
+            Stmt::Labeled(label, stmt) => {
+                self.label = Some(label.clone());
+                self.stmt(stmt)?;
+            }
+            Stmt::Break(label) => {
+                let jump = self.emit(Op::Jump(0));
+                let index = self.target(label)?;
+                self.loops[index].breaks.push(jump);
+            }
+            Stmt::Continue(label) => {
+                let index = self.target(label)?;
+                match self.loops[index].start {
+                    Some(start) => {
+                        self.emit(Op::Jump(start));
+                    }
+                    None => {
+                        let jump = self.emit(Op::Jump(0));
+                        self.loops[index].continues.push(jump);
+                    }
+                }
+            }
+            Stmt::Return(Some(_)) if self.kind == FunctionKind::Generator => {
+                return Err("`return` in a generator can't have a value".to_string());
+            }
+            Stmt::Return(exp) => {
+                match exp {
+                    Some(exp) => self.expr(exp)?,
+                    None => {
+                        let void = self.constant(Constant::Void);
+                        self.emit(Op::Const(void));
+                    }
+                }
+                self.emit(Op::Return);
+            }
+            Stmt::Yield(exp) => {
+                if self.kind != FunctionKind::Generator {
+                    return Err("`yield` is only allowed in generator functions".to_string());
+                }
+                self.expr(exp)?;
+                self.emit(Op::Yield);
+            }
+            Stmt::YieldFrom(exp) => {
+                if self.kind != FunctionKind::Generator {
+                    return Err("`yield from` is only allowed in generator functions".to_string());
+                }
+                // a `for` loop yielding every value
+                let var = "yield from".to_string();
+                self.for_(&tree::For {
+                    index: None,
+                    var_name: var.clone(),
+                    iter: Iterable::Expr(exp.clone()),
+                    body: vec![Stmt::Yield(Expr::Var(var))],
+                })?;
+            }
+            Stmt::Expr(exp) => {
+                self.expr(exp)?;
+                self.emit(Op::Pop);
+            }
+        }
+        Ok(())
+    }
+
+    /// Tries the arms in order, each one jumps to the next when its pattern
+    /// doesn't match. The arms cover every value, so the last one is taken
+    /// without testing it.
+    fn match_(&mut self, value: &Expr, arms: &[(Pattern, Expr)]) -> Result<(), String> {
+        let program = &self.program;
+        tree::exhaustive(arms.iter().map(|(pattern, _)| pattern), |name| {
+            let x = &program.enums[program.enum_(name)? as usize];
+            Some(
+                x.variants
+                    .iter()
+                    .map(|(v, types)| (v.clone(), types.len()))
+                    .collect(),
+            )
+        })?;
+        self.scopes.push(vec![]);
+        self.expr(value)?;
+        let slot = self.declare("match value")?;
+        self.emit(Op::SetLocal(slot));
+        let mut ends = vec![];
+        for (i, (pattern, exp)) in arms.iter().enumerate() {
+            self.scopes.push(vec![]);
+            let mut next = vec![];
+            let mut test = |this: &mut Self, ops: &[Op]| {
+                if i + 1 < arms.len() {
+                    this.emit(Op::GetLocal(slot));
+                    for op in ops {
+                        this.emit(*op);
+                    }
+                    next.push(this.emit(Op::JumpIfFalse(0)));
+                }
+            };
+            match pattern {
+                Pattern::Wildcard => {}
+                Pattern::Binding(name) => {
+                    self.emit(Op::GetLocal(slot));
+                    let var = self.declare(name)?;
+                    self.emit(Op::SetLocal(var));
+                }
+                Pattern::Int(n) => {
+                    let c = self.constant(Constant::Int(*n));
+                    test(self, &[Op::Const(c), Op::Binary(Operator::Equals)]);
+                }
+                Pattern::Range(start, end) => {
+                    let c = self.constant(Constant::Int(*start));
+                    test(self, &[Op::Const(c), Op::Binary(Operator::GreaterEquals)]);
+                    let c = self.constant(Constant::Int(*end));
+                    test(self, &[Op::Const(c), Op::Binary(Operator::LessEquals)]);
+                }
+                Pattern::Bool(b) => {
+                    let c = self.constant(Constant::Bool(*b));
+                    test(self, &[Op::Const(c), Op::Binary(Operator::Equals)]);
+                }
+                Pattern::Str(s) => {
+                    let c = self.constant(Constant::Str(s.clone()));
+                    test(self, &[Op::Const(c), Op::Binary(Operator::Equals)]);
+                }
+                Pattern::Variant(name, variant, names) => {
+                    // checked to exist above
+                    let index = self.program.enum_(name).unwrap();
+                    let at = self.program.enums[index as usize]
+                        .variants
+                        .iter()
+                        .position(|(v, _)| v == variant)
+                        .unwrap();
+                    test(self, &[Op::IsVariant(index, at as u16)]);
+                    for (held, name) in names.iter().enumerate() {
+                        if let Some(name) = name {
+                            self.emit(Op::GetLocal(slot));
+                            self.emit(Op::Held(held as u8));
+                            let var = self.declare(name)?;
+                            self.emit(Op::SetLocal(var));
+                        }
+                    }
+                }
+            }
+            self.expr(exp)?;
+            self.scopes.pop();
+            ends.push(self.emit(Op::Jump(0)));
+            for jump in next {
+                self.patch(jump);
+            }
+        }
+        for end in ends {
+            self.patch(end);
+        }
+        self.scopes.pop();
+        Ok(())
+    }
+
+    fn for_(&mut self, x: &tree::For) -> Result<(), String> {
+        self.scopes.push(vec![]);
+        // the names of the hidden locals aren't valid names, so the code
+        // can't access them
+        let count = match x.index {
+            Some(_) => {
+                let zero = self.constant(Constant::Int(0));
+                self.emit(Op::Const(zero));
+                let count = self.declare("for count")?;
+                self.emit(Op::SetLocal(count));
+                Some(count)
+            }
+            None => None,
+        };
+        match &x.iter {
+            Iterable::Expr(exp) => {
+                self.expr(exp)?;
+                let iter = self.declare("for iterator")?;
+                self.emit(Op::Iter(iter));
+                let start = self.here();
+                let next = self.emit(Op::Next(iter, 0));
+                let var = self.declare(&x.var_name)?;
+                self.emit(Op::SetLocal(var));
+                self.index(x, count)?;
+                self.loop_(Some(start), &x.body)?;
+                self.emit(Op::Jump(start));
+                self.patch(next);
+            }
+            Iterable::Range(range) => {
+                if let Some(Expr::Int(step)) = &range.step {
+                    if *step <= 0 {
+                        return Err(format!("The step of a range must be positive, not {step}"));
+                    }
+                }
+                let (first, cmp, bound) = range.counted();
+                let hidden = |this: &mut Self, name: &str, exp: &Expr| {
+                    this.expr(exp)?;
+                    let slot = this.declare(name)?;
+                    this.emit(Op::SetLocal(slot));
+                    Ok::<u16, String>(slot)
+                };
+                let counter = hidden(self, "for counter", &first)?;
+                let bound = hidden(self, "for bound", &bound)?;
+                let step = hidden(self, "for step", &range.step())?;
+                let start = self.here();
+                self.emit(Op::GetLocal(counter));
+                self.emit(Op::GetLocal(bound));
+                self.emit(Op::Binary(cmp));
+                let end = self.emit(Op::JumpIfFalse(0));
+                self.emit(Op::GetLocal(counter));
+                let var = self.declare(&x.var_name)?;
+                self.emit(Op::SetLocal(var));
+                self.index(x, count)?;
+                // `continue` goes on with the next value
+                self.loop_(None, &x.body)?;
+                self.continue_here();
+                self.emit(Op::GetLocal(counter));
+                self.emit(Op::GetLocal(step));
+                self.emit(Op::Binary(match range.rev {
+                    true => Operator::Minus,
+                    false => Operator::Plus,
+                }));
+                self.emit(Op::SetLocal(counter));
+                self.emit(Op::Jump(start));
+                self.patch(end);
+            }
+        }
+        self.end_loop();
+        self.scopes.pop();
+        Ok(())
+    }
+
+    /// Sets the index of `for i, x in ...` to the iteration count and
+    /// counts the iteration.
+    fn index(&mut self, x: &tree::For, count: Option<u16>) -> Result<(), String> {
+        let (Some(index), Some(count)) = (&x.index, count) else {
+            return Ok(());
+        };
+        self.emit(Op::GetLocal(count));
+        let slot = self.declare(index)?;
+        self.emit(Op::SetLocal(slot));
+        self.emit(Op::GetLocal(count));
+        let one = self.constant(Constant::Int(1));
+        self.emit(Op::Const(one));
+        self.emit(Op::Binary(Operator::Plus));
+        self.emit(Op::SetLocal(count));
+        Ok(())
+    }
+
+    /// The loop a `break` or `continue` leaves, the innermost one without a
+    /// label.
+    fn target(&self, label: &Option<String>) -> Result<usize, String> {
+        match self
+            .loops
+            .iter()
+            .rposition(|loop_| label.is_none() || loop_.label == *label)
+        {
+            Some(index) => Ok(index),
+            None => Err("There is no loop to leave".to_string()),
+        }
+    }
+
+    /// Compiles the body of a loop, `continue` jumps to `start`, or to
+    /// [`Self::continue_here`] without it.
+    fn loop_(&mut self, start: Option<u32>, stmts: &[Stmt]) -> Result<(), String> {
+        self.loops.push(Loop {
+            label: self.label.take(),
+            start,
+            continues: vec![],
+            breaks: vec![],
+        });
+        self.block(stmts)
+    }
+
+    /// Points the `continue`s of the innermost loop to the current position.
+    fn continue_here(&mut self) {
+        if let Some(loop_) = self.loops.last_mut() {
+            let continues = std::mem::take(&mut loop_.continues);
+            for jump in continues {
+                self.patch(jump);
+            }
+        }
+    }
+
+    /// Points the `break`s of the innermost loop to the current position.
+    fn end_loop(&mut self) {
+        if let Some(loop_) = self.loops.pop() {
+            for jump in loop_.breaks {
+                self.patch(jump);
+            }
+        }
+    }
+
+    fn expr(&mut self, exp: &Expr) -> Result<(), String> {
+        match exp {
+            Expr::Int(i) => {
+                let c = self.constant(Constant::Int(*i));
+                self.emit(Op::Const(c));
+            }
+            Expr::Char(c) => {
+                let c = self.constant(Constant::Int(*c as i64));
+                self.emit(Op::Const(c));
+            }
+            Expr::Float(f) => {
+                let c = self.constant(Constant::Float(*f));
+                self.emit(Op::Const(c));
+            }
+            Expr::Bool(b) => {
+                let c = self.constant(Constant::Bool(*b));
+                self.emit(Op::Const(c));
+            }
+            Expr::Str(s) => {
+                let c = self.constant(Constant::Str(s.clone()));
+                self.emit(Op::Const(c));
+            }
+            Expr::Var(name) => {
+                if let Some(slot) = self.local(name) {
+                    self.emit(Op::GetLocal(slot));
+                } else if let Some((index, _)) = self.global(name) {
+                    self.emit(Op::GetGlobal(index));
+                } else if let Some(index) = self.program.function(name) {
+                    let c = self.constant(Constant::Function(index));
+                    self.emit(Op::Const(c));
+                } else if let Some(builtin) = BUILTINS.iter().position(|b| b == name) {
+                    let c = self.constant(Constant::Builtin(builtin as u8));
+                    self.emit(Op::Const(c));
+                } else {
+                    return Err(format!("Unknown variable `{name}`"));
+                }
+            }
+            Expr::Array(items) => {
+                for item in items {
+                    self.expr(item)?;
+                }
+                match u16::try_from(items.len()) {
+                    Ok(len) => self.emit(Op::Array(len)),
+                    Err(_) => return Err("Array literal is too long".to_string()),
+                };
+            }
+            Expr::Call(name, args) => {
+                for arg in args {
+                    self.expr(arg)?;
+                }
+                let argc = match u8::try_from(args.len()) {
+                    Ok(argc) => argc,
+                    Err(_) => return Err(format!("Too many arguments for `{name}`")),
+                };
+                // variables holding functions are called like functions
+                if let Some(slot) = self.local(name) {
+                    self.emit(Op::GetLocal(slot));
+                    self.emit(Op::CallValue(argc));
+                } else if let Some((index, _)) = self.global(name) {
+                    self.emit(Op::GetGlobal(index));
+                    self.emit(Op::CallValue(argc));
+                } else if let Some(index) = self.program.function(name) {
+                    let params = self.program.functions[index as usize].params.len();
+                    if params != args.len() {
+                        return Err(format!(
+                            "`{name}` takes {params} arguments but got {}",
+                            args.len()
+                        ));
+                    }
+                    self.emit(Op::Call(index, argc));
+                } else if let Some(builtin) = BUILTINS.iter().position(|b| b == name) {
+                    self.emit(Op::Builtin(builtin as u8, argc));
+                } else {
+                    return Err(format!("Unknown function `{name}`"));
+                }
+            }
+            Expr::Index(exp, index) => {
+                self.expr(exp)?;
+                self.expr(index)?;
+                self.emit(Op::Index);
+            }
+            Expr::Struct(name, fields) => {
+                let index = match self.program.struct_(name) {
+                    Some(index) => index,
+                    None => return Err(format!("Unknown struct `{name}`")),
+                };
+                let declared = self.program.structs[index as usize].fields.clone();
+                if let Some((field, _)) = fields
+                    .iter()
+                    .find(|(field, _)| !declared.iter().any(|(f, _)| f == field))
+                {
+                    return Err(format!("The struct `{name}` has no field `{field}`"));
+                }
+                // in the order of the declaration, like in C++
+                for (field, _) in &declared {
+                    match fields.iter().find(|(f, _)| f == field) {
+                        Some((_, exp)) => self.expr(exp)?,
+                        None => {
+                            return Err(format!("Missing field `{field}` in the `{name}` literal"))
+                        }
+                    }
+                }
+                self.emit(Op::Struct(index));
+            }
+            Expr::Variant(name, variant, args) if self.program.enum_(name).is_none() => {
+                let function = format!("{name}::{variant}");
+                let methods = self.program.struct_(name).map(|index| {
+                    let x = &self.program.structs[index as usize];
+                    x.methods.iter().any(|(method, _)| method == variant)
+                });
+                match methods {
+                    _ if self.program.function(&function).is_none() => match methods {
+                        Some(_) => {
+                            return Err(format!("The struct `{name}` has no function `{variant}`"))
+                        }
+                        None => return Err(format!("Unknown enum `{name}`")),
+                    },
+                    Some(true) => {
+                        return Err(format!(
+                            "`{function}` takes `self`, call it like `value.{variant}()`"
+                        ))
+                    }
+                    _ => self.expr(&Expr::Call(function, args.clone()))?,
+                }
+            }
+            Expr::Method(receiver, method, args) => {
+                let structs = &self.program.structs;
+                if !structs
+                    .iter()
+                    .any(|x| x.methods.iter().any(|(m, _)| m == method))
+                {
+                    let function = (structs.iter())
+                        .map(|x| format!("{}::{method}", x.name))
+                        .find(|function| self.program.function(function).is_some());
+                    return Err(match function {
+                        Some(function) => {
+                            format!("`{function}` doesn't take `self`, call it like `{function}()`")
+                        }
+                        None => format!("No struct has a method `{method}`"),
+                    });
+                }
+                self.expr(receiver)?;
+                for arg in args {
+                    self.expr(arg)?;
+                }
+                let argc = match u8::try_from(args.len()) {
+                    Ok(argc) => argc,
+                    Err(_) => return Err(format!("Too many arguments for `{method}`")),
+                };
+                let c = self.constant(Constant::Str(method.clone()));
+                self.emit(Op::CallMethod(c, argc));
+            }
+            Expr::Variant(name, variant, values) => {
+                let index = match self.program.enum_(name) {
+                    Some(index) => index,
+                    None => return Err(format!("Unknown enum `{name}`")),
+                };
+                let variants = &self.program.enums[index as usize].variants;
+                let (at, types) = match variants.iter().position(|(v, _)| v == variant) {
+                    Some(at) => (at as u16, variants[at].1.len()),
+                    None => return Err(format!("The enum `{name}` has no variant `{variant}`")),
+                };
+                if values.len() != types {
+                    return Err(format!(
+                        "`{name}::{variant}` holds {types} values but got {}",
+                        values.len()
+                    ));
+                }
+                for value in values {
+                    self.expr(value)?;
+                }
+                self.emit(Op::Variant(index, at));
+            }
+            Expr::Match(value, arms) => self.match_(value, arms)?,
+            Expr::Field(exp, field) => {
+                self.expr(exp)?;
+                let c = self.constant(Constant::Str(field.clone()));
+                self.emit(Op::Field(c));
+            }
+            Expr::Neg(exp) => {
+                self.expr(exp)?;
+                self.emit(Op::Neg);
+            }
+            // the right side is only evaluated when needed, like in C
+            Expr::Binary(lhs, Operator::And, rhs) => {
+                self.expr(lhs)?;
+                let short = self.emit(Op::JumpIfFalse(0));
+                self.expr(rhs)?;
+                self.emit(Op::Truthy);
+                let end = self.emit(Op::Jump(0));
+                self.patch(short);
+                let c = self.constant(Constant::Bool(false));
+                self.emit(Op::Const(c));
+                self.patch(end);
+            }
+            Expr::Binary(lhs, Operator::Or, rhs) => {
+                self.expr(lhs)?;
+                let rhs_start = self.emit(Op::JumpIfFalse(0));
+                let c = self.constant(Constant::Bool(true));
+                self.emit(Op::Const(c));
+                let end = self.emit(Op::Jump(0));
+                self.patch(rhs_start);
+                self.expr(rhs)?;
+                self.emit(Op::Truthy);
+                self.patch(end);
+            }
+            Expr::Binary(lhs, o, rhs) => {
+                self.expr(lhs)?;
+                self.expr(rhs)?;
+                self.emit(Op::Binary(*o));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,732 @@
+//! A stack based virtual machine running compiled lang programs.
+//!
+//! Every call gets a [`Frame`] with its own locals and operand stack. A
+//! generator call creates a frame without running it; `for` loops push the
+//! frame when they need the next value and a `yield` takes it off the stack
+//! again, so the generator continues where it stopped on the next iteration.
+//! The VM only needs `std`, it runs on wasm32 too.
+use {
+    crate::{
+        interpreter::{self, value, MAX_DEPTH},
+        lexer::{
+            token::expression::Operator,
+            types::{Primitive, Type},
+        },
+    },
+    bytecode::{Constant, EnumCode, FunctionKind, Op, Program, StructCode, TypeTag},
+    std::{cell::RefCell, fmt, rc::Rc, thread, time::Duration},
+};
+
+pub mod bytecode;
+pub mod compiler;
+
+pub use crate::interpreter::{predefined, BUILTINS, PRELUDE};
+
+#[derive(Clone, Debug)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(Rc<str>),
+    Array(Rc<Vec<Value>>),
+    /// the fields in the order the struct declares them
+    Struct(Rc<StructCode>, Rc<Vec<Value>>),
+    /// the index of the variant and the values it holds
+    Enum(Rc<EnumCode>, u16, Rc<Vec<Value>>),
+    Generator(Rc<RefCell<Generator>>),
+    /// the state of a `for` loop, only ever stored in a local
+    Iter(Iter),
+    Function(u16),
+    Builtin(u8),
+    Void,
+}
+
+#[derive(Clone, Debug)]
+pub enum Iter {
+    Array(Rc<Vec<Value>>, usize),
+    Generator(Rc<RefCell<Generator>>),
+}
+
+#[derive(Debug)]
+pub struct Generator {
+    /// `None` while the generator is running
+    frame: Option<Frame>,
+    done: bool,
+}
+
+#[derive(Debug)]
+pub struct Frame {
+    /// `None` for the code initializing the globals
+    function: Option<u16>,
+    ip: usize,
+    locals: Vec<Value>,
+    stack: Vec<Value>,
+    /// set while the frame of a generator runs
+    generator: Option<Rc<RefCell<Generator>>>,
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Generator(_) | Value::Iter(_) => write!(f, "<generator>"),
+            Value::Function(_) | Value::Builtin(_) => write!(f, "<fn>"),
+            v => write!(f, "{}", to_value(v).unwrap()),
+        }
+    }
+}
+
+/// The interpreter's value for a value that doesn't hold any state, used to
+/// share the arithmetic with the interpreter.
+fn to_value(v: &Value) -> Result<interpreter::Value, String> {
+    Ok(match v {
+        Value::Int(i) => interpreter::Value::Int(*i),
+        Value::Float(f) => interpreter::Value::Float(*f),
+        Value::Bool(b) => interpreter::Value::Bool(*b),
+        Value::Str(s) => interpreter::Value::Str(s.to_string()),
+        Value::Array(items) => {
+            let mut values = vec![];
+            for item in items.iter() {
+                values.push(to_value(item)?);
+            }
+            interpreter::Value::Array(values)
+        }
+        Value::Struct(x, values) => {
+            let mut fields = vec![];
+            for ((field, _), value) in x.fields.iter().zip(values.iter()) {
+                fields.push((field.clone(), to_value(value)?));
+            }
+            interpreter::Value::Struct(x.name.clone(), fields)
+        }
+        Value::Enum(x, variant, values) => {
+            let mut held = vec![];
+            for value in values.iter() {
+                held.push(to_value(value)?);
+            }
+            let (variant, _) = &x.variants[*variant as usize];
+            interpreter::Value::Enum(x.name.clone(), variant.clone(), held)
+        }
+        Value::Void => interpreter::Value::Void,
+        Value::Generator(_) | Value::Iter(_) => {
+            return Err("Generators can't be used as values".to_string())
+        }
+        Value::Function(_) | Value::Builtin(_) => {
+            return Err("Functions can't be used as values".to_string())
+        }
+    })
+}
+
+fn from_value(v: interpreter::Value) -> Value {
+    match v {
+        interpreter::Value::Int(i) => Value::Int(i),
+        interpreter::Value::Float(f) => Value::Float(f),
+        interpreter::Value::Bool(b) => Value::Bool(b),
+        interpreter::Value::Str(s) => Value::Str(s.into()),
+        interpreter::Value::Array(items) => {
+            Value::Array(Rc::new(items.into_iter().map(from_value).collect()))
+        }
+        interpreter::Value::Struct(name, fields) => {
+            let (fields, values): (Vec<_>, Vec<_>) = fields
+                .into_iter()
+                .map(|(field, value)| ((field, TypeTag::Any), from_value(value)))
+                .unzip();
+            let x = StructCode {
+                name,
+                fields,
+                methods: vec![],
+            };
+            Value::Struct(Rc::new(x), Rc::new(values))
+        }
+        interpreter::Value::Enum(name, variant, values) => {
+            let types = vec![TypeTag::Any; values.len()];
+            let x = EnumCode {
+                name,
+                variants: vec![(variant, types)],
+            };
+            let values = values.into_iter().map(from_value).collect();
+            Value::Enum(Rc::new(x), 0, Rc::new(values))
+        }
+        interpreter::Value::Generator(_)
+        | interpreter::Value::Function(_)
+        | interpreter::Value::Void => Value::Void,
+    }
+}
+
+fn coerce(v: Value, type_: TypeTag) -> Result<Value, String> {
+    let type_ = match type_.primitive() {
+        Some(Primitive::Void) | None => return Ok(v),
+        Some(p) => Type::Primitive(p),
+    };
+    match v {
+        Value::Int(_) | Value::Float(_) | Value::Bool(_) => {
+            Ok(from_value(to_value(&v)?.coerce(&type_)?))
+        }
+        Value::Void => Err(format!("Expected a value of type `{type_}`")),
+        v => Ok(v),
+    }
+}
+
+/// `l o r` like [`value::binary`] in the interpreter, numbers and strings
+/// are worked on without converting them.
+fn binary(l: Value, o: &Operator, r: Value) -> Result<Value, String> {
+    // booleans take part in arithmetic as 0 and 1, like in C
+    let number = |v: Value| match v {
+        Value::Bool(b) => Value::Int(b as i64),
+        v => v,
+    };
+    Ok(match (number(l), number(r)) {
+        (Value::Int(a), Value::Int(b)) => match o {
+            Operator::Plus => Value::Int(a.wrapping_add(b)),
+            Operator::Minus => Value::Int(a.wrapping_sub(b)),
+            Operator::Mul => Value::Int(a.wrapping_mul(b)),
+            Operator::Div | Operator::Mod if b == 0 => {
+                return Err("Division by zero".to_string());
+            }
+            Operator::Div => Value::Int(a.wrapping_div(b)),
+            Operator::Mod => Value::Int(a.wrapping_rem(b)),
+            Operator::BitAnd => Value::Int(a & b),
+            Operator::BitOr => Value::Int(a | b),
+            Operator::BitShiftLeft => Value::Int(a.wrapping_shl(b as u32)),
+            Operator::BitShiftRight => Value::Int(a.wrapping_shr(b as u32)),
+            _ => from_value(value::compare(o, a.cmp(&b))?),
+        },
+        (Value::Int(a), Value::Float(b)) => from_value(value::float(a as f64, o, b)?),
+        (Value::Float(a), Value::Int(b)) => from_value(value::float(a, o, b as f64)?),
+        (Value::Float(a), Value::Float(b)) => from_value(value::float(a, o, b)?),
+        (Value::Str(a), Value::Str(b)) => match o {
+            Operator::Plus => Value::Str(format!("{a}{b}").into()),
+            _ => from_value(value::compare(o, a.cmp(&b))?),
+        },
+        // enums and the errors
+        (l, r) => from_value(value::binary(to_value(&l)?, o, to_value(&r)?)?),
+    })
+}
+
+fn truthy(v: &Value) -> Result<bool, String> {
+    match v {
+        Value::Bool(b) => Ok(*b),
+        Value::Int(i) => Ok(*i != 0),
+        v => to_value(v)?.truthy(),
+    }
+}
+
+pub struct Vm {
+    program: Program,
+    constants: Vec<Value>,
+    structs: Vec<Rc<StructCode>>,
+    enums: Vec<Rc<EnumCode>>,
+    globals: Vec<Value>,
+    frames: Vec<Frame>,
+    /// output of `print`, written to stdout when `None`
+    pub output: Option<String>,
+}
+
+impl Vm {
+    pub fn new(program: Program) -> Vm {
+        let constants = program
+            .constants
+            .iter()
+            .map(|c| match c {
+                Constant::Int(i) => Value::Int(*i),
+                Constant::Float(f) => Value::Float(*f),
+                Constant::Bool(b) => Value::Bool(*b),
+                Constant::Str(s) => Value::Str(s.as_str().into()),
+                Constant::Void => Value::Void,
+                Constant::Function(index) => Value::Function(*index),
+                Constant::Builtin(index) => Value::Builtin(*index),
+            })
+            .collect();
+        Vm {
+            globals: vec![Value::Void; program.globals.len()],
+            structs: program.structs.iter().cloned().map(Rc::new).collect(),
+            enums: program.enums.iter().cloned().map(Rc::new).collect(),
+            program,
+            constants,
+            frames: vec![],
+            output: None,
+        }
+    }
+
+    /// A VM collecting everything printed in `output`.
+    pub fn capturing(program: Program) -> Vm {
+        Vm {
+            output: Some(String::new()),
+            ..Vm::new(program)
+        }
+    }
+
+    /// Initializes the globals and calls `main`, its return value is the
+    /// exit code.
+    pub fn run(&mut self) -> Result<i32, String> {
+        self.frames.push(Frame {
+            function: None,
+            ip: 0,
+            locals: vec![],
+            stack: vec![],
+            generator: None,
+        });
+        self.execute()?;
+        let main = match self.program.function("main") {
+            Some(main) => main,
+            None => return Err("There is no `main` function".to_string()),
+        };
+        let frame = self.frame(main, vec![])?;
+        self.frames.push(frame);
+        match self.execute()? {
+            Value::Int(code) => Ok(code as i32),
+            _ => Ok(0),
+        }
+    }
+
+    /// A new frame for a call of a function.
+    fn frame(&self, index: u16, args: Vec<Value>) -> Result<Frame, String> {
+        let function = match self.program.functions.get(index as usize) {
+            Some(function) => function,
+            None => return Err(format!("Unknown function {index}")),
+        };
+        if function.params.len() != args.len() {
+            return Err(format!(
+                "`{}` takes {} arguments but got {}",
+                function.name,
+                function.params.len(),
+                args.len()
+            ));
+        }
+        let mut locals = Vec::with_capacity(function.locals as usize);
+        for (arg, type_) in args.into_iter().zip(function.params.iter()) {
+            locals.push(coerce(arg, *type_)?);
+        }
+        locals.resize(
+            function.locals.max(locals.len() as u16) as usize,
+            Value::Void,
+        );
+        Ok(Frame {
+            function: Some(index),
+            ip: 0,
+            locals,
+            stack: vec![],
+            generator: None,
+        })
+    }
+
+    fn name(&self, frame: &Frame) -> String {
+        match frame.function {
+            Some(index) => self.program.functions[index as usize].name.clone(),
+            None => "globals".to_string(),
+        }
+    }
+
+    /// Runs until the frame on top of the stack returns.
+    fn execute(&mut self) -> Result<Value, String> {
+        let depth = self.frames.len();
+        loop {
+            match self.step(depth) {
+                Ok(Some(value)) => return Ok(value),
+                Ok(None) => {}
+                Err(e) => {
+                    // the frames that were running, innermost first, a function
+                    // recursing into itself is in the trace once
+                    let mut trace = e;
+                    while self.frames.len() >= depth {
+                        let frame = self.frames.pop().unwrap();
+                        let line = format!("\n    in `{}`", self.name(&frame));
+                        if !trace.ends_with(&line) {
+                            trace.push_str(&line);
+                        }
+                    }
+                    return Err(trace);
+                }
+            }
+        }
+    }
+
+    /// Runs one instruction, returns the value of the frame at `depth` once
+    /// it returns.
+    fn step(&mut self, depth: usize) -> Result<Option<Value>, String> {
+        let frame = self.frames.last_mut().unwrap();
+        let code = match frame.function {
+            Some(index) => &self.program.functions[index as usize].code,
+            None => &self.program.init,
+        };
+        let op = match code.get(frame.ip) {
+            Some(op) => *op,
+            // only the globals' code ends without `return`
+            None if frame.function.is_none() => {
+                self.frames.pop();
+                return Ok(Some(Value::Void));
+            }
+            None => return Err("Reached the end of the code without `return`".to_string()),
+        };
+        frame.ip += 1;
+        let pop = |frame: &mut Frame| match frame.stack.pop() {
+            Some(value) => Ok(value),
+            None => Err("Stack underflow".to_string()),
+        };
+        match op {
+            Op::Const(i) => match self.constants.get(i as usize) {
+                Some(c) => frame.stack.push(c.clone()),
+                None => return Err(format!("Unknown constant {i}")),
+            },
+            Op::Pop => {
+                pop(frame)?;
+            }
+            Op::GetLocal(i) => match frame.locals.get(i as usize) {
+                Some(v) => frame.stack.push(v.clone()),
+                None => return Err(format!("Unknown local {i}")),
+            },
+            Op::SetLocal(i) => {
+                let v = pop(frame)?;
+                match frame.locals.get_mut(i as usize) {
+                    Some(local) => *local = v,
+                    None => return Err(format!("Unknown local {i}")),
+                }
+            }
+            Op::GetGlobal(i) => match self.globals.get(i as usize) {
+                Some(v) => frame.stack.push(v.clone()),
+                None => return Err(format!("Unknown global {i}")),
+            },
+            Op::SetGlobal(i) => {
+                let v = pop(frame)?;
+                match self.globals.get_mut(i as usize) {
+                    Some(global) => *global = v,
+                    None => return Err(format!("Unknown global {i}")),
+                }
+            }
+            Op::Binary(o) => {
+                let r = pop(frame)?;
+                let l = pop(frame)?;
+                frame.stack.push(binary(l, &o, r)?);
+            }
+            Op::Neg => {
+                let v = match pop(frame)? {
+                    Value::Int(i) => Value::Int(i.wrapping_neg()),
+                    Value::Float(f) => Value::Float(-f),
+                    _ => return Err("Only numbers can be negated".to_string()),
+                };
+                frame.stack.push(v);
+            }
+            Op::Truthy => {
+                let v = pop(frame)?;
+                frame.stack.push(Value::Bool(truthy(&v)?));
+            }
+            Op::Jump(to) => frame.ip = to as usize,
+            Op::JumpIfFalse(to) => {
+                if !truthy(&pop(frame)?)? {
+                    frame.ip = to as usize;
+                }
+            }
+            Op::Array(len) => {
+                let items = split(frame, len as usize)?;
+                frame.stack.push(Value::Array(Rc::new(items)));
+            }
+            Op::Index => {
+                let index = pop(frame)?;
+                let v = pop(frame)?;
+                let item = match (&v, &index) {
+                    (Value::Array(items), Value::Int(i)) => match items.get(*i as usize) {
+                        Some(item) if *i >= 0 => item.clone(),
+                        _ => {
+                            return Err(format!(
+                                "Index {i} is out of bounds for an array of length {}",
+                                items.len()
+                            ))
+                        }
+                    },
+                    (Value::Str(s), Value::Int(i)) => match s.chars().nth(*i as usize) {
+                        Some(c) if *i >= 0 => Value::Str(c.to_string().into()),
+                        _ => return Err(format!("Index {i} is out of bounds for `{s}`")),
+                    },
+                    _ => return Err("Only arrays and strings can be indexed".to_string()),
+                };
+                frame.stack.push(item);
+            }
+            Op::Struct(index) => {
+                let x = match self.structs.get(index as usize) {
+                    Some(x) => x.clone(),
+                    None => return Err(format!("Unknown struct {index}")),
+                };
+                let values = split(frame, x.fields.len())?;
+                let mut fields = Vec::with_capacity(values.len());
+                for ((field, type_), value) in x.fields.iter().zip(values) {
+                    fields.push(coerce(value, *type_).map_err(|e| format!("{e} for `{field}`"))?);
+                }
+                frame.stack.push(Value::Struct(x, Rc::new(fields)));
+            }
+            Op::Variant(index, variant) => {
+                let x = match self.enums.get(index as usize) {
+                    Some(x) => x.clone(),
+                    None => return Err(format!("Unknown enum {index}")),
+                };
+                let types = match x.variants.get(variant as usize) {
+                    Some((_, types)) => types.clone(),
+                    None => return Err(format!("Unknown variant {variant} of `{}`", x.name)),
+                };
+                let mut values = Vec::with_capacity(types.len());
+                for (value, type_) in split(frame, types.len())?.into_iter().zip(types) {
+                    values.push(coerce(value, type_)?);
+                }
+                frame.stack.push(Value::Enum(x, variant, Rc::new(values)));
+            }
+            Op::IsVariant(index, variant) => {
+                let x = match self.enums.get(index as usize) {
+                    Some(x) => x.clone(),
+                    None => return Err(format!("Unknown enum {index}")),
+                };
+                let is = match pop(frame)? {
+                    Value::Enum(e, v, _) => Rc::ptr_eq(&e, &x) && v == variant,
+                    v => return Err(format!("`{v}` isn't a `{}`", x.name)),
+                };
+                frame.stack.push(Value::Bool(is));
+            }
+            Op::Held(at) => {
+                let value = match pop(frame)? {
+                    Value::Enum(x, variant, values) => match values.get(at as usize) {
+                        Some(value) => value.clone(),
+                        None => {
+                            let name = &x.variants[variant as usize].0;
+                            return Err(format!("`{}::{name}` holds no value {at}", x.name));
+                        }
+                    },
+                    _ => return Err("Only enums hold values".to_string()),
+                };
+                frame.stack.push(value);
+            }
+            Op::Field(name) => {
+                let name = match self.constants.get(name as usize) {
+                    Some(Value::Str(name)) => name.clone(),
+                    _ => return Err(format!("Unknown field name {name}")),
+                };
+                let value = match pop(frame)? {
+                    Value::Struct(x, values) => {
+                        match x.fields.iter().position(|(field, _)| **field == *name) {
+                            Some(i) => values[i].clone(),
+                            None => {
+                                return Err(format!(
+                                    "The struct `{}` has no field `{name}`",
+                                    x.name
+                                ))
+                            }
+                        }
+                    }
+                    _ => return Err(format!("Only structs have fields like `{name}`")),
+                };
+                frame.stack.push(value);
+            }
+            Op::Call(index, argc) => {
+                let args = split(frame, argc as usize)?;
+                self.call(index, args)?;
+            }
+            Op::CallValue(argc) => {
+                let callee = pop(frame)?;
+                let args = split(frame, argc as usize)?;
+                match callee {
+                    Value::Function(index) => self.call(index, args)?,
+                    Value::Builtin(builtin) => {
+                        let v = self.builtin(builtin, args)?;
+                        self.frames.last_mut().unwrap().stack.push(v);
+                    }
+                    _ => return Err("Only functions can be called".to_string()),
+                }
+            }
+            Op::CallMethod(name, argc) => {
+                let name = match self.constants.get(name as usize) {
+                    Some(Value::Str(name)) => name.clone(),
+                    _ => return Err(format!("Unknown method name {name}")),
+                };
+                let args = split(frame, argc as usize + 1)?;
+                let x = match &args[0] {
+                    Value::Struct(x, _) => x.clone(),
+                    v => return Err(format!("`{v}` has no method `{name}`")),
+                };
+                let index = match x.methods.iter().find(|(method, _)| **method == *name) {
+                    Some((_, index)) => *index,
+                    None => return Err(format!("The struct `{}` has no method `{name}`", x.name)),
+                };
+                let params = self.program.functions[index as usize].params.len() - 1;
+                if params != argc as usize {
+                    return Err(format!(
+                        "`{}::{name}` takes {params} arguments but got {argc}",
+                        x.name
+                    ));
+                }
+                self.call(index, args)?;
+            }
+            Op::Builtin(builtin, argc) => {
+                let args = split(frame, argc as usize)?;
+                let v = self.builtin(builtin, args)?;
+                self.frames.last_mut().unwrap().stack.push(v);
+            }
+            Op::Return => {
+                let v = pop(frame)?;
+                let index = match frame.function {
+                    Some(index) => index,
+                    None => return Err("`return` outside of a function".to_string()),
+                };
+                let frame = self.frames.pop().unwrap();
+                let function = &self.program.functions[index as usize];
+                let v = match v {
+                    Value::Void if function.name == "main" => Value::Int(0),
+                    // reaching the end of a generator just ends the `for`
+                    Value::Void if frame.generator.is_some() => Value::Void,
+                    Value::Void if function.result != TypeTag::Void => {
+                        return Err(format!(
+                            "`{}` ended without returning a value",
+                            function.name
+                        ))
+                    }
+                    v => coerce(v, function.result)?,
+                };
+                if let Some(generator) = frame.generator {
+                    // generators end when they return or reach their end
+                    generator.borrow_mut().done = true;
+                    let caller = self.frames.last_mut().unwrap();
+                    caller.ip = match code_at(&self.program, caller) {
+                        Some(Op::Next(_, to)) => to as usize,
+                        _ => return Err("A generator returned outside of `for`".to_string()),
+                    };
+                } else if self.frames.len() < depth {
+                    return Ok(Some(v));
+                } else {
+                    self.frames.last_mut().unwrap().stack.push(v);
+                }
+            }
+            Op::Iter(local) => {
+                let iter = match pop(frame)? {
+                    Value::Array(items) => Iter::Array(items, 0),
+                    Value::Generator(generator) => Iter::Generator(generator),
+                    _ => return Err("Only arrays and generators can be iterated".to_string()),
+                };
+                match frame.locals.get_mut(local as usize) {
+                    Some(slot) => *slot = Value::Iter(iter),
+                    None => return Err(format!("Unknown local {local}")),
+                }
+            }
+            Op::Next(local, to) => {
+                let generator = match frame.locals.get_mut(local as usize) {
+                    Some(Value::Iter(Iter::Array(items, index))) => {
+                        match items.get(*index) {
+                            Some(item) => {
+                                *index += 1;
+                                let item = item.clone();
+                                frame.stack.push(item);
+                            }
+                            None => frame.ip = to as usize,
+                        }
+                        return Ok(None);
+                    }
+                    Some(Value::Iter(Iter::Generator(generator))) => generator.clone(),
+                    _ => return Err(format!("Local {local} is not an iterator")),
+                };
+                if generator.borrow().done {
+                    frame.ip = to as usize;
+                    return Ok(None);
+                }
+                let callee = generator.borrow_mut().frame.take();
+                let mut callee = match callee {
+                    Some(callee) => callee,
+                    None => return Err("A generator can't iterate over itself".to_string()),
+                };
+                // continues at `Next` once the generator yields or returns
+                frame.ip -= 1;
+                callee.generator = Some(generator);
+                self.enter(callee)?;
+            }
+            Op::Yield => {
+                let v = pop(frame)?;
+                let (index, generator) = match (frame.function, frame.generator.take()) {
+                    (Some(index), Some(generator)) => (index, generator),
+                    _ => return Err("`yield` outside of a generator".to_string()),
+                };
+                let v = coerce(v, self.program.functions[index as usize].result)?;
+                let frame = self.frames.pop().unwrap();
+                generator.borrow_mut().frame = Some(frame);
+                let caller = self.frames.last_mut().unwrap();
+                caller.ip += 1;
+                caller.stack.push(v);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Pushes the frame of a call, or the generator it creates.
+    fn call(&mut self, index: u16, args: Vec<Value>) -> Result<(), String> {
+        let callee = self.frame(index, args)?;
+        if self.program.functions[index as usize].kind == FunctionKind::Generator {
+            // nothing runs until the first value is asked for
+            let generator = Generator {
+                frame: Some(callee),
+                done: false,
+            };
+            let frame = self.frames.last_mut().unwrap();
+            frame
+                .stack
+                .push(Value::Generator(Rc::new(RefCell::new(generator))));
+        } else {
+            self.enter(callee)?;
+        }
+        Ok(())
+    }
+
+    /// Pushes a frame one call deeper, recursing deeper than [`MAX_DEPTH`]
+    /// is an error like in the interpreter.
+    fn enter(&mut self, frame: Frame) -> Result<(), String> {
+        if self.frames.len() == MAX_DEPTH {
+            return Err(format!(
+                "More than {MAX_DEPTH} calls running at once\n    in `{}`",
+                self.name(&frame)
+            ));
+        }
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    fn builtin(&mut self, builtin: u8, args: Vec<Value>) -> Result<Value, String> {
+        match BUILTINS.get(builtin as usize) {
+            Some(&"print") => {
+                let line: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                let line = line.join(" ");
+                match &mut self.output {
+                    Some(output) => {
+                        output.push_str(&line);
+                        output.push('\n');
+                    }
+                    None => println!("{line}"),
+                }
+                Ok(Value::Void)
+            }
+            Some(&"len") => match args.as_slice() {
+                [Value::Array(items)] => Ok(Value::Int(items.len() as i64)),
+                [Value::Str(s)] => Ok(Value::Int(s.chars().count() as i64)),
+                _ => Err("`len` takes an array or a string".to_string()),
+            },
+            Some(&"sleep") => match args.as_slice() {
+                // there are no threads to put to sleep in the browser
+                [Value::Int(_)] if cfg!(target_arch = "wasm32") => {
+                    Err("`sleep` isn't available on wasm32".to_string())
+                }
+                [Value::Int(ms)] if *ms >= 0 => {
+                    thread::sleep(Duration::from_millis(*ms as u64));
+                    Ok(Value::Void)
+                }
+                _ => Err("`sleep` takes a number of milliseconds".to_string()),
+            },
+            _ => Err(format!("Unknown builtin {builtin}")),
+        }
+    }
+}
+
+/// The instruction a frame is at.
+fn code_at(program: &Program, frame: &Frame) -> Option<Op> {
+    let code = match frame.function {
+        Some(index) => &program.functions[index as usize].code,
+        None => &program.init,
+    };
+    code.get(frame.ip).copied()
+}
+
+/// Pops the top `len` values, in the order they were pushed.
+fn split(frame: &mut Frame, len: usize) -> Result<Vec<Value>, String> {
+    match frame.stack.len().checked_sub(len) {
+        Some(at) => Ok(frame.stack.split_off(at)),
+        None => Err("Stack underflow".to_string()),
+    }
+}